@@ -0,0 +1,37 @@
+-- Per-project mapping between Dooray workflows and kanban task statuses.
+-- Several workflows may map to the same status; the row flagged as push target
+-- is the workflow set on Dooray when a task moves into that status locally.
+
+CREATE TABLE dooray_workflow_mappings (
+    id                  BLOB PRIMARY KEY,
+    dooray_project_id   TEXT NOT NULL,
+    workflow_id         TEXT NOT NULL,
+    workflow_name       TEXT,
+    task_status         TEXT NOT NULL
+                        CHECK (task_status IN ('todo','inprogress','inreview','done','cancelled')),
+    is_push_target      BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    UNIQUE (dooray_project_id, workflow_id)
+);
+
+CREATE INDEX idx_dooray_workflow_mappings_project ON dooray_workflow_mappings(dooray_project_id);
+
+-- Last status both sides agreed on for each linked task. A local status that
+-- differs from task_status, or a remote workflow that differs from workflow_id,
+-- means that side changed since the last sync.
+CREATE TABLE dooray_task_sync_states (
+    task_id                 BLOB PRIMARY KEY,
+    task_status             TEXT NOT NULL
+                            CHECK (task_status IN ('todo','inprogress','inreview','done','cancelled')),
+    workflow_id             TEXT,
+    -- Remote workflow that conflicted with a local change, until resolved
+    conflict_workflow_id    TEXT,
+    synced_at               TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
+
+-- Tasks linked before status sync start from their current status. Their post's
+-- workflow is unknown, so the first sync treats a local change as the only change.
+INSERT INTO dooray_task_sync_states (task_id, task_status, workflow_id)
+SELECT id, status, NULL FROM tasks WHERE dooray_task_id IS NOT NULL;
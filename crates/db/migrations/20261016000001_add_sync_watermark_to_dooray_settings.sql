@@ -0,0 +1,4 @@
+-- Local kanban project that scheduled syncs import into (set by the last manual sync)
+ALTER TABLE dooray_settings ADD COLUMN sync_project_id BLOB;
+-- Newest post updatedAt seen by the last sync; later syncs only look at posts changed after it
+ALTER TABLE dooray_settings ADD COLUMN sync_watermark TEXT;
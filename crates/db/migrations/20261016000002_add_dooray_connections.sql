@@ -0,0 +1,47 @@
+-- Additional Dooray tokens (e.g. a second tenant). The single dooray_settings row
+-- remains the default connection.
+CREATE TABLE dooray_connections (
+    id              BLOB PRIMARY KEY,
+    name            TEXT NOT NULL,
+    dooray_token    TEXT NOT NULL,
+    dooray_domain   TEXT,
+    member_id       TEXT,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+-- Dooray projects a kanban project syncs with, imports from and creates posts in.
+-- connection_id NULL means the default connection from dooray_settings.
+CREATE TABLE project_dooray_bindings (
+    id                  BLOB PRIMARY KEY,
+    project_id          BLOB NOT NULL,
+    connection_id       BLOB,
+    dooray_project_id   TEXT NOT NULL,
+    dooray_project_code TEXT NOT NULL,
+    -- JSON array of tag IDs to filter when syncing
+    tag_ids             TEXT NOT NULL DEFAULT '[]',
+    -- Binding used for new Dooray posts created from this kanban project
+    is_default          BOOLEAN NOT NULL DEFAULT FALSE,
+    -- Newest post updatedAt seen by the last sync of this binding
+    sync_watermark      TEXT,
+    created_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    FOREIGN KEY (connection_id) REFERENCES dooray_connections(id) ON DELETE CASCADE,
+    UNIQUE (project_id, dooray_project_id)
+);
+
+CREATE INDEX idx_project_dooray_bindings_dooray_project ON project_dooray_bindings(dooray_project_id);
+
+-- The last synced project of the default connection becomes its first binding
+INSERT INTO project_dooray_bindings (id, project_id, connection_id, dooray_project_id, dooray_project_code, tag_ids, is_default, sync_watermark)
+SELECT randomblob(16), s.sync_project_id, NULL, s.selected_project_id, s.selected_project_name,
+       COALESCE(s.selected_tag_ids, '[]'), TRUE, s.sync_watermark
+FROM dooray_settings s
+JOIN projects p ON p.id = s.sync_project_id
+WHERE s.selected_project_id IS NOT NULL
+  AND s.selected_project_name IS NOT NULL;
+
+-- Sync state now lives on the bindings
+ALTER TABLE dooray_settings DROP COLUMN sync_project_id;
+ALTER TABLE dooray_settings DROP COLUMN sync_watermark;
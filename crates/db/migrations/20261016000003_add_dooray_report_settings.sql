@@ -0,0 +1,13 @@
+-- Opt-in comments on the linked Dooray post when an attempt finishes or its PR
+-- is created or merged. Projects without a row report nothing.
+CREATE TABLE dooray_report_settings (
+    project_id              BLOB PRIMARY KEY,
+    on_execution_finished   BOOLEAN NOT NULL DEFAULT FALSE,
+    on_pr_created           BOOLEAN NOT NULL DEFAULT FALSE,
+    on_pr_merged            BOOLEAN NOT NULL DEFAULT FALSE,
+    -- Comment template with {placeholders}; NULL uses the built-in template
+    comment_template        TEXT,
+    created_at              TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at              TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
@@ -0,0 +1,4 @@
+-- Task linked to the parent Dooray post of this task's post (sub-post hierarchy)
+ALTER TABLE tasks ADD COLUMN parent_task_id BLOB REFERENCES tasks(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_tasks_parent_task_id ON tasks(parent_task_id);
@@ -0,0 +1,10 @@
+-- Dooray post files downloaded into the local image store, so re-syncing a
+-- post reuses them instead of downloading them again.
+CREATE TABLE dooray_file_images (
+    dooray_file_id  TEXT PRIMARY KEY,
+    image_id        BLOB NOT NULL,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (image_id) REFERENCES images(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_dooray_file_images_image_id ON dooray_file_images(image_id);
@@ -0,0 +1,3 @@
+-- Coding agent's own session id for design sessions. Workspace sessions keep it
+-- on their coding agent turns; design sessions have no execution processes.
+ALTER TABLE sessions ADD COLUMN agent_session_id TEXT;
@@ -0,0 +1,2 @@
+-- Implementation plan (markdown) distilled from the task's design session
+ALTER TABLE tasks ADD COLUMN plan TEXT;
@@ -0,0 +1,21 @@
+-- Tool approval requests of coding agents. Kept so the pending requests of
+-- executions still running are restored after a server restart (the others are
+-- closed as timed out) and answered ones can be audited per execution process.
+CREATE TABLE approvals (
+    id                    TEXT PRIMARY KEY,
+    execution_process_id  BLOB NOT NULL,
+    tool_name             TEXT NOT NULL,
+    tool_input            TEXT NOT NULL DEFAULT 'null',
+    tool_call_id          TEXT NOT NULL,
+    status                TEXT NOT NULL DEFAULT 'pending'
+                            CHECK (status IN ('pending', 'approved', 'denied', 'timed_out')),
+    denial_reason         TEXT,
+    responded_by          TEXT,
+    created_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    timeout_at            TEXT NOT NULL,
+    responded_at          TEXT,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_approvals_execution_process_id ON approvals(execution_process_id);
+CREATE INDEX idx_approvals_pending ON approvals(status) WHERE status = 'pending';
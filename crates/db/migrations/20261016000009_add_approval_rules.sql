@@ -0,0 +1,19 @@
+-- Per-project rules answering agent tool approval requests without asking the
+-- user. Rules are evaluated by position; the first match decides.
+CREATE TABLE approval_rules (
+    id          BLOB PRIMARY KEY,
+    project_id  BLOB NOT NULL,
+    position    INTEGER NOT NULL,
+    action      TEXT NOT NULL CHECK (action IN ('allow', 'deny', 'ask')),
+    tool_name   TEXT,
+    command     TEXT,
+    path        TEXT,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_approval_rules_project_id ON approval_rules(project_id, position);
+
+-- Rule that answered a request, for requests answered by policy
+ALTER TABLE approvals ADD COLUMN matched_rule TEXT;
@@ -0,0 +1,15 @@
+-- Follow-up messages queued for a session, started one by one as its
+-- executions finish. Kept so a queue survives a server restart.
+CREATE TABLE queued_messages (
+    id                   BLOB PRIMARY KEY,
+    session_id           BLOB NOT NULL,
+    position             INTEGER NOT NULL,
+    message              TEXT NOT NULL,
+    -- JSON executor profile id; NULL runs with the session's latest profile
+    executor_profile_id  TEXT,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_queued_messages_session_id ON queued_messages(session_id, position);
@@ -0,0 +1,15 @@
+-- What happens when a coding agent run of a project fails. Projects without a
+-- row never retry or fall back.
+CREATE TABLE project_retry_policies (
+    project_id                    BLOB PRIMARY KEY,
+    -- Retries of the same executor on transient failures (rate limits, network)
+    max_retries                   INTEGER NOT NULL DEFAULT 0,
+    -- Delay before the first retry, doubled for every further retry
+    backoff_seconds               INTEGER NOT NULL DEFAULT 30,
+    -- JSON executor profile id to continue with in a new session once the
+    -- failure can't be retried; NULL disables the fallback
+    fallback_executor_profile_id  TEXT,
+    created_at                    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at                    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
@@ -0,0 +1,35 @@
+-- Token usage a coding agent run last reported, recorded when the run exits.
+-- Billed tokens are zero if the executor doesn't report them; input tokens
+-- exclude cache reads and writes.
+CREATE TABLE execution_process_token_usage (
+    execution_process_id  BLOB PRIMARY KEY,
+    total_tokens          INTEGER NOT NULL,
+    model_context_window  INTEGER NOT NULL,
+    model                 TEXT,
+    input_tokens          INTEGER NOT NULL DEFAULT 0,
+    output_tokens         INTEGER NOT NULL DEFAULT 0,
+    cache_read_tokens     INTEGER NOT NULL DEFAULT 0,
+    cache_write_tokens    INTEGER NOT NULL DEFAULT 0,
+    created_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+-- Price table used to turn token usage into cost. A model is priced by the
+-- longest pattern it starts with; prices are USD per million tokens.
+CREATE TABLE model_prices (
+    model_pattern        TEXT PRIMARY KEY,
+    input_price          REAL NOT NULL,
+    output_price         REAL NOT NULL,
+    cache_read_price     REAL NOT NULL,
+    cache_write_price    REAL NOT NULL,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+INSERT INTO model_prices (model_pattern, input_price, output_price, cache_read_price, cache_write_price) VALUES
+    ('claude-opus-4',     15.0, 75.0, 1.5,   18.75),
+    ('claude-opus-4-5',    5.0, 25.0, 0.5,    6.25),
+    ('claude-sonnet-4',    3.0, 15.0, 0.3,    3.75),
+    ('claude-haiku-4',     1.0,  5.0, 0.1,    1.25),
+    ('gpt-5',             1.25, 10.0, 0.125,  0.0),
+    ('gpt-5-mini',        0.25,  2.0, 0.025,  0.0);
@@ -0,0 +1,11 @@
+-- Projects whose workspaces run their setup scripts, agents and dev servers in
+-- Docker. Projects without a row run them on the host.
+CREATE TABLE project_docker_settings (
+    project_id  BLOB PRIMARY KEY,
+    -- Image every process of a workspace runs in; it must provide the
+    -- executors and tools the project needs
+    image       TEXT NOT NULL,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
@@ -0,0 +1,39 @@
+-- Searchable text of the normalized log entries of execution processes:
+-- messages, tool calls (commands, file paths, queries) and errors. Entries
+-- are rewritten while they stream, keyed by their index in the conversation.
+CREATE TABLE execution_log_entries (
+    id            INTEGER PRIMARY KEY,
+    execution_id  BLOB NOT NULL,
+    entry_index   INTEGER NOT NULL,
+    kind          TEXT NOT NULL
+                     CHECK (kind IN ('user_message','assistant_message','tool_use','error_message')),
+    content       TEXT NOT NULL,
+    created_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_id) REFERENCES execution_processes(id) ON DELETE CASCADE,
+    UNIQUE (execution_id, entry_index)
+);
+
+-- Trigram tokens match any substring of three or more characters, which
+-- suits file paths and Korean text without word stemming
+CREATE VIRTUAL TABLE execution_log_entries_fts USING fts5(
+    content,
+    content = 'execution_log_entries',
+    content_rowid = 'id',
+    tokenize = 'trigram'
+);
+
+CREATE TRIGGER execution_log_entries_ai AFTER INSERT ON execution_log_entries BEGIN
+    INSERT INTO execution_log_entries_fts (rowid, content) VALUES (new.id, new.content);
+END;
+
+CREATE TRIGGER execution_log_entries_ad AFTER DELETE ON execution_log_entries BEGIN
+    INSERT INTO execution_log_entries_fts (execution_log_entries_fts, rowid, content)
+    VALUES ('delete', old.id, old.content);
+END;
+
+CREATE TRIGGER execution_log_entries_au AFTER UPDATE OF content ON execution_log_entries BEGIN
+    INSERT INTO execution_log_entries_fts (execution_log_entries_fts, rowid, content)
+    VALUES ('delete', old.id, old.content);
+    INSERT INTO execution_log_entries_fts (rowid, content) VALUES (new.id, new.content);
+END;
@@ -0,0 +1,15 @@
+-- Logs of finished execution processes, moved out of execution_process_logs
+-- into zstd-compressed JSONL chunks. 'raw' chunks hold the lines as they were
+-- logged; the 'conversation' chunk holds the final normalized entries of a
+-- coding agent run, which stay readable after the raw output is pruned.
+CREATE TABLE execution_process_log_chunks (
+    execution_id     BLOB NOT NULL,
+    kind             TEXT NOT NULL CHECK (kind IN ('raw','conversation')),
+    chunk_index      INTEGER NOT NULL,
+    data             BLOB NOT NULL,
+    byte_size        INTEGER NOT NULL,   -- uncompressed
+    compressed_size  INTEGER NOT NULL,
+    created_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (execution_id, kind, chunk_index),
+    FOREIGN KEY (execution_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
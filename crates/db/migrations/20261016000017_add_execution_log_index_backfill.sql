@@ -0,0 +1,15 @@
+-- Coding agent runs logged before the log search index existed. The server
+-- replays their stored logs into execution_log_entries once and removes them
+-- from here as it goes.
+CREATE TABLE execution_log_index_backfill (
+    execution_id  BLOB PRIMARY KEY,
+    FOREIGN KEY (execution_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+INSERT INTO execution_log_index_backfill (execution_id)
+SELECT ep.id
+FROM execution_processes ep
+WHERE ep.run_reason = 'codingagent'
+  AND NOT EXISTS (SELECT 1 FROM execution_log_entries e WHERE e.execution_id = ep.id)
+  AND (EXISTS (SELECT 1 FROM execution_process_logs l WHERE l.execution_id = ep.id)
+       OR EXISTS (SELECT 1 FROM execution_process_log_chunks c WHERE c.execution_id = ep.id));
@@ -0,0 +1,236 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{SqlitePool, Type, types::Json};
+use ts_rs::TS;
+use utils::approvals::{ApprovalRequest, ApprovalStatus};
+use uuid::Uuid;
+
+/// Who resolved an approval request
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "text", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalResponder {
+    /// Answered in the UI
+    User,
+    /// Not answered before the request's deadline
+    Timeout,
+    /// The agent stopped waiting, e.g. because its execution was stopped
+    Cancellation,
+    /// The server restarted while the agent was waiting
+    Restart,
+    /// Answered by an auto-approval rule of the project
+    Policy,
+}
+
+/// Tool approval request of an execution process and its answer
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ApprovalRecord {
+    pub id: String,
+    pub execution_process_id: Uuid,
+    pub tool_name: String,
+    pub tool_input: Value,
+    pub tool_call_id: String,
+    pub status: ApprovalStatus,
+    pub responded_by: Option<ApprovalResponder>,
+    /// Rule that answered the request, if it was answered by policy
+    pub matched_rule: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub timeout_at: DateTime<Utc>,
+    pub responded_at: Option<DateTime<Utc>>,
+}
+
+struct ApprovalRow {
+    id: String,
+    execution_process_id: Uuid,
+    tool_name: String,
+    tool_input: Json<Value>,
+    tool_call_id: String,
+    status: String,
+    denial_reason: Option<String>,
+    responded_by: Option<ApprovalResponder>,
+    matched_rule: Option<String>,
+    created_at: DateTime<Utc>,
+    timeout_at: DateTime<Utc>,
+    responded_at: Option<DateTime<Utc>>,
+}
+
+impl From<ApprovalRow> for ApprovalRecord {
+    fn from(row: ApprovalRow) -> Self {
+        let status = match row.status.as_str() {
+            "approved" => ApprovalStatus::Approved,
+            "denied" => ApprovalStatus::Denied {
+                reason: row.denial_reason,
+            },
+            "timed_out" => ApprovalStatus::TimedOut,
+            _ => ApprovalStatus::Pending,
+        };
+        Self {
+            id: row.id,
+            execution_process_id: row.execution_process_id,
+            tool_name: row.tool_name,
+            tool_input: row.tool_input.0,
+            tool_call_id: row.tool_call_id,
+            status,
+            responded_by: row.responded_by,
+            matched_rule: row.matched_rule,
+            created_at: row.created_at,
+            timeout_at: row.timeout_at,
+            responded_at: row.responded_at,
+        }
+    }
+}
+
+impl ApprovalRecord {
+    /// Request that was not answered yet
+    pub fn to_request(&self) -> ApprovalRequest {
+        ApprovalRequest {
+            id: self.id.clone(),
+            tool_name: self.tool_name.clone(),
+            tool_input: self.tool_input.clone(),
+            tool_call_id: self.tool_call_id.clone(),
+            execution_process_id: self.execution_process_id,
+            created_at: self.created_at,
+            timeout_at: self.timeout_at,
+        }
+    }
+
+    pub async fn create(pool: &SqlitePool, request: &ApprovalRequest) -> Result<(), sqlx::Error> {
+        let tool_input = Json(&request.tool_input);
+        sqlx::query!(
+            r#"INSERT INTO approvals (id, execution_process_id, tool_name, tool_input, tool_call_id, created_at, timeout_at)
+               VALUES ($1, $2, $3, $4, $5, $6, $7)"#,
+            request.id,
+            request.execution_process_id,
+            request.tool_name,
+            tool_input,
+            request.tool_call_id,
+            request.created_at,
+            request.timeout_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: &str) -> Result<Option<Self>, sqlx::Error> {
+        let row = sqlx::query_as!(
+            ApprovalRow,
+            r#"SELECT id as "id!", execution_process_id as "execution_process_id!: Uuid", tool_name, tool_input as "tool_input!: Json<Value>", tool_call_id, status, denial_reason, responded_by as "responded_by: ApprovalResponder", matched_rule, created_at as "created_at!: DateTime<Utc>", timeout_at as "timeout_at!: DateTime<Utc>", responded_at as "responded_at: DateTime<Utc>"
+               FROM approvals
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(row.map(Into::into))
+    }
+
+    /// Approval requests of an execution process, oldest first
+    pub async fn find_by_execution_process_id(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let rows = sqlx::query_as!(
+            ApprovalRow,
+            r#"SELECT id as "id!", execution_process_id as "execution_process_id!: Uuid", tool_name, tool_input as "tool_input!: Json<Value>", tool_call_id, status, denial_reason, responded_by as "responded_by: ApprovalResponder", matched_rule, created_at as "created_at!: DateTime<Utc>", timeout_at as "timeout_at!: DateTime<Utc>", responded_at as "responded_at: DateTime<Utc>"
+               FROM approvals
+               WHERE execution_process_id = $1
+               ORDER BY created_at ASC"#,
+            execution_process_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    /// Requests still waiting for an answer
+    pub async fn find_pending(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        let rows = sqlx::query_as!(
+            ApprovalRow,
+            r#"SELECT id as "id!", execution_process_id as "execution_process_id!: Uuid", tool_name, tool_input as "tool_input!: Json<Value>", tool_call_id, status, denial_reason, responded_by as "responded_by: ApprovalResponder", matched_rule, created_at as "created_at!: DateTime<Utc>", timeout_at as "timeout_at!: DateTime<Utc>", responded_at as "responded_at: DateTime<Utc>"
+               FROM approvals
+               WHERE status = 'pending'
+               ORDER BY created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    /// Pending request of an execution process for a tool call, if the agent
+    /// asked about it before
+    pub async fn find_pending_for_tool_call(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        tool_call_id: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let row = sqlx::query_as!(
+            ApprovalRow,
+            r#"SELECT id as "id!", execution_process_id as "execution_process_id!: Uuid", tool_name, tool_input as "tool_input!: Json<Value>", tool_call_id, status, denial_reason, responded_by as "responded_by: ApprovalResponder", matched_rule, created_at as "created_at!: DateTime<Utc>", timeout_at as "timeout_at!: DateTime<Utc>", responded_at as "responded_at: DateTime<Utc>"
+               FROM approvals
+               WHERE execution_process_id = $1 AND tool_call_id = $2 AND status = 'pending'"#,
+            execution_process_id,
+            tool_call_id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(row.map(Into::into))
+    }
+
+    /// Record the answer to a pending request. Returns `false` if the request
+    /// was already answered.
+    pub async fn resolve(
+        pool: &SqlitePool,
+        id: &str,
+        status: &ApprovalStatus,
+        responded_by: ApprovalResponder,
+    ) -> Result<bool, sqlx::Error> {
+        let (status, denial_reason) = match status {
+            ApprovalStatus::Pending => return Ok(false),
+            ApprovalStatus::Approved => ("approved", None),
+            ApprovalStatus::Denied { reason } => ("denied", reason.as_deref()),
+            ApprovalStatus::TimedOut => ("timed_out", None),
+        };
+        let result = sqlx::query!(
+            r#"UPDATE approvals
+               SET status = $2, denial_reason = $3, responded_by = $4, responded_at = datetime('now', 'subsec')
+               WHERE id = $1 AND status = 'pending'"#,
+            id,
+            status,
+            denial_reason,
+            responded_by
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Record the answer of an auto-approval rule to a pending request
+    pub async fn resolve_by_rule(
+        pool: &SqlitePool,
+        id: &str,
+        status: &ApprovalStatus,
+        rule: &str,
+    ) -> Result<bool, sqlx::Error> {
+        let (status, denial_reason) = match status {
+            ApprovalStatus::Approved => ("approved", None),
+            ApprovalStatus::Denied { reason } => ("denied", reason.as_deref()),
+            ApprovalStatus::Pending | ApprovalStatus::TimedOut => return Ok(false),
+        };
+        let responded_by = ApprovalResponder::Policy;
+        let result = sqlx::query!(
+            r#"UPDATE approvals
+               SET status = $2, denial_reason = $3, responded_by = $4, matched_rule = $5, responded_at = datetime('now', 'subsec')
+               WHERE id = $1 AND status = 'pending'"#,
+            id,
+            status,
+            denial_reason,
+            responded_by,
+            rule
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,129 @@
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// What happens to a tool approval request matched by a rule
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "text", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalRuleAction {
+    /// Approve without asking
+    Allow,
+    /// Deny without asking
+    Deny,
+    /// Ask the user, even if a later rule would decide
+    Ask,
+}
+
+/// Auto-approval rule of a project. Every set matcher must match a request;
+/// a rule without matchers matches all of them.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ApprovalRule {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub position: i64,
+    pub action: ApprovalRuleAction,
+    /// Glob on the tool name, case-insensitive
+    pub tool_name: Option<String>,
+    /// Glob on the command of command runs
+    pub command: Option<String>,
+    /// Glob on the path of file edits, relative to the workspace
+    pub path: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpsertApprovalRule {
+    pub action: ApprovalRuleAction,
+    pub tool_name: Option<String>,
+    pub command: Option<String>,
+    pub path: Option<String>,
+}
+
+impl fmt::Display for ApprovalRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let action = match self.action {
+            ApprovalRuleAction::Allow => "allow",
+            ApprovalRuleAction::Deny => "deny",
+            ApprovalRuleAction::Ask => "ask",
+        };
+        write!(f, "#{} {}", self.position + 1, action)?;
+        let matchers = [
+            ("tool", &self.tool_name),
+            ("command", &self.command),
+            ("path", &self.path),
+        ];
+        let mut any = false;
+        for (name, glob) in matchers {
+            if let Some(glob) = glob {
+                write!(f, " {name} \"{glob}\"")?;
+                any = true;
+            }
+        }
+        if !any {
+            write!(f, " all")?;
+        }
+        Ok(())
+    }
+}
+
+impl ApprovalRule {
+    /// Rules of a project in evaluation order
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ApprovalRule,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", position, action as "action!: ApprovalRuleAction", tool_name, command, path, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM approval_rules
+               WHERE project_id = $1
+               ORDER BY position ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Replace every rule of a project with `rules`, in their order
+    pub async fn replace_for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        rules: &[UpsertApprovalRule],
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query!(
+            "DELETE FROM approval_rules WHERE project_id = $1",
+            project_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        for (position, rule) in rules.iter().enumerate() {
+            let id = Uuid::new_v4();
+            let position = position as i64;
+            sqlx::query!(
+                r#"INSERT INTO approval_rules (id, project_id, position, action, tool_name, command, path)
+                   VALUES ($1, $2, $3, $4, $5, $6, $7)"#,
+                id,
+                project_id,
+                position,
+                rule.action,
+                rule.tool_name,
+                rule.command,
+                rule.path
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Self::find_by_project_id(pool, project_id).await
+    }
+}
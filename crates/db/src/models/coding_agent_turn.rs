@@ -56,6 +56,28 @@ impl CodingAgentTurn {
         .await
     }
 
+    /// Summary of the latest coding agent turn across the sessions of a workspace
+    pub async fn find_latest_summary_for_workspace(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Option<String>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT cat.summary as "summary!"
+               FROM execution_processes ep
+               JOIN sessions s ON ep.session_id = s.id
+               JOIN coding_agent_turns cat ON ep.id = cat.execution_process_id
+               WHERE s.workspace_id = $1
+                 AND ep.run_reason = 'codingagent'
+                 AND ep.dropped = FALSE
+                 AND cat.summary IS NOT NULL
+               ORDER BY ep.created_at DESC
+               LIMIT 1"#,
+            workspace_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
     /// Find coding agent turn by execution process ID
     pub async fn find_by_execution_process_id(
         pool: &SqlitePool,
@@ -81,6 +103,34 @@ impl CodingAgentTurn {
         .await
     }
 
+    /// Coding agent turns of a session, oldest first
+    pub async fn find_by_session_id(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            CodingAgentTurn,
+            r#"SELECT
+                cat.id as "id!: Uuid",
+                cat.execution_process_id as "execution_process_id!: Uuid",
+                cat.agent_session_id,
+                cat.agent_message_id,
+                cat.prompt,
+                cat.summary,
+                cat.seen as "seen!: bool",
+                cat.created_at as "created_at!: DateTime<Utc>",
+                cat.updated_at as "updated_at!: DateTime<Utc>"
+               FROM coding_agent_turns cat
+               JOIN execution_processes ep ON ep.id = cat.execution_process_id
+               WHERE ep.session_id = $1
+                 AND ep.dropped = FALSE
+               ORDER BY ep.created_at ASC"#,
+            session_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
     pub async fn find_by_agent_session_id(
         pool: &SqlitePool,
         agent_session_id: &str,
@@ -0,0 +1,125 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A Dooray API token in addition to the default one in `dooray_settings`,
+/// e.g. for a second tenant
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct DoorayConnection {
+    pub id: Uuid,
+    pub name: String,
+    pub dooray_token: String,
+    /// Dooray domain (e.g., "nhnent.dooray.com")
+    pub dooray_domain: Option<String>,
+    /// Token owner's Dooray organizationMemberId
+    pub member_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateDoorayConnection {
+    pub name: String,
+    pub dooray_token: String,
+    pub dooray_domain: Option<String>,
+    pub member_id: Option<String>,
+}
+
+impl DoorayConnection {
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DoorayConnection,
+            r#"SELECT id as "id!: Uuid", name, dooray_token, dooray_domain, member_id, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM dooray_connections
+               ORDER BY created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DoorayConnection,
+            r#"SELECT id as "id!: Uuid", name, dooray_token, dooray_domain, member_id, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM dooray_connections
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateDoorayConnection,
+        id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            DoorayConnection,
+            r#"INSERT INTO dooray_connections (id, name, dooray_token, dooray_domain, member_id)
+               VALUES ($1, $2, $3, $4, $5)
+               RETURNING id as "id!: Uuid", name, dooray_token, dooray_domain, member_id, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.name,
+            data.dooray_token,
+            data.dooray_domain,
+            data.member_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &CreateDoorayConnection,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DoorayConnection,
+            r#"UPDATE dooray_connections
+               SET name = $2,
+                   dooray_token = $3,
+                   dooray_domain = $4,
+                   member_id = $5,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid", name, dooray_token, dooray_domain, member_id, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.name,
+            data.dooray_token,
+            data.dooray_domain,
+            data.member_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Replace the stored token only (e.g. when re-encrypting it)
+    pub async fn update_token(
+        pool: &SqlitePool,
+        id: Uuid,
+        dooray_token: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE dooray_connections
+               SET dooray_token = $2,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            dooray_token
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Delete a connection together with its project bindings
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM dooray_connections WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,51 @@
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use super::image::Image;
+
+/// Dooray post file that was downloaded into the local image store
+pub struct DoorayFileImage;
+
+impl DoorayFileImage {
+    /// Image a Dooray file was stored as, if it was downloaded before.
+    pub async fn find_image(
+        pool: &SqlitePool,
+        dooray_file_id: &str,
+    ) -> Result<Option<Image>, sqlx::Error> {
+        sqlx::query_as!(
+            Image,
+            r#"SELECT i.id as "id!: Uuid",
+                      i.file_path as "file_path!",
+                      i.original_name as "original_name!",
+                      i.mime_type,
+                      i.size_bytes as "size_bytes!",
+                      i.hash as "hash!",
+                      i.created_at as "created_at!: DateTime<Utc>",
+                      i.updated_at as "updated_at!: DateTime<Utc>"
+               FROM images i
+               JOIN dooray_file_images dfi ON i.id = dfi.image_id
+               WHERE dfi.dooray_file_id = $1"#,
+            dooray_file_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn record(
+        pool: &SqlitePool,
+        dooray_file_id: &str,
+        image_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO dooray_file_images (dooray_file_id, image_id)
+               VALUES ($1, $2)
+               ON CONFLICT(dooray_file_id) DO UPDATE SET image_id = excluded.image_id"#,
+            dooray_file_id,
+            image_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
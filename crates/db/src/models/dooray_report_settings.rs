@@ -0,0 +1,75 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Which progress of a kanban project's tasks is reported as a comment on the
+/// linked Dooray post
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct DoorayReportSettings {
+    pub project_id: Uuid,
+    /// Comment when a coding agent run finishes (successfully or not)
+    pub on_execution_finished: bool,
+    pub on_pr_created: bool,
+    pub on_pr_merged: bool,
+    /// Comment template with `{placeholders}`; `None` uses the built-in template
+    pub comment_template: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpsertDoorayReportSettings {
+    pub on_execution_finished: bool,
+    pub on_pr_created: bool,
+    pub on_pr_merged: bool,
+    pub comment_template: Option<String>,
+}
+
+impl DoorayReportSettings {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DoorayReportSettings,
+            r#"SELECT project_id as "project_id!: Uuid", on_execution_finished as "on_execution_finished!: bool", on_pr_created as "on_pr_created!: bool", on_pr_merged as "on_pr_merged!: bool", comment_template, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM dooray_report_settings
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &UpsertDoorayReportSettings,
+    ) -> Result<Self, sqlx::Error> {
+        let comment_template = data
+            .comment_template
+            .as_deref()
+            .filter(|t| !t.trim().is_empty());
+        sqlx::query_as!(
+            DoorayReportSettings,
+            r#"INSERT INTO dooray_report_settings (project_id, on_execution_finished, on_pr_created, on_pr_merged, comment_template)
+               VALUES ($1, $2, $3, $4, $5)
+               ON CONFLICT(project_id) DO UPDATE SET
+                   on_execution_finished = excluded.on_execution_finished,
+                   on_pr_created = excluded.on_pr_created,
+                   on_pr_merged = excluded.on_pr_merged,
+                   comment_template = excluded.comment_template,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING project_id as "project_id!: Uuid", on_execution_finished as "on_execution_finished!: bool", on_pr_created as "on_pr_created!: bool", on_pr_merged as "on_pr_merged!: bool", comment_template, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            data.on_execution_finished,
+            data.on_pr_created,
+            data.on_pr_merged,
+            comment_template
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
@@ -42,6 +42,14 @@ pub struct UpdateDooraySettings {
 }
 
 impl DooraySettings {
+    /// Parsed `selected_tag_ids`
+    pub fn tag_ids(&self) -> Vec<String> {
+        self.selected_tag_ids
+            .as_deref()
+            .and_then(|s| serde_json::from_str(s).ok())
+            .unwrap_or_default()
+    }
+
     /// Get the single dooray settings record (there should only be one)
     pub async fn get(pool: &SqlitePool) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
@@ -91,6 +99,28 @@ impl DooraySettings {
         .await
     }
 
+    /// Replace the token (and its owner) while keeping the project and tag selection
+    pub async fn update_token(
+        pool: &SqlitePool,
+        dooray_token: &str,
+        member_id: Option<&str>,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DooraySettings,
+            r#"UPDATE dooray_settings
+               SET dooray_token = $1,
+                   member_id = $2,
+                   updated_at = CURRENT_TIMESTAMP
+               RETURNING id, dooray_token, selected_project_id, selected_project_name, selected_tag_ids, dooray_domain, member_id,
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            dooray_token,
+            member_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
     /// Update selected project only
     pub async fn update_selected_project(
         pool: &SqlitePool,
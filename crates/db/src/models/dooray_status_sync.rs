@@ -0,0 +1,190 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::task::TaskStatus;
+
+/// Maps one Dooray workflow of a project to a kanban task status
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct DoorayWorkflowMapping {
+    pub id: Uuid,
+    pub dooray_project_id: String,
+    pub workflow_id: String,
+    pub workflow_name: Option<String>,
+    pub task_status: TaskStatus,
+    /// Workflow to set on Dooray when a task moves into `task_status` locally
+    pub is_push_target: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpsertDoorayWorkflowMapping {
+    pub workflow_id: String,
+    pub workflow_name: Option<String>,
+    pub task_status: TaskStatus,
+    #[serde(default)]
+    pub is_push_target: bool,
+}
+
+impl DoorayWorkflowMapping {
+    pub async fn find_by_dooray_project_id(
+        pool: &SqlitePool,
+        dooray_project_id: &str,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DoorayWorkflowMapping,
+            r#"SELECT id as "id!: Uuid", dooray_project_id, workflow_id, workflow_name, task_status as "task_status!: TaskStatus", is_push_target as "is_push_target!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM dooray_workflow_mappings
+               WHERE dooray_project_id = $1
+               ORDER BY created_at ASC"#,
+            dooray_project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Replace every mapping of a Dooray project with `mappings`
+    pub async fn replace_for_project(
+        pool: &SqlitePool,
+        dooray_project_id: &str,
+        mappings: &[UpsertDoorayWorkflowMapping],
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query!(
+            "DELETE FROM dooray_workflow_mappings WHERE dooray_project_id = $1",
+            dooray_project_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        for mapping in mappings {
+            let id = Uuid::new_v4();
+            sqlx::query!(
+                r#"INSERT INTO dooray_workflow_mappings (id, dooray_project_id, workflow_id, workflow_name, task_status, is_push_target)
+                   VALUES ($1, $2, $3, $4, $5, $6)"#,
+                id,
+                dooray_project_id,
+                mapping.workflow_id,
+                mapping.workflow_name,
+                mapping.task_status,
+                mapping.is_push_target
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Self::find_by_dooray_project_id(pool, dooray_project_id).await
+    }
+}
+
+/// Last status both sides agreed on for a task linked to a Dooray post
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct DoorayTaskSyncState {
+    pub task_id: Uuid,
+    pub task_status: TaskStatus,
+    pub workflow_id: Option<String>,
+    /// Remote workflow that conflicted with a local change, until resolved
+    pub conflict_workflow_id: Option<String>,
+    pub synced_at: DateTime<Utc>,
+}
+
+impl DoorayTaskSyncState {
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DoorayTaskSyncState,
+            r#"SELECT task_id as "task_id!: Uuid", task_status as "task_status!: TaskStatus", workflow_id, conflict_workflow_id, synced_at as "synced_at!: DateTime<Utc>"
+               FROM dooray_task_sync_states
+               WHERE task_id = $1"#,
+            task_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Tasks whose unresolved status conflict needs user attention
+    pub async fn find_conflicts(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DoorayTaskSyncState,
+            r#"SELECT task_id as "task_id!: Uuid", task_status as "task_status!: TaskStatus", workflow_id, conflict_workflow_id, synced_at as "synced_at!: DateTime<Utc>"
+               FROM dooray_task_sync_states
+               WHERE conflict_workflow_id IS NOT NULL
+               ORDER BY synced_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// IDs of linked tasks whose local status changed since the last sync and
+    /// has not been pushed yet
+    pub async fn find_locally_changed_task_ids(
+        pool: &SqlitePool,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        let records = sqlx::query!(
+            r#"SELECT t.id as "id!: Uuid"
+               FROM tasks t
+               JOIN dooray_task_sync_states s ON s.task_id = t.id
+               WHERE t.dooray_task_id IS NOT NULL
+                 AND t.status != s.task_status
+                 AND s.conflict_workflow_id IS NULL"#
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(records.into_iter().map(|r| r.id).collect())
+    }
+
+    /// Record that the task status and the Dooray workflow are in agreement,
+    /// clearing any pending conflict.
+    pub async fn mark_synced(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        task_status: &TaskStatus,
+        workflow_id: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO dooray_task_sync_states (task_id, task_status, workflow_id, conflict_workflow_id, synced_at)
+               VALUES ($1, $2, $3, NULL, datetime('now', 'subsec'))
+               ON CONFLICT(task_id) DO UPDATE SET
+                   task_status = excluded.task_status,
+                   workflow_id = excluded.workflow_id,
+                   conflict_workflow_id = NULL,
+                   synced_at = excluded.synced_at"#,
+            task_id,
+            task_status,
+            workflow_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Record a conflict with the given remote workflow, keeping the last agreed
+    /// state so the user can pick a side later. Tasks without a previous sync use
+    /// `task_status` as their baseline.
+    pub async fn mark_conflict(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        task_status: &TaskStatus,
+        conflict_workflow_id: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO dooray_task_sync_states (task_id, task_status, workflow_id, conflict_workflow_id)
+               VALUES ($1, $2, NULL, $3)
+               ON CONFLICT(task_id) DO UPDATE SET
+                   conflict_workflow_id = excluded.conflict_workflow_id"#,
+            task_id,
+            task_status,
+            conflict_workflow_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
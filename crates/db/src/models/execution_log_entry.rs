@@ -0,0 +1,150 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::execution_process::ExecutionProcessRunReason;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "log_entry_kind", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum LogEntryKind {
+    UserMessage,
+    AssistantMessage,
+    ToolUse,
+    ErrorMessage,
+}
+
+/// Log entry matching a search, with the attempt it was logged in
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct LogSearchHit {
+    pub execution_id: Uuid,
+    /// Index of the entry in the normalized conversation of the process
+    pub entry_index: i64,
+    pub kind: LogEntryKind,
+    /// Text around the matches, which are wrapped in `<mark>` tags; the rest
+    /// is not escaped
+    pub snippet: String,
+    pub run_reason: ExecutionProcessRunReason,
+    pub session_id: Uuid,
+    pub executor: Option<String>,
+    pub workspace_id: Uuid,
+    pub workspace_branch: String,
+    pub task_id: Uuid,
+    pub task_title: String,
+    pub project_id: Uuid,
+    pub logged_at: DateTime<Utc>,
+}
+
+/// Searchable text of a normalized log entry
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct ExecutionLogEntry {
+    pub execution_id: Uuid,
+    pub entry_index: i64,
+    pub kind: LogEntryKind,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ExecutionLogEntry {
+    /// Index the text of a normalized entry, replacing what was indexed for
+    /// it before
+    pub async fn upsert(
+        pool: &SqlitePool,
+        execution_id: Uuid,
+        entry_index: i64,
+        kind: LogEntryKind,
+        content: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO execution_log_entries (execution_id, entry_index, kind, content)
+               VALUES ($1, $2, $3, $4)
+               ON CONFLICT(execution_id, entry_index) DO UPDATE SET
+                   kind = excluded.kind,
+                   content = excluded.content,
+                   updated_at = datetime('now', 'subsec')
+               WHERE content != excluded.content OR kind != excluded.kind"#,
+            execution_id,
+            entry_index,
+            kind,
+            content
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Processes logged before the index existed that are still to be
+    /// indexed, oldest first
+    pub async fn find_backfill_pending(pool: &SqlitePool) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT b.execution_id as "execution_id!: Uuid"
+               FROM execution_log_index_backfill b
+               JOIN execution_processes ep ON ep.id = b.execution_id
+               ORDER BY ep.created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Mark a process from before the index as indexed
+    pub async fn complete_backfill(
+        pool: &SqlitePool,
+        execution_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "DELETE FROM execution_log_index_backfill WHERE execution_id = $1",
+            execution_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Best matches of an FTS5 query, optionally within one project or kind
+    /// of entry
+    pub async fn search(
+        pool: &SqlitePool,
+        fts_query: &str,
+        project_id: Option<Uuid>,
+        kind: Option<LogEntryKind>,
+        limit: i64,
+    ) -> Result<Vec<LogSearchHit>, sqlx::Error> {
+        sqlx::query_as!(
+            LogSearchHit,
+            r#"SELECT
+                e.execution_id as "execution_id!: Uuid",
+                e.entry_index,
+                e.kind as "kind!: LogEntryKind",
+                snippet(execution_log_entries_fts, 0, '<mark>', '</mark>', '…', 24) as "snippet!: String",
+                ep.run_reason as "run_reason!: ExecutionProcessRunReason",
+                s.id as "session_id!: Uuid",
+                s.executor,
+                w.id as "workspace_id!: Uuid",
+                w.branch as workspace_branch,
+                t.id as "task_id!: Uuid",
+                t.title as task_title,
+                t.project_id as "project_id!: Uuid",
+                e.updated_at as "logged_at!: DateTime<Utc>"
+               FROM execution_log_entries_fts f
+               JOIN execution_log_entries e ON e.id = f.rowid
+               JOIN execution_processes ep ON ep.id = e.execution_id
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               JOIN tasks t ON t.id = w.task_id
+               WHERE execution_log_entries_fts MATCH $1
+                 AND ($2 IS NULL OR t.project_id = $2)
+                 AND ($3 IS NULL OR e.kind = $3)
+               ORDER BY f.rank
+               LIMIT $4"#,
+            fts_query,
+            project_id,
+            kind,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
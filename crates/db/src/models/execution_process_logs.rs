@@ -1,10 +1,42 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool};
+use sqlx::{FromRow, SqliteConnection, SqlitePool, Type};
 use ts_rs::TS;
 use utils::log_msg::LogMsg;
 use uuid::Uuid;
 
+/// Uncompressed size that compacted logs are split at
+const LOG_CHUNK_BYTES: usize = 4 * 1024 * 1024;
+const ZSTD_LEVEL: i32 = 3;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq)]
+#[sqlx(type_name = "log_chunk_kind", rename_all = "snake_case")]
+pub enum LogChunkKind {
+    Raw,
+    Conversation,
+}
+
+struct LogChunk {
+    kind: LogChunkKind,
+    data: Vec<u8>,
+    byte_size: i64,
+    created_at: DateTime<Utc>,
+}
+
+/// Log storage of the execution processes of a project
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct ProjectLogUsage {
+    pub project_id: Uuid,
+    pub project_name: String,
+    pub execution_count: i64,
+    /// Uncompressed size of the logs
+    pub log_bytes: i64,
+    /// Size on disk of the logs
+    pub stored_bytes: i64,
+    /// Size on disk of raw output, the part that retention prunes
+    pub raw_stored_bytes: i64,
+}
+
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
 pub struct ExecutionProcessLogs {
     pub execution_id: Uuid,
@@ -14,12 +46,14 @@ pub struct ExecutionProcessLogs {
 }
 
 impl ExecutionProcessLogs {
-    /// Find logs by execution process ID
+    /// Find logs by execution process ID. Compacted logs come first, one
+    /// decompressed record per chunk: the raw output, or the normalized
+    /// conversation once the raw output was pruned.
     pub async fn find_by_execution_id(
         pool: &SqlitePool,
         execution_id: Uuid,
     ) -> Result<Vec<Self>, sqlx::Error> {
-        sqlx::query_as!(
+        let rows = sqlx::query_as!(
             ExecutionProcessLogs,
             r#"SELECT 
                 execution_id as "execution_id!: Uuid",
@@ -32,7 +66,40 @@ impl ExecutionProcessLogs {
             execution_id
         )
         .fetch_all(pool)
-        .await
+        .await?;
+
+        let chunks = sqlx::query_as!(
+            LogChunk,
+            r#"SELECT
+                kind as "kind!: LogChunkKind",
+                data,
+                byte_size,
+                created_at as "created_at!: DateTime<Utc>"
+               FROM execution_process_log_chunks
+               WHERE execution_id = $1
+               ORDER BY chunk_index ASC"#,
+            execution_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let has_raw = !rows.is_empty() || chunks.iter().any(|c| c.kind == LogChunkKind::Raw);
+        let kind = if has_raw {
+            LogChunkKind::Raw
+        } else {
+            LogChunkKind::Conversation
+        };
+        let mut records = Vec::with_capacity(chunks.len() + rows.len());
+        for chunk in chunks.into_iter().filter(|c| c.kind == kind) {
+            records.push(Self {
+                execution_id,
+                logs: decompress(&chunk.data)?,
+                byte_size: chunk.byte_size,
+                inserted_at: chunk.created_at,
+            });
+        }
+        records.extend(rows);
+        Ok(records)
     }
 
     /// Parse JSONL logs back into Vec<LogMsg>
@@ -66,4 +133,237 @@ impl ExecutionProcessLogs {
 
         Ok(())
     }
+
+    /// Move the logged lines of a finished execution process into compressed
+    /// chunks. `conversation` holds its normalized entries as JSONL lines,
+    /// replacing the stored conversation unless it is empty.
+    pub async fn compact(
+        pool: &SqlitePool,
+        execution_id: Uuid,
+        conversation: &[String],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        let lines = sqlx::query_scalar!(
+            r#"SELECT logs FROM execution_process_logs
+               WHERE execution_id = $1
+               ORDER BY inserted_at ASC"#,
+            execution_id
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        let next_index = sqlx::query_scalar!(
+            r#"SELECT COALESCE(MAX(chunk_index) + 1, 0) as "next_index!: i64"
+               FROM execution_process_log_chunks
+               WHERE execution_id = $1 AND kind = 'raw'"#,
+            execution_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        for (i, chunk) in split_chunks(&lines).iter().enumerate() {
+            insert_chunk(
+                &mut tx,
+                execution_id,
+                LogChunkKind::Raw,
+                next_index + i as i64,
+                chunk,
+            )
+            .await?;
+        }
+
+        if !conversation.is_empty() {
+            sqlx::query!(
+                "DELETE FROM execution_process_log_chunks WHERE execution_id = $1 AND kind = 'conversation'",
+                execution_id
+            )
+            .execute(&mut *tx)
+            .await?;
+            for (i, chunk) in split_chunks(conversation).iter().enumerate() {
+                insert_chunk(
+                    &mut tx,
+                    execution_id,
+                    LogChunkKind::Conversation,
+                    i as i64,
+                    chunk,
+                )
+                .await?;
+            }
+        }
+
+        sqlx::query!(
+            "DELETE FROM execution_process_logs WHERE execution_id = $1",
+            execution_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await
+    }
+
+    /// Finished execution processes that still have uncompacted lines
+    pub async fn find_uncompacted_finished(pool: &SqlitePool) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT DISTINCT l.execution_id as "execution_id!: Uuid"
+               FROM execution_process_logs l
+               JOIN execution_processes ep ON ep.id = l.execution_id
+               WHERE ep.status != 'running'"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Drop the raw output of processes that completed before `cutoff`.
+    /// Coding agent runs keep it unless their conversation is stored.
+    pub async fn prune_raw_before(
+        pool: &SqlitePool,
+        cutoff: DateTime<Utc>,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"DELETE FROM execution_process_log_chunks
+               WHERE kind = 'raw'
+                 AND execution_id IN (
+                     SELECT ep.id FROM execution_processes ep
+                     WHERE datetime(ep.completed_at) < datetime($1)
+                       AND (ep.run_reason != 'codingagent'
+                            OR EXISTS (SELECT 1 FROM execution_process_log_chunks c
+                                       WHERE c.execution_id = ep.id AND c.kind = 'conversation'))
+                 )"#,
+            cutoff
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Drop the raw output of the oldest processes until the compressed raw
+    /// output of all processes fits `max_bytes`, with the same exception for
+    /// coding agent runs as [`Self::prune_raw_before`]
+    pub async fn prune_raw_over(pool: &SqlitePool, max_bytes: i64) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"DELETE FROM execution_process_log_chunks
+               WHERE kind = 'raw'
+                 AND execution_id IN (
+                     SELECT sized.execution_id FROM (
+                         SELECT c.execution_id,
+                                ep.run_reason,
+                                SUM(SUM(c.compressed_size)) OVER (
+                                    ORDER BY ep.completed_at DESC
+                                    ROWS UNBOUNDED PRECEDING
+                                ) AS retained
+                         FROM execution_process_log_chunks c
+                         JOIN execution_processes ep ON ep.id = c.execution_id
+                         WHERE c.kind = 'raw'
+                         GROUP BY c.execution_id, ep.run_reason, ep.completed_at
+                     ) sized
+                     WHERE sized.retained > $1
+                       AND (sized.run_reason != 'codingagent'
+                            OR EXISTS (SELECT 1 FROM execution_process_log_chunks c
+                                       WHERE c.execution_id = sized.execution_id
+                                         AND c.kind = 'conversation'))
+                 )"#,
+            max_bytes
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Log storage per project, largest first
+    pub async fn usage_by_project(pool: &SqlitePool) -> Result<Vec<ProjectLogUsage>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectLogUsage,
+            r#"SELECT
+                p.id as "project_id!: Uuid",
+                p.name as project_name,
+                COUNT(DISTINCT u.execution_id) as "execution_count!: i64",
+                COALESCE(SUM(u.byte_size), 0) as "log_bytes!: i64",
+                COALESCE(SUM(u.stored_size), 0) as "stored_bytes!: i64",
+                COALESCE(SUM(CASE WHEN u.kind = 'raw' THEN u.stored_size ELSE 0 END), 0) as "raw_stored_bytes!: i64"
+               FROM (
+                   SELECT execution_id, kind, byte_size, compressed_size AS stored_size
+                   FROM execution_process_log_chunks
+                   UNION ALL
+                   SELECT execution_id, 'raw', byte_size, byte_size
+                   FROM execution_process_logs
+               ) u
+               JOIN execution_processes ep ON ep.id = u.execution_id
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               JOIN tasks t ON t.id = w.task_id
+               JOIN projects p ON p.id = t.project_id
+               GROUP BY p.id, p.name
+               ORDER BY 5 DESC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
+
+/// Group JSONL lines into chunks of about [`LOG_CHUNK_BYTES`]
+fn split_chunks(lines: &[String]) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    for line in lines {
+        if !current.is_empty() && current.len() + line.len() > LOG_CHUNK_BYTES {
+            chunks.push(std::mem::take(&mut current));
+        }
+        current.push_str(line);
+        if !current.ends_with('\n') {
+            current.push('\n');
+        }
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+async fn insert_chunk(
+    conn: &mut SqliteConnection,
+    execution_id: Uuid,
+    kind: LogChunkKind,
+    chunk_index: i64,
+    content: &str,
+) -> Result<(), sqlx::Error> {
+    let data = zstd::encode_all(content.as_bytes(), ZSTD_LEVEL)
+        .map_err(|e| sqlx::Error::Encode(Box::new(e)))?;
+    let byte_size = content.len() as i64;
+    let compressed_size = data.len() as i64;
+    sqlx::query!(
+        r#"INSERT INTO execution_process_log_chunks
+               (execution_id, kind, chunk_index, data, byte_size, compressed_size)
+           VALUES ($1, $2, $3, $4, $5, $6)"#,
+        execution_id,
+        kind,
+        chunk_index,
+        data,
+        byte_size,
+        compressed_size
+    )
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+fn decompress(data: &[u8]) -> Result<String, sqlx::Error> {
+    let bytes = zstd::decode_all(data).map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+    String::from_utf8(bytes).map_err(|e| sqlx::Error::Decode(Box::new(e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunks_split_between_lines() {
+        let line = "x".repeat(LOG_CHUNK_BYTES / 2 + 1) + "\n";
+        let lines = vec![line.clone(), line.clone(), "{}".to_string()];
+
+        let chunks = split_chunks(&lines);
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0], line);
+        assert_eq!(chunks[1], format!("{line}{{}}\n"));
+
+        let compressed = zstd::encode_all(chunks[1].as_bytes(), ZSTD_LEVEL).unwrap();
+        assert_eq!(decompress(&compressed).unwrap(), chunks[1]);
+    }
 }
@@ -0,0 +1,160 @@
+use chrono::{DateTime, Utc};
+use executors::logs::TokenUsageInfo;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Token usage a coding agent run last reported before it exited
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ExecutionProcessTokenUsage {
+    pub execution_process_id: Uuid,
+    pub total_tokens: i64,
+    pub model_context_window: i64,
+    pub model: Option<String>,
+    /// Billed tokens; zero if the executor doesn't report them
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cache_read_tokens: i64,
+    pub cache_write_tokens: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Billed tokens of one model, summed over the coding agent runs of a task
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskModelTokenUsage {
+    pub task_id: Uuid,
+    pub model: Option<String>,
+    pub runs: i64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cache_read_tokens: i64,
+    pub cache_write_tokens: i64,
+}
+
+impl ExecutionProcessTokenUsage {
+    pub async fn upsert(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        info: &TokenUsageInfo,
+    ) -> Result<(), sqlx::Error> {
+        let total_tokens = i64::from(info.total_tokens);
+        let model_context_window = i64::from(info.model_context_window);
+        let usage = info.usage.clone().unwrap_or_default();
+        let input_tokens = usage.input_tokens as i64;
+        let output_tokens = usage.output_tokens as i64;
+        let cache_read_tokens = usage.cache_read_tokens as i64;
+        let cache_write_tokens = usage.cache_write_tokens as i64;
+        sqlx::query!(
+            r#"INSERT INTO execution_process_token_usage (execution_process_id, total_tokens, model_context_window, model, input_tokens, output_tokens, cache_read_tokens, cache_write_tokens)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+               ON CONFLICT(execution_process_id) DO UPDATE SET
+                   total_tokens = excluded.total_tokens,
+                   model_context_window = excluded.model_context_window,
+                   model = excluded.model,
+                   input_tokens = excluded.input_tokens,
+                   output_tokens = excluded.output_tokens,
+                   cache_read_tokens = excluded.cache_read_tokens,
+                   cache_write_tokens = excluded.cache_write_tokens"#,
+            execution_process_id,
+            total_tokens,
+            model_context_window,
+            usage.model,
+            input_tokens,
+            output_tokens,
+            cache_read_tokens,
+            cache_write_tokens
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Token usage of the non-dropped runs across the sessions of a workspace
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionProcessTokenUsage,
+            r#"SELECT
+                u.execution_process_id as "execution_process_id!: Uuid",
+                u.total_tokens,
+                u.model_context_window,
+                u.model,
+                u.input_tokens,
+                u.output_tokens,
+                u.cache_read_tokens,
+                u.cache_write_tokens,
+                u.created_at as "created_at!: DateTime<Utc>"
+               FROM execution_process_token_usage u
+               JOIN execution_processes ep ON ep.id = u.execution_process_id
+               JOIN sessions s ON s.id = ep.session_id
+               WHERE s.workspace_id = $1
+                 AND ep.dropped = FALSE
+               ORDER BY ep.created_at ASC"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Billed tokens of a task per model. Dropped runs count too, they were
+    /// billed all the same.
+    pub async fn sum_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<TaskModelTokenUsage>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskModelTokenUsage,
+            r#"SELECT
+                w.task_id as "task_id!: Uuid",
+                u.model,
+                COUNT(*) as "runs!: i64",
+                SUM(u.input_tokens) as "input_tokens!: i64",
+                SUM(u.output_tokens) as "output_tokens!: i64",
+                SUM(u.cache_read_tokens) as "cache_read_tokens!: i64",
+                SUM(u.cache_write_tokens) as "cache_write_tokens!: i64"
+               FROM execution_process_token_usage u
+               JOIN execution_processes ep ON ep.id = u.execution_process_id
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               WHERE w.task_id = $1
+               GROUP BY w.task_id, u.model
+               ORDER BY u.model"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Billed tokens of every task of a project per model, dropped runs
+    /// included
+    pub async fn sum_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<TaskModelTokenUsage>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskModelTokenUsage,
+            r#"SELECT
+                w.task_id as "task_id!: Uuid",
+                u.model,
+                COUNT(*) as "runs!: i64",
+                SUM(u.input_tokens) as "input_tokens!: i64",
+                SUM(u.output_tokens) as "output_tokens!: i64",
+                SUM(u.cache_read_tokens) as "cache_read_tokens!: i64",
+                SUM(u.cache_write_tokens) as "cache_write_tokens!: i64"
+               FROM execution_process_token_usage u
+               JOIN execution_processes ep ON ep.id = u.execution_process_id
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               JOIN tasks t ON t.id = w.task_id
+               WHERE t.project_id = $1
+               GROUP BY w.task_id, u.model
+               ORDER BY w.task_id, u.model"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
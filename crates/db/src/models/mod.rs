@@ -1,14 +1,27 @@
+pub mod approval;
+pub mod approval_rule;
 pub mod coding_agent_turn;
 pub mod design_message;
+pub mod dooray_connection;
+pub mod dooray_file_image;
+pub mod dooray_report_settings;
 pub mod dooray_settings;
+pub mod dooray_status_sync;
 pub mod execution_process;
+pub mod execution_log_entry;
 pub mod execution_process_logs;
 pub mod execution_process_repo_state;
+pub mod execution_process_token_usage;
 pub mod image;
 pub mod merge;
 pub mod migration_state;
+pub mod model_price;
 pub mod project;
+pub mod project_docker_settings;
+pub mod project_dooray_binding;
 pub mod project_repo;
+pub mod project_retry_policy;
+pub mod queued_message;
 pub mod repo;
 pub mod scratch;
 pub mod session;
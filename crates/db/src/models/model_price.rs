@@ -0,0 +1,79 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+
+/// Price of the models whose name starts with `model_pattern`, in USD per
+/// million tokens
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ModelPrice {
+    pub model_pattern: String,
+    pub input_price: f64,
+    pub output_price: f64,
+    pub cache_read_price: f64,
+    pub cache_write_price: f64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpsertModelPrice {
+    pub model_pattern: String,
+    pub input_price: f64,
+    pub output_price: f64,
+    pub cache_read_price: f64,
+    pub cache_write_price: f64,
+}
+
+impl ModelPrice {
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ModelPrice,
+            r#"SELECT model_pattern as "model_pattern!", input_price, output_price, cache_read_price, cache_write_price, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM model_prices
+               ORDER BY model_pattern"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn upsert(pool: &SqlitePool, data: &UpsertModelPrice) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            ModelPrice,
+            r#"INSERT INTO model_prices (model_pattern, input_price, output_price, cache_read_price, cache_write_price)
+               VALUES ($1, $2, $3, $4, $5)
+               ON CONFLICT(model_pattern) DO UPDATE SET
+                   input_price = excluded.input_price,
+                   output_price = excluded.output_price,
+                   cache_read_price = excluded.cache_read_price,
+                   cache_write_price = excluded.cache_write_price,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING model_pattern as "model_pattern!", input_price, output_price, cache_read_price, cache_write_price, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            data.model_pattern,
+            data.input_price,
+            data.output_price,
+            data.cache_read_price,
+            data.cache_write_price
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, model_pattern: &str) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM model_prices WHERE model_pattern = $1",
+            model_pattern
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Price of `model`: the entry with the longest pattern it starts with
+    pub fn find_for_model<'a>(prices: &'a [Self], model: &str) -> Option<&'a Self> {
+        prices
+            .iter()
+            .filter(|price| model.starts_with(&price.model_pattern))
+            .max_by_key(|price| price.model_pattern.len())
+    }
+}
@@ -0,0 +1,68 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Docker image the workspaces of a project run their processes in
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectDockerSettings {
+    pub project_id: Uuid,
+    /// Must provide the executors and tools the project needs
+    pub image: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpsertProjectDockerSettings {
+    pub image: String,
+}
+
+impl ProjectDockerSettings {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectDockerSettings,
+            r#"SELECT project_id as "project_id!: Uuid", image, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_docker_settings
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &UpsertProjectDockerSettings,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectDockerSettings,
+            r#"INSERT INTO project_docker_settings (project_id, image)
+               VALUES ($1, $2)
+               ON CONFLICT(project_id) DO UPDATE SET
+                   image = excluded.image,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING project_id as "project_id!: Uuid", image, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            data.image
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Run the project's workspaces on the host again
+    pub async fn delete(pool: &SqlitePool, project_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM project_docker_settings WHERE project_id = $1",
+            project_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,224 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Links a kanban project to a Dooray project it syncs with, imports from and
+/// creates posts in
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectDoorayBinding {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    /// Connection whose token is used; `None` for the default connection in settings
+    pub connection_id: Option<Uuid>,
+    pub dooray_project_id: String,
+    /// Used for task numbers (e.g. `PROJ/123`)
+    pub dooray_project_code: String,
+    /// Tag IDs to filter when syncing
+    #[ts(type = "Array<string>")]
+    pub tag_ids: Json<Vec<String>>,
+    /// Binding used for new Dooray posts created from this kanban project
+    pub is_default: bool,
+    /// Newest post `updatedAt` seen by the last sync
+    pub sync_watermark: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateProjectDoorayBinding {
+    pub project_id: Uuid,
+    pub connection_id: Option<Uuid>,
+    pub dooray_project_id: String,
+    pub dooray_project_code: String,
+    #[serde(default)]
+    pub tag_ids: Vec<String>,
+    #[serde(default)]
+    pub is_default: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdateProjectDoorayBinding {
+    pub tag_ids: Option<Vec<String>>,
+    pub is_default: Option<bool>,
+}
+
+impl ProjectDoorayBinding {
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectDoorayBinding,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", connection_id as "connection_id: Uuid", dooray_project_id, dooray_project_code, tag_ids as "tag_ids!: Json<Vec<String>>", is_default as "is_default!: bool", sync_watermark as "sync_watermark: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_dooray_bindings
+               ORDER BY created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectDoorayBinding,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", connection_id as "connection_id: Uuid", dooray_project_id, dooray_project_code, tag_ids as "tag_ids!: Json<Vec<String>>", is_default as "is_default!: bool", sync_watermark as "sync_watermark: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_dooray_bindings
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Bindings of a kanban project, default binding first
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectDoorayBinding,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", connection_id as "connection_id: Uuid", dooray_project_id, dooray_project_code, tag_ids as "tag_ids!: Json<Vec<String>>", is_default as "is_default!: bool", sync_watermark as "sync_watermark: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_dooray_bindings
+               WHERE project_id = $1
+               ORDER BY is_default DESC, created_at ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Binding to `dooray_project_id`, preferring the one of `project_id` when
+    /// several kanban projects are bound to the same Dooray project
+    pub async fn find_for_dooray_project(
+        pool: &SqlitePool,
+        project_id: Option<Uuid>,
+        dooray_project_id: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectDoorayBinding,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", connection_id as "connection_id: Uuid", dooray_project_id, dooray_project_code, tag_ids as "tag_ids!: Json<Vec<String>>", is_default as "is_default!: bool", sync_watermark as "sync_watermark: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_dooray_bindings
+               WHERE dooray_project_id = $1
+               ORDER BY project_id IS $2 DESC, created_at ASC
+               LIMIT 1"#,
+            dooray_project_id,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Create a binding. The first binding of a kanban project becomes its default.
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateProjectDoorayBinding,
+        id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        let has_default = sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM project_dooray_bindings WHERE project_id = $1 AND is_default) as "exists!: bool""#,
+            data.project_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        let is_default = data.is_default || !has_default;
+        if is_default {
+            sqlx::query!(
+                "UPDATE project_dooray_bindings SET is_default = FALSE WHERE project_id = $1",
+                data.project_id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        let tag_ids = Json(&data.tag_ids);
+        sqlx::query!(
+            r#"INSERT INTO project_dooray_bindings (id, project_id, connection_id, dooray_project_id, dooray_project_code, tag_ids, is_default)
+               VALUES ($1, $2, $3, $4, $5, $6, $7)"#,
+            id,
+            data.project_id,
+            data.connection_id,
+            data.dooray_project_id,
+            data.dooray_project_code,
+            tag_ids,
+            is_default
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Self::find_by_id(pool, id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    /// Update the tag filter and/or default flag. Changing the tag filter clears
+    /// the sync watermark so the next sync walks every post again.
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateProjectDoorayBinding,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let Some(existing) = Self::find_by_id(pool, id).await? else {
+            return Ok(None);
+        };
+        let mut tx = pool.begin().await?;
+
+        if let Some(tag_ids) = &data.tag_ids
+            && *tag_ids != existing.tag_ids.0
+        {
+            let tag_ids = Json(tag_ids);
+            sqlx::query!(
+                r#"UPDATE project_dooray_bindings
+                   SET tag_ids = $2,
+                       sync_watermark = NULL,
+                       updated_at = datetime('now', 'subsec')
+                   WHERE id = $1"#,
+                id,
+                tag_ids
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        if data.is_default == Some(true) {
+            sqlx::query!(
+                r#"UPDATE project_dooray_bindings
+                   SET is_default = (id = $1),
+                       updated_at = datetime('now', 'subsec')
+                   WHERE project_id = $2"#,
+                id,
+                existing.project_id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Self::find_by_id(pool, id).await
+    }
+
+    pub async fn update_sync_watermark(
+        pool: &SqlitePool,
+        id: Uuid,
+        sync_watermark: Option<DateTime<Utc>>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE project_dooray_bindings
+               SET sync_watermark = $2,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            sync_watermark
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM project_dooray_bindings WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,94 @@
+use chrono::{DateTime, Utc};
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use sqlx::{SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// What happens when a coding agent run of a project fails
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ProjectRetryPolicy {
+    pub project_id: Uuid,
+    /// Retries of the same executor on transient failures
+    pub max_retries: i64,
+    /// Delay before the first retry, doubled for every further retry
+    pub backoff_seconds: i64,
+    /// Profile to continue with in a new session once a failure can't be
+    /// retried; no fallback if unset
+    pub fallback_executor_profile_id: Option<ExecutorProfileId>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpsertProjectRetryPolicy {
+    pub max_retries: i64,
+    pub backoff_seconds: i64,
+    pub fallback_executor_profile_id: Option<ExecutorProfileId>,
+}
+
+struct ProjectRetryPolicyRow {
+    project_id: Uuid,
+    max_retries: i64,
+    backoff_seconds: i64,
+    fallback_executor_profile_id: Option<Json<ExecutorProfileId>>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<ProjectRetryPolicyRow> for ProjectRetryPolicy {
+    fn from(row: ProjectRetryPolicyRow) -> Self {
+        Self {
+            project_id: row.project_id,
+            max_retries: row.max_retries,
+            backoff_seconds: row.backoff_seconds,
+            fallback_executor_profile_id: row.fallback_executor_profile_id.map(|p| p.0),
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+impl ProjectRetryPolicy {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let row = sqlx::query_as!(
+            ProjectRetryPolicyRow,
+            r#"SELECT project_id as "project_id!: Uuid", max_retries, backoff_seconds, fallback_executor_profile_id as "fallback_executor_profile_id: Json<ExecutorProfileId>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_retry_policies
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(row.map(Into::into))
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &UpsertProjectRetryPolicy,
+    ) -> Result<Self, sqlx::Error> {
+        let fallback_executor_profile_id = data.fallback_executor_profile_id.as_ref().map(Json);
+        let row = sqlx::query_as!(
+            ProjectRetryPolicyRow,
+            r#"INSERT INTO project_retry_policies (project_id, max_retries, backoff_seconds, fallback_executor_profile_id)
+               VALUES ($1, $2, $3, $4)
+               ON CONFLICT(project_id) DO UPDATE SET
+                   max_retries = excluded.max_retries,
+                   backoff_seconds = excluded.backoff_seconds,
+                   fallback_executor_profile_id = excluded.fallback_executor_profile_id,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING project_id as "project_id!: Uuid", max_retries, backoff_seconds, fallback_executor_profile_id as "fallback_executor_profile_id: Json<ExecutorProfileId>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            data.max_retries,
+            data.backoff_seconds,
+            fallback_executor_profile_id
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(row.into())
+    }
+}
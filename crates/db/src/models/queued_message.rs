@@ -0,0 +1,211 @@
+use chrono::{DateTime, Utc};
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use sqlx::{SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Follow-up message waiting for the running execution of its session to finish
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct QueuedMessage {
+    pub id: Uuid,
+    pub session_id: Uuid,
+    pub position: i64,
+    pub message: String,
+    /// Profile to run the message with; the session's latest profile if unset
+    pub executor_profile_id: Option<ExecutorProfileId>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+struct QueuedMessageRow {
+    id: Uuid,
+    session_id: Uuid,
+    position: i64,
+    message: String,
+    executor_profile_id: Option<Json<ExecutorProfileId>>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<QueuedMessageRow> for QueuedMessage {
+    fn from(row: QueuedMessageRow) -> Self {
+        Self {
+            id: row.id,
+            session_id: row.session_id,
+            position: row.position,
+            message: row.message,
+            executor_profile_id: row.executor_profile_id.map(|profile| profile.0),
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+impl QueuedMessage {
+    /// Queue of a session, next message first
+    pub async fn find_by_session_id(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let rows = sqlx::query_as!(
+            QueuedMessageRow,
+            r#"SELECT id as "id!: Uuid", session_id as "session_id!: Uuid", position, message, executor_profile_id as "executor_profile_id: Json<ExecutorProfileId>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM queued_messages
+               WHERE session_id = $1
+               ORDER BY position ASC"#,
+            session_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    pub async fn exists_for_session(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM queued_messages WHERE session_id = $1) as "exists!: bool""#,
+            session_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Add a message to the end of a session's queue
+    pub async fn create(
+        pool: &SqlitePool,
+        session_id: Uuid,
+        message: &str,
+        executor_profile_id: Option<&ExecutorProfileId>,
+    ) -> Result<(), sqlx::Error> {
+        let id = Uuid::new_v4();
+        let executor_profile_id = executor_profile_id.map(Json);
+        sqlx::query!(
+            r#"INSERT INTO queued_messages (id, session_id, position, message, executor_profile_id)
+               VALUES ($1, $2, (SELECT COALESCE(MAX(position), -1) + 1 FROM queued_messages WHERE session_id = $2), $3, $4)"#,
+            id,
+            session_id,
+            message,
+            executor_profile_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Change the text and profile of a queued message. Returns `false` if it
+    /// is no longer queued.
+    pub async fn update(
+        pool: &SqlitePool,
+        session_id: Uuid,
+        id: Uuid,
+        message: &str,
+        executor_profile_id: Option<&ExecutorProfileId>,
+    ) -> Result<bool, sqlx::Error> {
+        let executor_profile_id = executor_profile_id.map(Json);
+        let result = sqlx::query!(
+            r#"UPDATE queued_messages
+               SET message = $3, executor_profile_id = $4, updated_at = datetime('now', 'subsec')
+               WHERE id = $1 AND session_id = $2"#,
+            id,
+            session_id,
+            message,
+            executor_profile_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Returns `false` if the message is no longer queued
+    pub async fn delete(
+        pool: &SqlitePool,
+        session_id: Uuid,
+        id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM queued_messages WHERE id = $1 AND session_id = $2",
+            id,
+            session_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn delete_by_session_id(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM queued_messages WHERE session_id = $1",
+            session_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Put the messages of a session in the order of `ids`, which must list
+    /// every queued message of the session
+    pub async fn reorder(
+        pool: &SqlitePool,
+        session_id: Uuid,
+        ids: &[Uuid],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        for (position, id) in ids.iter().enumerate() {
+            let position = position as i64;
+            sqlx::query!(
+                r#"UPDATE queued_messages
+                   SET position = $3, updated_at = datetime('now', 'subsec')
+                   WHERE id = $1 AND session_id = $2"#,
+                id,
+                session_id,
+                position
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Remove and return the next message of a session's queue
+    pub async fn take_next(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let row = sqlx::query_as!(
+            QueuedMessageRow,
+            r#"DELETE FROM queued_messages
+               WHERE id = (SELECT id FROM queued_messages WHERE session_id = $1 ORDER BY position ASC LIMIT 1)
+               RETURNING id as "id!: Uuid", session_id as "session_id!: Uuid", position, message, executor_profile_id as "executor_profile_id: Json<ExecutorProfileId>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            session_id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(row.map(Into::into))
+    }
+
+    /// Put a message taken with [`Self::take_next`] back at the front of its
+    /// session's queue
+    pub async fn requeue(pool: &SqlitePool, message: &Self) -> Result<(), sqlx::Error> {
+        let executor_profile_id = message.executor_profile_id.as_ref().map(Json);
+        sqlx::query!(
+            r#"INSERT INTO queued_messages (id, session_id, position, message, executor_profile_id, created_at, updated_at)
+               VALUES ($1, $2, (SELECT COALESCE(MIN(position), 1) - 1 FROM queued_messages WHERE session_id = $2), $3, $4, $5, $6)"#,
+            message.id,
+            message.session_id,
+            message.message,
+            executor_profile_id,
+            message.created_at,
+            message.updated_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
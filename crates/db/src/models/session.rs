@@ -154,6 +154,34 @@ impl Session {
         .await?)
     }
 
+    /// Agent session id a design session resumes on its next turn
+    pub async fn find_agent_session_id(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<Option<String>, sqlx::Error> {
+        Ok(
+            sqlx::query_scalar!(r#"SELECT agent_session_id FROM sessions WHERE id = $1"#, id)
+                .fetch_optional(pool)
+                .await?
+                .flatten(),
+        )
+    }
+
+    pub async fn update_agent_session_id(
+        pool: &SqlitePool,
+        id: Uuid,
+        agent_session_id: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE sessions SET agent_session_id = $1, updated_at = CURRENT_TIMESTAMP WHERE id = $2"#,
+            agent_session_id,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
     pub async fn update_executor(
         pool: &SqlitePool,
         id: Uuid,
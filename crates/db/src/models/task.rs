@@ -40,6 +40,9 @@ pub struct Task {
     pub design_session_id: Option<Uuid>,
     // Reference Dooray task URL (e.g., QA task URL for auto cross-reference)
     pub reference_dooray_url: Option<String>,
+    pub parent_task_id: Option<Uuid>, // Task of the parent Dooray post
+    // Implementation plan distilled from the design session (markdown)
+    pub plan: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -71,6 +74,8 @@ pub struct TaskRelationships {
     pub parent_task: Option<Task>, // The task that owns the parent workspace
     pub current_workspace: Workspace, // The workspace we're viewing
     pub children: Vec<Task>,       // Tasks created from this workspace
+    pub dooray_parent_task: Option<Task>, // Task of the parent Dooray post
+    pub dooray_subtasks: Vec<Task>, // Tasks of the Dooray sub-posts
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -86,6 +91,9 @@ pub struct CreateTask {
     pub dooray_project_id: Option<String>,
     pub dooray_task_number: Option<String>,
     pub reference_dooray_url: Option<String>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub parent_task_id: Option<Uuid>,
 }
 
 impl CreateTask {
@@ -105,6 +113,7 @@ impl CreateTask {
             dooray_project_id: None,
             dooray_task_number: None,
             reference_dooray_url: None,
+            parent_task_id: None,
         }
     }
 }
@@ -150,6 +159,8 @@ impl Task {
   t.dooray_task_number,
   t.design_session_id             AS "design_session_id: Uuid",
   t.reference_dooray_url,
+  t.parent_task_id                AS "parent_task_id: Uuid",
+  t.plan,
 
   CASE WHEN EXISTS (
     SELECT 1
@@ -213,6 +224,8 @@ ORDER BY t.created_at DESC"#,
                     dooray_task_number: rec.dooray_task_number,
                     design_session_id: rec.design_session_id,
                     reference_dooray_url: rec.reference_dooray_url,
+                    parent_task_id: rec.parent_task_id,
+                    plan: rec.plan,
                 },
                 has_in_progress_attempt: rec.has_in_progress_attempt != 0,
                 last_attempt_failed: rec.last_attempt_failed != 0,
@@ -227,7 +240,7 @@ ORDER BY t.created_at DESC"#,
     pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
         sqlx::query_as!(
             Task,
-            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>", dooray_task_id, dooray_project_id, dooray_task_number, design_session_id as "design_session_id: Uuid", reference_dooray_url
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>", dooray_task_id, dooray_project_id, dooray_task_number, design_session_id as "design_session_id: Uuid", reference_dooray_url, parent_task_id as "parent_task_id: Uuid", plan
                FROM tasks
                ORDER BY created_at ASC"#
         )
@@ -238,7 +251,7 @@ ORDER BY t.created_at DESC"#,
     pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             Task,
-            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>", dooray_task_id, dooray_project_id, dooray_task_number, design_session_id as "design_session_id: Uuid", reference_dooray_url
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>", dooray_task_id, dooray_project_id, dooray_task_number, design_session_id as "design_session_id: Uuid", reference_dooray_url, parent_task_id as "parent_task_id: Uuid", plan
                FROM tasks
                WHERE id = $1"#,
             id
@@ -250,7 +263,7 @@ ORDER BY t.created_at DESC"#,
     pub async fn find_by_rowid(pool: &SqlitePool, rowid: i64) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             Task,
-            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>", dooray_task_id, dooray_project_id, dooray_task_number, design_session_id as "design_session_id: Uuid", reference_dooray_url
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>", dooray_task_id, dooray_project_id, dooray_task_number, design_session_id as "design_session_id: Uuid", reference_dooray_url, parent_task_id as "parent_task_id: Uuid", plan
                FROM tasks
                WHERE rowid = $1"#,
             rowid
@@ -266,7 +279,7 @@ ORDER BY t.created_at DESC"#,
     ) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             Task,
-            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>", dooray_task_id, dooray_project_id, dooray_task_number, design_session_id as "design_session_id: Uuid", reference_dooray_url
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>", dooray_task_id, dooray_project_id, dooray_task_number, design_session_id as "design_session_id: Uuid", reference_dooray_url, parent_task_id as "parent_task_id: Uuid", plan
                FROM tasks
                WHERE dooray_task_id = $1"#,
             dooray_task_id
@@ -283,9 +296,9 @@ ORDER BY t.created_at DESC"#,
         let status = data.status.clone().unwrap_or_default();
         sqlx::query_as!(
             Task,
-            r#"INSERT INTO tasks (id, project_id, title, description, status, parent_workspace_id, dooray_task_id, dooray_project_id, dooray_task_number, reference_dooray_url)
-               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
-               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>", dooray_task_id, dooray_project_id, dooray_task_number, design_session_id as "design_session_id: Uuid", reference_dooray_url"#,
+            r#"INSERT INTO tasks (id, project_id, title, description, status, parent_workspace_id, dooray_task_id, dooray_project_id, dooray_task_number, reference_dooray_url, parent_task_id)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>", dooray_task_id, dooray_project_id, dooray_task_number, design_session_id as "design_session_id: Uuid", reference_dooray_url, parent_task_id as "parent_task_id: Uuid", plan"#,
             task_id,
             data.project_id,
             data.title,
@@ -295,7 +308,8 @@ ORDER BY t.created_at DESC"#,
             data.dooray_task_id,
             data.dooray_project_id,
             data.dooray_task_number,
-            data.reference_dooray_url
+            data.reference_dooray_url,
+            data.parent_task_id
         )
         .fetch_one(pool)
         .await
@@ -315,7 +329,7 @@ ORDER BY t.created_at DESC"#,
             r#"UPDATE tasks
                SET title = $3, description = $4, status = $5, parent_workspace_id = $6
                WHERE id = $1 AND project_id = $2
-               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>", dooray_task_id, dooray_project_id, dooray_task_number, design_session_id as "design_session_id: Uuid", reference_dooray_url"#,
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>", dooray_task_id, dooray_project_id, dooray_task_number, design_session_id as "design_session_id: Uuid", reference_dooray_url, parent_task_id as "parent_task_id: Uuid", plan"#,
             id,
             project_id,
             title,
@@ -374,6 +388,55 @@ ORDER BY t.created_at DESC"#,
         Ok(())
     }
 
+    /// Update the parent_task_id field for a task
+    pub async fn update_parent_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        parent_task_id: Option<Uuid>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE tasks SET parent_task_id = $2, updated_at = CURRENT_TIMESTAMP WHERE id = $1",
+            task_id,
+            parent_task_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Update the plan field for a task
+    pub async fn update_plan(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        plan: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE tasks SET plan = $2, updated_at = CURRENT_TIMESTAMP WHERE id = $1",
+            task_id,
+            plan
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Find the tasks of the Dooray sub-posts of a task
+    pub async fn find_by_parent_task_id(
+        pool: &SqlitePool,
+        parent_task_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Task,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>", dooray_task_id, dooray_project_id, dooray_task_number, design_session_id as "design_session_id: Uuid", reference_dooray_url, parent_task_id as "parent_task_id: Uuid", plan
+               FROM tasks
+               WHERE parent_task_id = $1
+               ORDER BY created_at ASC"#,
+            parent_task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
     /// Find a task by its design_session_id
     pub async fn find_by_design_session_id(
         pool: &SqlitePool,
@@ -381,7 +444,7 @@ ORDER BY t.created_at DESC"#,
     ) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             Task,
-            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>", dooray_task_id, dooray_project_id, dooray_task_number, design_session_id as "design_session_id: Uuid", reference_dooray_url
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>", dooray_task_id, dooray_project_id, dooray_task_number, design_session_id as "design_session_id: Uuid", reference_dooray_url, parent_task_id as "parent_task_id: Uuid", plan
                FROM tasks
                WHERE design_session_id = $1"#,
             design_session_id
@@ -425,7 +488,7 @@ ORDER BY t.created_at DESC"#,
         // Find only child tasks that have this workspace as their parent
         sqlx::query_as!(
             Task,
-            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>", dooray_task_id, dooray_project_id, dooray_task_number, design_session_id as "design_session_id: Uuid", reference_dooray_url
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>", dooray_task_id, dooray_project_id, dooray_task_number, design_session_id as "design_session_id: Uuid", reference_dooray_url, parent_task_id as "parent_task_id: Uuid", plan
                FROM tasks
                WHERE parent_workspace_id = $1
                ORDER BY created_at DESC"#,
@@ -462,10 +525,19 @@ ORDER BY t.created_at DESC"#,
         // 3. Get children tasks (created from this workspace)
         let children = Self::find_children_by_workspace_id(pool, workspace.id).await?;
 
+        // 4. Get the Dooray post hierarchy (parent post and sub-posts)
+        let dooray_parent_task = match current_task.parent_task_id {
+            Some(parent_task_id) => Self::find_by_id(pool, parent_task_id).await?,
+            None => None,
+        };
+        let dooray_subtasks = Self::find_by_parent_task_id(pool, current_task.id).await?;
+
         Ok(TaskRelationships {
             parent_task,
             current_workspace: workspace.clone(),
             children,
+            dooray_parent_task,
+            dooray_subtasks,
         })
     }
 }
@@ -53,15 +53,34 @@ impl Executable for ScriptRequest {
             None => current_dir.to_path_buf(),
         };
 
-        let (shell_cmd, shell_arg) = get_shell_command();
-        let mut command = Command::new(shell_cmd);
+        let mut command = match &env.container {
+            // The user's shell may not exist in the image
+            Some(container) => {
+                let (docker, args) = container
+                    .wrap(
+                        "sh".to_string(),
+                        vec!["-c".to_string(), self.script.clone()],
+                        &effective_dir,
+                        env,
+                        None,
+                    )
+                    .await?;
+                let mut command = Command::new(docker);
+                command.args(args);
+                command
+            }
+            None => {
+                let (shell_cmd, shell_arg) = get_shell_command();
+                let mut command = Command::new(shell_cmd);
+                command.arg(shell_arg).arg(&self.script);
+                command
+            }
+        };
         command
             .kill_on_drop(true)
             .stdin(std::process::Stdio::null())
             .stdout(std::process::Stdio::piped())
             .stderr(std::process::Stdio::piped())
-            .arg(shell_arg)
-            .arg(&self.script)
             .current_dir(&effective_dir);
 
         // Apply environment variables
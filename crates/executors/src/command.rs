@@ -1,4 +1,7 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -6,7 +9,7 @@ use thiserror::Error;
 use ts_rs::TS;
 use workspace_utils::shell::resolve_executable_path;
 
-use crate::executors::ExecutorError;
+use crate::{env::ExecutionEnv, executors::ExecutorError, sandbox::LinuxSandbox};
 
 #[derive(Debug, Error)]
 pub enum CommandBuildError {
@@ -31,6 +34,19 @@ impl CommandParts {
         Self { program, args }
     }
 
+    /// Program and arguments as built, before the program is resolved
+    pub fn into_parts(self) -> (String, Vec<String>) {
+        (self.program, self.args)
+    }
+
+    /// Program and arguments joined with spaces, unquoted
+    pub fn command_line(&self) -> String {
+        std::iter::once(self.program.as_str())
+            .chain(self.args.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
     pub async fn into_resolved(self) -> Result<(PathBuf, Vec<String>), ExecutorError> {
         let CommandParts { program, args } = self;
         let executable = resolve_executable_path(&program)
@@ -38,6 +54,28 @@ impl CommandParts {
             .ok_or(ExecutorError::ExecutableNotFound { program })?;
         Ok((executable, args))
     }
+
+    /// Resolve the executable, wrapped in the profile's Linux sandbox if it
+    /// has one. In a workspace container the container isolates the process
+    /// instead and the executable is looked up in its image.
+    pub async fn into_sandboxed(
+        self,
+        overrides: &CmdOverrides,
+        current_dir: &Path,
+        env: &ExecutionEnv,
+    ) -> Result<(PathBuf, Vec<String>), ExecutorError> {
+        if let Some(container) = &env.container {
+            let CommandParts { program, args } = self;
+            return container
+                .wrap(program, args, current_dir, env, overrides.env.as_ref())
+                .await;
+        }
+        let (executable, args) = self.into_resolved().await?;
+        match &overrides.linux_sandbox {
+            Some(sandbox) => sandbox.wrap(executable, args, current_dir, env).await,
+            None => Ok((executable, args)),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema, Default)]
@@ -60,6 +98,12 @@ pub struct CmdOverrides {
     )]
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub env: Option<HashMap<String, String>>,
+    #[schemars(
+        title = "Linux Sandbox",
+        description = "Run the executor in a sandbox where only the workspace and the executor's own state are writable (Linux only)"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub linux_sandbox: Option<LinuxSandbox>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
@@ -0,0 +1,158 @@
+//! Running executor processes in a workspace's Docker container.
+//!
+//! The workspace container only holds a network namespace. Every process runs
+//! in a `docker run --rm` container of the same image that joins it, with the
+//! paths the Linux sandbox keeps writable bind-mounted at their host paths.
+//! Processes of one workspace reach each other on localhost, while dependency
+//! installs and dev servers of parallel workspaces can't collide.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use workspace_utils::shell::resolve_executable_path;
+
+use crate::{
+    env::ExecutionEnv,
+    executors::ExecutorError,
+    sandbox::{current_id, writable_paths},
+};
+
+/// Label on every container of a workspace, whose value is the workspace id
+pub const WORKSPACE_LABEL: &str = "vibe-kanban.workspace";
+
+/// Variables executors set on their command directly instead of through the
+/// execution env
+const PASSTHROUGH_VARS: &[&str] = &["NPM_CONFIG_LOGLEVEL", "NODE_NO_WARNINGS", "NO_COLOR"];
+
+#[derive(Debug, Clone)]
+pub struct WorkspaceContainer {
+    /// Container whose network namespace processes join
+    pub name: String,
+    pub image: String,
+    pub workspace_id: String,
+}
+
+impl WorkspaceContainer {
+    /// `docker` command line that runs `program` with `args` in `current_dir`
+    /// next to the workspace container. `program` is looked up in the image.
+    pub async fn wrap(
+        &self,
+        program: String,
+        args: Vec<String>,
+        current_dir: &Path,
+        env: &ExecutionEnv,
+        profile_env: Option<&HashMap<String, String>>,
+    ) -> Result<(PathBuf, Vec<String>), ExecutorError> {
+        let docker = resolve_executable_path("docker").await.ok_or_else(|| {
+            ExecutorError::ExecutableNotFound {
+                program: "docker".to_string(),
+            }
+        })?;
+        let home = dirs::home_dir();
+        let args = self.run_args(
+            program,
+            args,
+            current_dir,
+            env,
+            profile_env,
+            home.as_deref(),
+        );
+        Ok((docker, args))
+    }
+
+    fn run_args(
+        &self,
+        program: String,
+        args: Vec<String>,
+        current_dir: &Path,
+        env: &ExecutionEnv,
+        profile_env: Option<&HashMap<String, String>>,
+        home: Option<&Path>,
+    ) -> Vec<String> {
+        let mut run: Vec<String> = ["run", "--rm", "-i", "--init"].map(String::from).to_vec();
+        run.extend([
+            "--network".to_string(),
+            format!("container:{}", self.name),
+            "--label".to_string(),
+            format!("{WORKSPACE_LABEL}={}", self.workspace_id),
+            "--user".to_string(),
+            format!("{}:{}", current_id("Uid:"), current_id("Gid:")),
+        ]);
+        if let Some(home) = home {
+            run.extend(["-e".to_string(), format!("HOME={}", home.display())]);
+        }
+
+        // Values are taken from the docker client's environment, where the
+        // caller applies them
+        let mut vars: Vec<&str> = env.vars.keys().map(String::as_str).collect();
+        vars.extend(
+            profile_env
+                .into_iter()
+                .flat_map(|vars| vars.keys().map(String::as_str)),
+        );
+        vars.extend(PASSTHROUGH_VARS);
+        vars.sort_unstable();
+        vars.dedup();
+        for var in vars {
+            run.extend(["-e".to_string(), var.to_string()]);
+        }
+
+        for path in writable_paths(current_dir, env, home, &[]) {
+            let path = path.to_string_lossy();
+            run.extend(["-v".to_string(), format!("{path}:{path}")]);
+        }
+
+        run.extend([
+            "-w".to_string(),
+            current_dir.to_string_lossy().to_string(),
+            "--entrypoint".to_string(),
+            program,
+            self.image.clone(),
+        ]);
+        run.extend(args);
+        run
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::RepoContext;
+
+    #[test]
+    fn runs_next_to_the_workspace_container() {
+        let workspace_dir = std::env::temp_dir();
+        let mut env = ExecutionEnv::new(
+            RepoContext::new(workspace_dir.clone(), vec![]),
+            false,
+            String::new(),
+        );
+        env.insert("VK_TASK_ID", "task");
+        let container = WorkspaceContainer {
+            name: "vk-1234".to_string(),
+            image: "node:22".to_string(),
+            workspace_id: "1234".to_string(),
+        };
+
+        let args = container
+            .run_args(
+                "npx".to_string(),
+                vec!["-y".to_string(), "pkg".to_string()],
+                &workspace_dir,
+                &env,
+                None,
+                None,
+            )
+            .join(" ");
+        let dir = workspace_dir.to_string_lossy();
+
+        assert!(args.starts_with(
+            "run --rm -i --init --network container:vk-1234 --label vibe-kanban.workspace=1234 "
+        ));
+        assert!(args.contains(" -e VK_TASK_ID "));
+        assert!(args.contains(&format!(" -v {dir}:{dir} ")));
+        assert!(args.ends_with(&format!(" -w {dir} --entrypoint npx node:22 -y pkg")));
+    }
+}
@@ -3,7 +3,7 @@ use std::{collections::HashMap, path::PathBuf};
 use git::GitService;
 use tokio::process::Command;
 
-use crate::command::CmdOverrides;
+use crate::{command::CmdOverrides, docker::WorkspaceContainer};
 
 /// Repository context for executor operations
 #[derive(Debug, Clone, Default)]
@@ -81,6 +81,8 @@ pub struct ExecutionEnv {
     pub repo_context: RepoContext,
     pub commit_reminder: bool,
     pub commit_reminder_prompt: String,
+    /// Set when the workspace's processes run in Docker
+    pub container: Option<WorkspaceContainer>,
 }
 
 impl ExecutionEnv {
@@ -94,6 +96,7 @@ impl ExecutionEnv {
             repo_context,
             commit_reminder,
             commit_reminder_prompt,
+            container: None,
         }
     }
 
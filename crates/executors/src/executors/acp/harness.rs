@@ -9,6 +9,7 @@ use agent_client_protocol as proto;
 use agent_client_protocol::Agent as _;
 use command_group::{AsyncCommandGroup, AsyncGroupChild};
 use futures::StreamExt;
+use serde::Deserialize;
 use tokio::{io::AsyncWriteExt, process::Command, sync::mpsc};
 use tokio_util::{
     compat::{TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt},
@@ -23,7 +24,11 @@ use crate::{
     approvals::ExecutorApprovalService,
     command::{CmdOverrides, CommandParts},
     env::ExecutionEnv,
-    executors::{ExecutorError, ExecutorExitResult, SpawnedChild, acp::AcpEvent},
+    executors::{
+        ExecutorError, ExecutorExitResult, SpawnedChild,
+        acp::{AcpAgentInfo, AcpEvent},
+    },
+    logs::TokenUsage,
 };
 
 /// Reusable harness for ACP-based conns (Gemini, Qwen, etc.)
@@ -78,7 +83,10 @@ impl AcpAgentHarness {
         cmd_overrides: &CmdOverrides,
         approvals: Option<std::sync::Arc<dyn ExecutorApprovalService>>,
     ) -> Result<SpawnedChild, ExecutorError> {
-        let (program_path, args) = command_parts.into_resolved().await?;
+        let command_line = command_parts.command_line();
+        let (program_path, args) = command_parts
+            .into_sandboxed(cmd_overrides, current_dir, env)
+            .await?;
         let mut command = Command::new(program_path);
         command
             .kill_on_drop(true)
@@ -108,6 +116,7 @@ impl AcpAgentHarness {
             self.session_namespace.clone(),
             self.model.clone(),
             self.mode.clone(),
+            command_line,
             approvals,
             cancel.clone(),
         )
@@ -131,7 +140,10 @@ impl AcpAgentHarness {
         cmd_overrides: &CmdOverrides,
         approvals: Option<std::sync::Arc<dyn ExecutorApprovalService>>,
     ) -> Result<SpawnedChild, ExecutorError> {
-        let (program_path, args) = command_parts.into_resolved().await?;
+        let command_line = command_parts.command_line();
+        let (program_path, args) = command_parts
+            .into_sandboxed(cmd_overrides, current_dir, env)
+            .await?;
         let mut command = Command::new(program_path);
         command
             .kill_on_drop(true)
@@ -161,6 +173,7 @@ impl AcpAgentHarness {
             self.session_namespace.clone(),
             self.model.clone(),
             self.mode.clone(),
+            command_line,
             approvals,
             cancel.clone(),
         )
@@ -183,6 +196,7 @@ impl AcpAgentHarness {
         session_namespace: String,
         model: Option<String>,
         mode: Option<String>,
+        command_line: String,
         approvals: Option<std::sync::Arc<dyn ExecutorApprovalService>>,
         cancel: CancellationToken,
     ) -> Result<(), ExecutorError> {
@@ -318,10 +332,14 @@ impl AcpAgentHarness {
                             let _ = io_fut.await;
                         });
 
-                        // Initialize
-                        let _ = conn
+                        // Initialize and keep what the agent supports
+                        match conn
                             .initialize(proto::InitializeRequest::new(proto::ProtocolVersion::V1))
-                            .await;
+                            .await
+                        {
+                            Ok(resp) => AcpAgentInfo::from_response(&resp).record(&command_line),
+                            Err(e) => error!("Failed to initialize ACP connection: {}", e),
+                        }
 
                         // Handle session creation/forking
                         let (acp_session_id, display_session_id, prompt_to_send) =
@@ -446,6 +464,10 @@ impl AcpAgentHarness {
                         );
 
                         let mut current_req = Some(initial_req);
+                        let mut run_usage = TokenUsage {
+                            model: model.clone(),
+                            ..Default::default()
+                        };
 
                         while let Some(req) = current_req.take() {
                             if cancel.is_cancelled() {
@@ -465,6 +487,11 @@ impl AcpAgentHarness {
 
                             match prompt_result {
                                 Ok(resp) => {
+                                    if let Some(usage) = PromptUsage::from_response(&resp) {
+                                        usage.add_to(&mut run_usage);
+                                        let _ = log_tx
+                                            .send(AcpEvent::Usage(run_usage.clone()).to_string());
+                                    }
                                     // Emit done with stop_reason
                                     let stop_reason = serde_json::to_string(&resp.stop_reason)
                                         .unwrap_or_default();
@@ -531,3 +558,37 @@ impl AcpAgentHarness {
         Ok(())
     }
 }
+
+/// Token usage of one prompt turn. Agents report it in the prompt response,
+/// either as `usage` or in its `_meta`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PromptUsage {
+    #[serde(default, alias = "input_tokens")]
+    input_tokens: u64,
+    #[serde(default, alias = "output_tokens")]
+    output_tokens: u64,
+    #[serde(default, alias = "thought_tokens")]
+    thought_tokens: u64,
+    #[serde(default, alias = "cached_read_tokens")]
+    cached_read_tokens: u64,
+    #[serde(default, alias = "cached_write_tokens")]
+    cached_write_tokens: u64,
+}
+
+impl PromptUsage {
+    fn from_response(resp: &proto::PromptResponse) -> Option<Self> {
+        let value = serde_json::to_value(resp).ok()?;
+        let usage = value
+            .get("usage")
+            .or_else(|| value.get("_meta").and_then(|meta| meta.get("usage")))?;
+        serde_json::from_value(usage.clone()).ok()
+    }
+
+    fn add_to(&self, usage: &mut TokenUsage) {
+        usage.input_tokens += self.input_tokens;
+        usage.output_tokens += self.output_tokens + self.thought_tokens;
+        usage.cache_read_tokens += self.cached_read_tokens;
+        usage.cache_write_tokens += self.cached_write_tokens;
+    }
+}
@@ -3,15 +3,26 @@ pub mod harness;
 pub mod normalize_logs;
 pub mod session;
 
-use std::{fmt::Display, str::FromStr};
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    str::FromStr,
+    sync::{LazyLock, PoisonError, RwLock},
+};
 
 pub use client::AcpClient;
 pub use harness::AcpAgentHarness;
 pub use normalize_logs::*;
 use serde::{Deserialize, Serialize};
 pub use session::SessionManager;
+use ts_rs::TS;
 use workspace_utils::approvals::ApprovalStatus;
 
+use crate::command::CommandParts;
+
+/// What each ACP agent command advertised when it was last initialized
+static AGENT_INFO: LazyLock<AcpAgentInfoStore> = LazyLock::new(Default::default);
+
 /// Parsed event types for internal processing
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum AcpEvent {
@@ -28,6 +39,8 @@ pub enum AcpEvent {
     ApprovalResponse(ApprovalResponse),
     Error(String),
     Done(String),
+    /// Tokens used by the prompt turns so far
+    Usage(crate::logs::TokenUsage),
     Other(agent_client_protocol::SessionNotification),
 }
 
@@ -45,8 +58,128 @@ impl FromStr for AcpEvent {
     }
 }
 
+/// Agent identity and capabilities from its `initialize` response
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, TS)]
+pub struct AcpAgentInfo {
+    pub name: Option<String>,
+    pub version: Option<String>,
+    /// Can resume sessions with `session/load`
+    pub load_session: bool,
+    /// Content types accepted in prompts besides text
+    pub image: bool,
+    pub audio: bool,
+    pub embedded_context: bool,
+    /// MCP server transports accepted besides stdio
+    pub mcp_http: bool,
+    pub mcp_sse: bool,
+}
+
+impl AcpAgentInfo {
+    /// Read from the wire format, which is stable across protocol crate versions
+    pub fn from_response(resp: &agent_client_protocol::InitializeResponse) -> Self {
+        let value = serde_json::to_value(resp).unwrap_or_default();
+        let flag = |pointer: &str| {
+            value
+                .pointer(pointer)
+                .and_then(serde_json::Value::as_bool)
+                .unwrap_or(false)
+        };
+        let text = |pointer: &str| {
+            value
+                .pointer(pointer)
+                .and_then(serde_json::Value::as_str)
+                .map(str::to_string)
+        };
+        Self {
+            name: text("/agentInfo/title").or_else(|| text("/agentInfo/name")),
+            version: text("/agentInfo/version"),
+            load_session: flag("/agentCapabilities/loadSession"),
+            image: flag("/agentCapabilities/promptCapabilities/image"),
+            audio: flag("/agentCapabilities/promptCapabilities/audio"),
+            embedded_context: flag("/agentCapabilities/promptCapabilities/embeddedContext"),
+            mcp_http: flag("/agentCapabilities/mcpCapabilities/http"),
+            mcp_sse: flag("/agentCapabilities/mcpCapabilities/sse"),
+        }
+    }
+
+    /// What the agent started by `command` advertised the last time it was
+    /// initialized by this server. `None` until it has run once.
+    pub fn last_seen(command: &CommandParts) -> Option<Self> {
+        AGENT_INFO.get(&command.command_line())
+    }
+
+    pub(crate) fn record(self, command_line: &str) {
+        AGENT_INFO.insert(command_line, self);
+    }
+}
+
+/// Agent info keyed by the command line that started the agent, so profiles
+/// running different binaries under one executor don't overwrite each other
+#[derive(Debug, Default)]
+struct AcpAgentInfoStore(RwLock<HashMap<String, AcpAgentInfo>>);
+
+impl AcpAgentInfoStore {
+    fn get(&self, command_line: &str) -> Option<AcpAgentInfo> {
+        self.0
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(command_line)
+            .cloned()
+    }
+
+    fn insert(&self, command_line: &str, info: AcpAgentInfo) {
+        self.0
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(command_line.to_string(), info);
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApprovalResponse {
     pub tool_call_id: String,
     pub status: ApprovalStatus,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_the_last_advertised_capabilities_per_command() {
+        let store = AcpAgentInfoStore::default();
+        assert_eq!(store.get("qwen --acp"), None);
+
+        let info = AcpAgentInfo {
+            name: Some("Qwen Code".to_string()),
+            load_session: true,
+            ..Default::default()
+        };
+        store.insert("qwen --acp", info.clone());
+        store.insert("qwen --acp", AcpAgentInfo::default());
+        store.insert("qwen --acp", info.clone());
+        store.insert("other-agent --acp", AcpAgentInfo::default());
+
+        assert_eq!(store.get("qwen --acp"), Some(info));
+        assert_eq!(
+            store.get("other-agent --acp"),
+            Some(AcpAgentInfo::default())
+        );
+        assert_eq!(store.get("gemini --experimental-acp"), None);
+    }
+
+    #[test]
+    fn joins_the_command_line_for_the_key() {
+        let command = CommandParts::new(
+            "npx".to_string(),
+            vec![
+                "-y".to_string(),
+                "@zed-industries/claude-code-acp".to_string(),
+            ],
+        );
+        assert_eq!(
+            command.command_line(),
+            "npx -y @zed-industries/claude-code-acp"
+        );
+    }
+}
@@ -16,7 +16,7 @@ use crate::{
     approvals::ToolCallMetadata,
     logs::{
         ActionType, FileChange, NormalizedEntry, NormalizedEntryError, NormalizedEntryType,
-        TodoItem, ToolResult, ToolResultValueType, ToolStatus as LogToolStatus,
+        TodoItem, TokenUsageInfo, ToolResult, ToolResultValueType, ToolStatus as LogToolStatus,
         stderr_processor::normalize_stderr_logs,
         utils::{ConversationPatch, EntryIndexProvider},
     },
@@ -64,6 +64,25 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                         streaming.assistant_text = None;
                         streaming.thinking_text = None;
                     }
+                    AcpEvent::Usage(usage) => {
+                        let total_tokens = usage.input_tokens
+                            + usage.output_tokens
+                            + usage.cache_read_tokens
+                            + usage.cache_write_tokens;
+                        let idx = entry_index.next();
+                        let entry = NormalizedEntry {
+                            timestamp: None,
+                            entry_type: NormalizedEntryType::TokenUsageInfo(TokenUsageInfo {
+                                total_tokens: total_tokens.min(u32::MAX as u64) as u32,
+                                // ACP doesn't report the context window
+                                model_context_window: 0,
+                                usage: Some(usage),
+                            }),
+                            content: format!("Tokens used: {total_tokens}"),
+                            metadata: None,
+                        };
+                        msg_store.push_patch(ConversationPatch::add_normalized_entry(idx, entry));
+                    }
                     AcpEvent::Message(content) => {
                         streaming.thinking_text = None;
                         if let agent_client_protocol::ContentBlock::Text(text) = content {
@@ -0,0 +1,151 @@
+use std::{path::Path, sync::Arc};
+
+use async_trait::async_trait;
+use derivative::Derivative;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use workspace_utils::{msg_store::MsgStore, shell::resolve_executable_path_blocking};
+
+use super::acp::AcpAgentHarness;
+use crate::{
+    approvals::ExecutorApprovalService,
+    command::{CmdOverrides, CommandBuildError, CommandBuilder, apply_overrides},
+    env::ExecutionEnv,
+    executors::{
+        AppendPrompt, AvailabilityInfo, ExecutorError, SpawnedChild, StandardCodingAgentExecutor,
+    },
+};
+
+/// Any agent that speaks the Agent Client Protocol over stdio
+#[derive(Derivative, Clone, Serialize, Deserialize, TS, JsonSchema)]
+#[derivative(Debug, PartialEq)]
+pub struct AcpAgent {
+    #[serde(default)]
+    pub append_prompt: AppendPrompt,
+    #[schemars(
+        title = "Command",
+        description = "Command that starts the agent in ACP mode, e.g. npx -y @zed-industries/claude-code-acp"
+    )]
+    pub command: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Model",
+        description = "Model ID to select for the session, if the agent offers models"
+    )]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Mode",
+        description = "Session mode ID to select, if the agent offers modes"
+    )]
+    pub mode: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Auto-approve",
+        description = "Allow every tool call the agent asks permission for"
+    )]
+    pub yolo: Option<bool>,
+    #[serde(flatten)]
+    pub cmd: CmdOverrides,
+    #[serde(skip)]
+    #[ts(skip)]
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    pub approvals: Option<Arc<dyn ExecutorApprovalService>>,
+}
+
+impl AcpAgent {
+    pub(crate) fn build_command_builder(&self) -> Result<CommandBuilder, CommandBuildError> {
+        apply_overrides(CommandBuilder::new(self.command.as_str()), &self.cmd)
+    }
+
+    fn harness(&self) -> AcpAgentHarness {
+        let mut harness = AcpAgentHarness::with_session_namespace("acp_agent_sessions");
+        if let Some(model) = &self.model {
+            harness = harness.with_model(model);
+        }
+        if let Some(mode) = &self.mode {
+            harness = harness.with_mode(mode);
+        }
+        harness
+    }
+
+    fn approvals(&self) -> Option<Arc<dyn ExecutorApprovalService>> {
+        if self.yolo.unwrap_or(false) {
+            None
+        } else {
+            self.approvals.clone()
+        }
+    }
+}
+
+#[async_trait]
+impl StandardCodingAgentExecutor for AcpAgent {
+    fn use_approvals(&mut self, approvals: Arc<dyn ExecutorApprovalService>) {
+        self.approvals = Some(approvals);
+    }
+
+    async fn spawn(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let combined_prompt = self.append_prompt.combine_prompt(prompt);
+        let acp_command = self.build_command_builder()?.build_initial()?;
+        self.harness()
+            .spawn_with_command(
+                current_dir,
+                combined_prompt,
+                acp_command,
+                env,
+                &self.cmd,
+                self.approvals(),
+            )
+            .await
+    }
+
+    async fn spawn_follow_up(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        session_id: &str,
+        _reset_to_message_id: Option<&str>,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let combined_prompt = self.append_prompt.combine_prompt(prompt);
+        let acp_command = self.build_command_builder()?.build_follow_up(&[])?;
+        self.harness()
+            .spawn_follow_up_with_command(
+                current_dir,
+                combined_prompt,
+                session_id,
+                acp_command,
+                env,
+                &self.cmd,
+                self.approvals(),
+            )
+            .await
+    }
+
+    fn normalize_logs(&self, msg_store: Arc<MsgStore>, worktree_path: &Path) {
+        super::acp::normalize_logs(msg_store, worktree_path);
+    }
+
+    fn default_mcp_config_path(&self) -> Option<std::path::PathBuf> {
+        None
+    }
+
+    fn get_availability_info(&self) -> AvailabilityInfo {
+        let program = CommandBuilder::new(self.command.as_str())
+            .build_initial()
+            .ok()
+            .map(|parts| parts.into_parts().0);
+        match program {
+            Some(program) if resolve_executable_path_blocking(&program).is_some() => {
+                AvailabilityInfo::InstallationFound
+            }
+            _ => AvailabilityInfo::NotFound,
+        }
+    }
+}
@@ -52,7 +52,9 @@ impl StandardCodingAgentExecutor for Amp {
         env: &ExecutionEnv,
     ) -> Result<SpawnedChild, ExecutorError> {
         let command_parts = self.build_command_builder()?.build_initial()?;
-        let (executable_path, args) = command_parts.into_resolved().await?;
+        let (executable_path, args) = command_parts
+            .into_sandboxed(&self.cmd, current_dir, env)
+            .await?;
 
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
@@ -96,7 +98,9 @@ impl StandardCodingAgentExecutor for Amp {
             "fork".to_string(),
             session_id.to_string(),
         ])?;
-        let (fork_program, fork_args) = fork_line.into_resolved().await?;
+        let (fork_program, fork_args) = fork_line
+            .into_sandboxed(&self.cmd, current_dir, env)
+            .await?;
         let fork_output = Command::new(fork_program)
             .kill_on_drop(true)
             .stdout(Stdio::piped())
@@ -128,7 +132,9 @@ impl StandardCodingAgentExecutor for Amp {
             "continue".to_string(),
             new_thread_id.clone(),
         ])?;
-        let (continue_program, continue_args) = continue_line.into_resolved().await?;
+        let (continue_program, continue_args) = continue_line
+            .into_sandboxed(&self.cmd, current_dir, env)
+            .await?;
 
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
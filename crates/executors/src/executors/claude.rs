@@ -298,7 +298,9 @@ impl ClaudeCode {
         command_parts: CommandParts,
         env: &ExecutionEnv,
     ) -> Result<SpawnedChild, ExecutorError> {
-        let (program_path, args) = command_parts.into_resolved().await?;
+        let (program_path, args) = command_parts
+            .into_sandboxed(&self.cmd, current_dir, env)
+            .await?;
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
         let mut command = Command::new(program_path);
@@ -410,6 +412,8 @@ pub struct ClaudeLogProcessor {
     main_model_name: Option<String>,
     main_model_context_window: u32,
     context_tokens_used: u32,
+    // Tokens billed for the whole run, reported with the result message
+    run_usage: Option<crate::logs::TokenUsage>,
 }
 
 impl ClaudeLogProcessor {
@@ -429,6 +433,7 @@ impl ClaudeLogProcessor {
             last_assistant_message: None,
             main_model_context_window: DEFAULT_CLAUDE_CONTEXT_WINDOW,
             context_tokens_used: 0,
+            run_usage: None,
         }
     }
 
@@ -1349,18 +1354,31 @@ impl ClaudeLogProcessor {
             ClaudeJson::Result {
                 is_error,
                 model_usage,
+                usage,
                 subtype,
                 result,
                 ..
             } => {
                 // get the real model context window and correct the context usage entry
-                if let Some(context_window) = model_usage.as_ref().and_then(|model_usage| {
+                let context_window = model_usage.as_ref().and_then(|model_usage| {
                     self.main_model_name
                         .as_ref()
                         .and_then(|name| model_usage.get(name))
                         .and_then(|usage| usage.context_window)
-                }) {
+                });
+                if let Some(context_window) = context_window {
                     self.main_model_context_window = context_window;
+                }
+                if let Some(usage) = usage {
+                    self.run_usage = Some(crate::logs::TokenUsage {
+                        model: self.main_model_name.clone(),
+                        input_tokens: usage.input_tokens.unwrap_or(0),
+                        output_tokens: usage.output_tokens.unwrap_or(0),
+                        cache_read_tokens: usage.cache_read_input_tokens.unwrap_or(0),
+                        cache_write_tokens: usage.cache_creation_input_tokens.unwrap_or(0),
+                    });
+                }
+                if context_window.is_some() || usage.is_some() {
                     patches.push(self.add_token_usage_entry(entry_index_provider));
                 }
 
@@ -1554,6 +1572,7 @@ impl ClaudeLogProcessor {
             entry_type: NormalizedEntryType::TokenUsageInfo(crate::logs::TokenUsageInfo {
                 total_tokens: self.context_tokens_used,
                 model_context_window: self.main_model_context_window,
+                usage: self.run_usage.clone(),
             }),
             content: format!(
                 "Tokens used: {} / Context window: {}",
@@ -2383,6 +2402,7 @@ mod tests {
                 base_command_override: None,
                 additional_params: None,
                 env: None,
+                linux_sandbox: None,
             },
             approvals_service: None,
             disable_api_key: None,
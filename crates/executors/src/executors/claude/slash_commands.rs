@@ -203,7 +203,10 @@ impl ClaudeCode {
             .build_slash_commands_discovery_command_builder()
             .await?;
         let command_parts = command_builder.build_initial()?;
-        let (program_path, args) = command_parts.into_resolved().await?;
+        let env = ExecutionEnv::new(RepoContext::default(), false, String::new());
+        let (program_path, args) = command_parts
+            .into_sandboxed(&self.cmd, current_dir, &env)
+            .await?;
 
         let mut command = Command::new(program_path);
         command
@@ -214,9 +217,7 @@ impl ClaudeCode {
             .current_dir(current_dir)
             .args(&args);
 
-        ExecutionEnv::new(RepoContext::default(), false, String::new())
-            .with_profile(&self.cmd)
-            .apply_to_command(&mut command);
+        env.with_profile(&self.cmd).apply_to_command(&mut command);
 
         if self.disable_api_key.unwrap_or(false) {
             command.env_remove("ANTHROPIC_API_KEY");
@@ -450,7 +450,9 @@ impl Codex {
         F: FnOnce(Arc<AppServerClient>, ExitSignalSender) -> Fut + Send + 'static,
         Fut: std::future::Future<Output = Result<(), ExecutorError>> + Send + 'static,
     {
-        let (program_path, args) = command_parts.into_resolved().await?;
+        let (program_path, args) = command_parts
+            .into_sandboxed(&self.cmd, current_dir, env)
+            .await?;
 
         let mut process = Command::new(program_path);
         process
@@ -16,8 +16,8 @@ use codex_protocol::{
         ErrorEvent, EventMsg, ExecApprovalRequestEvent, ExecCommandBeginEvent, ExecCommandEndEvent,
         ExecCommandOutputDeltaEvent, ExecOutputStream, FileChange as CodexProtoFileChange,
         McpInvocation, McpToolCallBeginEvent, McpToolCallEndEvent, PatchApplyBeginEvent,
-        PatchApplyEndEvent, StreamErrorEvent, ViewImageToolCallEvent, WarningEvent,
-        WebSearchBeginEvent, WebSearchEndEvent,
+        PatchApplyEndEvent, StreamErrorEvent, TokenUsageInfo as CodexTokenUsageInfo,
+        ViewImageToolCallEvent, WarningEvent, WebSearchBeginEvent, WebSearchEndEvent,
     },
 };
 use futures::StreamExt;
@@ -34,8 +34,8 @@ use crate::{
     executors::codex::session::SessionHandler,
     logs::{
         ActionType, CommandExitStatus, CommandRunResult, FileChange, NormalizedEntry,
-        NormalizedEntryError, NormalizedEntryType, TodoItem, ToolResult, ToolResultValueType,
-        ToolStatus,
+        NormalizedEntryError, NormalizedEntryType, TodoItem, TokenUsage, ToolResult,
+        ToolResultValueType, ToolStatus,
         stderr_processor::normalize_stderr_logs,
         utils::{
             ConversationPatch, EntryIndexProvider,
@@ -214,6 +214,9 @@ struct LogState {
     mcp_tools: HashMap<String, McpToolState>,
     patches: HashMap<String, PatchState>,
     web_searches: HashMap<String, WebSearchState>,
+    model: Option<String>,
+    usage: TokenUsage,
+    last_total_tokens: i64,
 }
 
 enum StreamingTextKind {
@@ -231,9 +234,28 @@ impl LogState {
             mcp_tools: HashMap::new(),
             patches: HashMap::new(),
             web_searches: HashMap::new(),
+            model: None,
+            usage: TokenUsage::default(),
+            last_total_tokens: 0,
         }
     }
 
+    /// Add the usage of the last model request to the run total. Token counts
+    /// are also re-sent without a new request (e.g. with rate limit updates),
+    /// which leaves the session total unchanged.
+    fn record_token_count(&mut self, info: &CodexTokenUsageInfo) -> TokenUsage {
+        if info.total_token_usage.total_tokens != self.last_total_tokens {
+            self.last_total_tokens = info.total_token_usage.total_tokens;
+            let last = &info.last_token_usage;
+            let cached = last.cached_input_tokens.max(0) as u64;
+            self.usage.input_tokens += (last.input_tokens.max(0) as u64).saturating_sub(cached);
+            self.usage.output_tokens += last.output_tokens.max(0) as u64;
+            self.usage.cache_read_tokens += cached;
+        }
+        self.usage.model = self.model.clone();
+        self.usage.clone()
+    }
+
     fn streaming_text_update(
         &mut self,
         content: String,
@@ -377,7 +399,9 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
             }
 
             if let Ok(response) = serde_json::from_str::<JSONRPCResponse>(&line) {
-                handle_jsonrpc_response(response, &msg_store, &entry_index);
+                if let Some(model) = handle_jsonrpc_response(response, &msg_store, &entry_index) {
+                    state.model = Some(model);
+                }
                 continue;
             }
 
@@ -386,6 +410,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                     server_notification
                 {
                     msg_store.push_session_id(session_configured.session_id.to_string());
+                    state.model = Some(session_configured.model.clone());
                     handle_model_params(
                         session_configured.model,
                         session_configured.reasoning_effort,
@@ -424,6 +449,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
             match event {
                 EventMsg::SessionConfigured(payload) => {
                     msg_store.push_session_id(payload.session_id.to_string());
+                    state.model = Some(payload.model.clone());
                     handle_model_params(
                         payload.model,
                         payload.reasoning_effort,
@@ -994,6 +1020,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                 }
                 EventMsg::TokenCount(payload) => {
                     if let Some(info) = payload.info {
+                        let usage = state.record_token_count(&info);
                         add_normalized_entry(
                             &msg_store,
                             &entry_index,
@@ -1006,6 +1033,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                                             .model_context_window
                                             .unwrap_or_default()
                                             as u32,
+                                        usage: Some(usage),
                                     },
                                 ),
                                 content: format!(
@@ -1078,14 +1106,15 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
     });
 }
 
+/// Handle the response of a new conversation request, returning its model
 fn handle_jsonrpc_response(
     response: JSONRPCResponse,
     msg_store: &Arc<MsgStore>,
     entry_index: &EntryIndexProvider,
-) {
+) -> Option<String> {
     let Ok(response) = serde_json::from_value::<NewConversationResponse>(response.result.clone())
     else {
-        return;
+        return None;
     };
 
     match SessionHandler::extract_session_id_from_rollout_path(response.rollout_path) {
@@ -1094,11 +1123,12 @@ fn handle_jsonrpc_response(
     }
 
     handle_model_params(
-        response.model,
+        response.model.clone(),
         response.reasoning_effort,
         msg_store,
         entry_index,
     );
+    Some(response.model)
 }
 
 fn handle_model_params(
@@ -43,7 +43,7 @@ pub struct Copilot {
 }
 
 impl Copilot {
-    fn build_command_builder(&self) -> Result<CommandBuilder, CommandBuildError> {
+    pub(crate) fn build_command_builder(&self) -> Result<CommandBuilder, CommandBuildError> {
         let mut builder = CommandBuilder::new("npx -y @github/copilot@0.0.403");
 
         if self.allow_all_tools.unwrap_or(false) {
@@ -0,0 +1,448 @@
+use std::{
+    path::{Path, PathBuf},
+    process::Stdio,
+    sync::Arc,
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use command_group::AsyncCommandGroup;
+use derivative::Derivative;
+use futures::StreamExt;
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::{io::AsyncWriteExt, process::Command};
+use ts_rs::TS;
+use workspace_utils::{msg_store::MsgStore, shell::resolve_executable_path_blocking};
+
+use super::{
+    acp::AcpAgentHarness,
+    claude::{ClaudeLogProcessor, HistoryStrategy},
+};
+use crate::{
+    approvals::ExecutorApprovalService,
+    command::{CmdOverrides, CommandBuildError, CommandBuilder, CommandParts, apply_overrides},
+    env::ExecutionEnv,
+    executors::{
+        AppendPrompt, AvailabilityInfo, ExecutorError, SpawnedChild, StandardCodingAgentExecutor,
+    },
+    logs::{
+        NormalizedEntry, NormalizedEntryType, plain_text_processor::PlainTextLogProcessor,
+        stderr_processor::normalize_stderr_logs, utils::EntryIndexProvider,
+    },
+    stdout_dup::duplicate_stdout,
+};
+
+const PROMPT_PLACEHOLDER: &str = "{prompt}";
+const PROMPT_FILE_PLACEHOLDER: &str = "{prompt_file}";
+const SESSION_ID_PLACEHOLDER: &str = "{session_id}";
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename = "CustomPromptInput")]
+pub enum PromptInput {
+    #[default]
+    Stdin,
+    Arg,
+    File,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename = "CustomLogFormat")]
+pub enum LogFormat {
+    #[default]
+    PlainText,
+    ClaudeStreamJson,
+    Acp,
+}
+
+/// Any CLI agent, described entirely by its profile
+#[derive(Derivative, Clone, Serialize, Deserialize, TS, JsonSchema)]
+#[derivative(Debug, PartialEq)]
+pub struct CustomAgent {
+    #[serde(default)]
+    pub append_prompt: AppendPrompt,
+    #[schemars(
+        title = "Command",
+        description = "Command that starts a new session. {prompt} or {prompt_file} is replaced according to the prompt input"
+    )]
+    pub command: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Follow-up Command",
+        description = "Command that continues a session, with {session_id} replaced by its id. Without it follow-ups run the command above"
+    )]
+    pub follow_up_command: Option<String>,
+    #[serde(default)]
+    #[schemars(
+        title = "Prompt Input",
+        description = "stdin writes the prompt to the agent's stdin, arg passes it as an argument and file passes the path of a file holding it. Ignored for ACP"
+    )]
+    pub prompt_input: PromptInput,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Session ID Pattern",
+        description = "Regex matched against plain text stdout; its first capture group is the session id follow-ups resume"
+    )]
+    pub session_id_pattern: Option<String>,
+    #[serde(default)]
+    #[schemars(
+        title = "Log Format",
+        description = "plain_text shows stdout as assistant messages, claude_stream_json parses Claude Code's --output-format stream-json and acp speaks the Agent Client Protocol"
+    )]
+    pub log_format: LogFormat,
+    #[serde(flatten)]
+    pub cmd: CmdOverrides,
+    #[serde(skip)]
+    #[ts(skip)]
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    pub approvals: Option<Arc<dyn ExecutorApprovalService>>,
+}
+
+impl CustomAgent {
+    fn build_command_builder(
+        &self,
+        session_id: Option<&str>,
+    ) -> Result<CommandBuilder, CommandBuildError> {
+        let template = match (session_id, &self.follow_up_command) {
+            (Some(_), Some(follow_up_command)) => follow_up_command,
+            _ => &self.command,
+        };
+        apply_overrides(CommandBuilder::new(template.as_str()), &self.cmd)
+    }
+
+    /// Command a new session starts the agent with, when it speaks ACP
+    pub(crate) fn acp_command(&self) -> Option<CommandParts> {
+        if self.log_format != LogFormat::Acp {
+            return None;
+        }
+        self.build_command_builder(None).ok()?.build_initial().ok()
+    }
+
+    fn session_id_regex(&self) -> Result<Option<Regex>, ExecutorError> {
+        self.session_id_pattern
+            .as_deref()
+            .map(Regex::new)
+            .transpose()
+            .map_err(|e| ExecutorError::InvalidConfig(format!("session_id_pattern: {e}")))
+    }
+
+    async fn spawn_internal(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        session_id: Option<&str>,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        self.session_id_regex()?;
+        let combined_prompt = self.append_prompt.combine_prompt(prompt);
+        let (program, args) = self
+            .build_command_builder(session_id)?
+            .build_initial()?
+            .into_parts();
+
+        if self.log_format == LogFormat::Acp {
+            let command = CommandParts::new(program, fill_args(args, session_id, None));
+            let harness = AcpAgentHarness::with_session_namespace("custom_sessions");
+            return match session_id {
+                Some(session_id) => {
+                    harness
+                        .spawn_follow_up_with_command(
+                            current_dir,
+                            combined_prompt,
+                            session_id,
+                            command,
+                            env,
+                            &self.cmd,
+                            self.approvals.clone(),
+                        )
+                        .await
+                }
+                None => {
+                    harness
+                        .spawn_with_command(
+                            current_dir,
+                            combined_prompt,
+                            command,
+                            env,
+                            &self.cmd,
+                            self.approvals.clone(),
+                        )
+                        .await
+                }
+            };
+        }
+
+        let mut prompt_file = None;
+        let prompt_arg = match self.prompt_input {
+            PromptInput::Stdin => None,
+            PromptInput::Arg => Some((PROMPT_PLACEHOLDER, combined_prompt.clone())),
+            PromptInput::File => {
+                // The sandbox mounts its own /tmp and containers only see the workspace
+                if env.container.is_some() || self.cmd.linux_sandbox.is_some() {
+                    return Err(ExecutorError::InvalidConfig(
+                        "prompt_input `file` can't be used in a sandbox or container".to_string(),
+                    ));
+                }
+                let file = write_prompt_file(&combined_prompt).await?;
+                let path = file.0.to_string_lossy().into_owned();
+                prompt_file = Some(file);
+                Some((PROMPT_FILE_PLACEHOLDER, path))
+            }
+        };
+        let args = fill_args(
+            args,
+            session_id,
+            prompt_arg
+                .as_ref()
+                .map(|(placeholder, value)| (*placeholder, value.as_str())),
+        );
+
+        let (program_path, args) = CommandParts::new(program, args)
+            .into_sandboxed(&self.cmd, current_dir, env)
+            .await?;
+
+        let mut command = Command::new(program_path);
+        command
+            .kill_on_drop(true)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .current_dir(current_dir)
+            .args(&args);
+
+        env.clone()
+            .with_profile(&self.cmd)
+            .apply_to_command(&mut command);
+
+        let mut child = command.group_spawn()?;
+
+        if let Some(mut stdin) = child.inner().stdin.take() {
+            if self.prompt_input == PromptInput::Stdin {
+                stdin.write_all(combined_prompt.as_bytes()).await?;
+            }
+            stdin.shutdown().await?;
+        }
+
+        if let Some(prompt_file) = prompt_file {
+            // The child's stdout closes when it exits
+            let mut stdout = duplicate_stdout(&mut child)?;
+            tokio::spawn(async move {
+                while stdout.next().await.is_some() {}
+                drop(prompt_file);
+            });
+        }
+
+        Ok(child.into())
+    }
+}
+
+#[async_trait]
+impl StandardCodingAgentExecutor for CustomAgent {
+    fn use_approvals(&mut self, approvals: Arc<dyn ExecutorApprovalService>) {
+        self.approvals = Some(approvals);
+    }
+
+    async fn spawn(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        self.spawn_internal(current_dir, prompt, None, env).await
+    }
+
+    async fn spawn_follow_up(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        session_id: &str,
+        _reset_to_message_id: Option<&str>,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        self.spawn_internal(current_dir, prompt, Some(session_id), env)
+            .await
+    }
+
+    fn normalize_logs(&self, msg_store: Arc<MsgStore>, worktree_path: &Path) {
+        match self.log_format {
+            LogFormat::Acp => super::acp::normalize_logs(msg_store, worktree_path),
+            LogFormat::ClaudeStreamJson => {
+                let entry_index_provider = EntryIndexProvider::start_from(&msg_store);
+                ClaudeLogProcessor::process_logs(
+                    msg_store.clone(),
+                    worktree_path,
+                    entry_index_provider.clone(),
+                    HistoryStrategy::Default,
+                );
+                normalize_stderr_logs(msg_store, entry_index_provider);
+            }
+            LogFormat::PlainText => {
+                // Invalid patterns are rejected at spawn
+                let session_id_regex = self.session_id_regex().ok().flatten();
+                normalize_plain_text_logs(msg_store, session_id_regex);
+            }
+        }
+    }
+
+    fn default_mcp_config_path(&self) -> Option<PathBuf> {
+        None
+    }
+
+    fn get_availability_info(&self) -> AvailabilityInfo {
+        let program = CommandBuilder::new(self.command.as_str())
+            .build_initial()
+            .ok()
+            .map(|parts| parts.into_parts().0);
+        match program {
+            Some(program) if resolve_executable_path_blocking(&program).is_some() => {
+                AvailabilityInfo::InstallationFound
+            }
+            _ => AvailabilityInfo::NotFound,
+        }
+    }
+}
+
+/// Stdout becomes assistant messages split on pauses in the output, stderr
+/// error messages
+fn normalize_plain_text_logs(msg_store: Arc<MsgStore>, session_id_regex: Option<Regex>) {
+    let entry_index_provider = EntryIndexProvider::start_from(&msg_store);
+    normalize_stderr_logs(msg_store.clone(), entry_index_provider.clone());
+
+    if let Some(regex) = session_id_regex {
+        let msg_store = msg_store.clone();
+        tokio::spawn(async move {
+            let mut lines = msg_store.stdout_lines_stream();
+            while let Some(Ok(line)) = lines.next().await {
+                if let Some(session_id) = extract_session_id(&regex, &line) {
+                    msg_store.push_session_id(session_id);
+                    break;
+                }
+            }
+        });
+    }
+
+    tokio::spawn(async move {
+        let mut stdout = msg_store.stdout_chunked_stream();
+        let mut processor = PlainTextLogProcessor::builder()
+            .normalized_entry_producer(Box::new(|content: String| NormalizedEntry {
+                timestamp: None,
+                entry_type: NormalizedEntryType::AssistantMessage,
+                content: strip_ansi_escapes::strip_str(&content),
+                metadata: None,
+            }))
+            .time_gap(Duration::from_secs(2))
+            .index_provider(entry_index_provider)
+            .build();
+
+        while let Some(Ok(chunk)) = stdout.next().await {
+            for patch in processor.process(chunk) {
+                msg_store.push_patch(patch);
+            }
+        }
+    });
+}
+
+/// First capture group of `regex` in `line`, or the whole match if it has none
+fn extract_session_id(regex: &Regex, line: &str) -> Option<String> {
+    let captures = regex.captures(line)?;
+    captures
+        .get(1)
+        .or_else(|| captures.get(0))
+        .map(|m| m.as_str().to_string())
+        .filter(|id| !id.is_empty())
+}
+
+/// Replace `{session_id}` in `args` and put the prompt argument in place of
+/// its placeholder, appending it when no argument has one
+fn fill_args(
+    mut args: Vec<String>,
+    session_id: Option<&str>,
+    prompt_arg: Option<(&str, &str)>,
+) -> Vec<String> {
+    if let Some(session_id) = session_id {
+        for arg in &mut args {
+            *arg = arg.replace(SESSION_ID_PLACEHOLDER, session_id);
+        }
+    }
+    if let Some((placeholder, value)) = prompt_arg {
+        if args.iter().any(|arg| arg.contains(placeholder)) {
+            for arg in &mut args {
+                *arg = arg.replace(placeholder, value);
+            }
+        } else {
+            args.push(value.to_string());
+        }
+    }
+    args
+}
+
+/// Prompt written for `{prompt_file}`, deleted when dropped
+struct PromptFile(PathBuf);
+
+impl Drop for PromptFile {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_file(&self.0) {
+            tracing::warn!("Failed to remove prompt file {}: {}", self.0.display(), e);
+        }
+    }
+}
+
+async fn write_prompt_file(prompt: &str) -> Result<PromptFile, ExecutorError> {
+    let dir = workspace_utils::path::get_vibe_kanban_temp_dir().join("prompts");
+    tokio::fs::create_dir_all(&dir)
+        .await
+        .map_err(ExecutorError::Io)?;
+    let path = dir.join(format!("{}.md", uuid::Uuid::new_v4()));
+    tokio::fs::write(&path, prompt)
+        .await
+        .map_err(ExecutorError::Io)?;
+    Ok(PromptFile(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn fills_placeholders_and_appends_prompt() {
+        assert_eq!(
+            fill_args(
+                args(&["--resume", "{session_id}", "--prompt={prompt}"]),
+                Some("abc"),
+                Some((PROMPT_PLACEHOLDER, "fix {session_id} bug")),
+            ),
+            args(&["--resume", "abc", "--prompt=fix {session_id} bug"])
+        );
+        assert_eq!(
+            fill_args(
+                args(&["run"]),
+                None,
+                Some((PROMPT_FILE_PLACEHOLDER, "/tmp/p.md"))
+            ),
+            args(&["run", "/tmp/p.md"])
+        );
+    }
+
+    #[test]
+    fn extracts_session_id_from_capture_group() {
+        let regex = Regex::new(r"session: (\S+)").unwrap();
+        assert_eq!(
+            extract_session_id(&regex, "started session: 1234-abcd"),
+            Some("1234-abcd".to_string())
+        );
+        assert_eq!(extract_session_id(&regex, "no id here"), None);
+
+        let regex = Regex::new(r"[0-9a-f]{8}-[0-9a-f]{4}").unwrap();
+        assert_eq!(
+            extract_session_id(&regex, "id deadbeef-cafe"),
+            Some("deadbeef-cafe".to_string())
+        );
+    }
+}
@@ -35,7 +35,7 @@ pub struct Gemini {
 }
 
 impl Gemini {
-    fn build_command_builder(&self) -> Result<CommandBuilder, CommandBuildError> {
+    pub(crate) fn build_command_builder(&self) -> Result<CommandBuilder, CommandBuildError> {
         let mut builder = CommandBuilder::new("npx -y @google/gemini-cli@0.27.0");
 
         if let Some(model) = &self.model {
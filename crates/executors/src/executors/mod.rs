@@ -21,19 +21,23 @@ use crate::{
     command::CommandBuildError,
     env::ExecutionEnv,
     executors::{
-        amp::Amp, claude::ClaudeCode, codex::Codex, copilot::Copilot, cursor::CursorAgent,
-        droid::Droid, gemini::Gemini, opencode::Opencode, qwen::QwenCode,
+        acp::AcpAgentInfo, acp_agent::AcpAgent, amp::Amp, claude::ClaudeCode, codex::Codex,
+        copilot::Copilot, cursor::CursorAgent, custom::CustomAgent, droid::Droid, gemini::Gemini,
+        opencode::Opencode, qwen::QwenCode,
     },
     logs::utils::patch,
     mcp_config::McpConfig,
+    sandbox::LinuxSandbox,
 };
 
 pub mod acp;
+pub mod acp_agent;
 pub mod amp;
 pub mod claude;
 pub mod codex;
 pub mod copilot;
 pub mod cursor;
+pub mod custom;
 pub mod droid;
 pub mod gemini;
 pub mod opencode;
@@ -87,6 +91,10 @@ pub enum ExecutorError {
     SetupHelperNotSupported,
     #[error("Auth required: {0}")]
     AuthRequired(String),
+    #[error("Sandbox unavailable: {0}")]
+    SandboxUnavailable(String),
+    #[error("Invalid executor configuration: {0}")]
+    InvalidConfig(String),
 }
 
 #[enum_dispatch]
@@ -117,6 +125,8 @@ pub enum CodingAgent {
     QwenCode,
     Copilot,
     Droid,
+    CustomAgent,
+    AcpAgent,
     #[cfg(feature = "qa-mode")]
     QaMock(QaMockExecutor),
 }
@@ -187,17 +197,92 @@ impl CodingAgent {
                 BaseAgentCapability::SetupHelper,
                 BaseAgentCapability::ContextUsage,
             ],
-            Self::Amp(_) | Self::Gemini(_) | Self::QwenCode(_) | Self::Droid(_) => {
-                vec![BaseAgentCapability::SessionFork]
-            }
+            Self::Amp(_)
+            | Self::Gemini(_)
+            | Self::QwenCode(_)
+            | Self::Droid(_)
+            | Self::AcpAgent(_) => vec![BaseAgentCapability::SessionFork],
             Self::CursorAgent(_) => vec![BaseAgentCapability::SetupHelper],
-            Self::Copilot(_) => vec![],
+            Self::Copilot(_) | Self::CustomAgent(_) => vec![],
             #[cfg(feature = "qa-mode")]
             Self::QaMock(_) => vec![], // QA mock doesn't need special capabilities
         }
     }
+
+    /// Capabilities the agent this configuration starts advertised on its
+    /// last run. `None` for non-ACP executors and for agents that haven't run
+    /// yet.
+    pub fn acp_agent_info(&self) -> Option<AcpAgentInfo> {
+        let command = match self {
+            Self::Gemini(gemini) => gemini.build_command_builder().ok()?.build_initial().ok()?,
+            Self::QwenCode(qwen) => qwen.build_command_builder().ok()?.build_initial().ok()?,
+            Self::Copilot(copilot) => copilot.build_command_builder().ok()?.build_initial().ok()?,
+            Self::AcpAgent(acp) => acp.build_command_builder().ok()?.build_initial().ok()?,
+            Self::CustomAgent(custom) => custom.acp_command()?,
+            _ => return None,
+        };
+        AcpAgentInfo::last_seen(&command)
+    }
+
+    /// This configuration restricted to reading the repository, for runs that
+    /// must not change it. `None` if the executor has no such mode.
+    pub fn read_only(&self) -> Option<Self> {
+        match self {
+            Self::ClaudeCode(claude) => {
+                let mut claude = claude.clone();
+                claude.plan = None;
+                claude.approvals = None;
+                claude
+                    .cmd
+                    .additional_params
+                    .get_or_insert_with(Vec::new)
+                    .push(format!("--tools={}", CLAUDE_READ_ONLY_TOOLS.join(",")));
+                Some(Self::ClaudeCode(claude))
+            }
+            Self::Codex(codex) => {
+                let mut codex = codex.clone();
+                codex.sandbox = Some(codex::SandboxMode::ReadOnly);
+                codex.ask_for_approval = Some(codex::AskForApproval::Never);
+                Some(Self::Codex(codex))
+            }
+            Self::Opencode(opencode) => {
+                let mut opencode = opencode.clone();
+                opencode.agent = Some("plan".to_string());
+                Some(Self::Opencode(opencode))
+            }
+            Self::Droid(droid) => {
+                let mut droid = droid.clone();
+                droid.autonomy = droid::Autonomy::Normal;
+                Some(Self::Droid(droid))
+            }
+            _ => None,
+        }
+    }
+
+    /// Sandbox the profile runs its executor in, if any
+    pub fn linux_sandbox(&self) -> Option<&LinuxSandbox> {
+        let cmd = match self {
+            Self::ClaudeCode(executor) => &executor.cmd,
+            Self::Amp(executor) => &executor.cmd,
+            Self::Gemini(executor) => &executor.cmd,
+            Self::Codex(executor) => &executor.cmd,
+            Self::Opencode(executor) => &executor.cmd,
+            Self::CursorAgent(executor) => &executor.cmd,
+            Self::QwenCode(executor) => &executor.cmd,
+            Self::Copilot(executor) => &executor.cmd,
+            Self::Droid(executor) => &executor.cmd,
+            Self::CustomAgent(executor) => &executor.cmd,
+            Self::AcpAgent(executor) => &executor.cmd,
+            #[cfg(feature = "qa-mode")]
+            Self::QaMock(_) => return None,
+        };
+        cmd.linux_sandbox.as_ref()
+    }
 }
 
+/// Claude Code tools that can't modify the working tree
+const CLAUDE_READ_ONLY_TOOLS: &[&str] = &["Read", "Glob", "Grep", "LSP", "WebSearch", "WebFetch"];
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
 #[ts(export)]
@@ -405,4 +490,38 @@ mod tests {
         assert!(result.is_ok(), "CURSOR should deserialize via serde");
         assert_eq!(result.unwrap(), BaseCodingAgent::CursorAgent);
     }
+
+    #[test]
+    fn read_only_restricts_supported_executors() {
+        let claude: CodingAgent =
+            serde_json::from_str(r#"{"CLAUDE_CODE": {"plan": true}}"#).unwrap();
+        let Some(CodingAgent::ClaudeCode(claude)) = claude.read_only() else {
+            panic!("Claude Code should support read-only runs");
+        };
+        assert_eq!(claude.plan, None);
+        assert_eq!(
+            claude.cmd.additional_params,
+            Some(vec![
+                "--tools=Read,Glob,Grep,LSP,WebSearch,WebFetch".to_string()
+            ])
+        );
+
+        let codex: CodingAgent = serde_json::from_str(r#"{"CODEX": {}}"#).unwrap();
+        let Some(CodingAgent::Codex(codex)) = codex.read_only() else {
+            panic!("Codex should support read-only runs");
+        };
+        assert_eq!(codex.sandbox, Some(codex::SandboxMode::ReadOnly));
+
+        let amp: CodingAgent = serde_json::from_str(r#"{"AMP": {}}"#).unwrap();
+        assert!(amp.read_only().is_none());
+    }
+
+    #[test]
+    fn acp_agent_profile_needs_only_a_command() {
+        let agent: CodingAgent =
+            serde_json::from_str(r#"{"ACP_AGENT": {"command": "my-agent --acp"}}"#).unwrap();
+        assert_eq!(BaseCodingAgent::from(&agent), BaseCodingAgent::AcpAgent);
+        assert_eq!(agent.capabilities(), vec![BaseAgentCapability::SessionFork]);
+        assert!(serde_json::from_str::<CodingAgent>(r#"{"ACP_AGENT": {}}"#).is_err());
+    }
 }
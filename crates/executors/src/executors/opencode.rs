@@ -20,6 +20,7 @@ use crate::{
         StandardCodingAgentExecutor, opencode::types::OpencodeExecutorEvent,
     },
     logs::utils::patch,
+    sandbox::LinuxSandboxNetwork,
     stdout_dup::create_stdout_pipe_writer,
 };
 
@@ -98,8 +99,27 @@ impl Opencode {
         current_dir: &Path,
         env: &ExecutionEnv,
     ) -> Result<(AsyncGroupChild, ServerPassword), ExecutorError> {
+        // The server is driven over HTTP from outside the sandbox
+        if self
+            .cmd
+            .linux_sandbox
+            .as_ref()
+            .is_some_and(|sandbox| sandbox.network == LinuxSandboxNetwork::Deny)
+        {
+            return Err(ExecutorError::SandboxUnavailable(
+                "OpenCode needs network access to its local server".to_string(),
+            ));
+        }
+        if env.container.is_some() {
+            return Err(ExecutorError::SandboxUnavailable(
+                "OpenCode's local server can't be reached in a workspace container".to_string(),
+            ));
+        }
+
         let command_parts = self.build_command_builder()?.build_initial()?;
-        let (program_path, args) = command_parts.into_resolved().await?;
+        let (program_path, args) = command_parts
+            .into_sandboxed(&self.cmd, current_dir, env)
+            .await?;
 
         let server_password = generate_server_password();
 
@@ -5,9 +5,12 @@ use std::{
 
 use serde_json::Value;
 
-use crate::executors::opencode::{
-    sdk::EventStreamContext,
-    types::{MessageRole, OpencodeExecutorEvent, ProviderListResponse, SdkEvent},
+use crate::{
+    executors::opencode::{
+        sdk::EventStreamContext,
+        types::{MessageRole, OpencodeExecutorEvent, ProviderListResponse, SdkEvent},
+    },
+    logs::TokenUsage,
 };
 
 type ProviderId = String;
@@ -146,11 +149,21 @@ pub(super) async fn maybe_emit_token_usage(context: &EventStreamContext<'_>, eve
         return;
     }
 
+    let usage = TokenUsage {
+        model: model_id.map(str::to_string),
+        input_tokens: tokens.input.into(),
+        output_tokens: u64::from(tokens.output) + u64::from(tokens.reasoning),
+        cache_read_tokens: tokens.cache.as_ref().map(|c| c.read).unwrap_or(0).into(),
+        cache_write_tokens: tokens.cache.as_ref().map(|c| c.write).unwrap_or(0).into(),
+    };
+
     let _ = context
         .log_writer
         .log_event(&OpencodeExecutorEvent::TokenUsage {
             total_tokens,
             model_context_window,
+            message_id: Some(message.id.clone()),
+            usage: Some(usage),
         })
         .await;
 }
@@ -13,8 +13,8 @@ use crate::{
     approvals::ToolCallMetadata,
     logs::{
         ActionType, CommandExitStatus, CommandRunResult, FileChange, NormalizedEntry,
-        NormalizedEntryError, NormalizedEntryType, TodoItem, TokenUsageInfo, ToolResult,
-        ToolStatus,
+        NormalizedEntryError, NormalizedEntryType, TodoItem, TokenUsage, TokenUsageInfo,
+        ToolResult, ToolStatus,
         stderr_processor::normalize_stderr_logs,
         utils::{
             EntryIndexProvider,
@@ -40,6 +40,8 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
     tokio::spawn(async move {
         let mut stored_session_id = false;
         let mut state = LogState::new(entry_index.clone(), msg_store.clone());
+        // Latest token counts of every assistant message, summed for the run
+        let mut message_usage: HashMap<String, TokenUsage> = HashMap::new();
 
         let mut stdout_lines = msg_store.stdout_lines_stream();
         while let Some(Ok(line)) = stdout_lines.next().await {
@@ -71,7 +73,13 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                 OpencodeExecutorEvent::TokenUsage {
                     total_tokens,
                     model_context_window,
+                    message_id,
+                    usage,
                 } => {
+                    if let (Some(message_id), Some(usage)) = (message_id, usage) {
+                        message_usage.insert(message_id, usage);
+                    }
+                    let usage = sum_message_usage(&message_usage);
                     add_normalized_entry(
                         &msg_store,
                         &entry_index,
@@ -80,6 +88,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                             entry_type: NormalizedEntryType::TokenUsageInfo(TokenUsageInfo {
                                 total_tokens,
                                 model_context_window,
+                                usage,
                             }),
                             content: format!(
                                 "Tokens used: {} / Context window: {}",
@@ -152,6 +161,21 @@ fn parse_event(line: &str) -> Option<OpencodeExecutorEvent> {
     serde_json::from_str::<OpencodeExecutorEvent>(line.trim()).ok()
 }
 
+fn sum_message_usage(message_usage: &HashMap<String, TokenUsage>) -> Option<TokenUsage> {
+    let mut total: Option<TokenUsage> = None;
+    for usage in message_usage.values() {
+        let total = total.get_or_insert_with(|| TokenUsage {
+            model: usage.model.clone(),
+            ..Default::default()
+        });
+        total.input_tokens += usage.input_tokens;
+        total.output_tokens += usage.output_tokens;
+        total.cache_read_tokens += usage.cache_read_tokens;
+        total.cache_write_tokens += usage.cache_write_tokens;
+    }
+    total
+}
+
 #[derive(Debug, Clone)]
 struct StreamingText {
     index: usize,
@@ -717,6 +741,7 @@ impl ToolCallState {
         if let Some(ApprovalStatus::Denied { reason }) = &self.approval {
             return ToolStatus::Denied {
                 reason: reason.clone(),
+                rule: None,
             };
         }
         if matches!(self.approval, Some(ApprovalStatus::TimedOut)) {
@@ -2,6 +2,8 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use workspace_utils::approvals::ApprovalStatus;
 
+use crate::logs::TokenUsage;
+
 /// JSON log events emitted by the OpenCode SDK executor.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -21,6 +23,11 @@ pub enum OpencodeExecutorEvent {
     TokenUsage {
         total_tokens: u32,
         model_context_window: u32,
+        /// Assistant message the tokens were billed for
+        #[serde(default)]
+        message_id: Option<String>,
+        #[serde(default)]
+        usage: Option<TokenUsage>,
     },
     ApprovalResponse {
         tool_call_id: String,
@@ -135,6 +142,8 @@ pub(super) struct MessageTokens {
     pub(super) input: u32,
     #[serde(default, deserialize_with = "deserialize_f64_as_u32")]
     pub(super) output: u32,
+    #[serde(default, deserialize_with = "deserialize_f64_as_u32")]
+    pub(super) reasoning: u32,
     pub(super) cache: Option<MessageTokensCache>,
 }
 
@@ -142,6 +151,8 @@ pub(super) struct MessageTokens {
 pub(super) struct MessageTokensCache {
     #[serde(default, deserialize_with = "deserialize_f64_as_u32")]
     pub(super) read: u32,
+    #[serde(default, deserialize_with = "deserialize_f64_as_u32")]
+    pub(super) write: u32,
 }
 
 fn deserialize_f64_as_u32<'de, D>(deserializer: D) -> Result<u32, D::Error>
@@ -33,7 +33,7 @@ pub struct QwenCode {
 }
 
 impl QwenCode {
-    fn build_command_builder(&self) -> Result<CommandBuilder, CommandBuildError> {
+    pub(crate) fn build_command_builder(&self) -> Result<CommandBuilder, CommandBuildError> {
         let mut builder = CommandBuilder::new("npx -y @qwen-code/qwen-code@0.9.1");
 
         if self.yolo.unwrap_or(false) {
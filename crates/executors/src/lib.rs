@@ -1,9 +1,11 @@
 pub mod actions;
 pub mod approvals;
 pub mod command;
+pub mod docker;
 pub mod env;
 pub mod executors;
 pub mod logs;
 pub mod mcp_config;
 pub mod profile;
+pub mod sandbox;
 pub mod stdout_dup;
@@ -102,6 +102,21 @@ pub enum NormalizedEntryType {
 pub struct TokenUsageInfo {
     pub total_tokens: u32,
     pub model_context_window: u32,
+    /// Tokens billed for the run so far, if the executor reports them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub usage: Option<TokenUsage>,
+}
+
+/// Cumulative token counts of one run. Input tokens exclude cache reads and
+/// writes, which are billed at their own rates.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct TokenUsage {
+    pub model: Option<String>,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cache_write_tokens: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -145,6 +160,14 @@ pub enum ToolStatus {
     Failed,
     Denied {
         reason: Option<String>,
+        /// Auto-approval rule that denied the call
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        rule: Option<String>,
+    },
+    /// Approved by an auto-approval rule without asking the user
+    AutoApproved {
+        rule: String,
     },
     PendingApproval {
         approval_id: String,
@@ -160,6 +183,7 @@ impl ToolStatus {
             ApprovalStatus::Approved => Some(ToolStatus::Created),
             ApprovalStatus::Denied { reason } => Some(ToolStatus::Denied {
                 reason: reason.clone(),
+                rule: None,
             }),
             ApprovalStatus::TimedOut => Some(ToolStatus::TimedOut),
             ApprovalStatus::Pending => None, // this should not happen
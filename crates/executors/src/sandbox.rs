@@ -0,0 +1,368 @@
+//! Linux sandbox for executor processes.
+//!
+//! A profile with `linux_sandbox` set runs its executor under bubblewrap or
+//! util-linux `unshare`. The workspace, the git directories its worktrees
+//! commit into, the executor's own state directories and any extra
+//! `writable_paths` stay writable; the rest of the home directory is
+//! read-only. Network access can be cut off entirely.
+
+use std::path::{Path, PathBuf};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use workspace_utils::{log_msg::LogMsg, shell::resolve_executable_path};
+
+use crate::{env::ExecutionEnv, executors::ExecutorError};
+
+/// State directories executors write to, relative to the home directory
+const AGENT_STATE_PATHS: &[&str] = &[
+    ".claude",
+    ".claude.json",
+    ".codex",
+    ".gemini",
+    ".qwen",
+    ".cursor",
+    ".copilot",
+    ".factory",
+    ".amp",
+    ".config/amp",
+    ".config/opencode",
+    ".local/share/opencode",
+    ".local/state/opencode",
+    ".cache",
+    ".npm",
+    ".vibe-kanban",
+];
+
+const READ_ONLY_MARKERS: &[&str] = &["read-only file system", "erofs"];
+
+const NETWORK_MARKERS: &[&str] = &[
+    "network is unreachable",
+    "enetunreach",
+    "could not resolve host",
+    "temporary failure in name resolution",
+    "getaddrinfo enotfound",
+    "eai_again",
+];
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LinuxSandboxBackend {
+    #[default]
+    Bubblewrap,
+    Unshare,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LinuxSandboxNetwork {
+    #[default]
+    Allow,
+    Deny,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+pub struct LinuxSandbox {
+    #[schemars(
+        title = "Backend",
+        description = "Sandbox tool: bubblewrap (bwrap) or unshare (util-linux 2.38 or newer)"
+    )]
+    #[serde(default)]
+    pub backend: LinuxSandboxBackend,
+    #[schemars(
+        title = "Network",
+        description = "Deny cuts off all network access, including the model API"
+    )]
+    #[serde(default)]
+    pub network: LinuxSandboxNetwork,
+    #[schemars(
+        title = "Writable Paths",
+        description = "Paths the executor may write to besides the workspace (~ is the home directory)"
+    )]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub writable_paths: Vec<String>,
+}
+
+/// Something a sandboxed run tried that the sandbox doesn't allow
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SandboxViolation {
+    ReadOnlyFileSystem,
+    NetworkDenied,
+}
+
+impl SandboxViolation {
+    pub fn explanation(&self) -> &'static str {
+        match self {
+            SandboxViolation::ReadOnlyFileSystem => {
+                "The sandbox blocked a write outside the workspace (read-only file system). \
+                 Add the path to the profile's sandbox writable paths to allow it."
+            }
+            SandboxViolation::NetworkDenied => {
+                "The sandbox blocked network access. Set the profile's sandbox network to \
+                 allow to permit it."
+            }
+        }
+    }
+}
+
+impl LinuxSandbox {
+    /// Command line that runs `program` with `args` in `current_dir` inside the
+    /// sandbox
+    pub async fn wrap(
+        &self,
+        program: PathBuf,
+        args: Vec<String>,
+        current_dir: &Path,
+        env: &ExecutionEnv,
+    ) -> Result<(PathBuf, Vec<String>), ExecutorError> {
+        if !cfg!(target_os = "linux") {
+            return Err(ExecutorError::SandboxUnavailable(
+                "the sandbox is only supported on Linux".to_string(),
+            ));
+        }
+
+        let tool = match self.backend {
+            LinuxSandboxBackend::Bubblewrap => "bwrap",
+            LinuxSandboxBackend::Unshare => "unshare",
+        };
+        let tool_path = resolve_executable_path(tool).await.ok_or_else(|| {
+            ExecutorError::SandboxUnavailable(format!("`{tool}` is not installed"))
+        })?;
+
+        let home = dirs::home_dir();
+        let writable = writable_paths(current_dir, env, home.as_deref(), &self.writable_paths);
+        let args = match self.backend {
+            LinuxSandboxBackend::Bubblewrap => {
+                self.bubblewrap_args(&writable, current_dir, &program, args)
+            }
+            LinuxSandboxBackend::Unshare => {
+                let home = home.ok_or_else(|| {
+                    ExecutorError::SandboxUnavailable("no home directory".to_string())
+                })?;
+                self.unshare_args(&home, &writable, &program, args)
+            }
+        };
+        Ok((tool_path, args))
+    }
+
+    fn bubblewrap_args(
+        &self,
+        writable: &[PathBuf],
+        current_dir: &Path,
+        program: &Path,
+        args: Vec<String>,
+    ) -> Vec<String> {
+        let mut bwrap: Vec<String> = [
+            "--die-with-parent",
+            "--ro-bind",
+            "/",
+            "/",
+            "--dev",
+            "/dev",
+            "--proc",
+            "/proc",
+            "--tmpfs",
+            "/tmp",
+        ]
+        .map(String::from)
+        .to_vec();
+        for path in writable {
+            let path = path.to_string_lossy().to_string();
+            bwrap.extend(["--bind".to_string(), path.clone(), path]);
+        }
+        if self.network == LinuxSandboxNetwork::Deny {
+            bwrap.push("--unshare-net".to_string());
+        }
+        bwrap.extend([
+            "--chdir".to_string(),
+            current_dir.to_string_lossy().to_string(),
+            "--".to_string(),
+            program.to_string_lossy().to_string(),
+        ]);
+        bwrap.extend(args);
+        bwrap
+    }
+
+    /// `unshare` has no bind options: as root of a new user namespace the
+    /// script binds the writable paths, remounts the home directory read-only
+    /// and then drops back to the caller's user in a nested namespace.
+    fn unshare_args(
+        &self,
+        home: &Path,
+        writable: &[PathBuf],
+        program: &Path,
+        args: Vec<String>,
+    ) -> Vec<String> {
+        const SCRIPT: &str = r#"set -e
+uid=$1 gid=$2 home=$3
+shift 3
+mount --rbind "$home" "$home"
+while [ "$1" != "--" ]; do
+  mount --bind "$1" "$1"
+  shift
+done
+shift
+mount -o remount,bind,ro "$home"
+exec unshare --user --map-user="$uid" --map-group="$gid" -- "$@""#;
+
+        let mut unshare: Vec<String> = ["--user", "--map-root-user", "--mount"]
+            .map(String::from)
+            .to_vec();
+        if self.network == LinuxSandboxNetwork::Deny {
+            unshare.push("--net".to_string());
+        }
+        unshare.extend([
+            "--".to_string(),
+            "sh".to_string(),
+            "-c".to_string(),
+            SCRIPT.to_string(),
+            "sh".to_string(),
+            current_id("Uid:"),
+            current_id("Gid:"),
+            home.to_string_lossy().to_string(),
+        ]);
+        unshare.extend(
+            writable
+                .iter()
+                .map(|path| path.to_string_lossy().to_string()),
+        );
+        unshare.extend(["--".to_string(), program.to_string_lossy().to_string()]);
+        unshare.extend(args);
+        unshare
+    }
+
+    /// What the sandbox blocked during a run, judging from its raw output
+    pub fn detect_violations(&self, history: &[LogMsg]) -> Vec<SandboxViolation> {
+        let mut violations = Vec::new();
+        for msg in history {
+            let text = match msg {
+                LogMsg::Stdout(text) | LogMsg::Stderr(text) => text.to_lowercase(),
+                _ => continue,
+            };
+            let has = |markers: &[&str]| markers.iter().any(|m| text.contains(m));
+            if has(READ_ONLY_MARKERS) && !violations.contains(&SandboxViolation::ReadOnlyFileSystem)
+            {
+                violations.push(SandboxViolation::ReadOnlyFileSystem);
+            }
+            if self.network == LinuxSandboxNetwork::Deny
+                && has(NETWORK_MARKERS)
+                && !violations.contains(&SandboxViolation::NetworkDenied)
+            {
+                violations.push(SandboxViolation::NetworkDenied);
+            }
+        }
+        violations
+    }
+}
+
+/// Existing paths an isolated executor process must be able to write to: the
+/// workspace, the git directories its worktrees commit into, the executors'
+/// state directories and `extra` paths (`~/` is the home directory)
+pub(crate) fn writable_paths(
+    current_dir: &Path,
+    env: &ExecutionEnv,
+    home: Option<&Path>,
+    extra: &[String],
+) -> Vec<PathBuf> {
+    let workspace_root = &env.repo_context.workspace_root;
+    let mut paths = vec![if workspace_root.as_os_str().is_empty() {
+        current_dir.to_path_buf()
+    } else {
+        workspace_root.clone()
+    }];
+    paths.extend(
+        env.repo_context
+            .repo_paths()
+            .into_iter()
+            .filter_map(|repo| git_common_dir(&repo)),
+    );
+    if let Some(home) = home {
+        paths.extend(AGENT_STATE_PATHS.iter().map(|path| home.join(path)));
+    }
+    paths.extend(
+        extra
+            .iter()
+            .map(|path| match (path.strip_prefix("~/"), home) {
+                (Some(rest), Some(home)) => home.join(rest),
+                _ => PathBuf::from(path),
+            }),
+    );
+
+    let mut existing: Vec<PathBuf> = Vec::new();
+    for path in paths {
+        if path.exists() && !existing.contains(&path) {
+            existing.push(path);
+        }
+    }
+    existing
+}
+
+/// Common git directory of a worktree, where its commits and refs are written
+fn git_common_dir(worktree: &Path) -> Option<PathBuf> {
+    let dot_git = std::fs::read_to_string(worktree.join(".git")).ok()?;
+    let git_dir = PathBuf::from(dot_git.strip_prefix("gitdir:")?.trim());
+    let common_dir = std::fs::read_to_string(git_dir.join("commondir")).ok()?;
+    git_dir.join(common_dir.trim()).canonicalize().ok()
+}
+
+/// Real user or group id of this process, from `/proc/self/status`
+pub(crate) fn current_id(key: &str) -> String {
+    std::fs::read_to_string("/proc/self/status")
+        .ok()
+        .and_then(|status| {
+            status
+                .lines()
+                .find_map(|line| line.strip_prefix(key))
+                .and_then(|ids| ids.split_whitespace().next().map(str::to_string))
+        })
+        .unwrap_or_else(|| "0".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bubblewrap_binds_writable_paths() {
+        let sandbox = LinuxSandbox {
+            network: LinuxSandboxNetwork::Deny,
+            ..Default::default()
+        };
+        let args = sandbox.bubblewrap_args(
+            &[PathBuf::from("/work/ws")],
+            Path::new("/work/ws/repo"),
+            Path::new("/usr/bin/claude"),
+            vec!["-p".to_string()],
+        );
+        let args = args.join(" ");
+
+        assert!(args.starts_with("--die-with-parent --ro-bind / / "));
+        assert!(args.contains(" --bind /work/ws /work/ws --unshare-net "));
+        assert!(args.ends_with(" --chdir /work/ws/repo -- /usr/bin/claude -p"));
+    }
+
+    #[test]
+    fn detects_blocked_writes_and_network() {
+        let history = [
+            LogMsg::Stderr("EROFS: read-only file system, open '/home/u/.zshrc'".to_string()),
+            LogMsg::Stdout("curl: (6) Could not resolve host: example.com".to_string()),
+        ];
+
+        assert_eq!(
+            LinuxSandbox::default().detect_violations(&history),
+            vec![SandboxViolation::ReadOnlyFileSystem]
+        );
+        let deny = LinuxSandbox {
+            network: LinuxSandboxNetwork::Deny,
+            ..Default::default()
+        };
+        assert_eq!(
+            deny.detect_violations(&history),
+            vec![
+                SandboxViolation::ReadOnlyFileSystem,
+                SandboxViolation::NetworkDenied
+            ]
+        );
+    }
+}
@@ -17,9 +17,10 @@ use db::{
             ExecutionContext, ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus,
         },
         execution_process_repo_state::ExecutionProcessRepoState,
+        execution_process_token_usage::ExecutionProcessTokenUsage,
+        project_retry_policy::ProjectRetryPolicy,
         repo::Repo,
-        scratch::{DraftFollowUpData, Scratch, ScratchType},
-        session::{Session, SessionError},
+        session::{CreateSession, Session},
         task::{Task, TaskStatus},
         workspace::Workspace,
         workspace_repo::WorkspaceRepo,
@@ -36,6 +37,7 @@ use executors::{
     env::{ExecutionEnv, RepoContext},
     executors::{BaseCodingAgent, CancellationToken, ExecutorExitResult, ExecutorExitSignal},
     logs::{NormalizedEntryType, utils::patch::extract_normalized_entry_from_patch},
+    profile::{ExecutorConfigs, ExecutorProfileId},
 };
 use futures::{FutureExt, TryStreamExt, stream::select};
 use git::GitService;
@@ -46,6 +48,7 @@ use services::services::{
     config::{Config, DEFAULT_COMMIT_REMINDER_PROMPT},
     container::{ContainerError, ContainerRef, ContainerService},
     diff_stream::{self, DiffStreamHandle},
+    execution_retry::{self, FailureKind, Recovery},
     image::ImageService,
     notification::NotificationService,
     queued_message::QueuedMessageService,
@@ -62,10 +65,15 @@ use utils::{
 };
 use uuid::Uuid;
 
-use crate::{command, copy};
+use crate::{
+    command, copy,
+    runtime::{HostRuntime, WorkspaceRuntime},
+};
 
+/// Container service on host worktrees. `R` decides where the processes of a
+/// workspace run, see [`crate::runtime`].
 #[derive(Clone)]
-pub struct LocalContainerService {
+pub struct LocalContainerService<R = HostRuntime> {
     db: DBService,
     child_store: Arc<RwLock<HashMap<Uuid, Arc<RwLock<AsyncGroupChild>>>>>,
     cancellation_tokens: Arc<RwLock<HashMap<Uuid, CancellationToken>>>,
@@ -82,9 +90,13 @@ pub struct LocalContainerService {
     queued_message_service: QueuedMessageService,
     notification_service: NotificationService,
     remote_client: Option<RemoteClient>,
+    /// Retries done so far, keyed by the session of the retried run. Recorded
+    /// before a retry starts so a fast failure sees it.
+    retry_attempts: Arc<RwLock<HashMap<Uuid, u32>>>,
+    runtime: R,
 }
 
-impl LocalContainerService {
+impl<R: WorkspaceRuntime> LocalContainerService<R> {
     #[allow(clippy::too_many_arguments)]
     pub async fn new(
         db: DBService,
@@ -96,6 +108,7 @@ impl LocalContainerService {
         approvals: Approvals,
         queued_message_service: QueuedMessageService,
         remote_client: Option<RemoteClient>,
+        runtime: R,
     ) -> Self {
         let child_store = Arc::new(RwLock::new(HashMap::new()));
         let cancellation_tokens = Arc::new(RwLock::new(HashMap::new()));
@@ -118,6 +131,8 @@ impl LocalContainerService {
             queued_message_service,
             notification_service,
             remote_client,
+            retry_attempts: Arc::new(RwLock::new(HashMap::new())),
+            runtime,
         };
 
         container.spawn_workspace_cleanup();
@@ -170,7 +185,7 @@ impl LocalContainerService {
         map.remove(id)
     }
 
-    pub async fn cleanup_workspace(db: &DBService, workspace: &Workspace) {
+    pub async fn cleanup_workspace(db: &DBService, runtime: &R, workspace: &Workspace) {
         let Some(container_ref) = &workspace.container_ref else {
             return;
         };
@@ -202,11 +217,16 @@ impl LocalContainerService {
                 });
         }
 
+        runtime.remove(workspace.id).await;
+
         // Clear container_ref so this workspace won't be picked up again
         let _ = Workspace::clear_container_ref(&db.pool, workspace.id).await;
     }
 
-    pub async fn cleanup_expired_workspaces(db: &DBService) -> Result<(), DeploymentError> {
+    pub async fn cleanup_expired_workspaces(
+        db: &DBService,
+        runtime: &R,
+    ) -> Result<(), DeploymentError> {
         if std::env::var("DISABLE_WORKTREE_CLEANUP").is_ok() {
             tracing::info!(
                 "Expired workspace cleanup is disabled via DISABLE_WORKTREE_CLEANUP environment variable"
@@ -224,13 +244,14 @@ impl LocalContainerService {
             expired_workspaces.len()
         );
         for workspace in &expired_workspaces {
-            Self::cleanup_workspace(db, workspace).await;
+            Self::cleanup_workspace(db, runtime, workspace).await;
         }
         Ok(())
     }
 
     pub fn spawn_workspace_cleanup(&self) {
         let db = self.db.clone();
+        let runtime = self.runtime.clone();
         let cleanup_expired = Self::cleanup_expired_workspaces;
         tokio::spawn(async move {
             WorkspaceManager::cleanup_orphan_workspaces(&db.pool).await;
@@ -240,7 +261,7 @@ impl LocalContainerService {
             loop {
                 cleanup_interval.tick().await;
                 tracing::info!("Starting periodic workspace cleanup...");
-                cleanup_expired(&db).await.unwrap_or_else(|e| {
+                cleanup_expired(&db, &runtime).await.unwrap_or_else(|e| {
                     tracing::error!("Failed to clean up expired workspaces: {}", e)
                 });
             }
@@ -480,6 +501,12 @@ impl LocalContainerService {
                     tracing::warn!("Failed to update executor session summary: {}", e);
                 }
 
+                if let Err(e) = container.record_token_usage(&exec_id).await {
+                    tracing::warn!("Failed to record token usage: {}", e);
+                }
+
+                container.report_sandbox_violations(&ctx).await;
+
                 let success = matches!(
                     ctx.execution_process.status,
                     ExecutionProcessStatus::Completed
@@ -493,6 +520,9 @@ impl LocalContainerService {
                     ExecutionProcessStatus::Running
                 );
 
+                // Whether the next action or a queued follow-up was started
+                let mut started_next = false;
+
                 if success || cleanup_done {
                     // Commit changes (if any) and get feedback about whether changes were made
                     let changes_committed = match container.try_commit_changes(&ctx).await {
@@ -520,8 +550,15 @@ impl LocalContainerService {
 
                     if should_start_next {
                         // If the process exited successfully, start the next action
-                        if let Err(e) = container.try_start_next_action(&ctx).await {
-                            tracing::error!("Failed to start next action after completion: {}", e);
+                        // (or the next queued follow-up once the chain is done)
+                        match container.try_start_next_action(&ctx).await {
+                            Ok(started) => started_next = started,
+                            Err(e) => {
+                                tracing::error!(
+                                    "Failed to start next action after completion: {}",
+                                    e
+                                );
+                            }
                         }
                     } else {
                         tracing::info!(
@@ -529,64 +566,43 @@ impl LocalContainerService {
                             ctx.workspace.id
                         );
 
-                        // Manually finalize task since we're bypassing normal execution flow
-                        container.finalize_task(&ctx).await;
-                    }
-                }
-
-                if container.should_finalize(&ctx) {
-                    // Only execute queued messages if the execution succeeded
-                    // If it failed or was killed, just clear the queue and finalize
-                    let should_execute_queued = !matches!(
-                        ctx.execution_process.status,
-                        ExecutionProcessStatus::Failed | ExecutionProcessStatus::Killed
-                    );
-
-                    if let Some(queued_msg) =
-                        container.queued_message_service.take_queued(ctx.session.id)
-                    {
-                        if should_execute_queued {
-                            tracing::info!(
-                                "Found queued message for session {}, starting follow-up execution",
-                                ctx.session.id
-                            );
-
-                            // Delete the scratch since we're consuming the queued message
-                            if let Err(e) = Scratch::delete(
-                                &db.pool,
-                                ctx.session.id,
-                                &ScratchType::DraftFollowUp,
-                            )
+                        // Go on with the session's queue, or manually finalize task
+                        // since we're bypassing normal execution flow
+                        match container
+                            .try_start_queued_follow_up(&ctx.workspace, &ctx.session)
                             .await
-                            {
-                                tracing::warn!(
-                                    "Failed to delete scratch after consuming queued message: {}",
-                                    e
-                                );
-                            }
-
-                            // Execute the queued follow-up
-                            if let Err(e) = container
-                                .start_queued_follow_up(&ctx, &queued_msg.data)
-                                .await
-                            {
-                                tracing::error!("Failed to start queued follow-up: {}", e);
-                                // Fall back to finalization if follow-up fails
-                                container.finalize_task(&ctx).await;
-                            }
-                        } else {
-                            // Execution failed or was killed - discard the queued message and finalize
-                            tracing::info!(
-                                "Discarding queued message for session {} due to execution status {:?}",
-                                ctx.session.id,
-                                ctx.execution_process.status
-                            );
+                        {
+                            Ok(started) => started_next = started.is_some(),
+                            Err(e) => tracing::error!("Failed to start queued follow-up: {}", e),
+                        }
+                        if !started_next {
                             container.finalize_task(&ctx).await;
                         }
-                    } else {
+                    }
+                }
+
+                // A recovered run finalizes the task once the retry or fallback ends.
+                // A failed or stopped run leaves the queue paused until it is resumed.
+                let mut recovering = false;
+                if !started_next && container.should_finalize(&ctx) {
+                    recovering = container.try_recover_failed_run(&ctx).await;
+                    if !recovering {
                         container.finalize_task(&ctx).await;
                     }
                 }
+                // The session's retry count lasts until a coding agent run isn't retried
+                if !recovering
+                    && matches!(
+                        ctx.execution_process.run_reason,
+                        ExecutionProcessRunReason::CodingAgent
+                    )
+                {
+                    container
+                        .retry_attempts
+                        .write()
+                        .await
+                        .remove(&ctx.session.id);
+                }
 
                 // Fire analytics event when CodingAgent execution has finished
                 if config.read().await.analytics_enabled
@@ -785,6 +801,59 @@ impl LocalContainerService {
         Ok(())
     }
 
+    /// Record the token usage the executor last reported, if it reports any
+    async fn record_token_usage(&self, exec_id: &Uuid) -> Result<(), anyhow::Error> {
+        let Some(msg_store) = self.get_msg_store_by_id(exec_id).await else {
+            return Ok(());
+        };
+
+        let usage = msg_store
+            .get_history()
+            .iter()
+            .rev()
+            .find_map(|msg| match msg {
+                LogMsg::JsonPatch(patch) => {
+                    match extract_normalized_entry_from_patch(patch)?.1.entry_type {
+                        NormalizedEntryType::TokenUsageInfo(usage) => Some(usage),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            });
+
+        if let Some(usage) = usage {
+            ExecutionProcessTokenUsage::upsert(&self.db.pool, *exec_id, &usage).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Explain in the run's logs what the executor's sandbox blocked, so a
+    /// failure caused by it isn't mistaken for a bug in the agent's work
+    async fn report_sandbox_violations(&self, ctx: &ExecutionContext) {
+        let Ok(action) = ctx.execution_process.executor_action() else {
+            return;
+        };
+        let profile = match action.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(request) => &request.executor_profile_id,
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => &request.executor_profile_id,
+            _ => return,
+        };
+        let Some(agent) = ExecutorConfigs::get_cached().get_coding_agent(profile) else {
+            return;
+        };
+        let Some(sandbox) = agent.linux_sandbox() else {
+            return;
+        };
+        let Some(msg_store) = self.get_msg_store_by_id(&ctx.execution_process.id).await else {
+            return;
+        };
+
+        for violation in sandbox.detect_violations(&msg_store.get_history()) {
+            msg_store.push_stderr(format!("[sandbox] {}", violation.explanation()));
+        }
+    }
+
     /// Copy project files and images to the workspace.
     /// Skips files/images that already exist (fast no-op if all exist).
     async fn copy_files_and_images(
@@ -882,40 +951,213 @@ impl LocalContainerService {
         Ok(())
     }
 
-    /// Start a follow-up execution from a queued message
-    async fn start_queued_follow_up(
-        &self,
-        ctx: &ExecutionContext,
-        queued_data: &DraftFollowUpData,
-    ) -> Result<ExecutionProcess, ContainerError> {
-        let executor_profile_id = queued_data.executor_profile_id.clone();
+    /// Apply the project's retry policy to a failed coding agent run. Returns
+    /// true if a retry was scheduled or a fallback session was started.
+    async fn try_recover_failed_run(&self, ctx: &ExecutionContext) -> bool {
+        let retries = self
+            .retry_attempts
+            .read()
+            .await
+            .get(&ctx.session.id)
+            .copied()
+            .unwrap_or(0);
 
-        // Validate executor matches session if session has prior executions
-        let expected_executor: Option<String> =
-            ExecutionProcess::latest_executor_profile_for_session(&self.db.pool, ctx.session.id)
-                .await?
-                .map(|profile| profile.executor.to_string())
-                .or_else(|| ctx.session.executor.clone());
+        if !matches!(ctx.execution_process.status, ExecutionProcessStatus::Failed)
+            || !matches!(
+                ctx.execution_process.run_reason,
+                ExecutionProcessRunReason::CodingAgent
+            )
+        {
+            return false;
+        }
+
+        let policy = match ProjectRetryPolicy::find_by_project_id(
+            &self.db.pool,
+            ctx.task.project_id,
+        )
+        .await
+        {
+            Ok(Some(policy)) => policy,
+            Ok(None) => return false,
+            Err(e) => {
+                tracing::error!("Failed to load retry policy: {}", e);
+                return false;
+            }
+        };
+
+        let Ok(action) = ctx.execution_process.executor_action() else {
+            return false;
+        };
+        let (initial_prompt, follow_up, profile) = match action.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(request) => (
+                Some(request.prompt.as_str()),
+                None,
+                &request.executor_profile_id,
+            ),
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => (
+                None,
+                Some(request.prompt.as_str()),
+                &request.executor_profile_id,
+            ),
+            _ => return false,
+        };
 
-        if let Some(expected) = expected_executor {
-            let actual = executor_profile_id.executor.to_string();
-            if expected != actual {
-                return Err(SessionError::ExecutorMismatch { expected, actual }.into());
+        let msg_store = self.get_msg_store_by_id(&ctx.execution_process.id).await;
+        let failure = msg_store
+            .as_ref()
+            .map(|store| execution_retry::classify_failure(&store.get_history()))
+            .unwrap_or(FailureKind::Other);
+
+        match execution_retry::plan_recovery(&policy, failure, retries, profile) {
+            Some(Recovery::Retry { attempt, delay }) => {
+                if let Some(store) = &msg_store {
+                    store.push_stderr(format!(
+                        "Run failed ({failure}), retrying in {}s (attempt {attempt}/{})",
+                        delay.as_secs(),
+                        policy.max_retries
+                    ));
+                }
+                let container = self.clone();
+                let exec_id = ctx.execution_process.id;
+                tokio::spawn(async move {
+                    tokio::time::sleep(delay).await;
+                    container.retry_failed_run(exec_id, attempt).await;
+                });
+                true
             }
+            Some(Recovery::Fallback(fallback)) => {
+                self.retry_attempts.write().await.remove(&ctx.session.id);
+                if let Some(store) = &msg_store {
+                    store.push_stderr(format!(
+                        "Run failed ({failure}), continuing with {} in a new session",
+                        fallback.executor
+                    ));
+                }
+                match self
+                    .start_fallback_session(
+                        ctx,
+                        initial_prompt,
+                        follow_up,
+                        profile,
+                        failure,
+                        &fallback,
+                    )
+                    .await
+                {
+                    Ok(_) => true,
+                    Err(e) => {
+                        tracing::error!("Failed to start fallback session: {}", e);
+                        false
+                    }
+                }
+            }
+            None => false,
         }
+    }
 
-        if ctx.session.executor.is_none() {
-            Session::update_executor(
-                &self.db.pool,
-                ctx.session.id,
-                &executor_profile_id.executor.to_string(),
+    /// Run the action of a failed execution process again in the same session
+    async fn retry_failed_run(&self, failed_exec_id: Uuid, attempt: u32) {
+        let ctx = match ExecutionProcess::load_context(&self.db.pool, failed_exec_id).await {
+            Ok(ctx) => ctx,
+            Err(e) => {
+                tracing::error!("Failed to load context for retry: {}", e);
+                return;
+            }
+        };
+
+        // The user may have started another run during the backoff
+        match ExecutionProcess::has_running_non_dev_server_processes_for_workspace(
+            &self.db.pool,
+            ctx.workspace.id,
+        )
+        .await
+        {
+            Ok(false) => {}
+            Ok(true) => {
+                tracing::info!(
+                    "Skipping retry of {} - workspace {} is busy",
+                    failed_exec_id,
+                    ctx.workspace.id
+                );
+                return;
+            }
+            Err(e) => {
+                tracing::error!("Failed to check running processes for retry: {}", e);
+                self.finalize_task(&ctx).await;
+                return;
+            }
+        }
+
+        let action = match ctx.execution_process.executor_action() {
+            Ok(action) => action.clone(),
+            Err(e) => {
+                tracing::error!("Failed to read executor action for retry: {}", e);
+                self.finalize_task(&ctx).await;
+                return;
+            }
+        };
+
+        self.retry_attempts
+            .write()
+            .await
+            .insert(ctx.session.id, attempt);
+        if let Err(e) = self
+            .start_execution(
+                &ctx.workspace,
+                &ctx.session,
+                &action,
+                &ExecutionProcessRunReason::CodingAgent,
             )
-            .await?;
+            .await
+        {
+            tracing::error!("Failed to start retry of {}: {}", failed_exec_id, e);
+            self.retry_attempts.write().await.remove(&ctx.session.id);
+            self.finalize_task(&ctx).await;
         }
+    }
+
+    /// Continue a failed run with the fallback executor in a new session of the
+    /// same workspace. A failed follow-up is continued from the prompt its
+    /// session started with (or the task), since the new session has none of
+    /// the earlier conversation.
+    async fn start_fallback_session(
+        &self,
+        ctx: &ExecutionContext,
+        initial_prompt: Option<&str>,
+        follow_up: Option<&str>,
+        failed: &ExecutorProfileId,
+        failure: FailureKind,
+        fallback: &ExecutorProfileId,
+    ) -> Result<ExecutionProcess, ContainerError> {
+        let initial_prompt = match initial_prompt {
+            Some(prompt) => prompt.to_string(),
+            None => ExecutionProcess::find_by_session_id(&self.db.pool, ctx.session.id, false)
+                .await?
+                .iter()
+                .find_map(|process| match process.executor_action().ok()?.typ() {
+                    ExecutorActionType::CodingAgentInitialRequest(request) => {
+                        Some(request.prompt.clone())
+                    }
+                    _ => None,
+                })
+                .unwrap_or_else(|| ctx.task.to_prompt()),
+        };
+        let summaries: Vec<String> =
+            CodingAgentTurn::find_by_session_id(&self.db.pool, ctx.session.id)
+                .await?
+                .into_iter()
+                .filter_map(|turn| turn.summary)
+                .collect();
 
-        // Get latest agent turn for session continuity (from coding agent turns)
-        let latest_session_info =
-            CodingAgentTurn::find_latest_session_info(&self.db.pool, ctx.session.id).await?;
+        let session = Session::create(
+            &self.db.pool,
+            &CreateSession {
+                executor: Some(fallback.executor.to_string()),
+            },
+            Uuid::new_v4(),
+            ctx.workspace.id,
+        )
+        .await?;
 
         let repos =
             WorkspaceRepo::find_repos_for_workspace(&self.db.pool, ctx.workspace.id).await?;
@@ -928,27 +1170,24 @@ impl LocalContainerService {
             .filter(|dir| !dir.is_empty())
             .cloned();
 
-        let action_type = if let Some(info) = latest_session_info {
-            ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
-                prompt: queued_data.message.clone(),
-                session_id: info.session_id,
-                reset_to_message_id: None,
-                executor_profile_id: executor_profile_id.clone(),
-                working_dir: working_dir.clone(),
-            })
-        } else {
+        let action = ExecutorAction::new(
             ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
-                prompt: queued_data.message.clone(),
-                executor_profile_id: executor_profile_id.clone(),
+                prompt: execution_retry::fallback_prompt(
+                    &initial_prompt,
+                    follow_up,
+                    failed,
+                    failure,
+                    &summaries,
+                ),
+                executor_profile_id: fallback.clone(),
                 working_dir,
-            })
-        };
-
-        let action = ExecutorAction::new(action_type, cleanup_action.map(Box::new));
+            }),
+            cleanup_action.map(Box::new),
+        );
 
         self.start_execution(
             &ctx.workspace,
-            &ctx.session,
+            &session,
             &action,
             &ExecutionProcessRunReason::CodingAgent,
         )
@@ -970,7 +1209,7 @@ fn failure_exit_status() -> std::process::ExitStatus {
 }
 
 #[async_trait]
-impl ContainerService for LocalContainerService {
+impl<R: WorkspaceRuntime> ContainerService for LocalContainerService<R> {
     fn msg_stores(&self) -> &Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>> {
         &self.msg_stores
     }
@@ -987,6 +1226,10 @@ impl ContainerService for LocalContainerService {
         &self.notification_service
     }
 
+    fn queued_message_service(&self) -> &QueuedMessageService {
+        &self.queued_message_service
+    }
+
     async fn store_db_stream_handle(&self, id: Uuid, handle: JoinHandle<()>) {
         self.add_db_stream_handle(id, handle).await;
     }
@@ -1009,8 +1252,7 @@ impl ContainerService for LocalContainerService {
             .await?
             .ok_or(sqlx::Error::RowNotFound)?;
 
-        let workspace_dir_name =
-            LocalContainerService::dir_name_from_workspace(&workspace.id, &task.title);
+        let workspace_dir_name = Self::dir_name_from_workspace(&workspace.id, &task.title);
         let workspace_dir = WorkspaceManager::get_workspace_base_dir().join(&workspace_dir_name);
 
         let workspace_repos =
@@ -1066,7 +1308,7 @@ impl ContainerService for LocalContainerService {
 
     async fn delete(&self, workspace: &Workspace) -> Result<(), ContainerError> {
         self.try_stop(workspace, true).await;
-        Self::cleanup_workspace(&self.db, workspace).await;
+        Self::cleanup_workspace(&self.db, &self.runtime, workspace).await;
         Ok(())
     }
 
@@ -1091,8 +1333,7 @@ impl ContainerService for LocalContainerService {
                 .parent_task(&self.db.pool)
                 .await?
                 .ok_or(sqlx::Error::RowNotFound)?;
-            let workspace_dir_name =
-                LocalContainerService::dir_name_from_workspace(&workspace.id, &task.title);
+            let workspace_dir_name = Self::dir_name_from_workspace(&workspace.id, &task.title);
             WorkspaceManager::get_workspace_base_dir().join(&workspace_dir_name)
         };
 
@@ -1230,6 +1471,16 @@ impl ContainerService for LocalContainerService {
             env.insert("VK_DOORAY_PROJECT_ID", dooray_project_id);
         }
 
+        self.runtime
+            .prepare(
+                &self.db,
+                workspace,
+                project.id,
+                &execution_process.run_reason,
+                &mut env,
+            )
+            .await?;
+
         // Create the child and stream, add to execution tracker with timeout
         let mut spawned = tokio::time::timeout(
             Duration::from_secs(30),
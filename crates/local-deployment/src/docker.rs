@@ -0,0 +1,239 @@
+//! Docker backend of [`LocalContainerService`], for projects whose processes
+//! run in Docker.
+//!
+//! A workspace container only holds the network namespace that the processes
+//! of the workspace join, see `executors::docker`. It is created on the first
+//! execution, recreated when the project's image changes and removed with the
+//! workspace. It publishes one port on the host's loopback for the workspace's
+//! dev server.
+//!
+//! [`LocalContainerService`]: crate::container::LocalContainerService
+
+use std::{net::TcpListener, path::Path};
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use db::{
+    DBService,
+    models::{
+        execution_process::ExecutionProcessRunReason,
+        project_docker_settings::ProjectDockerSettings, workspace::Workspace,
+    },
+};
+use executors::{
+    docker::{WORKSPACE_LABEL, WorkspaceContainer},
+    env::ExecutionEnv,
+};
+use services::services::container::ContainerError;
+use tokio::process::Command;
+use utils::shell::resolve_executable_path;
+use uuid::Uuid;
+
+use crate::{container::LocalContainerService, runtime::WorkspaceRuntime};
+
+/// Label holding the host port a workspace container publishes
+const DEV_SERVER_PORT_LABEL: &str = "vibe-kanban.dev-server-port";
+
+/// Container service that runs the processes of workspaces in Docker when
+/// their project has Docker settings, and on the host otherwise
+pub type DockerContainerService = LocalContainerService<DockerRuntime>;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DockerRuntime;
+
+#[async_trait]
+impl WorkspaceRuntime for DockerRuntime {
+    async fn prepare(
+        &self,
+        db: &DBService,
+        workspace: &Workspace,
+        project_id: Uuid,
+        run_reason: &ExecutionProcessRunReason,
+        env: &mut ExecutionEnv,
+    ) -> Result<(), ContainerError> {
+        let Some(settings) =
+            ProjectDockerSettings::find_by_project_id(&db.pool, project_id).await?
+        else {
+            return Ok(());
+        };
+
+        let (container, port) = ensure_workspace_container(workspace.id, &settings.image).await?;
+        if matches!(run_reason, ExecutionProcessRunReason::DevServer) {
+            // The port is published on the same number, so URLs the dev
+            // server logs open from the host
+            env.insert("PORT", port.to_string());
+            env.insert("HOST", "0.0.0.0");
+        }
+        env.container = Some(container);
+        Ok(())
+    }
+
+    async fn remove(&self, workspace_id: Uuid) {
+        remove_workspace_containers(workspace_id).await;
+    }
+}
+
+fn container_name(workspace_id: &Uuid) -> String {
+    format!("vk-{workspace_id}")
+}
+
+/// State of an existing workspace container, from `docker inspect`
+struct Inspected {
+    running: bool,
+    image: String,
+    port: Option<u16>,
+}
+
+async fn inspect(docker: &Path, name: &str) -> Option<Inspected> {
+    let format = format!(
+        "{{{{.State.Running}}}} {{{{index .Config.Labels \"{DEV_SERVER_PORT_LABEL}\"}}}} {{{{.Config.Image}}}}"
+    );
+    let output = run_docker(docker, &["inspect", "--format", &format, name])
+        .await
+        .ok()?;
+    let mut fields = output.splitn(3, ' ');
+    Some(Inspected {
+        running: fields.next()? == "true",
+        port: fields.next().and_then(|port| port.parse().ok()),
+        image: fields.next()?.to_string(),
+    })
+}
+
+/// Free port on the host's loopback. It is only reserved until the container
+/// publishing it starts.
+fn free_port() -> Result<u16, ContainerError> {
+    Ok(TcpListener::bind(("127.0.0.1", 0))?.local_addr()?.port())
+}
+
+/// Start the container of a workspace unless it is running with `image`.
+/// Returns it with the host port it publishes.
+pub async fn ensure_workspace_container(
+    workspace_id: Uuid,
+    image: &str,
+) -> Result<(WorkspaceContainer, u16), ContainerError> {
+    let docker = resolve_executable_path("docker")
+        .await
+        .ok_or_else(|| ContainerError::Other(anyhow!("docker is not installed")))?;
+    let name = container_name(&workspace_id);
+
+    let existing = match inspect(&docker, &name).await {
+        Some(existing) if existing.image != image => {
+            // Processes still running next to the old image go with it
+            tracing::info!(
+                "Image of {} changed from {} to {}, recreating",
+                name,
+                existing.image,
+                image
+            );
+            remove_workspace_containers(workspace_id).await;
+            None
+        }
+        // Containers from before ports were published get one
+        Some(existing) if existing.port.is_none() => {
+            remove_workspace_containers(workspace_id).await;
+            None
+        }
+        existing => existing,
+    };
+
+    let port = match existing {
+        Some(Inspected {
+            running,
+            port: Some(port),
+            ..
+        }) => {
+            if !running {
+                run_docker(&docker, &["start", &name]).await?;
+            }
+            port
+        }
+        _ => {
+            let port = free_port()?;
+            let label = format!("{WORKSPACE_LABEL}={workspace_id}");
+            let port_label = format!("{DEV_SERVER_PORT_LABEL}={port}");
+            let publish = format!("127.0.0.1:{port}:{port}");
+            tracing::info!("Creating container {} from {}", name, image);
+            run_docker(
+                &docker,
+                &[
+                    "run",
+                    "-d",
+                    "--init",
+                    "--name",
+                    &name,
+                    "--label",
+                    &label,
+                    "--label",
+                    &port_label,
+                    "-p",
+                    &publish,
+                    "--entrypoint",
+                    "tail",
+                    image,
+                    "-f",
+                    "/dev/null",
+                ],
+            )
+            .await?;
+            port
+        }
+    };
+
+    Ok((
+        WorkspaceContainer {
+            name,
+            image: image.to_string(),
+            workspace_id: workspace_id.to_string(),
+        },
+        port,
+    ))
+}
+
+/// Remove the container of a workspace and any process containers still
+/// running next to it
+pub async fn remove_workspace_containers(workspace_id: Uuid) {
+    let Some(docker) = resolve_executable_path("docker").await else {
+        return;
+    };
+    let filter = format!("label={WORKSPACE_LABEL}={workspace_id}");
+    let ids = match run_docker(&docker, &["ps", "-aq", "--filter", &filter]).await {
+        Ok(ids) => ids,
+        Err(e) => {
+            tracing::debug!(
+                "Failed to list containers of workspace {}: {}",
+                workspace_id,
+                e
+            );
+            return;
+        }
+    };
+    if ids.is_empty() {
+        return;
+    }
+
+    let mut args = vec!["rm", "-f"];
+    args.extend(ids.lines());
+    if let Err(e) = run_docker(&docker, &args).await {
+        tracing::warn!(
+            "Failed to remove containers of workspace {}: {}",
+            workspace_id,
+            e
+        );
+    }
+}
+
+async fn run_docker(docker: &Path, args: &[&str]) -> Result<String, ContainerError> {
+    let output = Command::new(docker)
+        .args(args)
+        .kill_on_drop(true)
+        .output()
+        .await?;
+    if !output.status.success() {
+        return Err(ContainerError::Other(anyhow!(
+            "docker {} failed: {}",
+            args.first().copied().unwrap_or_default(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
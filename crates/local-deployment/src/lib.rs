@@ -11,10 +11,14 @@ use services::services::{
     auth::AuthContext,
     config::{Config, load_config_from_file, save_config_to_file},
     container::ContainerService,
+    dooray::{
+        secret::seal_stored_tokens, status_sync::DoorayStatusSyncService, sync::DooraySyncService,
+    },
     events::EventService,
     file_search::FileSearchCache,
     filesystem::FilesystemService,
     image::ImageService,
+    log_retention::LogRetentionService,
     oauth_credentials::OAuthCredentials,
     pr_monitor::PrMonitorService,
     project::ProjectService,
@@ -31,11 +35,21 @@ use utils::{
 };
 use uuid::Uuid;
 
-use crate::{container::LocalContainerService, pty::PtyService};
+use crate::pty::PtyService;
 mod command;
 pub mod container;
 mod copy;
+pub mod docker;
 pub mod pty;
+pub mod runtime;
+
+/// Container service of the deployment. With the `docker` feature (default)
+/// projects with Docker settings run their processes in containers, without it
+/// everything runs on the host.
+#[cfg(feature = "docker")]
+type WorkspaceContainerService = docker::DockerContainerService;
+#[cfg(not(feature = "docker"))]
+type WorkspaceContainerService = container::LocalContainerService;
 
 #[derive(Clone)]
 pub struct LocalDeployment {
@@ -43,7 +57,7 @@ pub struct LocalDeployment {
     user_id: String,
     db: DBService,
     analytics: Option<AnalyticsService>,
-    container: LocalContainerService,
+    container: WorkspaceContainerService,
     git: GitService,
     project: ProjectService,
     repo: RepoService,
@@ -131,8 +145,9 @@ impl Deployment for LocalDeployment {
             });
         }
 
-        let approvals = Approvals::new(msg_stores.clone());
-        let queued_message_service = QueuedMessageService::new();
+        let approvals = Approvals::new(db.pool.clone(), msg_stores.clone());
+        let queued_message_service =
+            QueuedMessageService::new(db.pool.clone(), events_msg_store.clone());
 
         let oauth_credentials = Arc::new(OAuthCredentials::new(credentials_path()));
         if let Err(e) = oauth_credentials.load().await {
@@ -171,7 +186,7 @@ impl Deployment for LocalDeployment {
             user_id: user_id.clone(),
             analytics_service: s.clone(),
         });
-        let container = LocalContainerService::new(
+        let container = WorkspaceContainerService::new(
             db.clone(),
             msg_stores.clone(),
             config.clone(),
@@ -181,6 +196,7 @@ impl Deployment for LocalDeployment {
             approvals.clone(),
             queued_message_service.clone(),
             remote_client.clone().ok(),
+            Default::default(),
         )
         .await;
 
@@ -199,6 +215,14 @@ impl Deployment for LocalDeployment {
             let rc = remote_client.clone().ok();
             PrMonitorService::spawn(db, analytics, container, rc).await;
         }
+        match seal_stored_tokens(&db.pool).await {
+            Ok(0) => {}
+            Ok(sealed) => tracing::info!("Encrypted {} stored Dooray token(s)", sealed),
+            Err(e) => tracing::warn!("Failed to encrypt stored Dooray tokens: {}", e),
+        }
+        DoorayStatusSyncService::spawn(db.clone(), events.msg_store().clone()).await;
+        DooraySyncService::spawn(db.clone(), events.msg_store().clone(), image.clone()).await;
+        LogRetentionService::spawn(db.clone(), config.clone()).await;
 
         let deployment = Self {
             config,
@@ -0,0 +1,53 @@
+//! Where the processes of a workspace run. [`LocalContainerService`] works on
+//! host worktrees either way; its runtime decides whether setup scripts,
+//! agents and dev servers run directly on the host ([`HostRuntime`]) or in a
+//! container per workspace ([`DockerRuntime`]).
+//!
+//! [`LocalContainerService`]: crate::container::LocalContainerService
+//! [`DockerRuntime`]: crate::docker::DockerRuntime
+
+use async_trait::async_trait;
+use db::{
+    DBService,
+    models::{execution_process::ExecutionProcessRunReason, workspace::Workspace},
+};
+use executors::env::ExecutionEnv;
+use services::services::container::ContainerError;
+use uuid::Uuid;
+
+#[async_trait]
+pub trait WorkspaceRuntime: Clone + Send + Sync + 'static {
+    /// Get a workspace ready to run a process started for `run_reason`, and
+    /// point the process's `env` at it
+    async fn prepare(
+        &self,
+        db: &DBService,
+        workspace: &Workspace,
+        project_id: Uuid,
+        run_reason: &ExecutionProcessRunReason,
+        env: &mut ExecutionEnv,
+    ) -> Result<(), ContainerError>;
+
+    /// Remove what the runtime keeps for a workspace whose worktrees are gone
+    async fn remove(&self, workspace_id: Uuid);
+}
+
+/// Runs every process directly on the host
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HostRuntime;
+
+#[async_trait]
+impl WorkspaceRuntime for HostRuntime {
+    async fn prepare(
+        &self,
+        _db: &DBService,
+        _workspace: &Workspace,
+        _project_id: Uuid,
+        _run_reason: &ExecutionProcessRunReason,
+        _env: &mut ExecutionEnv,
+    ) -> Result<(), ContainerError> {
+        Ok(())
+    }
+
+    async fn remove(&self, _workspace_id: Uuid) {}
+}
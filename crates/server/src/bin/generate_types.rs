@@ -20,6 +20,12 @@ fn generate_types_content() -> String {
         db::models::repo::UpdateRepo::decl(),
         db::models::project_repo::ProjectRepo::decl(),
         db::models::project_repo::CreateProjectRepo::decl(),
+        db::models::project_docker_settings::ProjectDockerSettings::decl(),
+        db::models::project_docker_settings::UpsertProjectDockerSettings::decl(),
+        db::models::project_retry_policy::ProjectRetryPolicy::decl(),
+        db::models::project_retry_policy::UpsertProjectRetryPolicy::decl(),
+        db::models::model_price::ModelPrice::decl(),
+        db::models::model_price::UpsertModelPrice::decl(),
         db::models::workspace_repo::WorkspaceRepo::decl(),
         db::models::workspace_repo::CreateWorkspaceRepo::decl(),
         db::models::workspace_repo::RepoWithTargetBranch::decl(),
@@ -35,33 +41,68 @@ fn generate_types_content() -> String {
         db::models::dooray_settings::DooraySettings::decl(),
         db::models::dooray_settings::CreateDooraySettings::decl(),
         db::models::dooray_settings::UpdateDooraySettings::decl(),
+        db::models::dooray_connection::DoorayConnection::decl(),
+        db::models::dooray_connection::CreateDoorayConnection::decl(),
+        db::models::project_dooray_binding::ProjectDoorayBinding::decl(),
+        db::models::project_dooray_binding::CreateProjectDoorayBinding::decl(),
+        db::models::project_dooray_binding::UpdateProjectDoorayBinding::decl(),
+        db::models::dooray_status_sync::DoorayWorkflowMapping::decl(),
+        db::models::dooray_status_sync::UpsertDoorayWorkflowMapping::decl(),
+        db::models::dooray_status_sync::DoorayTaskSyncState::decl(),
         db::models::design_message::DesignMessage::decl(),
         db::models::design_message::DesignMessageRole::decl(),
         db::models::design_message::CreateDesignMessage::decl(),
         server::routes::dooray::DoorayProject::decl(),
-        server::routes::dooray::DoorayTask::decl(),
-        server::routes::dooray::DoorayTaskBody::decl(),
+        services::services::dooray::DoorayTask::decl(),
+        services::services::dooray::DoorayPostRef::decl(),
+        services::services::dooray::DoorayTaskBody::decl(),
         server::routes::dooray::DoorayTag::decl(),
         server::routes::dooray::DoorayTagGroup::decl(),
         server::routes::dooray::DoorayTagsResponse::decl(),
         server::routes::dooray::UpdateSelectedTagsRequest::decl(),
         server::routes::dooray::SaveSettingsRequest::decl(),
+        server::routes::dooray::RotateTokenRequest::decl(),
+        server::routes::dooray::ValidateTokenRequest::decl(),
+        services::services::dooray::token::DoorayTokenProblem::decl(),
+        services::services::dooray::token::DoorayTokenValidation::decl(),
+        server::routes::dooray::SaveDoorayConnectionRequest::decl(),
         server::routes::dooray::SyncRequest::decl(),
-        server::routes::dooray::SyncResult::decl(),
+        server::routes::dooray::SyncBindingRequest::decl(),
+        services::services::dooray::sync::SyncResult::decl(),
+        services::services::dooray::sync::DooraySyncTrigger::decl(),
+        services::services::dooray::sync::DooraySyncPhase::decl(),
+        services::services::dooray::sync::DooraySyncProgress::decl(),
+        services::services::dooray::webhook::DoorayWebhookEvent::decl(),
+        services::services::dooray::webhook::DoorayWebhookAction::decl(),
+        services::services::dooray::webhook::DoorayWebhookResult::decl(),
         server::routes::dooray::ImportByNumberRequest::decl(),
         server::routes::dooray::ImportByIdRequest::decl(),
         server::routes::dooray::ImportResult::decl(),
+        server::routes::dooray::ImportSubPostsRequest::decl(),
+        services::services::dooray::hierarchy::SubPostImportResult::decl(),
         server::routes::dooray::CreateDoorayCommentRequest::decl(),
         server::routes::dooray::CreateDoorayCommentResult::decl(),
         server::routes::dooray::DoorayComment::decl(),
         server::routes::dooray::GetDoorayCommentsResponse::decl(),
+        server::routes::dooray::DoorayPost::decl(),
+        server::routes::dooray::SetDoorayWorkflowRequest::decl(),
         server::routes::dooray::CreateDoorayTaskRequest::decl(),
         server::routes::dooray::CreateDoorayTaskResult::decl(),
-        server::routes::dooray::DoorayTemplate::decl(),
-        server::routes::dooray::DoorayTemplateDetail::decl(),
+        services::services::dooray::DoorayTemplate::decl(),
+        services::services::dooray::DoorayTemplateDetail::decl(),
         server::routes::dooray::CrossReferenceRequest::decl(),
         server::routes::dooray::DoorayMember::decl(),
         server::routes::dooray::ChangeAssigneeRequest::decl(),
+        services::services::dooray::DoorayWorkflow::decl(),
+        services::services::dooray::DoorayWorkflowRef::decl(),
+        services::services::dooray::status_sync::StatusConflictResolution::decl(),
+        server::routes::dooray::DoorayWorkflowMappingsResponse::decl(),
+        server::routes::dooray::UpdateWorkflowMappingsRequest::decl(),
+        server::routes::dooray::DoorayStatusConflict::decl(),
+        server::routes::dooray::ResolveStatusConflictRequest::decl(),
+        db::models::dooray_report_settings::DoorayReportSettings::decl(),
+        db::models::dooray_report_settings::UpsertDoorayReportSettings::decl(),
+        server::routes::dooray::DoorayReportSettingsResponse::decl(),
         db::models::scratch::DraftFollowUpData::decl(),
         db::models::scratch::DraftWorkspaceData::decl(),
         db::models::scratch::DraftWorkspaceLinkedIssue::decl(),
@@ -85,6 +126,14 @@ fn generate_types_content() -> String {
         db::models::execution_process::ExecutionProcessStatus::decl(),
         db::models::execution_process::ExecutionProcessRunReason::decl(),
         db::models::execution_process_repo_state::ExecutionProcessRepoState::decl(),
+        db::models::execution_log_entry::LogEntryKind::decl(),
+        db::models::execution_log_entry::LogSearchHit::decl(),
+        db::models::execution_process_logs::ProjectLogUsage::decl(),
+        db::models::approval::ApprovalRecord::decl(),
+        db::models::approval::ApprovalResponder::decl(),
+        db::models::approval_rule::ApprovalRule::decl(),
+        db::models::approval_rule::ApprovalRuleAction::decl(),
+        db::models::approval_rule::UpsertApprovalRule::decl(),
         db::models::merge::Merge::decl(),
         db::models::merge::DirectMerge::decl(),
         db::models::merge::PrMerge::decl(),
@@ -157,11 +206,32 @@ fn generate_types_content() -> String {
         server::routes::tasks::DesignChatRequest::decl(),
         server::routes::tasks::DesignChatResponse::decl(),
         server::routes::tasks::DesignChatStreamEvent::decl(),
+        server::routes::tasks::CreateDesignPlanRequest::decl(),
+        services::services::design_plan::DesignPlan::decl(),
+        services::services::design_plan::PlanStep::decl(),
+        services::services::design_plan::DesignPlanResult::decl(),
         server::routes::task_attempts::pr::CreatePrApiRequest::decl(),
         server::routes::images::ImageResponse::decl(),
         server::routes::images::ImageMetadata::decl(),
         server::routes::task_attempts::CreateTaskAttemptBody::decl(),
+        server::routes::task_attempts::CreateTaskAttemptResponse::decl(),
+        server::routes::task_attempts::FailedAttempt::decl(),
         server::routes::task_attempts::WorkspaceRepoInput::decl(),
+        server::routes::task_attempts::compare::CompareAttemptsRequest::decl(),
+        services::services::attempt_comparison::AttemptComparison::decl(),
+        services::services::attempt_comparison::AttemptSummary::decl(),
+        services::services::attempt_comparison::AttemptRanking::decl(),
+        services::services::attempt_comparison::ScriptRunResult::decl(),
+        services::services::token_cost::ModelCost::decl(),
+        services::services::token_cost::TaskCost::decl(),
+        services::services::token_cost::ProjectCost::decl(),
+        services::services::transcript::TranscriptFormat::decl(),
+        services::services::transcript::Transcript::decl(),
+        services::services::transcript::TranscriptTurn::decl(),
+        services::services::transcript::TranscriptEntryKind::decl(),
+        services::services::transcript::TranscriptEntry::decl(),
+        services::services::transcript::TranscriptToolStatus::decl(),
+        services::services::transcript::TranscriptToolCall::decl(),
         server::routes::task_attempts::RunAgentSetupRequest::decl(),
         server::routes::task_attempts::RunAgentSetupResponse::decl(),
         server::routes::task_attempts::gh_cli_setup::GhCliSetupError::decl(),
@@ -207,8 +277,9 @@ fn generate_types_content() -> String {
         services::services::config::UiLanguage::decl(),
         services::services::config::ShowcaseState::decl(),
         services::services::config::SendMessageShortcut::decl(),
+        services::services::config::LogRetentionConfig::decl(),
         git::GitBranch::decl(),
-        services::services::queued_message::QueuedMessage::decl(),
+        db::models::queued_message::QueuedMessage::decl(),
         services::services::queued_message::QueueStatus::decl(),
         git::ConflictOp::decl(),
         executors::actions::ExecutorAction::decl(),
@@ -226,6 +297,7 @@ fn generate_types_content() -> String {
         executors::profile::ExecutorConfig::decl(),
         executors::profile::ExecutorConfigs::decl(),
         executors::executors::BaseAgentCapability::decl(),
+        executors::executors::acp::AcpAgentInfo::decl(),
         executors::executors::claude::ClaudeCode::decl(),
         executors::executors::gemini::Gemini::decl(),
         executors::executors::amp::Amp::decl(),
@@ -242,6 +314,13 @@ fn generate_types_content() -> String {
         executors::executors::droid::Droid::decl(),
         executors::executors::droid::Autonomy::decl(),
         executors::executors::droid::ReasoningEffortLevel::decl(),
+        executors::executors::custom::CustomAgent::decl(),
+        executors::executors::custom::PromptInput::decl(),
+        executors::executors::custom::LogFormat::decl(),
+        executors::executors::acp_agent::AcpAgent::decl(),
+        executors::sandbox::LinuxSandbox::decl(),
+        executors::sandbox::LinuxSandboxBackend::decl(),
+        executors::sandbox::LinuxSandboxNetwork::decl(),
         executors::executors::AppendPrompt::decl(),
         executors::actions::coding_agent_initial::CodingAgentInitialRequest::decl(),
         executors::actions::coding_agent_follow_up::CodingAgentFollowUpRequest::decl(),
@@ -252,6 +331,7 @@ fn generate_types_content() -> String {
         executors::logs::NormalizedEntry::decl(),
         executors::logs::NormalizedEntryType::decl(),
         executors::logs::TokenUsageInfo::decl(),
+        executors::logs::TokenUsage::decl(),
         executors::logs::FileChange::decl(),
         executors::logs::ActionType::decl(),
         executors::logs::TodoItem::decl(),
@@ -344,6 +424,14 @@ fn generate_schemas() -> Result<HashMap<&'static str, String>, serde_json::Error
             "droid",
             generate_json_schema::<executors::executors::droid::Droid>()?,
         ),
+        (
+            "custom_agent",
+            generate_json_schema::<executors::executors::custom::CustomAgent>()?,
+        ),
+        (
+            "acp_agent",
+            generate_json_schema::<executors::executors::acp_agent::AcpAgent>()?,
+        ),
     ]);
     println!(
         "✅ JSON schemas generated. {} schemas created.",
@@ -15,12 +15,21 @@ use git::GitServiceError;
 use git2::Error as Git2Error;
 use local_deployment::pty::PtyError;
 use services::services::{
+    attempt_comparison::AttemptComparisonError,
     config::{ConfigError, EditorOpenError},
     container::ContainerError,
+    design_plan::DesignPlanError,
+    design_session::DesignSessionError,
+    dooray::{
+        DoorayError, account::DoorayAccountError, secret::DooraySecretError,
+        status_sync::StatusSyncError, sync::DooraySyncError,
+    },
     git_host::GitHostError,
     image::ImageError,
     migration::MigrationError,
     project::ProjectServiceError,
+    queued_message::QueuedMessageError,
+    read_only_agent::ReadOnlyAgentError,
     remote_client::RemoteClientError,
     repo::RepoError as RepoServiceError,
     worktree_manager::WorktreeError,
@@ -69,6 +78,8 @@ pub enum ApiError {
     EditorOpen(#[from] EditorOpenError),
     #[error(transparent)]
     RemoteClient(#[from] RemoteClientError),
+    #[error(transparent)]
+    Dooray(#[from] DoorayError),
     #[error("Unauthorized")]
     Unauthorized,
     #[error("Bad request: {0}")]
@@ -241,6 +252,39 @@ fn remote_client_error(err: &RemoteClientError) -> ErrorInfo {
     }
 }
 
+fn dooray_error(err: &DoorayError) -> ErrorInfo {
+    match err {
+        DoorayError::Unauthorized => ErrorInfo::bad_request(
+            "DoorayError",
+            "Dooray rejected the API token. It may have expired or been revoked; please check your Dooray settings.",
+        ),
+        DoorayError::Forbidden => ErrorInfo::bad_request(
+            "DoorayError",
+            "The Dooray API token does not have access to this resource.",
+        ),
+        DoorayError::InvalidToken(_) => {
+            ErrorInfo::bad_request("DoorayError", "Invalid Dooray token format.")
+        }
+        DoorayError::NotFound | DoorayError::EmptyResult => {
+            ErrorInfo::not_found("DoorayError", "Dooray resource not found.")
+        }
+        DoorayError::Api { message, .. } => {
+            ErrorInfo::bad_request("DoorayError", format!("Dooray API error: {}", message))
+        }
+        DoorayError::Transport(_) | DoorayError::Http { .. } => ErrorInfo::with_status(
+            StatusCode::BAD_GATEWAY,
+            "DoorayError",
+            format!("Dooray API error: {}", err),
+        ),
+        DoorayError::Serde(_) => ErrorInfo::with_status(
+            StatusCode::BAD_GATEWAY,
+            "DoorayError",
+            "Unexpected response from Dooray.",
+        ),
+        DoorayError::InvalidBaseUrl(_) => ErrorInfo::internal("DoorayError"),
+    }
+}
+
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
         let info = match &self {
@@ -349,6 +393,7 @@ impl IntoResponse for ApiError {
             }
 
             ApiError::RemoteClient(err) => remote_client_error(err),
+            ApiError::Dooray(err) => dooray_error(err),
 
             ApiError::Pty(PtyError::SessionNotFound(_)) => {
                 ErrorInfo::not_found("PtyError", "PTY session not found.")
@@ -507,3 +552,96 @@ impl From<ProjectRepoError> for ApiError {
         }
     }
 }
+
+impl From<ReadOnlyAgentError> for ApiError {
+    fn from(err: ReadOnlyAgentError) -> Self {
+        match err {
+            ReadOnlyAgentError::Executor(executor_err) => ApiError::Executor(executor_err),
+            other => ApiError::BadRequest(other.to_string()),
+        }
+    }
+}
+
+impl From<DesignSessionError> for ApiError {
+    fn from(err: DesignSessionError) -> Self {
+        match err {
+            DesignSessionError::Database(db_err) => ApiError::Database(db_err),
+            DesignSessionError::Agent(agent_err) => agent_err.into(),
+        }
+    }
+}
+
+impl From<DesignPlanError> for ApiError {
+    fn from(err: DesignPlanError) -> Self {
+        match err {
+            DesignPlanError::Session(session_err) => session_err.into(),
+            DesignPlanError::Database(db_err) => ApiError::Database(db_err),
+            other => ApiError::BadRequest(other.to_string()),
+        }
+    }
+}
+
+impl From<AttemptComparisonError> for ApiError {
+    fn from(err: AttemptComparisonError) -> Self {
+        match err {
+            AttemptComparisonError::Database(db_err) => ApiError::Database(db_err),
+            AttemptComparisonError::Agent(agent_err) => agent_err.into(),
+            other => ApiError::BadRequest(other.to_string()),
+        }
+    }
+}
+
+impl From<StatusSyncError> for ApiError {
+    fn from(err: StatusSyncError) -> Self {
+        match err {
+            StatusSyncError::Dooray(dooray_err) => ApiError::Dooray(dooray_err),
+            StatusSyncError::Database(db_err) => ApiError::Database(db_err),
+            StatusSyncError::Account(account_err) => account_err.into(),
+        }
+    }
+}
+
+impl From<DooraySyncError> for ApiError {
+    fn from(err: DooraySyncError) -> Self {
+        match err {
+            DooraySyncError::Dooray(dooray_err) => ApiError::Dooray(dooray_err),
+            DooraySyncError::Database(db_err) => ApiError::Database(db_err),
+            DooraySyncError::Account(account_err) => account_err.into(),
+        }
+    }
+}
+
+impl From<DoorayAccountError> for ApiError {
+    fn from(err: DoorayAccountError) -> Self {
+        match err {
+            DoorayAccountError::NotConfigured => {
+                ApiError::BadRequest("Dooray integration not configured".to_string())
+            }
+            DoorayAccountError::ConnectionNotFound(_) => {
+                ApiError::Database(sqlx::Error::RowNotFound)
+            }
+            DoorayAccountError::Dooray(dooray_err) => ApiError::Dooray(dooray_err),
+            DoorayAccountError::Secret(secret_err) => secret_err.into(),
+            DoorayAccountError::Database(db_err) => ApiError::Database(db_err),
+        }
+    }
+}
+
+impl From<DooraySecretError> for ApiError {
+    fn from(err: DooraySecretError) -> Self {
+        match err {
+            DooraySecretError::Database(db_err) => ApiError::Database(db_err),
+            DooraySecretError::KeyFile(io_err) => ApiError::Io(io_err),
+            other => ApiError::BadRequest(other.to_string()),
+        }
+    }
+}
+
+impl From<QueuedMessageError> for ApiError {
+    fn from(err: QueuedMessageError) -> Self {
+        match err {
+            QueuedMessageError::Database(db_err) => ApiError::Database(db_err),
+            other => ApiError::BadRequest(other.to_string()),
+        }
+    }
+}
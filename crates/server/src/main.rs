@@ -57,6 +57,9 @@ async fn main() -> Result<(), VibeKanbanError> {
         .cleanup_orphan_executions()
         .await
         .map_err(DeploymentError::from)?;
+    if let Err(e) = deployment.approvals().restore_pending().await {
+        tracing::warn!("Failed to restore pending approvals: {}", e);
+    }
     deployment
         .container()
         .backfill_before_head_commits()
@@ -70,6 +73,17 @@ async fn main() -> Result<(), VibeKanbanError> {
     deployment
         .track_if_analytics_allowed("session_start", serde_json::json!({}))
         .await;
+    // Replaying old logs takes a while, so the server starts without waiting
+    let deployment_for_log_index = deployment.clone();
+    tokio::spawn(async move {
+        if let Err(e) = deployment_for_log_index
+            .container()
+            .backfill_log_index()
+            .await
+        {
+            tracing::warn!("Failed to backfill log index: {}", e);
+        }
+    });
     // Pre-warm file search cache for most active projects
     let deployment_for_cache = deployment.clone();
     tokio::spawn(async move {
@@ -19,13 +19,33 @@ use rmcp::{
 };
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use serde_json;
+use services::services::dooray::{
+    DoorayTask, DoorayTemplate, DoorayTemplateDetail, DoorayWorkflow,
+};
 use uuid::Uuid;
 
 use crate::routes::{
     containers::ContainerQuery,
+    dooray::{
+        CreateDoorayCommentResult, DoorayComment, DoorayPost, GetDoorayCommentsResponse,
+        SetDoorayWorkflowRequest,
+    },
     task_attempts::{CreateTaskAttemptBody, WorkspaceRepoInput},
 };
 
+/// Tools that act on the Dooray post linked to the current task; only registered when
+/// the workspace context has one.
+const DOORAY_TOOLS: &[&str] = &[
+    "get_dooray_post",
+    "list_dooray_comments",
+    "add_dooray_comment",
+    "search_dooray_posts",
+    "list_dooray_templates",
+    "get_dooray_template",
+    "list_dooray_workflows",
+    "update_dooray_workflow",
+];
+
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct CreateTaskRequest {
     #[schemars(description = "The ID of the project to create the task in. This is required!")]
@@ -307,6 +327,164 @@ pub struct GetTaskResponse {
     pub task: TaskDetails,
 }
 
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct DoorayPostDetails {
+    #[schemars(description = "The Dooray post ID")]
+    pub id: String,
+    #[schemars(description = "The post number within its Dooray project")]
+    pub number: Option<i64>,
+    #[schemars(description = "The post subject")]
+    pub subject: String,
+    #[schemars(description = "The workflow (status column) the post is in")]
+    pub workflow: Option<String>,
+    #[schemars(description = "The post body (markdown)")]
+    pub body: Option<String>,
+}
+
+impl DoorayPostDetails {
+    fn from_post(post: DoorayPost) -> Self {
+        Self {
+            id: post.id,
+            number: post.number,
+            subject: post.subject,
+            workflow: post.workflow.and_then(|w| w.name),
+            body: post.body,
+        }
+    }
+
+    fn from_task(task: DoorayTask) -> Self {
+        Self {
+            id: task.id,
+            number: Some(task.number),
+            subject: task.subject,
+            workflow: task.workflow.and_then(|w| w.name),
+            body: task.body.and_then(|b| b.content),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ListDoorayCommentsResponse {
+    pub comments: Vec<DoorayCommentSummary>,
+    pub count: usize,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct DoorayCommentSummary {
+    #[schemars(description = "The comment author's name")]
+    pub author: String,
+    #[schemars(description = "The comment content")]
+    pub content: String,
+    #[schemars(description = "When the comment was written")]
+    pub created_at: String,
+}
+
+impl DoorayCommentSummary {
+    fn from_comment(comment: DoorayComment) -> Self {
+        Self {
+            author: comment.author_name,
+            content: comment.content,
+            created_at: comment.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct AddDoorayCommentRequest {
+    #[schemars(
+        description = "The comment content in markdown. Dooray task URLs are turned into mentions."
+    )]
+    pub content: String,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct AddDoorayCommentResponse {
+    pub success: bool,
+    pub dooray_task_id: String,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct SearchDoorayPostsRequest {
+    #[schemars(description = "Post number to look up (the `123` in `PROJ/123`)")]
+    pub number: Option<i64>,
+    #[schemars(description = "Keyword to search for in post subjects")]
+    pub keyword: Option<String>,
+    #[schemars(description = "Maximum number of posts to return (default: 20)")]
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct SearchDoorayPostsResponse {
+    pub posts: Vec<DoorayPostDetails>,
+    pub count: usize,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct DoorayTemplateSummary {
+    #[schemars(description = "The template ID")]
+    pub id: String,
+    #[schemars(description = "The template name")]
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ListDoorayTemplatesResponse {
+    pub templates: Vec<DoorayTemplateSummary>,
+    pub count: usize,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetDoorayTemplateRequest {
+    #[schemars(
+        description = "The ID of the template to retrieve. Use `list_dooray_templates` to find it."
+    )]
+    pub template_id: String,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct DoorayTemplateDetails {
+    #[schemars(description = "The template ID")]
+    pub id: String,
+    #[schemars(description = "The template name")]
+    pub name: String,
+    #[schemars(description = "Default subject of posts created from the template")]
+    pub subject: Option<String>,
+    #[schemars(description = "The template body (markdown)")]
+    pub body: Option<String>,
+    #[schemars(description = "Writing guide shown with the template")]
+    pub guide: Option<String>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct DoorayWorkflowSummary {
+    #[schemars(description = "The workflow ID")]
+    pub id: String,
+    #[schemars(description = "The workflow name")]
+    pub name: String,
+    #[schemars(description = "One of 'backlog', 'registered', 'working' or 'closed'")]
+    pub class: String,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ListDoorayWorkflowsResponse {
+    pub workflows: Vec<DoorayWorkflowSummary>,
+    pub current_workflow: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct UpdateDoorayWorkflowRequest {
+    #[schemars(
+        description = "The ID of the workflow to move the post to. Use `list_dooray_workflows` to find it."
+    )]
+    pub workflow_id: String,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct UpdateDoorayWorkflowResponse {
+    pub success: bool,
+    pub workflow_id: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct TaskServer {
     client: reqwest::Client,
@@ -336,6 +514,12 @@ pub struct McpContext {
         description = "Repository info and target branches for each repo in this workspace"
     )]
     pub workspace_repos: Vec<McpRepoContext>,
+    #[schemars(description = "Dooray project of the post linked to the task, if any")]
+    pub dooray_project_id: Option<String>,
+    #[schemars(description = "Dooray post linked to the task, if any")]
+    pub dooray_task_id: Option<String>,
+    #[schemars(description = "Number of the linked Dooray post (e.g. 'PROJECT-123')")]
+    pub dooray_task_number: Option<String>,
 }
 
 impl TaskServer {
@@ -358,6 +542,15 @@ impl TaskServer {
             tracing::info!("VK context loaded, get_context tool available");
         }
 
+        if context.as_ref().and_then(McpContext::dooray_post).is_none() {
+            for tool in DOORAY_TOOLS {
+                self.tool_router.map.remove(*tool);
+            }
+            tracing::debug!(
+                "Task is not linked to a Dooray post, Dooray tools will not be registered"
+            );
+        }
+
         self.context = context;
         self
     }
@@ -410,10 +603,23 @@ impl TaskServer {
             workspace_id: ctx.workspace.id,
             workspace_branch: ctx.workspace.branch,
             workspace_repos,
+            dooray_project_id: ctx.task.dooray_project_id,
+            dooray_task_id: ctx.task.dooray_task_id,
+            dooray_task_number: ctx.task.dooray_task_number,
         })
     }
 }
 
+impl McpContext {
+    /// `(dooray_project_id, dooray_task_id)` of the linked Dooray post
+    fn dooray_post(&self) -> Option<(&str, &str)> {
+        Some((
+            self.dooray_project_id.as_deref()?,
+            self.dooray_task_id.as_deref()?,
+        ))
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct ApiResponseEnvelope<T> {
     success: bool,
@@ -505,6 +711,16 @@ impl TaskServer {
         Ok(())
     }
 
+    /// The Dooray post linked to the current task, or an error result for the tool call
+    fn linked_dooray_post(&self) -> Result<(&str, &str), CallToolResult> {
+        self.context
+            .as_ref()
+            .and_then(McpContext::dooray_post)
+            .ok_or_else(|| {
+                Self::err("The current task is not linked to a Dooray post", None).unwrap()
+            })
+    }
+
     fn url(&self, path: &str) -> String {
         format!(
             "{}/{}",
@@ -897,6 +1113,7 @@ impl TaskServer {
             task_id,
             executor_profile_id,
             repos: workspace_repos,
+            additional_executor_profile_ids: None,
         };
 
         let url = self.url("/api/task-attempts");
@@ -999,6 +1216,267 @@ impl TaskServer {
 
         TaskServer::success(&response)
     }
+
+    #[tool(
+        description = "Read the Dooray post linked to the current task: subject, number, workflow and markdown body. Use it to gather the task's requirements."
+    )]
+    async fn get_dooray_post(&self) -> Result<CallToolResult, ErrorData> {
+        let (project_id, task_id) = match self.linked_dooray_post() {
+            Ok(ids) => ids,
+            Err(e) => return Ok(e),
+        };
+        let url = self.url(&format!(
+            "/api/dooray/projects/{}/tasks/{}",
+            project_id, task_id
+        ));
+        let post: DoorayPost = match self.send_json(self.client.get(&url)).await {
+            Ok(p) => p,
+            Err(e) => return Ok(e),
+        };
+
+        TaskServer::success(&DoorayPostDetails::from_post(post))
+    }
+
+    #[tool(description = "List the comments on the Dooray post linked to the current task.")]
+    async fn list_dooray_comments(&self) -> Result<CallToolResult, ErrorData> {
+        let (project_id, task_id) = match self.linked_dooray_post() {
+            Ok(ids) => ids,
+            Err(e) => return Ok(e),
+        };
+        let url = self.url(&format!(
+            "/api/dooray/projects/{}/tasks/{}/comments",
+            project_id, task_id
+        ));
+        let response: GetDoorayCommentsResponse = match self.send_json(self.client.get(&url)).await
+        {
+            Ok(r) => r,
+            Err(e) => return Ok(e),
+        };
+
+        let comments: Vec<DoorayCommentSummary> = response
+            .comments
+            .into_iter()
+            .map(DoorayCommentSummary::from_comment)
+            .collect();
+        TaskServer::success(&ListDoorayCommentsResponse {
+            count: comments.len(),
+            comments,
+        })
+    }
+
+    #[tool(
+        description = "Add a markdown comment to the Dooray post linked to the current task. Use it to report progress or ask the task owner questions."
+    )]
+    async fn add_dooray_comment(
+        &self,
+        Parameters(AddDoorayCommentRequest { content }): Parameters<AddDoorayCommentRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let (project_id, task_id) = match self.linked_dooray_post() {
+            Ok(ids) => ids,
+            Err(e) => return Ok(e),
+        };
+        if content.trim().is_empty() {
+            return Self::err("Comment content must not be empty.".to_string(), None);
+        }
+
+        let url = self.url("/api/dooray/comment");
+        let payload = serde_json::json!({
+            "dooray_project_id": project_id,
+            "dooray_task_id": task_id,
+            "content": content,
+        });
+        let result: CreateDoorayCommentResult =
+            match self.send_json(self.client.post(&url).json(&payload)).await {
+                Ok(r) => r,
+                Err(e) => return Ok(e),
+            };
+        if !result.success {
+            return Self::err(
+                "Failed to add the Dooray comment".to_string(),
+                Some(result.message),
+            );
+        }
+
+        TaskServer::success(&AddDoorayCommentResponse {
+            success: true,
+            dooray_task_id: task_id.to_string(),
+        })
+    }
+
+    #[tool(
+        description = "Search posts in the Dooray project of the current task by post number or by a keyword in the subject. Pass `number`, `keyword`, or both."
+    )]
+    async fn search_dooray_posts(
+        &self,
+        Parameters(SearchDoorayPostsRequest {
+            number,
+            keyword,
+            limit,
+        }): Parameters<SearchDoorayPostsRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let (project_id, _) = match self.linked_dooray_post() {
+            Ok(ids) => ids,
+            Err(e) => return Ok(e),
+        };
+        if number.is_none() && keyword.as_deref().is_none_or(|k| k.trim().is_empty()) {
+            return Self::err("Pass `number` or `keyword`.".to_string(), None);
+        }
+
+        let url = self.url(&format!("/api/dooray/projects/{}/search", project_id));
+        let mut query: Vec<(&str, String)> = Vec::new();
+        if let Some(number) = number {
+            query.push(("number", number.to_string()));
+        }
+        if let Some(keyword) = keyword {
+            query.push(("keyword", keyword));
+        }
+        if let Some(limit) = limit {
+            query.push(("limit", limit.to_string()));
+        }
+        let posts: Vec<DoorayTask> = match self.send_json(self.client.get(&url).query(&query)).await
+        {
+            Ok(p) => p,
+            Err(e) => return Ok(e),
+        };
+
+        let posts: Vec<DoorayPostDetails> = posts
+            .into_iter()
+            .map(DoorayPostDetails::from_task)
+            .collect();
+        TaskServer::success(&SearchDoorayPostsResponse {
+            count: posts.len(),
+            posts,
+        })
+    }
+
+    #[tool(description = "List the post templates of the Dooray project of the current task.")]
+    async fn list_dooray_templates(&self) -> Result<CallToolResult, ErrorData> {
+        let (project_id, _) = match self.linked_dooray_post() {
+            Ok(ids) => ids,
+            Err(e) => return Ok(e),
+        };
+        let url = self.url(&format!("/api/dooray/projects/{}/templates", project_id));
+        let templates: Vec<DoorayTemplate> = match self.send_json(self.client.get(&url)).await {
+            Ok(t) => t,
+            Err(e) => return Ok(e),
+        };
+
+        let templates: Vec<DoorayTemplateSummary> = templates
+            .into_iter()
+            .map(|t| DoorayTemplateSummary {
+                id: t.id,
+                name: t.template_name,
+            })
+            .collect();
+        TaskServer::success(&ListDoorayTemplatesResponse {
+            count: templates.len(),
+            templates,
+        })
+    }
+
+    #[tool(
+        description = "Read a Dooray post template, including its body and writing guide. `template_id` is required."
+    )]
+    async fn get_dooray_template(
+        &self,
+        Parameters(GetDoorayTemplateRequest { template_id }): Parameters<GetDoorayTemplateRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let (project_id, _) = match self.linked_dooray_post() {
+            Ok(ids) => ids,
+            Err(e) => return Ok(e),
+        };
+        let url = self.url(&format!(
+            "/api/dooray/projects/{}/templates/{}",
+            project_id, template_id
+        ));
+        let template: DoorayTemplateDetail = match self.send_json(self.client.get(&url)).await {
+            Ok(t) => t,
+            Err(e) => return Ok(e),
+        };
+
+        TaskServer::success(&DoorayTemplateDetails {
+            id: template.id,
+            name: template.template_name,
+            subject: template.subject,
+            body: template.body.and_then(|b| b.content),
+            guide: template.guide.and_then(|g| g.content),
+        })
+    }
+
+    #[tool(
+        description = "List the workflows (status columns) of the Dooray project of the current task and the workflow the linked post is in."
+    )]
+    async fn list_dooray_workflows(&self) -> Result<CallToolResult, ErrorData> {
+        let (project_id, task_id) = match self.linked_dooray_post() {
+            Ok(ids) => ids,
+            Err(e) => return Ok(e),
+        };
+
+        #[derive(Deserialize)]
+        struct WorkflowsResponse {
+            workflows: Vec<DoorayWorkflow>,
+        }
+
+        let url = self.url(&format!("/api/dooray/projects/{}/workflows", project_id));
+        let response: WorkflowsResponse = match self.send_json(self.client.get(&url)).await {
+            Ok(r) => r,
+            Err(e) => return Ok(e),
+        };
+        let url = self.url(&format!(
+            "/api/dooray/projects/{}/tasks/{}",
+            project_id, task_id
+        ));
+        let post: DoorayPost = match self.send_json(self.client.get(&url)).await {
+            Ok(p) => p,
+            Err(e) => return Ok(e),
+        };
+
+        TaskServer::success(&ListDoorayWorkflowsResponse {
+            workflows: response
+                .workflows
+                .into_iter()
+                .map(|w| DoorayWorkflowSummary {
+                    id: w.id,
+                    name: w.name,
+                    class: w.class,
+                })
+                .collect(),
+            current_workflow: post.workflow.map(|w| w.id),
+        })
+    }
+
+    #[tool(
+        description = "Move the Dooray post linked to the current task to another workflow (status column). Use `list_dooray_workflows` to find the `workflow_id`."
+    )]
+    async fn update_dooray_workflow(
+        &self,
+        Parameters(UpdateDoorayWorkflowRequest { workflow_id }): Parameters<
+            UpdateDoorayWorkflowRequest,
+        >,
+    ) -> Result<CallToolResult, ErrorData> {
+        let (project_id, task_id) = match self.linked_dooray_post() {
+            Ok(ids) => ids,
+            Err(e) => return Ok(e),
+        };
+        let url = self.url(&format!(
+            "/api/dooray/projects/{}/tasks/{}/workflow",
+            project_id, task_id
+        ));
+        let payload = SetDoorayWorkflowRequest {
+            workflow_id: workflow_id.clone(),
+        };
+        if let Err(e) = self
+            .send_empty_json(self.client.post(&url).json(&payload))
+            .await
+        {
+            return Ok(e);
+        }
+
+        TaskServer::success(&UpdateDoorayWorkflowResponse {
+            success: true,
+            workflow_id,
+        })
+    }
 }
 
 #[tool_handler]
@@ -1009,6 +1487,15 @@ impl ServerHandler for TaskServer {
             let context_instruction = "Use 'get_context' to fetch project/task/workspace metadata for the active Vibe Kanban workspace session when available.";
             instruction = format!("{} {}", context_instruction, instruction);
         }
+        if self
+            .context
+            .as_ref()
+            .and_then(McpContext::dooray_post)
+            .is_some()
+        {
+            let dooray_instruction = "The current task is linked to a Dooray post. Read its requirements with 'get_dooray_post' and 'list_dooray_comments', search related posts with 'search_dooray_posts', read templates with 'list_dooray_templates'/'get_dooray_template', report progress with 'add_dooray_comment', and move the post with 'list_dooray_workflows'/'update_dooray_workflow'.";
+            instruction = format!("{} {}", instruction, dooray_instruction);
+        }
 
         ServerInfo {
             protocol_version: ProtocolVersion::V_2025_03_26,
@@ -9,6 +9,7 @@ use db::models::{
     workspace::Workspace,
 };
 use deployment::Deployment;
+use serde::Deserialize;
 use uuid::Uuid;
 
 use crate::DeploymentImpl;
@@ -148,9 +149,15 @@ pub async fn load_tag_middleware(
     Ok(next.run(request).await)
 }
 
+/// Path parameters of session routes; nested routes may capture more
+#[derive(Deserialize)]
+pub struct SessionPath {
+    session_id: Uuid,
+}
+
 pub async fn load_session_middleware(
     State(deployment): State<DeploymentImpl>,
-    Path(session_id): Path<Uuid>,
+    Path(SessionPath { session_id }): Path<SessionPath>,
     mut request: Request,
     next: Next,
 ) -> Result<Response, StatusCode> {
@@ -15,6 +15,7 @@ use deployment::{Deployment, DeploymentError};
 use executors::{
     executors::{
         AvailabilityInfo, BaseAgentCapability, BaseCodingAgent, StandardCodingAgentExecutor,
+        acp::AcpAgentInfo,
     },
     mcp_config::{McpConfig, read_agent_config, write_agent_config},
     profile::{ExecutorConfigs, ExecutorProfileId},
@@ -90,6 +91,9 @@ pub struct UserSystemInfo {
     pub environment: Environment,
     /// Capabilities supported per executor (e.g., { "CLAUDE_CODE": ["SESSION_FORK"] })
     pub capabilities: HashMap<String, Vec<BaseAgentCapability>>,
+    /// What ACP agents advertised on their last run, per executor and variant
+    /// (e.g., { "ACP_AGENT": { "DEFAULT": { ... } } })
+    pub acp_capabilities: HashMap<String, HashMap<String, AcpAgentInfo>>,
 }
 
 // TODO: update frontend, BE schema has changed, this replaces GET /config and /config/constants
@@ -105,22 +109,31 @@ async fn get_user_system_info(
     .await
     .unwrap_or(LoginStatus::LoggedOut);
 
+    let profiles = ExecutorConfigs::get_cached();
+    let mut capabilities = HashMap::new();
+    let mut acp_capabilities: HashMap<String, HashMap<String, AcpAgentInfo>> = HashMap::new();
+    for (key, executor) in &profiles.executors {
+        if let Some(agent) = profiles.get_coding_agent(&ExecutorProfileId::new(*key)) {
+            capabilities.insert(key.to_string(), agent.capabilities());
+        }
+        for (variant, agent) in &executor.configurations {
+            if let Some(info) = agent.acp_agent_info() {
+                acp_capabilities
+                    .entry(key.to_string())
+                    .or_default()
+                    .insert(variant.clone(), info);
+            }
+        }
+    }
+
     let user_system_info = UserSystemInfo {
         config: config.clone(),
         analytics_user_id: deployment.user_id().to_string(),
         login_status,
-        profiles: ExecutorConfigs::get_cached(),
+        profiles,
         environment: Environment::new(),
-        capabilities: {
-            let mut caps: HashMap<String, Vec<BaseAgentCapability>> = HashMap::new();
-            let profs = ExecutorConfigs::get_cached();
-            for key in profs.executors.keys() {
-                if let Some(agent) = profs.get_coding_agent(&ExecutorProfileId::new(*key)) {
-                    caps.insert(key.to_string(), agent.capabilities());
-                }
-            }
-            caps
-        },
+        capabilities,
+        acp_capabilities,
     };
 
     ResponseJson(ApiResponse::success(user_system_info))
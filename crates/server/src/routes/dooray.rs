@@ -1,44 +1,133 @@
+use std::collections::{HashMap, HashSet};
+
 use axum::{
     Json, Router,
-    extract::State,
+    extract::{Path, Query, State},
+    http::HeaderMap,
     response::Json as ResponseJson,
     routing::{get, post, put},
 };
 use db::models::{
+    dooray_connection::{CreateDoorayConnection, DoorayConnection},
+    dooray_report_settings::{DoorayReportSettings, UpsertDoorayReportSettings},
     dooray_settings::{CreateDooraySettings, DooraySettings},
+    dooray_status_sync::{DoorayTaskSyncState, DoorayWorkflowMapping, UpsertDoorayWorkflowMapping},
+    project::Project,
+    project_dooray_binding::{
+        CreateProjectDoorayBinding, ProjectDoorayBinding, UpdateProjectDoorayBinding,
+    },
     task::{CreateTask, Task, TaskStatus},
 };
 use deployment::Deployment;
-use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
 use serde::{Deserialize, Serialize};
+use services::services::dooray::{
+    CreatePostRequest, DoorayClient, DoorayError, DoorayTask, DoorayTaskBody, DoorayTemplate,
+    DoorayTemplateDetail, DoorayWorkflow, DoorayWorkflowRef, PostListQuery,
+    account::DoorayAccount,
+    attachments,
+    hierarchy::{self, ImportTarget, SubPostImportResult},
+    mentions::{
+        build_mention_html, fetch_mention_info, process_body_with_mentions, task_id_from_url,
+    },
+    report::DEFAULT_REPORT_TEMPLATE,
+    secret::{reveal_token, seal_token},
+    status_sync::{self, StatusConflictResolution, StatusMapping},
+    sync::{DooraySync, DooraySyncTrigger, SyncMode, SyncResult},
+    token::{self, DoorayTokenValidation},
+    webhook::{self, DoorayWebhookPayload, DoorayWebhookResult},
+};
 use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
 use crate::{DeploymentImpl, error::ApiError};
 
-const DOORAY_API_BASE: &str = "https://api.dooray.com";
-
 pub fn router() -> Router<DeploymentImpl> {
     Router::new()
-        .route("/dooray/settings", get(get_settings).post(save_settings).delete(delete_settings))
+        .route(
+            "/dooray/settings",
+            get(get_settings)
+                .post(save_settings)
+                .delete(delete_settings),
+        )
+        .route("/dooray/settings/token", post(rotate_settings_token))
         .route("/dooray/settings/tags", post(update_selected_tags))
         .route("/dooray/settings/project", post(update_selected_project))
+        .route(
+            "/dooray/connections",
+            get(get_connections).post(create_connection),
+        )
+        .route(
+            "/dooray/connections/{connection_id}",
+            put(update_connection).delete(delete_connection),
+        )
+        .route("/dooray/bindings", get(get_bindings).post(create_binding))
+        .route(
+            "/dooray/bindings/{binding_id}",
+            put(update_binding).delete(delete_binding),
+        )
+        .route("/dooray/bindings/{binding_id}/sync", post(sync_binding))
+        .route("/dooray/validate-token", post(validate_token))
         .route("/dooray/projects", get(get_dooray_projects))
-        .route("/dooray/projects/{dooray_project_id}/tasks", get(get_dooray_tasks))
-        .route("/dooray/projects/{dooray_project_id}/tags", get(get_dooray_tags))
+        .route(
+            "/dooray/projects/{dooray_project_id}/tasks",
+            get(get_dooray_tasks),
+        )
+        .route(
+            "/dooray/projects/{dooray_project_id}/tasks/{dooray_task_id}",
+            get(get_dooray_post),
+        )
+        .route(
+            "/dooray/projects/{dooray_project_id}/tasks/{dooray_task_id}/workflow",
+            post(set_dooray_post_workflow),
+        )
+        .route(
+            "/dooray/projects/{dooray_project_id}/search",
+            get(search_dooray_posts),
+        )
+        .route(
+            "/dooray/projects/{dooray_project_id}/tags",
+            get(get_dooray_tags),
+        )
         .route("/dooray/sync", post(sync_dooray_tasks))
+        .route("/dooray/webhook", post(receive_webhook))
         .route("/dooray/import-by-number", post(import_by_number))
         .route("/dooray/import-by-id", post(import_by_id))
+        .route("/dooray/import-sub-posts", post(import_sub_posts))
         .route("/dooray/comment", post(create_dooray_comment))
-        .route("/dooray/projects/{dooray_project_id}/tasks/{dooray_task_id}/comments", get(get_dooray_comments))
+        .route(
+            "/dooray/projects/{dooray_project_id}/tasks/{dooray_task_id}/comments",
+            get(get_dooray_comments),
+        )
         .route("/dooray/tasks", post(create_dooray_task))
         .route("/dooray/tasks/{dooray_task_id}", put(update_dooray_task))
-        .route("/dooray/projects/{dooray_project_id}/templates", get(get_dooray_templates))
-        .route("/dooray/projects/{dooray_project_id}/templates/{template_id}", get(get_dooray_template))
+        .route(
+            "/dooray/projects/{dooray_project_id}/templates",
+            get(get_dooray_templates),
+        )
+        .route(
+            "/dooray/projects/{dooray_project_id}/templates/{template_id}",
+            get(get_dooray_template),
+        )
         .route("/dooray/cross-reference", post(create_cross_reference))
-        .route("/dooray/projects/{dooray_project_id}/members", get(get_dooray_members))
+        .route(
+            "/dooray/projects/{dooray_project_id}/members",
+            get(get_dooray_members),
+        )
         .route("/dooray/change-assignee", post(change_assignee))
+        .route(
+            "/dooray/projects/{dooray_project_id}/workflows",
+            get(get_workflow_mappings).put(update_workflow_mappings),
+        )
+        .route("/dooray/status-conflicts", get(get_status_conflicts))
+        .route(
+            "/dooray/status-conflicts/{task_id}/resolve",
+            post(resolve_status_conflict),
+        )
+        .route(
+            "/dooray/report-settings/{project_id}",
+            get(get_report_settings).put(update_report_settings),
+        )
 }
 
 // ============== Settings Endpoints ==============
@@ -48,11 +137,7 @@ async fn get_settings(
 ) -> Result<ResponseJson<ApiResponse<Option<DooraySettings>>>, ApiError> {
     let settings = DooraySettings::get(&deployment.db().pool).await?;
     // Don't expose the full token in response - mask it
-    let masked_settings = settings.map(|s| DooraySettings {
-        dooray_token: mask_token(&s.dooray_token),
-        ..s
-    });
-    Ok(ResponseJson(ApiResponse::success(masked_settings)))
+    Ok(ResponseJson(ApiResponse::success(settings.map(masked))))
 }
 
 #[derive(Debug, Deserialize, TS)]
@@ -68,53 +153,19 @@ async fn save_settings(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<SaveSettingsRequest>,
 ) -> Result<ResponseJson<ApiResponse<DooraySettings>>, ApiError> {
-    tracing::debug!("save_settings called, token length: {}", payload.dooray_token.len());
-
-    // Validate token by making a test API call (check if we can access projects)
-    let client = create_dooray_client(&payload.dooray_token)?;
-    let url = format!("{}/project/v1/projects", DOORAY_API_BASE);
-    tracing::debug!("Validating token against: {}", url);
-
-    let response = client
-        .get(&url)
-        .query(&[("member", "me"), ("page", "0"), ("size", "1")])
-        .send()
-        .await
-        .map_err(|e| {
-            tracing::error!("Failed to validate Dooray token: {}", e);
-            ApiError::BadRequest(format!("Failed to validate Dooray token: {}", e))
-        })?;
-
-    tracing::debug!("Dooray API response status: {}", response.status());
-
-    if !response.status().is_success() {
-        let body = response.text().await.unwrap_or_default();
-        tracing::error!("Invalid Dooray token, response: {}", body);
-        return Ok(ResponseJson(ApiResponse::error("Invalid Dooray token")));
-    }
+    tracing::debug!(
+        "save_settings called, token length: {}",
+        payload.dooray_token.len()
+    );
 
-    // Fetch current user's member ID for auto-assignee
-    let member_id = match client
-        .get(format!("{}/common/v1/members/me", DOORAY_API_BASE))
-        .send()
-        .await
-    {
-        Ok(resp) if resp.status().is_success() => {
-            resp.json::<DoorayMemberMeResponse>()
-                .await
-                .ok()
-                .and_then(|r| r.result)
-                .map(|m| m.id)
-        }
-        Ok(_) | Err(_) => {
-            tracing::warn!("Failed to fetch Dooray member info, assignee auto-set will be disabled");
-            None
-        }
+    let member_id = match check_token(&payload.dooray_token).await? {
+        TokenCheck::Valid { member_id } => member_id,
+        TokenCheck::Invalid { message } => return Ok(ResponseJson(ApiResponse::error(&message))),
     };
 
     // Don't auto-set a default project - let user choose from project list
     let data = CreateDooraySettings {
-        dooray_token: payload.dooray_token,
+        dooray_token: seal_token(&payload.dooray_token)?,
         selected_project_id: payload.selected_project_id,
         selected_project_name: payload.selected_project_name,
         selected_tag_ids: None,
@@ -125,94 +176,290 @@ async fn save_settings(
     let settings = DooraySettings::upsert(&deployment.db().pool, &data).await?;
     tracing::info!("Dooray settings saved successfully, id: {}", settings.id);
 
-    // Mask token in response
-    let masked = DooraySettings {
-        dooray_token: mask_token(&settings.dooray_token),
-        ..settings
-    };
-
-    Ok(ResponseJson(ApiResponse::success(masked)))
+    Ok(ResponseJson(ApiResponse::success(masked(settings))))
 }
 
 async fn delete_settings(
     State(deployment): State<DeploymentImpl>,
 ) -> Result<ResponseJson<ApiResponse<String>>, ApiError> {
     DooraySettings::delete(&deployment.db().pool).await?;
-    Ok(ResponseJson(ApiResponse::success("Dooray integration removed".to_string())))
+    Ok(ResponseJson(ApiResponse::success(
+        "Dooray integration removed".to_string(),
+    )))
 }
 
-// ============== Dooray API Proxy Endpoints ==============
+#[derive(Debug, Deserialize, TS)]
+pub struct RotateTokenRequest {
+    pub dooray_token: String,
+}
 
-#[derive(Debug, Serialize, Deserialize, TS)]
-pub struct DoorayProject {
-    pub id: String,
-    pub code: String,
+/// Replace the default connection's token, keeping the project and tag selection
+async fn rotate_settings_token(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<RotateTokenRequest>,
+) -> Result<ResponseJson<ApiResponse<DooraySettings>>, ApiError> {
+    let member_id = match check_token(&payload.dooray_token).await? {
+        TokenCheck::Valid { member_id } => member_id,
+        TokenCheck::Invalid { message } => return Ok(ResponseJson(ApiResponse::error(&message))),
+    };
+
+    let dooray_token = seal_token(&payload.dooray_token)?;
+    let settings =
+        DooraySettings::update_token(&deployment.db().pool, &dooray_token, member_id.as_deref())
+            .await?
+            .ok_or_else(|| ApiError::BadRequest("Dooray integration not configured".to_string()))?;
+    tracing::info!("Dooray token rotated");
+
+    Ok(ResponseJson(ApiResponse::success(masked(settings))))
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct ValidateTokenRequest {
+    /// Token to check; omit to check the stored token of `connection_id`
+    #[ts(optional)]
+    pub dooray_token: Option<String>,
+    /// Stored connection to check; omit for the default connection
+    #[ts(optional)]
+    pub connection_id: Option<Uuid>,
+}
+
+async fn validate_token(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ValidateTokenRequest>,
+) -> Result<ResponseJson<ApiResponse<DoorayTokenValidation>>, ApiError> {
+    let validation = match payload.dooray_token.filter(|t| !t.trim().is_empty()) {
+        Some(dooray_token) => token::validate_token(&dooray_token).await,
+        None => {
+            let account =
+                DoorayAccount::for_connection(&deployment.db().pool, payload.connection_id).await?;
+            token::validate_client(&account.client).await
+        }
+    };
+    Ok(ResponseJson(ApiResponse::success(validation)))
+}
+
+enum TokenCheck {
+    Valid { member_id: Option<String> },
+    Invalid { message: String },
+}
+
+/// Validate a token against Dooray and look up its owner's member ID
+async fn check_token(token: &str) -> Result<TokenCheck, ApiError> {
+    let validation = token::validate_token(token).await;
+    let message = validation
+        .message
+        .unwrap_or_else(|| "Invalid Dooray token".to_string());
+    if validation.valid {
+        if validation.problem.is_some() {
+            tracing::warn!("{}", message);
+        }
+        return Ok(TokenCheck::Valid {
+            member_id: validation.member_id,
+        });
+    }
+
+    tracing::error!("Failed to validate Dooray token: {}", message);
+    match validation.problem {
+        Some(token::DoorayTokenProblem::Unreachable) => Err(ApiError::BadRequest(format!(
+            "Failed to validate Dooray token: {}",
+            message
+        ))),
+        _ => Ok(TokenCheck::Invalid { message }),
+    }
+}
+
+// ============== Connection Endpoints ==============
+
+#[derive(Debug, Deserialize, TS)]
+pub struct SaveDoorayConnectionRequest {
     pub name: String,
-    pub description: Option<String>,
+    /// Required when creating; omit to keep the current token on update
+    pub dooray_token: Option<String>,
+    /// Dooray domain (e.g., "nhnent.dooray.com")
+    pub dooray_domain: Option<String>,
 }
 
-/// Raw project from Dooray API (may have different field names)
-#[derive(Debug, Deserialize)]
-struct DoorayRawProject {
-    pub id: String,
-    pub code: String,
-    pub description: Option<String>,
+async fn get_connections(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<DoorayConnection>>>, ApiError> {
+    let connections = DoorayConnection::find_all(&deployment.db().pool)
+        .await?
+        .into_iter()
+        .map(masked_connection)
+        .collect();
+    Ok(ResponseJson(ApiResponse::success(connections)))
 }
 
-#[derive(Debug, Deserialize)]
-struct DoorayProjectsApiResponse {
-    result: Option<Vec<DoorayRawProject>>,
-    #[allow(dead_code)]
-    header: DoorayApiHeader,
+async fn create_connection(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<SaveDoorayConnectionRequest>,
+) -> Result<ResponseJson<ApiResponse<DoorayConnection>>, ApiError> {
+    let Some(dooray_token) = payload.dooray_token.filter(|t| !t.trim().is_empty()) else {
+        return Err(ApiError::BadRequest("dooray_token is required".to_string()));
+    };
+    let member_id = match check_token(&dooray_token).await? {
+        TokenCheck::Valid { member_id } => member_id,
+        TokenCheck::Invalid { message } => return Ok(ResponseJson(ApiResponse::error(&message))),
+    };
+
+    let data = CreateDoorayConnection {
+        name: payload.name,
+        dooray_token: seal_token(&dooray_token)?,
+        dooray_domain: payload.dooray_domain,
+        member_id,
+    };
+    let connection = DoorayConnection::create(&deployment.db().pool, &data, Uuid::new_v4()).await?;
+    Ok(ResponseJson(ApiResponse::success(masked_connection(
+        connection,
+    ))))
 }
 
-#[derive(Debug, Deserialize)]
-#[allow(dead_code)]
-struct DoorayApiResponse<T> {
-    result: Option<T>,
-    header: DoorayApiHeader,
+async fn update_connection(
+    State(deployment): State<DeploymentImpl>,
+    Path(connection_id): Path<Uuid>,
+    Json(payload): Json<SaveDoorayConnectionRequest>,
+) -> Result<ResponseJson<ApiResponse<DoorayConnection>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let existing = DoorayConnection::find_by_id(pool, connection_id)
+        .await?
+        .ok_or(ApiError::Database(sqlx::Error::RowNotFound))?;
+
+    let (dooray_token, member_id) = match payload.dooray_token.filter(|t| !t.trim().is_empty()) {
+        Some(token) => match check_token(&token).await? {
+            TokenCheck::Valid { member_id } => (seal_token(&token)?, member_id),
+            TokenCheck::Invalid { message } => {
+                return Ok(ResponseJson(ApiResponse::error(&message)));
+            }
+        },
+        None => (existing.dooray_token, existing.member_id),
+    };
+
+    let data = CreateDoorayConnection {
+        name: payload.name,
+        dooray_token,
+        dooray_domain: payload.dooray_domain,
+        member_id,
+    };
+    let connection = DoorayConnection::update(pool, connection_id, &data)
+        .await?
+        .ok_or(ApiError::Database(sqlx::Error::RowNotFound))?;
+    Ok(ResponseJson(ApiResponse::success(masked_connection(
+        connection,
+    ))))
+}
+
+async fn delete_connection(
+    State(deployment): State<DeploymentImpl>,
+    Path(connection_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = DoorayConnection::delete(&deployment.db().pool, connection_id).await?;
+    if rows_affected == 0 {
+        return Err(ApiError::Database(sqlx::Error::RowNotFound));
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
 }
 
+// ============== Project Binding Endpoints ==============
+
 #[derive(Debug, Deserialize)]
-#[allow(dead_code)]
-struct DoorayApiHeader {
-    #[serde(rename = "resultCode")]
-    result_code: i32,
-    #[serde(rename = "resultMessage")]
-    result_message: Option<String>,
-    #[serde(rename = "isSuccessful")]
-    is_successful: bool,
+pub struct BindingQuery {
+    pub project_id: Option<Uuid>,
 }
 
-async fn get_dooray_projects(
+async fn get_bindings(
     State(deployment): State<DeploymentImpl>,
-) -> Result<ResponseJson<ApiResponse<Vec<DoorayProject>>>, ApiError> {
-    tracing::debug!("get_dooray_projects called");
-    let settings = get_required_settings(&deployment).await?;
-    tracing::debug!("Got settings, token length: {}", settings.dooray_token.len());
-    let client = create_dooray_client(&settings.dooray_token)?;
-
-    let response = client
-        .get(format!("{}/project/v1/projects", DOORAY_API_BASE))
-        .query(&[("member", "me"), ("page", "0"), ("size", "100")])
-        .send()
-        .await
-        .map_err(|e| ApiError::BadRequest(format!("Dooray API error: {}", e)))?;
+    Query(query): Query<BindingQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<ProjectDoorayBinding>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let bindings = match query.project_id {
+        Some(project_id) => ProjectDoorayBinding::find_by_project_id(pool, project_id).await?,
+        None => ProjectDoorayBinding::find_all(pool).await?,
+    };
+    Ok(ResponseJson(ApiResponse::success(bindings)))
+}
+
+async fn create_binding(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateProjectDoorayBinding>,
+) -> Result<ResponseJson<ApiResponse<ProjectDoorayBinding>>, ApiError> {
+    let pool = &deployment.db().pool;
+    if Project::find_by_id(pool, payload.project_id)
+        .await?
+        .is_none()
+    {
+        return Err(ApiError::Database(sqlx::Error::RowNotFound));
+    }
+    if ProjectDoorayBinding::find_by_project_id(pool, payload.project_id)
+        .await?
+        .iter()
+        .any(|b| b.dooray_project_id == payload.dooray_project_id)
+    {
+        return Err(ApiError::Conflict(format!(
+            "Project is already bound to Dooray project {}",
+            payload.dooray_project_code
+        )));
+    }
+
+    // Make sure the connection can actually reach the Dooray project
+    let account = DoorayAccount::for_connection(pool, payload.connection_id).await?;
+    account
+        .client
+        .get_project(&payload.dooray_project_id)
+        .await?;
 
-    if !response.status().is_success() {
-        return Ok(ResponseJson(ApiResponse::error("Failed to fetch Dooray projects")));
+    let binding = ProjectDoorayBinding::create(pool, &payload, Uuid::new_v4()).await?;
+    Ok(ResponseJson(ApiResponse::success(binding)))
+}
+
+async fn update_binding(
+    State(deployment): State<DeploymentImpl>,
+    Path(binding_id): Path<Uuid>,
+    Json(payload): Json<UpdateProjectDoorayBinding>,
+) -> Result<ResponseJson<ApiResponse<ProjectDoorayBinding>>, ApiError> {
+    let binding = ProjectDoorayBinding::update(&deployment.db().pool, binding_id, &payload)
+        .await?
+        .ok_or(ApiError::Database(sqlx::Error::RowNotFound))?;
+    Ok(ResponseJson(ApiResponse::success(binding)))
+}
+
+async fn delete_binding(
+    State(deployment): State<DeploymentImpl>,
+    Path(binding_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = ProjectDoorayBinding::delete(&deployment.db().pool, binding_id).await?;
+    if rows_affected == 0 {
+        return Err(ApiError::Database(sqlx::Error::RowNotFound));
     }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
 
-    // Get raw text first for debugging
-    let text = response.text().await
-        .map_err(|e| ApiError::BadRequest(format!("Failed to read response: {}", e)))?;
-    tracing::debug!("Dooray projects raw response: {}", &text[..text.len().min(500)]);
+// ============== Dooray API Proxy Endpoints ==============
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct DoorayProject {
+    pub id: String,
+    pub code: String,
+    pub name: String,
+    pub description: Option<String>,
+}
+
+/// Selects a stored connection instead of the default one (or the one of the
+/// binding to the Dooray project in the path)
+#[derive(Debug, Deserialize)]
+pub struct ConnectionQuery {
+    pub connection_id: Option<Uuid>,
+}
 
-    let api_response: DoorayProjectsApiResponse = serde_json::from_str(&text)
-        .map_err(|e| ApiError::BadRequest(format!("Failed to parse Dooray response: {}. Raw: {}", e, &text[..text.len().min(200)])))?;
+async fn get_dooray_projects(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ConnectionQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<DoorayProject>>>, ApiError> {
+    let account = DoorayAccount::for_connection(&deployment.db().pool, query.connection_id).await?;
 
-    // Convert raw projects to our format
-    let projects: Vec<DoorayProject> = api_response.result.unwrap_or_default()
+    let projects = account
+        .client
+        .list_my_projects()
+        .await?
         .into_iter()
         .map(|p| DoorayProject {
             id: p.id,
@@ -225,23 +472,6 @@ async fn get_dooray_projects(
     Ok(ResponseJson(ApiResponse::success(projects)))
 }
 
-#[derive(Debug, Serialize, Deserialize, TS)]
-pub struct DoorayTask {
-    pub id: String,
-    pub number: i64,
-    pub subject: String,
-    #[serde(rename = "workflowClass")]
-    pub workflow_class: Option<String>,
-    pub body: Option<DoorayTaskBody>,
-}
-
-#[derive(Debug, Serialize, Deserialize, TS)]
-pub struct DoorayTaskBody {
-    #[serde(rename = "mimeType")]
-    pub mime_type: Option<String>,
-    pub content: Option<String>,
-}
-
 // ============== Dooray Tags Types ==============
 
 #[derive(Debug, Serialize, Deserialize, TS)]
@@ -266,68 +496,6 @@ pub struct DoorayTagsResponse {
     pub tag_groups: Vec<DoorayTagGroup>,
 }
 
-// ============== Dooray Templates Types ==============
-
-#[derive(Debug, Serialize, Deserialize, TS)]
-#[ts(export)]
-pub struct DoorayTemplate {
-    pub id: String,
-    #[serde(rename = "templateName")]
-    pub template_name: String,
-}
-
-#[derive(Debug, Serialize, Deserialize, TS)]
-#[ts(export)]
-pub struct DoorayTemplateDetail {
-    pub id: String,
-    #[serde(rename = "templateName")]
-    pub template_name: String,
-    pub body: Option<DoorayTaskBody>,
-    pub guide: Option<DoorayTaskBody>,
-    pub subject: Option<String>,
-}
-
-#[derive(Debug, Deserialize)]
-struct DoorayTemplatesApiResponse {
-    result: Option<Vec<DoorayTemplate>>,
-    #[serde(rename = "totalCount")]
-    #[allow(dead_code)]
-    total_count: Option<i64>,
-    #[allow(dead_code)]
-    header: DoorayApiHeader,
-}
-
-#[derive(Debug, Deserialize)]
-struct DoorayTemplateDetailApiResponse {
-    result: Option<DoorayTemplateDetail>,
-    #[allow(dead_code)]
-    header: DoorayApiHeader,
-}
-
-#[derive(Debug, Deserialize)]
-struct DoorayTagApiResponse {
-    result: Option<Vec<DoorayTagApiItem>>,
-    #[allow(dead_code)]
-    header: DoorayApiHeader,
-}
-
-#[derive(Debug, Deserialize)]
-struct DoorayTagApiItem {
-    id: String,
-    name: String,
-    #[serde(rename = "tagGroup")]
-    tag_group: Option<DoorayTagGroupInfo>,
-}
-
-#[derive(Debug, Deserialize)]
-struct DoorayTagGroupInfo {
-    id: String,
-    name: Option<String>,
-    mandatory: Option<bool>,
-    #[serde(rename = "selectOne")]
-    select_one: Option<bool>,
-}
-
 #[derive(Debug, Deserialize, TS)]
 pub struct UpdateSelectedTagsRequest {
     pub selected_tag_ids: Option<Vec<String>>,
@@ -339,143 +507,178 @@ pub struct UpdateSelectedProjectRequest {
     pub project_name: String,
 }
 
-#[derive(Debug, Deserialize)]
-struct DoorayTasksApiResponse {
-    result: Option<Vec<DoorayTask>>,
-    #[serde(rename = "totalCount")]
-    #[allow(dead_code)]
-    total_count: Option<i64>,
-    #[allow(dead_code)]
-    header: DoorayApiHeader,
+async fn get_dooray_tasks(
+    State(deployment): State<DeploymentImpl>,
+    Path(dooray_project_id): Path<String>,
+    Query(query): Query<ConnectionQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<DoorayTask>>>, ApiError> {
+    let account = account_for(&deployment, query.connection_id, &dooray_project_id).await?;
+
+    // Fetch tasks that are not closed (backlog, registered, working)
+    let tasks = account
+        .client
+        .list_all_posts(&dooray_project_id, &PostListQuery::open())
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(tasks)))
 }
 
-// Response for individual task detail
-#[derive(Debug, Deserialize)]
-struct DoorayTaskDetailResponse {
-    result: Option<DoorayTaskDetail>,
-    #[allow(dead_code)]
-    header: DoorayApiHeader,
+// ============== Single Post Endpoints ==============
+
+/// A Dooray post with its markdown body
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct DoorayPost {
+    pub id: String,
+    pub number: Option<i64>,
+    pub subject: String,
+    pub workflow: Option<DoorayWorkflowRef>,
+    pub body: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
-#[allow(dead_code)]
-struct DoorayTaskDetail {
-    id: String,
-    subject: String,
-    #[serde(default)]
-    number: Option<i64>,
-    #[serde(rename = "workflowClass")]
-    workflow_class: Option<String>,
-    body: Option<DoorayTaskBody>,
+#[derive(Debug, Deserialize, TS)]
+pub struct SearchDoorayPostsQuery {
+    /// Project-local post number (the `123` in `PROJ/123`)
+    pub number: Option<i64>,
+    /// Keyword matched against post subjects
+    pub keyword: Option<String>,
+    /// Maximum number of posts to return (default 20)
+    pub limit: Option<u32>,
 }
 
-async fn get_dooray_tasks(
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct SetDoorayWorkflowRequest {
+    pub workflow_id: String,
+}
+
+async fn get_dooray_post(
+    State(deployment): State<DeploymentImpl>,
+    Path((dooray_project_id, dooray_task_id)): Path<(String, String)>,
+) -> Result<ResponseJson<ApiResponse<DoorayPost>>, ApiError> {
+    let client = account_for(&deployment, None, &dooray_project_id)
+        .await?
+        .client;
+
+    let post = client.get_post(&dooray_project_id, &dooray_task_id).await?;
+    Ok(ResponseJson(ApiResponse::success(DoorayPost {
+        id: post.id,
+        number: post.number,
+        subject: post.subject,
+        workflow: post.workflow,
+        body: post.body.and_then(|b| b.content),
+    })))
+}
+
+async fn search_dooray_posts(
     State(deployment): State<DeploymentImpl>,
-    axum::extract::Path(dooray_project_id): axum::extract::Path<String>,
+    Path(dooray_project_id): Path<String>,
+    Query(query): Query<SearchDoorayPostsQuery>,
 ) -> Result<ResponseJson<ApiResponse<Vec<DoorayTask>>>, ApiError> {
-    let settings = get_required_settings(&deployment).await?;
-    let client = create_dooray_client(&settings.dooray_token)?;
+    let keyword = query
+        .keyword
+        .as_deref()
+        .map(str::trim)
+        .filter(|k| !k.is_empty());
+    if query.number.is_none() && keyword.is_none() {
+        return Err(ApiError::BadRequest(
+            "Either number or keyword is required".to_string(),
+        ));
+    }
 
-    // Fetch tasks that are not closed (backlog, registered, working)
-    let response = client
-        .get(format!("{}/project/v1/projects/{}/posts", DOORAY_API_BASE, dooray_project_id))
-        .query(&[
-            ("page", "0"),
-            ("size", "100"),
-            ("postWorkflowClasses", "backlog,registered,working"),
-        ])
-        .send()
-        .await
-        .map_err(|e| ApiError::BadRequest(format!("Dooray API error: {}", e)))?;
+    let client = account_for(&deployment, None, &dooray_project_id)
+        .await?
+        .client;
 
-    if !response.status().is_success() {
-        return Ok(ResponseJson(ApiResponse::error("Failed to fetch Dooray tasks")));
+    let mut post_query = PostListQuery::default().newest_first();
+    post_query.post_number = query.number;
+    if let Some(keyword) = keyword {
+        post_query = post_query.with_subject(keyword);
     }
+    let size = query.limit.unwrap_or(20).clamp(1, DoorayClient::PAGE_SIZE);
+    let page = client
+        .list_posts_page(&dooray_project_id, &post_query, 0, size)
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(page.items)))
+}
 
-    let api_response: DoorayTasksApiResponse = response
-        .json()
-        .await
-        .map_err(|e| ApiError::BadRequest(format!("Failed to parse Dooray response: {}", e)))?;
+async fn set_dooray_post_workflow(
+    State(deployment): State<DeploymentImpl>,
+    Path((dooray_project_id, dooray_task_id)): Path<(String, String)>,
+    Json(payload): Json<SetDoorayWorkflowRequest>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let client = account_for(&deployment, None, &dooray_project_id)
+        .await?
+        .client;
 
-    let tasks = api_response.result.unwrap_or_default();
-    Ok(ResponseJson(ApiResponse::success(tasks)))
+    let workflows = client.list_workflows(&dooray_project_id).await?;
+    if !workflows.iter().any(|w| w.id == payload.workflow_id) {
+        return Err(ApiError::BadRequest(format!(
+            "Unknown Dooray workflow: {}",
+            payload.workflow_id
+        )));
+    }
+
+    client
+        .set_post_workflow(&dooray_project_id, &dooray_task_id, &payload.workflow_id)
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(())))
 }
 
 // ============== Tags Endpoint ==============
 
 async fn get_dooray_tags(
     State(deployment): State<DeploymentImpl>,
-    axum::extract::Path(dooray_project_id): axum::extract::Path<String>,
+    Path(dooray_project_id): Path<String>,
+    Query(query): Query<ConnectionQuery>,
 ) -> Result<ResponseJson<ApiResponse<DoorayTagsResponse>>, ApiError> {
-    let settings = get_required_settings(&deployment).await?;
-    let client = create_dooray_client(&settings.dooray_token)?;
-
-    // Fetch tags from Dooray API
-    let response = client
-        .get(format!("{}/project/v1/projects/{}/tags", DOORAY_API_BASE, dooray_project_id))
-        .query(&[("page", "0"), ("size", "100")])
-        .send()
-        .await
-        .map_err(|e| ApiError::BadRequest(format!("Dooray API error: {}", e)))?;
-
-    if !response.status().is_success() {
-        return Ok(ResponseJson(ApiResponse::error("Failed to fetch Dooray tags")));
-    }
+    let account = account_for(&deployment, query.connection_id, &dooray_project_id).await?;
 
-    let api_response: DoorayTagApiResponse = response
-        .json()
-        .await
-        .map_err(|e| ApiError::BadRequest(format!("Failed to parse Dooray response: {}", e)))?;
+    let tags = account.client.list_tags(&dooray_project_id).await?;
 
     // Group tags by tag group
-    let tags = api_response.result.unwrap_or_default();
-    let mut tag_groups_map: std::collections::HashMap<String, DoorayTagGroup> = std::collections::HashMap::new();
-
+    let mut tag_groups_map: HashMap<String, DoorayTagGroup> = HashMap::new();
     for tag in tags {
-        let group_id = tag.tag_group.as_ref().map(|g| g.id.clone()).unwrap_or_else(|| "ungrouped".to_string());
-        let group_name = tag.tag_group.as_ref().and_then(|g| g.name.clone());
-        let mandatory = tag.tag_group.as_ref().and_then(|g| g.mandatory).unwrap_or(false);
-        let select_one = tag.tag_group.as_ref().and_then(|g| g.select_one).unwrap_or(false);
-
-        let group = tag_groups_map.entry(group_id.clone()).or_insert_with(|| DoorayTagGroup {
-            id: group_id,
-            name: group_name,
-            mandatory,
-            select_one,
-            tags: Vec::new(),
-        });
-
-        group.tags.push(DoorayTag {
-            id: tag.id,
-            name: tag.name,
-        });
+        let group = tag.tag_group.as_ref();
+        let group_id = group
+            .map(|g| g.id.clone())
+            .unwrap_or_else(|| "ungrouped".to_string());
+
+        tag_groups_map
+            .entry(group_id.clone())
+            .or_insert_with(|| DoorayTagGroup {
+                id: group_id,
+                name: group.and_then(|g| g.name.clone()),
+                mandatory: group.and_then(|g| g.mandatory).unwrap_or(false),
+                select_one: group.and_then(|g| g.select_one).unwrap_or(false),
+                tags: Vec::new(),
+            })
+            .tags
+            .push(DoorayTag {
+                id: tag.id,
+                name: tag.name,
+            });
     }
 
     let tag_groups: Vec<DoorayTagGroup> = tag_groups_map.into_values().collect();
 
-    Ok(ResponseJson(ApiResponse::success(DoorayTagsResponse { tag_groups })))
+    Ok(ResponseJson(ApiResponse::success(DoorayTagsResponse {
+        tag_groups,
+    })))
 }
 
 async fn update_selected_tags(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<UpdateSelectedTagsRequest>,
 ) -> Result<ResponseJson<ApiResponse<DooraySettings>>, ApiError> {
-    let tag_ids_json = payload.selected_tag_ids.map(|ids| serde_json::to_string(&ids).unwrap_or_default());
+    let tag_ids_json = payload
+        .selected_tag_ids
+        .map(|ids| serde_json::to_string(&ids).unwrap_or_default());
 
-    let settings = DooraySettings::update_selected_tags(
-        &deployment.db().pool,
-        tag_ids_json.as_deref(),
-    ).await?;
+    let settings =
+        DooraySettings::update_selected_tags(&deployment.db().pool, tag_ids_json.as_deref())
+            .await?;
 
     match settings {
-        Some(s) => {
-            let masked = DooraySettings {
-                dooray_token: mask_token(&s.dooray_token),
-                ..s
-            };
-            Ok(ResponseJson(ApiResponse::success(masked)))
-        }
-        None => Ok(ResponseJson(ApiResponse::error("No Dooray settings found")))
+        Some(s) => Ok(ResponseJson(ApiResponse::success(masked(s)))),
+        None => Ok(ResponseJson(ApiResponse::error("No Dooray settings found"))),
     }
 }
 
@@ -487,17 +690,12 @@ async fn update_selected_project(
         &deployment.db().pool,
         Some(&payload.project_id),
         Some(&payload.project_name),
-    ).await?;
+    )
+    .await?;
 
     match settings {
-        Some(s) => {
-            let masked = DooraySettings {
-                dooray_token: mask_token(&s.dooray_token),
-                ..s
-            };
-            Ok(ResponseJson(ApiResponse::success(masked)))
-        }
-        None => Ok(ResponseJson(ApiResponse::error("No Dooray settings found")))
+        Some(s) => Ok(ResponseJson(ApiResponse::success(masked(s)))),
+        None => Ok(ResponseJson(ApiResponse::error("No Dooray settings found"))),
     }
 }
 
@@ -505,38 +703,43 @@ async fn update_selected_project(
 
 #[derive(Debug, Deserialize, TS)]
 pub struct SyncRequest {
-    pub project_id: Uuid,  // Local vibe-kanban project ID
+    pub project_id: Uuid, // Local vibe-kanban project ID
     pub dooray_project_id: String,
-    pub dooray_project_code: String,  // For task number formatting
-}
-
-#[derive(Debug, Serialize, TS)]
-pub struct SyncResult {
-    pub created: i32,
-    pub updated: i32,
-    pub skipped: i32,
+    pub dooray_project_code: String, // For task number formatting
+    /// Re-walk every open post instead of only those changed since the last sync
+    #[serde(default)]
+    #[ts(optional)]
+    pub full: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, TS)]
 pub struct ImportByNumberRequest {
-    pub project_id: Uuid,  // Local vibe-kanban project ID
+    pub project_id: Uuid, // Local vibe-kanban project ID
     pub dooray_project_id: String,
-    pub dooray_project_code: String,  // For task number formatting
-    pub task_number: i64,  // The task number to import
+    pub dooray_project_code: String, // For task number formatting
+    pub task_number: i64,            // The task number to import
 }
 
 #[derive(Debug, Deserialize, TS)]
 pub struct ImportByIdRequest {
-    pub project_id: Uuid,  // Local vibe-kanban project ID
+    pub project_id: Uuid, // Local vibe-kanban project ID
+    pub dooray_project_id: String,
+    pub dooray_project_code: String, // For task number formatting
+    pub dooray_task_id: String,      // The Dooray post ID to import directly
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct ImportSubPostsRequest {
+    pub project_id: Uuid, // Local vibe-kanban project ID
     pub dooray_project_id: String,
-    pub dooray_project_code: String,  // For task number formatting
-    pub dooray_task_id: String,  // The Dooray post ID to import directly
+    pub dooray_project_code: String, // For task number formatting
+    pub dooray_task_id: String,      // The parent Dooray post whose sub-posts are imported
 }
 
 #[derive(Debug, Serialize, TS)]
 pub struct ImportResult {
     pub success: bool,
-    pub task_id: Option<Uuid>,  // The created local task ID
+    pub task_id: Option<Uuid>, // The created local task ID
     pub message: String,
 }
 
@@ -547,7 +750,7 @@ pub struct CreateDoorayCommentRequest {
     pub content: String,
 }
 
-#[derive(Debug, Serialize, TS)]
+#[derive(Debug, Serialize, Deserialize, TS)]
 pub struct CreateDoorayCommentResult {
     pub success: bool,
     pub message: String,
@@ -604,134 +807,155 @@ pub struct ChangeAssigneeRequest {
     pub target_url: String,
     pub member_id: String,
     pub member_name: String,
+    /// Dooray project of the target task; defaults to the project selected in settings
+    #[serde(default)]
+    #[ts(optional)]
+    pub dooray_project_id: Option<String>,
 }
 
 async fn sync_dooray_tasks(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<SyncRequest>,
 ) -> Result<ResponseJson<ApiResponse<SyncResult>>, ApiError> {
-    let settings = get_required_settings(&deployment).await?;
-    let client = create_dooray_client(&settings.dooray_token)?;
-
-    // Parse selected tag IDs from settings
-    let selected_tag_ids: Option<Vec<String>> = settings.selected_tag_ids
-        .as_ref()
-        .and_then(|s| serde_json::from_str(s).ok());
-
-    // Build query parameters
-    let mut query_params = vec![
-        ("page", "0".to_string()),
-        ("size", "100".to_string()),
-        ("postWorkflowClasses", "backlog,registered,working".to_string()),
-    ];
-
-    // Add tag filter if selected
-    if let Some(ref tag_ids) = selected_tag_ids {
-        if !tag_ids.is_empty() {
-            query_params.push(("tagIds", tag_ids.join(",")));
-        }
-    }
+    let pool = &deployment.db().pool;
+    let settings = DooraySettings::get(pool).await?;
 
-    // Fetch tasks from Dooray
-    let response = client
-        .get(format!("{}/project/v1/projects/{}/posts", DOORAY_API_BASE, payload.dooray_project_id))
-        .query(&query_params)
-        .send()
-        .await
-        .map_err(|e| ApiError::BadRequest(format!("Dooray API error: {}", e)))?;
-
-    if !response.status().is_success() {
-        return Ok(ResponseJson(ApiResponse::error("Failed to fetch Dooray tasks")));
-    }
+    // Update selected project in settings
+    DooraySettings::update_selected_project(
+        pool,
+        Some(&payload.dooray_project_id),
+        Some(&payload.dooray_project_code),
+    )
+    .await?;
 
-    let api_response: DoorayTasksApiResponse = response
-        .json()
-        .await
-        .map_err(|e| ApiError::BadRequest(format!("Failed to parse Dooray response: {}", e)))?;
-
-    let dooray_tasks = api_response.result.unwrap_or_default();
-    let mut created = 0;
-    let mut updated = 0;
-    let skipped = 0;
-
-    for dooray_task in dooray_tasks {
-        // Check if task already exists locally
-        let existing = Task::find_by_dooray_task_id(&deployment.db().pool, &dooray_task.id).await?;
-
-        // Fetch task detail to get body content
-        let detail_response = client
-            .get(format!(
-                "{}/project/v1/projects/{}/posts/{}",
-                DOORAY_API_BASE, payload.dooray_project_id, dooray_task.id
-            ))
-            .send()
-            .await
-            .map_err(|e| ApiError::BadRequest(format!("Failed to fetch task detail: {}", e)))?;
-
-        let description = if detail_response.status().is_success() {
-            let detail: DoorayTaskDetailResponse = detail_response
-                .json()
-                .await
-                .unwrap_or(DoorayTaskDetailResponse { result: None, header: DoorayApiHeader { result_code: 0, result_message: None, is_successful: false } });
-            detail.result.and_then(|d| d.body).and_then(|b| b.content)
-        } else {
-            None
-        };
-
-        let status = match dooray_task.workflow_class.as_deref() {
-            Some("working") => TaskStatus::InProgress,
-            Some("registered") => TaskStatus::Todo,
-            Some("backlog") => TaskStatus::Todo,
-            _ => TaskStatus::Todo,
-        };
-
-        if let Some(existing_task) = existing {
-            // Update existing task with latest Dooray content
-            Task::update(
-                &deployment.db().pool,
-                existing_task.id,
-                existing_task.project_id,
-                dooray_task.subject,
-                description,
-                status,
-                existing_task.parent_workspace_id,
-            ).await?;
-            updated += 1;
-        } else {
-            // Create new local task
-            let task_number = format!("{}/{}", payload.dooray_project_code, dooray_task.number);
-
-            let create_data = CreateTask {
+    // Sync through the project's binding, creating it on first sync with the
+    // connection of any other binding to the same Dooray project
+    let binding = match ProjectDoorayBinding::find_for_dooray_project(
+        pool,
+        Some(payload.project_id),
+        &payload.dooray_project_id,
+    )
+    .await?
+    {
+        Some(binding) if binding.project_id == payload.project_id => binding,
+        other => {
+            let data = CreateProjectDoorayBinding {
                 project_id: payload.project_id,
-                title: dooray_task.subject,
-                description,
-                status: Some(status),
-                parent_workspace_id: None,
-                image_ids: None,
-                dooray_task_id: Some(dooray_task.id),
-                dooray_project_id: Some(payload.dooray_project_id.clone()),
-                dooray_task_number: Some(task_number),
-                reference_dooray_url: None,
+                connection_id: other.and_then(|b| b.connection_id),
+                dooray_project_id: payload.dooray_project_id.clone(),
+                dooray_project_code: payload.dooray_project_code.clone(),
+                tag_ids: settings.as_ref().map(|s| s.tag_ids()).unwrap_or_default(),
+                is_default: false,
             };
+            ProjectDoorayBinding::create(pool, &data, Uuid::new_v4()).await?
+        }
+    };
 
-            let task_id = Uuid::new_v4();
-            Task::create(&deployment.db().pool, &create_data, task_id).await?;
-            created += 1;
+    // Bindings on the default connection follow the tag filter in settings
+    let binding = match settings {
+        Some(settings)
+            if binding.connection_id.is_none() && settings.tag_ids() != binding.tag_ids.0 =>
+        {
+            let data = UpdateProjectDoorayBinding {
+                tag_ids: Some(settings.tag_ids()),
+                is_default: None,
+            };
+            ProjectDoorayBinding::update(pool, binding.id, &data)
+                .await?
+                .ok_or(ApiError::Database(sqlx::Error::RowNotFound))?
         }
+        _ => binding,
+    };
+
+    let result = run_binding_sync(&deployment, &binding, payload.full.unwrap_or(false)).await?;
+    Ok(ResponseJson(ApiResponse::success(result)))
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct SyncBindingRequest {
+    /// Re-walk every open post instead of only those changed since the last sync
+    #[serde(default)]
+    #[ts(optional)]
+    pub full: Option<bool>,
+}
+
+async fn sync_binding(
+    State(deployment): State<DeploymentImpl>,
+    Path(binding_id): Path<Uuid>,
+    Json(payload): Json<SyncBindingRequest>,
+) -> Result<ResponseJson<ApiResponse<SyncResult>>, ApiError> {
+    let binding = ProjectDoorayBinding::find_by_id(&deployment.db().pool, binding_id)
+        .await?
+        .ok_or(ApiError::Database(sqlx::Error::RowNotFound))?;
+
+    let result = run_binding_sync(&deployment, &binding, payload.full.unwrap_or(false)).await?;
+    Ok(ResponseJson(ApiResponse::success(result)))
+}
+
+async fn run_binding_sync(
+    deployment: &DeploymentImpl,
+    binding: &ProjectDoorayBinding,
+    full: bool,
+) -> Result<SyncResult, ApiError> {
+    let pool = &deployment.db().pool;
+    let account = DoorayAccount::for_binding(pool, binding).await?;
+    let mode = if full {
+        SyncMode::Full
+    } else {
+        SyncMode::Incremental
+    };
+
+    Ok(
+        DooraySync::new(pool, &account.client, DooraySyncTrigger::Manual)
+            .with_events(deployment.events().msg_store())
+            .with_images(deployment.image())
+            .run(binding, mode)
+            .await?,
+    )
+}
+
+// ============== Webhook Endpoint ==============
+
+#[derive(Debug, Deserialize)]
+pub struct DoorayWebhookQuery {
+    /// Shared secret, for hook URLs that can't set headers
+    pub token: Option<String>,
+}
+
+async fn receive_webhook(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<DoorayWebhookQuery>,
+    headers: HeaderMap,
+    Json(payload): Json<DoorayWebhookPayload>,
+) -> Result<ResponseJson<ApiResponse<DoorayWebhookResult>>, ApiError> {
+    let Some(secret) = webhook::webhook_secret() else {
+        return Err(ApiError::Forbidden(format!(
+            "Dooray webhooks are disabled; set {} to enable them",
+            webhook::WEBHOOK_SECRET_ENV
+        )));
+    };
+    let provided = headers
+        .get(webhook::WEBHOOK_SECRET_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .or(query.token.as_deref());
+    if !webhook::verify_secret(&secret, provided) {
+        return Err(ApiError::Unauthorized);
     }
 
-    // Update selected project in settings
-    DooraySettings::update_selected_project(
+    let result = webhook::handle_webhook(
         &deployment.db().pool,
-        Some(&payload.dooray_project_id),
-        Some(&payload.dooray_project_code),
-    ).await?;
-
-    Ok(ResponseJson(ApiResponse::success(SyncResult {
-        created,
-        updated,
-        skipped,
-    })))
+        deployment.image(),
+        deployment.events().msg_store(),
+        payload,
+    )
+    .await?;
+    tracing::debug!(
+        "Handled Dooray webhook {:?} for post {:?}: {:?}",
+        result.event,
+        result.dooray_post_id,
+        result.action
+    );
+    Ok(ResponseJson(ApiResponse::success(result)))
 }
 
 // ============== Import by Task Number Endpoint ==============
@@ -740,43 +964,37 @@ async fn import_by_number(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<ImportByNumberRequest>,
 ) -> Result<ResponseJson<ApiResponse<ImportResult>>, ApiError> {
-    let settings = get_required_settings(&deployment).await?;
-    let client = create_dooray_client(&settings.dooray_token)?;
+    let account = DoorayAccount::for_dooray_project(
+        &deployment.db().pool,
+        Some(payload.project_id),
+        &payload.dooray_project_id,
+    )
+    .await?;
+    let client = account.client;
 
     // Fetch task by post number
-    let response = client
-        .get(format!("{}/project/v1/projects/{}/posts", DOORAY_API_BASE, payload.dooray_project_id))
-        .query(&[
-            ("postNumber", payload.task_number.to_string()),
-        ])
-        .send()
+    let dooray_task = match client
+        .find_post_by_number(&payload.dooray_project_id, payload.task_number)
         .await
-        .map_err(|e| ApiError::BadRequest(format!("Dooray API error: {}", e)))?;
-
-    if !response.status().is_success() {
-        return Ok(ResponseJson(ApiResponse::success(ImportResult {
-            success: false,
-            task_id: None,
-            message: "해당 태스크를 찾을 수 없습니다.".to_string(),
-        })));
-    }
-
-    let api_response: DoorayTasksApiResponse = response
-        .json()
-        .await
-        .map_err(|e| ApiError::BadRequest(format!("Failed to parse Dooray response: {}", e)))?;
-
-    let dooray_tasks = api_response.result.unwrap_or_default();
-
-    if dooray_tasks.is_empty() {
-        return Ok(ResponseJson(ApiResponse::success(ImportResult {
-            success: false,
-            task_id: None,
-            message: "해당 번호의 태스크를 찾을 수 없습니다.".to_string(),
-        })));
-    }
-
-    let dooray_task = &dooray_tasks[0];
+    {
+        Ok(Some(task)) => task,
+        Ok(None) => {
+            return Ok(ResponseJson(ApiResponse::success(ImportResult {
+                success: false,
+                task_id: None,
+                message: "해당 번호의 태스크를 찾을 수 없습니다.".to_string(),
+            })));
+        }
+        Err(e) if !e.should_retry() => {
+            tracing::warn!("Failed to look up Dooray task by number: {}", e);
+            return Ok(ResponseJson(ApiResponse::success(ImportResult {
+                success: false,
+                task_id: None,
+                message: "해당 태스크를 찾을 수 없습니다.".to_string(),
+            })));
+        }
+        Err(e) => return Err(e.into()),
+    };
 
     // Check if task already exists locally
     let existing = Task::find_by_dooray_task_id(&deployment.db().pool, &dooray_task.id).await?;
@@ -790,50 +1008,57 @@ async fn import_by_number(
     }
 
     // Fetch task detail to get body content
-    let detail_response = client
-        .get(format!(
-            "{}/project/v1/projects/{}/posts/{}",
-            DOORAY_API_BASE, payload.dooray_project_id, dooray_task.id
-        ))
-        .send()
-        .await
-        .map_err(|e| ApiError::BadRequest(format!("Failed to fetch task detail: {}", e)))?;
-
-    let description = if detail_response.status().is_success() {
-        let detail: DoorayTaskDetailResponse = detail_response
-            .json()
-            .await
-            .unwrap_or(DoorayTaskDetailResponse { result: None, header: DoorayApiHeader { result_code: 0, result_message: None, is_successful: false } });
-        detail.result.and_then(|d| d.body).and_then(|b| b.content)
-    } else {
-        None
-    };
+    let description =
+        fetch_post_description(&client, &payload.dooray_project_id, &dooray_task.id).await?;
+    let localized = attachments::localize_post_images(
+        &deployment.db().pool,
+        deployment.image(),
+        &client,
+        &payload.dooray_project_id,
+        &dooray_task.id,
+        description,
+    )
+    .await;
 
     // Create new local task
     let task_number = format!("{}/{}", payload.dooray_project_code, dooray_task.number);
 
-    let status = match dooray_task.workflow_class.as_deref() {
-        Some("working") => TaskStatus::InProgress,
-        Some("registered") => TaskStatus::Todo,
-        Some("backlog") => TaskStatus::Todo,
-        _ => TaskStatus::Todo,
-    };
+    let workflow_id = dooray_task.workflow.as_ref().map(|w| w.id.as_str());
+    let mapping =
+        StatusMapping::load(&deployment.db().pool, &client, &payload.dooray_project_id).await?;
+    let status = mapping.status_for(workflow_id, dooray_task.workflow_class.as_deref());
+    let parent_task_id = hierarchy::parent_task_id_for(
+        &deployment.db().pool,
+        dooray_task.parent.as_ref().map(|p| p.id.as_str()),
+    )
+    .await?;
 
     let create_data = CreateTask {
         project_id: payload.project_id,
         title: dooray_task.subject.clone(),
-        description,
-        status: Some(status),
+        description: localized.description.clone(),
+        status: Some(status.clone()),
         parent_workspace_id: None,
         image_ids: None,
         dooray_task_id: Some(dooray_task.id.clone()),
         dooray_project_id: Some(payload.dooray_project_id.clone()),
         dooray_task_number: Some(task_number),
         reference_dooray_url: None,
+        parent_task_id,
     };
 
     let task_id = Uuid::new_v4();
     Task::create(&deployment.db().pool, &create_data, task_id).await?;
+    localized.attach_to(&deployment.db().pool, task_id).await?;
+    DoorayTaskSyncState::mark_synced(&deployment.db().pool, task_id, &status, workflow_id).await?;
+    adopt_sub_posts(
+        &deployment,
+        &client,
+        &payload.dooray_project_id,
+        &dooray_task.id,
+        task_id,
+    )
+    .await;
 
     Ok(ResponseJson(ApiResponse::success(ImportResult {
         success: true,
@@ -848,11 +1073,17 @@ async fn import_by_id(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<ImportByIdRequest>,
 ) -> Result<ResponseJson<ApiResponse<ImportResult>>, ApiError> {
-    let settings = get_required_settings(&deployment).await?;
-    let client = create_dooray_client(&settings.dooray_token)?;
+    let account = DoorayAccount::for_dooray_project(
+        &deployment.db().pool,
+        Some(payload.project_id),
+        &payload.dooray_project_id,
+    )
+    .await?;
+    let client = account.client;
 
     // Check if task already exists locally
-    let existing = Task::find_by_dooray_task_id(&deployment.db().pool, &payload.dooray_task_id).await?;
+    let existing =
+        Task::find_by_dooray_task_id(&deployment.db().pool, &payload.dooray_task_id).await?;
 
     if let Some(existing_task) = existing {
         return Ok(ResponseJson(ApiResponse::success(ImportResult {
@@ -863,67 +1094,79 @@ async fn import_by_id(
     }
 
     // Fetch task detail directly by ID
-    let detail_response = client
-        .get(format!(
-            "{}/project/v1/projects/{}/posts/{}",
-            DOORAY_API_BASE, payload.dooray_project_id, payload.dooray_task_id
-        ))
-        .send()
-        .await
-        .map_err(|e| ApiError::BadRequest(format!("Failed to fetch task detail: {}", e)))?;
-
-    if !detail_response.status().is_success() {
-        return Ok(ResponseJson(ApiResponse::success(ImportResult {
-            success: false,
-            task_id: None,
-            message: "해당 태스크를 찾을 수 없습니다.".to_string(),
-        })));
-    }
-
-    let detail: DoorayTaskDetailResponse = detail_response
-        .json()
+    let task_detail = match client
+        .get_post(&payload.dooray_project_id, &payload.dooray_task_id)
         .await
-        .map_err(|e| ApiError::BadRequest(format!("Failed to parse Dooray response: {}", e)))?;
-
-    let task_detail = match detail.result {
-        Some(d) => d,
-        None => {
+    {
+        Ok(detail) => detail,
+        Err(DoorayError::EmptyResult) => {
             return Ok(ResponseJson(ApiResponse::success(ImportResult {
                 success: false,
                 task_id: None,
                 message: "태스크 정보를 가져올 수 없습니다.".to_string(),
             })));
         }
+        Err(e) if !e.should_retry() => {
+            tracing::warn!("Failed to fetch Dooray task detail: {}", e);
+            return Ok(ResponseJson(ApiResponse::success(ImportResult {
+                success: false,
+                task_id: None,
+                message: "해당 태스크를 찾을 수 없습니다.".to_string(),
+            })));
+        }
+        Err(e) => return Err(e.into()),
     };
 
-    let description = task_detail.body.and_then(|b| b.content);
+    let localized = attachments::localize_post_images(
+        &deployment.db().pool,
+        deployment.image(),
+        &client,
+        &payload.dooray_project_id,
+        &payload.dooray_task_id,
+        task_detail.body.and_then(|b| b.content),
+    )
+    .await;
     let task_number = match task_detail.number {
         Some(num) => format!("{}/{}", payload.dooray_project_code, num),
         None => format!("{}/{}", payload.dooray_project_code, payload.dooray_task_id),
     };
 
-    let status = match task_detail.workflow_class.as_deref() {
-        Some("working") => TaskStatus::InProgress,
-        Some("registered") => TaskStatus::Todo,
-        Some("backlog") => TaskStatus::Todo,
-        _ => TaskStatus::Todo,
-    };
+    let workflow_id = task_detail.workflow.as_ref().map(|w| w.id.as_str());
+    let mapping =
+        StatusMapping::load(&deployment.db().pool, &client, &payload.dooray_project_id).await?;
+    let status = mapping.status_for(workflow_id, task_detail.workflow_class.as_deref());
+    let parent_task_id = hierarchy::parent_task_id_for(
+        &deployment.db().pool,
+        task_detail.parent.as_ref().map(|p| p.id.as_str()),
+    )
+    .await?;
 
     let create_data = CreateTask {
         project_id: payload.project_id,
         title: task_detail.subject,
-        description,
-        status: Some(status),
+        description: localized.description.clone(),
+        status: Some(status.clone()),
         parent_workspace_id: None,
         image_ids: None,
         dooray_task_id: Some(payload.dooray_task_id.clone()),
         dooray_project_id: Some(payload.dooray_project_id.clone()),
         dooray_task_number: Some(task_number),
         reference_dooray_url: None,
+        parent_task_id,
     };
 
     let task_id = Uuid::new_v4();
     Task::create(&deployment.db().pool, &create_data, task_id).await?;
+    localized.attach_to(&deployment.db().pool, task_id).await?;
+    DoorayTaskSyncState::mark_synced(&deployment.db().pool, task_id, &status, workflow_id).await?;
+    adopt_sub_posts(
+        &deployment,
+        &client,
+        &payload.dooray_project_id,
+        &payload.dooray_task_id,
+        task_id,
+    )
+    .await;
 
     Ok(ResponseJson(ApiResponse::success(ImportResult {
         success: true,
@@ -932,72 +1175,84 @@ async fn import_by_id(
     })))
 }
 
+// ============== Import Sub-posts Endpoint ==============
+
+async fn import_sub_posts(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ImportSubPostsRequest>,
+) -> Result<ResponseJson<ApiResponse<SubPostImportResult>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let account = DoorayAccount::for_dooray_project(
+        pool,
+        Some(payload.project_id),
+        &payload.dooray_project_id,
+    )
+    .await?;
+
+    let target = ImportTarget {
+        project_id: payload.project_id,
+        dooray_project_id: &payload.dooray_project_id,
+        dooray_project_code: &payload.dooray_project_code,
+    };
+    let result = hierarchy::import_with_sub_posts(
+        pool,
+        deployment.image(),
+        &account.client,
+        target,
+        &payload.dooray_task_id,
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(result)))
+}
+
 // ============== Create Dooray Comment Endpoint ==============
 
 async fn create_dooray_comment(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<CreateDoorayCommentRequest>,
 ) -> Result<ResponseJson<ApiResponse<CreateDoorayCommentResult>>, ApiError> {
-    let settings = get_required_settings(&deployment).await?;
-    let client = create_dooray_client(&settings.dooray_token)?;
+    let account = account_for(&deployment, None, &payload.dooray_project_id).await?;
+    let client = &account.client;
 
     // Process content to convert task URLs to rich mentions
-    let project_code = settings
-        .selected_project_name
-        .as_deref()
-        .unwrap_or("PROJECT");
+    let project_code = account.project_code();
 
-    let processed_content = match super::dooray_body::process_body_with_mentions(
-        &client,
+    let processed_content = process_body_with_mentions(
+        client,
         &payload.content,
-        DOORAY_API_BASE,
         &payload.dooray_project_id,
         project_code,
     )
-    .await
-    {
-        Ok(content) => content,
-        Err(e) => {
-            tracing::warn!(
-                "Failed to process content mentions, using raw content: {}",
-                e
-            );
-            payload.content.clone()
-        }
-    };
+    .await;
 
-    // Dooray API: POST /project/v1/projects/{projectId}/posts/{postId}/logs
-    let response = client
-        .post(format!(
-            "{}/project/v1/projects/{}/posts/{}/logs",
-            DOORAY_API_BASE, payload.dooray_project_id, payload.dooray_task_id
-        ))
-        .json(&serde_json::json!({
-            "body": {
-                "mimeType": "text/x-markdown",
-                "content": processed_content
-            }
-        }))
-        .send()
+    if let Err(e) = client
+        .create_post_log(
+            &payload.dooray_project_id,
+            &payload.dooray_task_id,
+            &processed_content,
+        )
         .await
-        .map_err(|e| ApiError::BadRequest(format!("Dooray API error: {}", e)))?;
-
-    if !response.status().is_success() {
-        return Ok(ResponseJson(ApiResponse::success(CreateDoorayCommentResult {
-            success: false,
-            message: "코멘트 추가에 실패했습니다.".to_string(),
-        })));
+    {
+        tracing::error!("Failed to create Dooray comment: {}", e);
+        return Ok(ResponseJson(ApiResponse::success(
+            CreateDoorayCommentResult {
+                success: false,
+                message: "코멘트 추가에 실패했습니다.".to_string(),
+            },
+        )));
     }
 
-    Ok(ResponseJson(ApiResponse::success(CreateDoorayCommentResult {
-        success: true,
-        message: "두레이에 기록되었습니다.".to_string(),
-    })))
+    Ok(ResponseJson(ApiResponse::success(
+        CreateDoorayCommentResult {
+            success: true,
+            message: "두레이에 기록되었습니다.".to_string(),
+        },
+    )))
 }
 
 // ============== Get Dooray Comments Endpoint ==============
 
-#[derive(Debug, Serialize, TS)]
+#[derive(Debug, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct DoorayComment {
     pub id: String,
@@ -1006,164 +1261,56 @@ pub struct DoorayComment {
     pub created_at: String,
 }
 
-#[derive(Debug, Serialize, TS)]
+#[derive(Debug, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct GetDoorayCommentsResponse {
     pub comments: Vec<DoorayComment>,
 }
 
-#[derive(Debug, Deserialize)]
-struct DoorayLogsApiResponse {
-    result: Option<Vec<DoorayLogItem>>,
-    #[allow(dead_code)]
-    header: DoorayApiHeader,
-}
-
-#[derive(Debug, Deserialize)]
-struct DoorayLogItem {
-    id: String,
-    #[serde(rename = "createdAt")]
-    created_at: Option<String>,
-    body: Option<DoorayLogBody>,
-    #[serde(rename = "creatorMember")]
-    creator_member: Option<DoorayLogCreator>,
-}
-
-#[derive(Debug, Deserialize)]
-struct DoorayLogBody {
-    content: Option<String>,
-}
-
-#[derive(Debug, Deserialize)]
-struct DoorayLogCreator {
-    name: Option<String>,
-    #[serde(rename = "organizationMemberId")]
-    organization_member_id: Option<String>,
-}
-
-// Current member API response types (GET /common/v1/members/me)
-#[derive(Debug, Deserialize)]
-struct DoorayMemberMeResponse {
-    result: Option<DoorayMemberMe>,
-    #[allow(dead_code)]
-    header: DoorayApiHeader,
-}
-
-#[derive(Debug, Deserialize)]
-struct DoorayMemberMe {
-    id: String,
-}
-
-// Member list API response types
-#[derive(Debug, Deserialize)]
-struct DoorayMembersApiResponse {
-    result: Option<Vec<DoorayProjectMember>>,
-    #[allow(dead_code)]
-    header: DoorayApiHeader,
-}
-
-#[derive(Debug, Deserialize)]
-struct DoorayProjectMember {
-    #[serde(rename = "organizationMemberId")]
-    organization_member_id: Option<String>,
-}
-
-// Member detail API response: GET /common/v1/members/{memberId}
-#[derive(Debug, Deserialize)]
-struct DoorayMemberDetailResponse {
-    result: Option<DoorayMemberDetail>,
-    #[allow(dead_code)]
-    header: DoorayApiHeader,
-}
-
-#[derive(Debug, Deserialize)]
-struct DoorayMemberDetail {
-    name: Option<String>,
-}
-
 async fn get_dooray_comments(
     State(deployment): State<DeploymentImpl>,
-    axum::extract::Path((dooray_project_id, dooray_task_id)): axum::extract::Path<(String, String)>,
+    Path((dooray_project_id, dooray_task_id)): Path<(String, String)>,
 ) -> Result<ResponseJson<ApiResponse<GetDoorayCommentsResponse>>, ApiError> {
-    let settings = get_required_settings(&deployment).await?;
-    let client = create_dooray_client(&settings.dooray_token)?;
+    let client = account_for(&deployment, None, &dooray_project_id)
+        .await?
+        .client;
 
     // First, fetch project members to build ID -> name mapping
-    let member_names: std::collections::HashMap<String, String> = {
-        let member_ids: Vec<String> = match client
-            .get(format!(
-                "{}/project/v1/projects/{}/members?size=100",
-                DOORAY_API_BASE, dooray_project_id
-            ))
-            .send()
-            .await
-        {
-            Ok(response) => response
-                .json::<DoorayMembersApiResponse>()
-                .await
-                .ok()
-                .and_then(|r| r.result)
-                .map(|members| {
-                    members
-                        .into_iter()
-                        .filter_map(|m| m.organization_member_id)
-                        .collect()
-                })
-                .unwrap_or_default(),
-            Err(_) => Vec::new(),
-        };
-        // Enrich with names via /common/v1/members/{id}
-        let futs: Vec<_> = member_ids
-            .into_iter()
-            .map(|id| {
-                let c = client.clone();
-                async move {
-                    let resp = c
-                        .get(format!("{}/common/v1/members/{}", DOORAY_API_BASE, id))
-                        .send()
-                        .await
-                        .ok()?;
-                    let detail: DoorayMemberDetailResponse = resp.json().await.ok()?;
-                    let name = detail.result?.name?;
-                    Some((id, name))
-                }
-            })
-            .collect();
-        futures_util::future::join_all(futs)
-            .await
-            .into_iter()
-            .flatten()
-            .collect()
-    };
-
-    // Dooray API: GET /project/v1/projects/{projectId}/posts/{postId}/logs
-    let response = client
-        .get(format!(
-            "{}/project/v1/projects/{}/posts/{}/logs",
-            DOORAY_API_BASE, dooray_project_id, dooray_task_id
-        ))
-        .send()
+    let member_ids: Vec<String> = client
+        .list_project_members(&dooray_project_id)
         .await
-        .map_err(|e| ApiError::BadRequest(format!("Dooray API error: {}", e)))?;
-
-    if !response.status().is_success() {
-        return Ok(ResponseJson(ApiResponse::success(GetDoorayCommentsResponse {
-            comments: vec![],
-        })));
-    }
+        .map(|members| {
+            members
+                .into_iter()
+                .filter_map(|m| m.organization_member_id)
+                .collect()
+        })
+        .unwrap_or_default();
+    let member_names: HashMap<String, String> = client
+        .resolve_member_names(member_ids)
+        .await
+        .into_iter()
+        .collect();
 
-    let api_response: DoorayLogsApiResponse = response
-        .json()
+    let logs = match client
+        .list_post_logs(&dooray_project_id, &dooray_task_id)
         .await
-        .map_err(|e| ApiError::BadRequest(format!("Failed to parse Dooray response: {}", e)))?;
+    {
+        Ok(logs) => logs,
+        Err(e) => {
+            tracing::warn!("Failed to fetch Dooray comments: {}", e);
+            return Ok(ResponseJson(ApiResponse::success(
+                GetDoorayCommentsResponse { comments: vec![] },
+            )));
+        }
+    };
 
-    let comments = api_response
-        .result
-        .unwrap_or_default()
+    let comments = logs
         .into_iter()
         .map(|log| {
             // Try to get name from: 1) log.creator_member.name, 2) member_names lookup, 3) "Unknown"
-            let author_name = log.creator_member
+            let author_name = log
+                .creator_member
                 .as_ref()
                 .and_then(|c| c.name.clone())
                 .or_else(|| {
@@ -1177,163 +1324,83 @@ async fn get_dooray_comments(
             DoorayComment {
                 id: log.id,
                 author_name,
-                content: log.body
-                    .and_then(|b| b.content)
-                    .unwrap_or_default(),
+                content: log.body.and_then(|b| b.content).unwrap_or_default(),
                 created_at: log.created_at.unwrap_or_default(),
             }
         })
         .collect();
 
-    Ok(ResponseJson(ApiResponse::success(GetDoorayCommentsResponse {
-        comments,
-    })))
+    Ok(ResponseJson(ApiResponse::success(
+        GetDoorayCommentsResponse { comments },
+    )))
 }
 
 // ============== Create Dooray Task Endpoint ==============
 
-#[derive(Debug, Deserialize)]
-struct DoorayCreateTaskResponse {
-    result: Option<DoorayCreatedTask>,
-    #[allow(dead_code)]
-    header: DoorayApiHeader,
-}
-
-#[derive(Debug, Deserialize)]
-struct DoorayCreatedTask {
-    id: String,
-    #[serde(default)]
-    number: Option<i64>,
-}
-
 async fn create_dooray_task(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<CreateDoorayTaskRequest>,
 ) -> Result<ResponseJson<ApiResponse<CreateDoorayTaskResult>>, ApiError> {
-    let settings = get_required_settings(&deployment).await?;
-    let client = create_dooray_client(&settings.dooray_token)?;
+    let pool = &deployment.db().pool;
+    let binding = ProjectDoorayBinding::find_for_dooray_project(
+        pool,
+        Some(payload.local_project_id),
+        &payload.dooray_project_id,
+    )
+    .await?;
+    let account = match &binding {
+        Some(binding) => DoorayAccount::for_binding(pool, binding).await?,
+        None => DoorayAccount::for_connection(pool, None).await?,
+    };
+    let client = &account.client;
 
-    // Create task in Dooray
-    let mut request_body = serde_json::json!({
-        "subject": payload.subject
-    });
+    // Get project code for task number formatting and mentions
+    let project_code = account.project_code();
 
-    if let Some(body_content) = &payload.body {
-        let project_code = settings
-            .selected_project_name
-            .as_deref()
-            .unwrap_or("PROJECT");
+    let mut request = CreatePostRequest::new(payload.subject.clone());
 
-        let processed_body = match super::dooray_body::process_body_with_mentions(
-            &client,
+    if let Some(body_content) = &payload.body {
+        let processed_body = process_body_with_mentions(
+            client,
             body_content,
-            DOORAY_API_BASE,
             &payload.dooray_project_id,
             project_code,
         )
-        .await
-        {
-            Ok(body) => body,
-            Err(e) => {
-                tracing::warn!(
-                    "Failed to process body mentions, using raw body: {}",
-                    e
-                );
-                body_content.clone()
-            }
-        };
-
-        request_body["body"] = serde_json::json!({
-            "mimeType": "text/x-markdown",
-            "content": processed_body
-        });
+        .await;
+        request.body = Some(DoorayTaskBody::markdown(processed_body));
     }
 
     // Add current user as assignee
     // Note: creator (등록자) is auto-set by Dooray based on API token owner
-    let member_id = if let Some(ref id) = settings.member_id {
-        Some(id.clone())
-    } else {
+    let member_id = match account.member_id.clone() {
+        Some(id) => Some(id),
         // member_id not stored yet - fetch it now
-        match client
-            .get(format!("{}/common/v1/members/me", DOORAY_API_BASE))
-            .send()
-            .await
-        {
-            Ok(resp) if resp.status().is_success() => {
-                resp.json::<DoorayMemberMeResponse>()
-                    .await
-                    .ok()
-                    .and_then(|r| r.result)
-                    .map(|m| m.id)
-            }
-            _ => None,
-        }
+        None => client.get_me().await.ok().map(|me| me.id),
     };
     if let Some(ref member_id) = member_id {
-        request_body["users"] = serde_json::json!({
-            "to": [{
-                "type": "member",
-                "member": {
-                    "organizationMemberId": member_id
-                }
-            }]
-        });
+        request = request.assign_to(member_id);
     }
 
-    // Add tag IDs if provided (required for projects with mandatory tag groups)
-    if let Some(ref tag_ids) = payload.tag_ids {
-        if !tag_ids.is_empty() {
-            request_body["tagIds"] = serde_json::json!(tag_ids);
-        }
+    // Add tag IDs if provided (required for projects with mandatory tag groups),
+    // else the project binding's sync filter so the post shows up in the next sync
+    if let Some(tag_ids) = &payload.tag_ids {
+        request.tag_ids = tag_ids.clone();
+    } else if let Some(binding) = binding
+        .as_ref()
+        .filter(|b| b.project_id == payload.local_project_id)
+    {
+        request.tag_ids = binding.tag_ids.0.clone();
     }
 
     // Add parent task ID if provided (for creating subtasks)
-    if let Some(ref parent_id) = payload.parent_task_id {
-        request_body["parentPostId"] = serde_json::json!(parent_id);
-    }
-
-    tracing::debug!("Dooray create task request body: {}", serde_json::to_string_pretty(&request_body).unwrap_or_default());
+    request.parent_post_id = payload.parent_task_id.clone();
 
-    let response = client
-        .post(format!(
-            "{}/project/v1/projects/{}/posts",
-            DOORAY_API_BASE, payload.dooray_project_id
-        ))
-        .json(&request_body)
-        .send()
+    let created_task = match client
+        .create_post(&payload.dooray_project_id, &request)
         .await
-        .map_err(|e| ApiError::BadRequest(format!("Dooray API error: {}", e)))?;
-
-    if !response.status().is_success() {
-        let error_text = response.text().await.unwrap_or_default();
-        tracing::error!("Failed to create Dooray task: {}", error_text);
-        return Ok(ResponseJson(ApiResponse::success(CreateDoorayTaskResult {
-            success: false,
-            dooray_task_id: None,
-            dooray_task_number: None,
-            local_task_id: None,
-            message: "Dooray 태스크 생성에 실패했습니다.".to_string(),
-        })));
-    }
-
-    // Get response text first for logging in case of parse failure
-    let response_text = response.text().await.map_err(|e| {
-        tracing::error!("Failed to read Dooray response: {}", e);
-        ApiError::BadRequest(format!("Failed to read Dooray response: {}", e))
-    })?;
-
-    tracing::debug!("Dooray create task response: {}", response_text);
-
-    let api_response: DoorayCreateTaskResponse = serde_json::from_str(&response_text)
-        .map_err(|e| {
-            tracing::error!("Failed to parse Dooray response: {}. Response body: {}", e, response_text);
-            ApiError::BadRequest(format!("Failed to parse Dooray response: {}", e))
-        })?;
-
-    let created_task = match api_response.result {
-        Some(task) => task,
-        None => {
+    {
+        Ok(created) => created,
+        Err(DoorayError::EmptyResult) => {
             return Ok(ResponseJson(ApiResponse::success(CreateDoorayTaskResult {
                 success: false,
                 dooray_task_id: None,
@@ -1342,48 +1409,32 @@ async fn create_dooray_task(
                 message: "Dooray 태스크 생성 응답이 올바르지 않습니다.".to_string(),
             })));
         }
-    };
-
-    // Get task number - if not in create response, fetch task details
-    let task_number_value = if let Some(num) = created_task.number {
-        Some(num)
-    } else {
-        // Fetch task details to get the number
-        tracing::debug!("Task number not in create response, fetching task details for id: {}", created_task.id);
-        let detail_response = client
-            .get(format!(
-                "{}/project/v1/projects/{}/posts/{}",
-                DOORAY_API_BASE, payload.dooray_project_id, created_task.id
-            ))
-            .send()
-            .await
-            .ok();
-
-        if let Some(resp) = detail_response {
-            if resp.status().is_success() {
-                if let Ok(text) = resp.text().await {
-                    tracing::debug!("Dooray task detail response: {}", text);
-                    if let Ok(detail_resp) = serde_json::from_str::<DoorayTaskDetailResponse>(&text) {
-                        detail_resp.result.and_then(|t| t.number)
-                    } else {
-                        None
-                    }
-                } else {
-                    None
-                }
-            } else {
-                None
-            }
-        } else {
-            None
+        Err(e) => {
+            tracing::error!("Failed to create Dooray task: {}", e);
+            return Ok(ResponseJson(ApiResponse::success(CreateDoorayTaskResult {
+                success: false,
+                dooray_task_id: None,
+                dooray_task_number: None,
+                local_task_id: None,
+                message: "Dooray 태스크 생성에 실패했습니다.".to_string(),
+            })));
         }
     };
 
-    // Get project code for task number formatting
-    let project_code = settings
-        .selected_project_name
-        .as_deref()
-        .unwrap_or("PROJECT");
+    // The create response carries no workflow (and sometimes no number); read the
+    // post back so the sync baseline holds the workflow it actually starts in
+    let created_detail = client
+        .get_post(&payload.dooray_project_id, &created_task.id)
+        .await
+        .inspect_err(|e| tracing::warn!("Failed to fetch created Dooray task: {}", e))
+        .ok();
+    let workflow_id = created_detail
+        .as_ref()
+        .and_then(|detail| detail.workflow.as_ref())
+        .map(|workflow| workflow.id.clone());
+    let task_number_value = created_task
+        .number
+        .or_else(|| created_detail.as_ref().and_then(|detail| detail.number));
     let task_number = task_number_value.map(|n| format!("{}/{}", project_code, n));
 
     // Create local task synced with Dooray
@@ -1398,69 +1449,54 @@ async fn create_dooray_task(
         dooray_project_id: Some(payload.dooray_project_id.clone()),
         dooray_task_number: task_number,
         reference_dooray_url: payload.reference_dooray_url.clone(),
+        parent_task_id: None,
     };
 
     let local_task_id = Uuid::new_v4();
-    Task::create(&deployment.db().pool, &create_data, local_task_id).await?;
+    Task::create(pool, &create_data, local_task_id).await?;
+    DoorayTaskSyncState::mark_synced(
+        pool,
+        local_task_id,
+        &TaskStatus::Todo,
+        workflow_id.as_deref(),
+    )
+    .await?;
 
     // Auto cross-reference: if reference_dooray_url is provided, post a reference comment to the target task
     let mut cross_ref_message = None;
-    if let Some(ref reference_url) = payload.reference_dooray_url {
-        use super::dooray_body::{DOORAY_TASK_URL_RE, build_mention_html, fetch_task_detail};
-
-        let target_task_id = DOORAY_TASK_URL_RE
-            .captures(reference_url)
-            .and_then(|cap| cap.get(1).or_else(|| cap.get(2)))
-            .map(|m| m.as_str().to_string());
-
-        if let Some(target_task_id) = target_task_id {
-            // Fetch the newly created source task detail for mention HTML
-            let mention_info = fetch_task_detail(
-                &client,
-                DOORAY_API_BASE,
-                &payload.dooray_project_id,
-                &created_task.id,
-                project_code,
+    if let Some(target_task_id) = payload
+        .reference_dooray_url
+        .as_deref()
+        .and_then(task_id_from_url)
+        // Fetch the newly created source task detail for mention HTML
+        && let Some(info) = fetch_mention_info(
+            client,
+            &payload.dooray_project_id,
+            &created_task.id,
+            project_code,
+        )
+        .await
+    {
+        // Determine target project_id (use the account's Dooray project as default)
+        let target_project_id = account
+            .dooray_project_id
+            .as_deref()
+            .unwrap_or(&payload.dooray_project_id);
+
+        match client
+            .create_post_log(
+                target_project_id,
+                &target_task_id,
+                &build_mention_html(&info),
             )
             .await
-            .ok()
-            .flatten();
-
-            if let Some(info) = mention_info {
-                let mention_html = build_mention_html(&info);
-
-                // Determine target project_id (use selected_project_id as default)
-                let target_project_id = settings
-                    .selected_project_id
-                    .as_deref()
-                    .unwrap_or(&payload.dooray_project_id);
-
-                let ref_response = client
-                    .post(format!(
-                        "{}/project/v1/projects/{}/posts/{}/logs",
-                        DOORAY_API_BASE, target_project_id, target_task_id
-                    ))
-                    .json(&serde_json::json!({
-                        "body": {
-                            "mimeType": "text/x-markdown",
-                            "content": mention_html
-                        }
-                    }))
-                    .send()
-                    .await;
-
-                match ref_response {
-                    Ok(resp) if resp.status().is_success() => {
-                        cross_ref_message = Some("참조도 자동 등록되었습니다.");
-                        tracing::debug!("Auto cross-reference posted successfully");
-                    }
-                    Ok(resp) => {
-                        tracing::warn!("Auto cross-reference failed with status: {}", resp.status());
-                    }
-                    Err(e) => {
-                        tracing::warn!("Auto cross-reference request failed: {}", e);
-                    }
-                }
+        {
+            Ok(()) => {
+                cross_ref_message = Some("참조도 자동 등록되었습니다.");
+                tracing::debug!("Auto cross-reference posted successfully");
+            }
+            Err(e) => {
+                tracing::warn!("Auto cross-reference failed: {}", e);
             }
         }
     }
@@ -1473,7 +1509,7 @@ async fn create_dooray_task(
     Ok(ResponseJson(ApiResponse::success(CreateDoorayTaskResult {
         success: true,
         dooray_task_id: Some(created_task.id),
-        dooray_task_number: created_task.number,
+        dooray_task_number: task_number_value,
         local_task_id: Some(local_task_id),
         message,
     })))
@@ -1483,73 +1519,38 @@ async fn create_dooray_task(
 
 async fn update_dooray_task(
     State(deployment): State<DeploymentImpl>,
-    axum::extract::Path(dooray_task_id): axum::extract::Path<String>,
+    Path(dooray_task_id): Path<String>,
     Json(payload): Json<UpdateDoorayTaskRequest>,
 ) -> Result<ResponseJson<ApiResponse<UpdateDoorayTaskResult>>, ApiError> {
     // Find local task by dooray_task_id to get the dooray_project_id
-    let local_task = Task::find_by_dooray_task_id(&deployment.db().pool, &dooray_task_id).await?;
-
-    let dooray_project_id = match local_task {
-        Some(task) => {
-            task.dooray_project_id.ok_or_else(|| {
-                ApiError::BadRequest("태스크에 Dooray 프로젝트 정보가 없습니다.".to_string())
-            })?
-        }
-        None => {
-            return Ok(ResponseJson(ApiResponse::success(UpdateDoorayTaskResult {
-                success: false,
-                message: "해당 Dooray 태스크를 찾을 수 없습니다.".to_string(),
-            })));
-        }
+    let pool = &deployment.db().pool;
+    let Some(local_task) = Task::find_by_dooray_task_id(pool, &dooray_task_id).await? else {
+        return Ok(ResponseJson(ApiResponse::success(UpdateDoorayTaskResult {
+            success: false,
+            message: "해당 Dooray 태스크를 찾을 수 없습니다.".to_string(),
+        })));
     };
+    let dooray_project_id = local_task.dooray_project_id.as_deref().ok_or_else(|| {
+        ApiError::BadRequest("태스크에 Dooray 프로젝트 정보가 없습니다.".to_string())
+    })?;
 
-    let settings = get_required_settings(&deployment).await?;
-    let client = create_dooray_client(&settings.dooray_token)?;
+    let account = DoorayAccount::for_task(pool, &local_task).await?;
+    let client = &account.client;
 
     // Process body to convert task URLs to rich mentions
-    let project_code = settings
-        .selected_project_name
-        .as_deref()
-        .unwrap_or("PROJECT");
-
-    let processed_body = match super::dooray_body::process_body_with_mentions(
-        &client,
+    let processed_body = process_body_with_mentions(
+        client,
         &payload.body,
-        DOORAY_API_BASE,
-        &dooray_project_id,
-        project_code,
+        dooray_project_id,
+        account.project_code(),
     )
-    .await
-    {
-        Ok(body) => body,
-        Err(e) => {
-            tracing::warn!(
-                "Failed to process body mentions, using raw body: {}",
-                e
-            );
-            payload.body.clone()
-        }
-    };
+    .await;
 
-    // Dooray API: PUT /project/v1/projects/{projectId}/posts/{postId}
-    let response = client
-        .put(format!(
-            "{}/project/v1/projects/{}/posts/{}",
-            DOORAY_API_BASE, dooray_project_id, dooray_task_id
-        ))
-        .json(&serde_json::json!({
-            "body": {
-                "mimeType": "text/x-markdown",
-                "content": processed_body
-            }
-        }))
-        .send()
+    if let Err(e) = client
+        .update_post_body(dooray_project_id, &dooray_task_id, &processed_body)
         .await
-        .map_err(|e| ApiError::BadRequest(format!("Dooray API error: {}", e)))?;
-
-    if !response.status().is_success() {
-        let error_text = response.text().await.unwrap_or_default();
-        tracing::error!("Failed to update Dooray task: {}", error_text);
+    {
+        tracing::error!("Failed to update Dooray task: {}", e);
         return Ok(ResponseJson(ApiResponse::success(UpdateDoorayTaskResult {
             success: false,
             message: "Dooray 태스크 업데이트에 실패했습니다.".to_string(),
@@ -1568,162 +1569,117 @@ async fn create_cross_reference(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<CrossReferenceRequest>,
 ) -> Result<ResponseJson<ApiResponse<CreateDoorayCommentResult>>, ApiError> {
-    use super::dooray_body::{DOORAY_TASK_URL_RE, build_mention_html, fetch_task_detail};
-
     // 1. Parse target_url to extract target_task_id
-    let target_task_id = DOORAY_TASK_URL_RE
-        .captures(&payload.target_url)
-        .and_then(|cap| cap.get(1).or_else(|| cap.get(2)))
-        .map(|m| m.as_str().to_string())
-        .ok_or_else(|| {
-            ApiError::BadRequest("유효한 Dooray 태스크 URL이 아닙니다.".to_string())
-        })?;
+    let target_task_id = task_id_from_url(&payload.target_url)
+        .ok_or_else(|| ApiError::BadRequest("유효한 Dooray 태스크 URL이 아닙니다.".to_string()))?;
 
     // 2. Look up the source task in local DB
-    let source_task = db::models::task::Task::find_by_id(&deployment.db().pool, payload.source_task_id)
+    let source_task = Task::find_by_id(&deployment.db().pool, payload.source_task_id)
         .await?
         .ok_or_else(|| ApiError::BadRequest("소스 태스크를 찾을 수 없습니다.".to_string()))?;
 
-    let source_dooray_task_id = source_task
-        .dooray_task_id
-        .as_deref()
-        .ok_or_else(|| ApiError::BadRequest("소스 태스크에 Dooray 연동 정보가 없습니다.".to_string()))?;
+    let source_dooray_task_id = source_task.dooray_task_id.as_deref().ok_or_else(|| {
+        ApiError::BadRequest("소스 태스크에 Dooray 연동 정보가 없습니다.".to_string())
+    })?;
 
-    let source_dooray_project_id = source_task
-        .dooray_project_id
-        .as_deref()
-        .ok_or_else(|| ApiError::BadRequest("소스 태스크에 Dooray 프로젝트 정보가 없습니다.".to_string()))?;
+    let source_dooray_project_id = source_task.dooray_project_id.as_deref().ok_or_else(|| {
+        ApiError::BadRequest("소스 태스크에 Dooray 프로젝트 정보가 없습니다.".to_string())
+    })?;
 
-    // 3. Get Dooray settings
-    let settings = get_required_settings(&deployment).await?;
-    let client = create_dooray_client(&settings.dooray_token)?;
+    // 3. Get the Dooray account of the source task
+    let account = DoorayAccount::for_task(&deployment.db().pool, &source_task).await?;
+    let client = &account.client;
 
-    // 4. Determine target project_id (use selected_project_id or source task's project_id)
-    let target_project_id = settings
-        .selected_project_id
+    // 4. Determine target project_id (use the account's Dooray project or source task's project_id)
+    let target_project_id = account
+        .dooray_project_id
         .as_deref()
         .unwrap_or(source_dooray_project_id);
 
-    // 5. Get project code for the source task
-    let project_code = settings
-        .selected_project_name
-        .as_deref()
-        .unwrap_or("PROJECT");
-
-    // 6. Fetch source task detail from Dooray to build mention HTML
-    let mention_info = fetch_task_detail(
-        &client,
-        DOORAY_API_BASE,
+    // 5. Fetch source task detail from Dooray to build mention HTML
+    let mention_info = fetch_mention_info(
+        client,
         source_dooray_project_id,
         source_dooray_task_id,
-        project_code,
+        account.project_code(),
     )
-    .await?
+    .await
     .ok_or_else(|| {
         ApiError::BadRequest("소스 태스크의 Dooray 정보를 가져올 수 없습니다.".to_string())
     })?;
 
-    // 7. Build mention HTML
-    let mention_html = build_mention_html(&mention_info);
-
-    // 8. Post comment to target task
-    let response = client
-        .post(format!(
-            "{}/project/v1/projects/{}/posts/{}/logs",
-            DOORAY_API_BASE, target_project_id, target_task_id
-        ))
-        .json(&serde_json::json!({
-            "body": {
-                "mimeType": "text/x-markdown",
-                "content": mention_html
-            }
-        }))
-        .send()
+    // 6. Post mention comment to target task
+    if let Err(e) = client
+        .create_post_log(
+            target_project_id,
+            &target_task_id,
+            &build_mention_html(&mention_info),
+        )
         .await
-        .map_err(|e| ApiError::BadRequest(format!("Dooray API error: {}", e)))?;
-
-    if !response.status().is_success() {
-        return Ok(ResponseJson(ApiResponse::success(CreateDoorayCommentResult {
-            success: false,
-            message: "대상 태스크에 참조 댓글 등록에 실패했습니다.".to_string(),
-        })));
+    {
+        tracing::warn!("Failed to post cross-reference comment: {}", e);
+        return Ok(ResponseJson(ApiResponse::success(
+            CreateDoorayCommentResult {
+                success: false,
+                message: "대상 태스크에 참조 댓글 등록에 실패했습니다.".to_string(),
+            },
+        )));
     }
 
-    Ok(ResponseJson(ApiResponse::success(CreateDoorayCommentResult {
-        success: true,
-        message: "대상 태스크에 참조가 등록되었습니다.".to_string(),
-    })))
+    Ok(ResponseJson(ApiResponse::success(
+        CreateDoorayCommentResult {
+            success: true,
+            message: "대상 태스크에 참조가 등록되었습니다.".to_string(),
+        },
+    )))
 }
 
 // ============== Dooray Members Endpoint ==============
 
 async fn get_dooray_members(
     State(deployment): State<DeploymentImpl>,
-    axum::extract::Path(dooray_project_id): axum::extract::Path<String>,
+    Path(dooray_project_id): Path<String>,
 ) -> Result<ResponseJson<ApiResponse<Vec<DoorayMember>>>, ApiError> {
     tracing::debug!("Fetching members for project {}", dooray_project_id);
 
-    let settings = get_required_settings(&deployment).await?;
-    let client = create_dooray_client(&settings.dooray_token)?;
+    let client = account_for(&deployment, None, &dooray_project_id)
+        .await?
+        .client;
 
     // Step 1: Fetch project member IDs
-    let response = client
-        .get(format!(
-            "{}/project/v1/projects/{}/members",
-            DOORAY_API_BASE, dooray_project_id
-        ))
-        .query(&[("size", "100")])
-        .send()
-        .await
-        .map_err(|e| ApiError::BadRequest(format!("Dooray API error: {}", e)))?;
-
-    if !response.status().is_success() {
-        tracing::warn!(
-            "Dooray members API returned status {} for project {}",
-            response.status(),
-            dooray_project_id
-        );
-        return Ok(ResponseJson(ApiResponse::error(
-            &format!("Dooray API 오류 (status: {})", response.status()),
-        )));
-    }
-
-    let api_response: DoorayMembersApiResponse = response
-        .json()
-        .await
-        .map_err(|e| ApiError::BadRequest(format!("Failed to parse Dooray response: {}", e)))?;
-
-    let member_ids: Vec<String> = api_response
-        .result
-        .unwrap_or_default()
-        .into_iter()
-        .filter_map(|m| m.organization_member_id)
-        .collect();
+    let member_ids: Vec<String> = match client.list_project_members(&dooray_project_id).await {
+        Ok(members) => members
+            .into_iter()
+            .filter_map(|m| m.organization_member_id)
+            .collect(),
+        Err(e) => {
+            tracing::warn!(
+                "Dooray members API failed for project {}: {}",
+                dooray_project_id,
+                e
+            );
+            return Ok(ResponseJson(ApiResponse::error(&format!(
+                "Dooray API 오류 ({})",
+                e
+            ))));
+        }
+    };
 
     tracing::debug!("Found {} member IDs, fetching details...", member_ids.len());
 
-    // Step 2: Fetch each member's detail (name) in parallel via /common/v1/members/{id}
-    let futures: Vec<_> = member_ids
+    // Step 2: Fetch each member's detail (name) in parallel
+    let members: Vec<DoorayMember> = client
+        .resolve_member_names(member_ids)
+        .await
         .into_iter()
-        .map(|id| {
-            let client = client.clone();
-            async move {
-                let resp = client
-                    .get(format!("{}/common/v1/members/{}", DOORAY_API_BASE, id))
-                    .send()
-                    .await
-                    .ok()?;
-                let detail: DoorayMemberDetailResponse = resp.json().await.ok()?;
-                let name = detail.result?.name?;
-                Some(DoorayMember { id, name })
-            }
-        })
+        .map(|(id, name)| DoorayMember { id, name })
         .collect();
 
-    let results = futures_util::future::join_all(futures).await;
-    let members: Vec<DoorayMember> = results.into_iter().flatten().collect();
-
-    tracing::debug!("Fetched {} members for project {}", members.len(), dooray_project_id);
+    tracing::debug!(
+        "Fetched {} members for project {}",
+        members.len(),
+        dooray_project_id
+    );
     Ok(ResponseJson(ApiResponse::success(members)))
 }
 
@@ -1733,43 +1689,33 @@ async fn change_assignee(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<ChangeAssigneeRequest>,
 ) -> Result<ResponseJson<ApiResponse<CreateDoorayCommentResult>>, ApiError> {
-    use super::dooray_body::DOORAY_TASK_URL_RE;
-
     // 1. Parse target_url to extract target_task_id
-    let target_task_id = DOORAY_TASK_URL_RE
-        .captures(&payload.target_url)
-        .and_then(|cap| cap.get(1).or_else(|| cap.get(2)))
-        .map(|m| m.as_str().to_string())
-        .ok_or_else(|| {
-            ApiError::BadRequest("유효한 Dooray 태스크 URL이 아닙니다.".to_string())
-        })?;
-
-    // 2. Get Dooray settings
-    let settings = get_required_settings(&deployment).await?;
-    let client = create_dooray_client(&settings.dooray_token)?;
+    let target_task_id = task_id_from_url(&payload.target_url)
+        .ok_or_else(|| ApiError::BadRequest("유효한 Dooray 태스크 URL이 아닙니다.".to_string()))?;
+
+    // 2. Get the Dooray account of the target project (default: project selected in settings)
+    let pool = &deployment.db().pool;
+    let account = match payload.dooray_project_id.as_deref() {
+        Some(dooray_project_id) => {
+            DoorayAccount::for_dooray_project(pool, None, dooray_project_id).await?
+        }
+        None => DoorayAccount::for_connection(pool, None).await?,
+    };
+    let client = &account.client;
 
-    let target_project_id = settings
-        .selected_project_id
-        .as_deref()
-        .ok_or_else(|| ApiError::BadRequest("Dooray 프로젝트가 설정되지 않았습니다.".to_string()))?;
+    let target_project_id = account.dooray_project_id.as_deref().ok_or_else(|| {
+        ApiError::BadRequest("Dooray 프로젝트가 설정되지 않았습니다.".to_string())
+    })?;
 
     // 3. Fetch organization ID from project detail
-    let project_response: DoorayApiResponse<DoorayProjectDetailForOrg> = client
-        .get(format!(
-            "{}/project/v1/projects/{}",
-            DOORAY_API_BASE, target_project_id
-        ))
-        .send()
-        .await
-        .map_err(|e| ApiError::BadRequest(format!("Dooray API error: {}", e)))?
-        .json()
-        .await
-        .map_err(|e| ApiError::BadRequest(format!("Failed to parse project detail: {}", e)))?;
-
-    let org_id = project_response
-        .result
-        .and_then(|p| p.organization.map(|o| o.id))
-        .ok_or_else(|| ApiError::BadRequest("프로젝트의 조직 ID를 가져올 수 없습니다.".to_string()))?;
+    let org_id = client
+        .get_project(target_project_id)
+        .await?
+        .organization
+        .map(|o| o.id)
+        .ok_or_else(|| {
+            ApiError::BadRequest("프로젝트의 조직 ID를 가져올 수 없습니다.".to_string())
+        })?;
 
     // 4. Build member mention in Dooray markdown format
     // Format: ->[@name](dooray://ORG_ID/members/MEMBER_ID "member")
@@ -1779,151 +1725,322 @@ async fn change_assignee(
     );
 
     // 5. Post comment to target Dooray task
-    let response = client
-        .post(format!(
-            "{}/project/v1/projects/{}/posts/{}/logs",
-            DOORAY_API_BASE, target_project_id, target_task_id
-        ))
-        .json(&serde_json::json!({
-            "body": {
-                "mimeType": "text/x-markdown",
-                "content": mention_md
-            }
-        }))
-        .send()
+    if let Err(e) = client
+        .create_post_log(target_project_id, &target_task_id, &mention_md)
         .await
-        .map_err(|e| ApiError::BadRequest(format!("Dooray API error: {}", e)))?;
+    {
+        tracing::warn!("Failed to post assignee change comment: {}", e);
+        return Ok(ResponseJson(ApiResponse::success(
+            CreateDoorayCommentResult {
+                success: false,
+                message: "담당자 변경 코멘트 등록에 실패했습니다.".to_string(),
+            },
+        )));
+    }
 
-    if !response.status().is_success() {
-        return Ok(ResponseJson(ApiResponse::success(CreateDoorayCommentResult {
-            success: false,
-            message: "담당자 변경 코멘트 등록에 실패했습니다.".to_string(),
-        })));
+    Ok(ResponseJson(ApiResponse::success(
+        CreateDoorayCommentResult {
+            success: true,
+            message: format!("담당자가 {}(으)로 변경되었습니다.", payload.member_name),
+        },
+    )))
+}
+
+// ============== Dooray Templates Endpoints ==============
+
+async fn get_dooray_templates(
+    State(deployment): State<DeploymentImpl>,
+    Path(dooray_project_id): Path<String>,
+) -> Result<ResponseJson<ApiResponse<Vec<DoorayTemplate>>>, ApiError> {
+    let client = account_for(&deployment, None, &dooray_project_id)
+        .await?
+        .client;
+
+    let templates = client.list_templates(&dooray_project_id).await?;
+    Ok(ResponseJson(ApiResponse::success(templates)))
+}
+
+async fn get_dooray_template(
+    State(deployment): State<DeploymentImpl>,
+    Path((dooray_project_id, template_id)): Path<(String, String)>,
+) -> Result<ResponseJson<ApiResponse<DoorayTemplateDetail>>, ApiError> {
+    let client = account_for(&deployment, None, &dooray_project_id)
+        .await?
+        .client;
+
+    match client.get_template(&dooray_project_id, &template_id).await {
+        Ok(template) => Ok(ResponseJson(ApiResponse::success(template))),
+        Err(DoorayError::EmptyResult | DoorayError::NotFound) => {
+            Ok(ResponseJson(ApiResponse::error("Template not found")))
+        }
+        Err(e) => Err(e.into()),
     }
+}
 
-    Ok(ResponseJson(ApiResponse::success(CreateDoorayCommentResult {
-        success: true,
-        message: format!("담당자가 {}(으)로 변경되었습니다.", payload.member_name),
-    })))
+// ============== Workflow Mapping / Status Sync Endpoints ==============
+
+#[derive(Debug, Serialize, TS)]
+pub struct DoorayWorkflowMappingsResponse {
+    pub workflows: Vec<DoorayWorkflow>,
+    pub mappings: Vec<DoorayWorkflowMapping>,
 }
 
-#[derive(Debug, Deserialize)]
-struct DoorayProjectDetailForOrg {
-    organization: Option<DoorayOrganizationRef>,
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateWorkflowMappingsRequest {
+    pub mappings: Vec<UpsertDoorayWorkflowMapping>,
 }
 
-#[derive(Debug, Deserialize)]
-struct DoorayOrganizationRef {
-    id: String,
+#[derive(Debug, Serialize, TS)]
+pub struct DoorayStatusConflict {
+    pub task_id: Uuid,
+    pub title: String,
+    pub dooray_task_number: Option<String>,
+    /// Current kanban status
+    pub local_status: TaskStatus,
+    /// Status both sides last agreed on
+    pub synced_status: TaskStatus,
+    /// Dooray workflow the post moved to in the meantime
+    pub remote_workflow_id: String,
 }
 
-// ============== Dooray Templates Endpoints ==============
+#[derive(Debug, Deserialize, TS)]
+pub struct ResolveStatusConflictRequest {
+    pub resolution: StatusConflictResolution,
+}
 
-async fn get_dooray_templates(
+async fn get_workflow_mappings(
     State(deployment): State<DeploymentImpl>,
-    axum::extract::Path(dooray_project_id): axum::extract::Path<String>,
-) -> Result<ResponseJson<ApiResponse<Vec<DoorayTemplate>>>, ApiError> {
-    let settings = get_required_settings(&deployment).await?;
-    let client = create_dooray_client(&settings.dooray_token)?;
-
-    let response = client
-        .get(format!(
-            "{}/project/v1/projects/{}/templates",
-            DOORAY_API_BASE, dooray_project_id
-        ))
-        .query(&[("page", "0"), ("size", "100")])
-        .send()
-        .await
-        .map_err(|e| ApiError::BadRequest(format!("Dooray API error: {}", e)))?;
+    Path(dooray_project_id): Path<String>,
+    Query(query): Query<ConnectionQuery>,
+) -> Result<ResponseJson<ApiResponse<DoorayWorkflowMappingsResponse>>, ApiError> {
+    let client = account_for(&deployment, query.connection_id, &dooray_project_id)
+        .await?
+        .client;
 
-    if !response.status().is_success() {
-        return Ok(ResponseJson(ApiResponse::error("Failed to fetch Dooray templates")));
-    }
+    let workflows = client.list_workflows(&dooray_project_id).await?;
+    let mappings =
+        DoorayWorkflowMapping::find_by_dooray_project_id(&deployment.db().pool, &dooray_project_id)
+            .await?;
 
-    let api_response: DoorayTemplatesApiResponse = response
-        .json()
-        .await
-        .map_err(|e| ApiError::BadRequest(format!("Failed to parse Dooray response: {}", e)))?;
+    Ok(ResponseJson(ApiResponse::success(
+        DoorayWorkflowMappingsResponse {
+            workflows,
+            mappings,
+        },
+    )))
+}
 
-    let templates = api_response.result.unwrap_or_default();
-    Ok(ResponseJson(ApiResponse::success(templates)))
+async fn update_workflow_mappings(
+    State(deployment): State<DeploymentImpl>,
+    Path(dooray_project_id): Path<String>,
+    Query(query): Query<ConnectionQuery>,
+    Json(payload): Json<UpdateWorkflowMappingsRequest>,
+) -> Result<ResponseJson<ApiResponse<Vec<DoorayWorkflowMapping>>>, ApiError> {
+    let client = account_for(&deployment, query.connection_id, &dooray_project_id)
+        .await?
+        .client;
+
+    let workflows = client.list_workflows(&dooray_project_id).await?;
+    let mut seen_workflows = HashSet::new();
+    let mut push_targets = HashSet::new();
+    for mapping in &payload.mappings {
+        if !workflows.iter().any(|w| w.id == mapping.workflow_id) {
+            return Err(ApiError::BadRequest(format!(
+                "Unknown Dooray workflow: {}",
+                mapping.workflow_id
+            )));
+        }
+        if !seen_workflows.insert(mapping.workflow_id.as_str()) {
+            return Err(ApiError::BadRequest(format!(
+                "Workflow {} is mapped more than once",
+                mapping.workflow_id
+            )));
+        }
+        if mapping.is_push_target && !push_targets.insert(mapping.task_status.to_string()) {
+            return Err(ApiError::BadRequest(format!(
+                "Only one push target is allowed per status ({})",
+                mapping.task_status
+            )));
+        }
+    }
+
+    let mappings = DoorayWorkflowMapping::replace_for_project(
+        &deployment.db().pool,
+        &dooray_project_id,
+        &payload.mappings,
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(mappings)))
 }
 
-async fn get_dooray_template(
+async fn get_status_conflicts(
     State(deployment): State<DeploymentImpl>,
-    axum::extract::Path((dooray_project_id, template_id)): axum::extract::Path<(String, String)>,
-) -> Result<ResponseJson<ApiResponse<DoorayTemplateDetail>>, ApiError> {
-    let settings = get_required_settings(&deployment).await?;
-    let client = create_dooray_client(&settings.dooray_token)?;
-
-    let response = client
-        .get(format!(
-            "{}/project/v1/projects/{}/templates/{}",
-            DOORAY_API_BASE, dooray_project_id, template_id
-        ))
-        .send()
-        .await
-        .map_err(|e| ApiError::BadRequest(format!("Dooray API error: {}", e)))?;
+) -> Result<ResponseJson<ApiResponse<Vec<DoorayStatusConflict>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let mut conflicts = Vec::new();
+
+    for state in DoorayTaskSyncState::find_conflicts(pool).await? {
+        let (Some(task), Some(remote_workflow_id)) = (
+            Task::find_by_id(pool, state.task_id).await?,
+            state.conflict_workflow_id,
+        ) else {
+            continue;
+        };
+        conflicts.push(DoorayStatusConflict {
+            task_id: task.id,
+            title: task.title,
+            dooray_task_number: task.dooray_task_number,
+            local_status: task.status,
+            synced_status: state.task_status,
+            remote_workflow_id,
+        });
+    }
+
+    Ok(ResponseJson(ApiResponse::success(conflicts)))
+}
 
-    if !response.status().is_success() {
-        return Ok(ResponseJson(ApiResponse::error("Failed to fetch Dooray template")));
+async fn resolve_status_conflict(
+    State(deployment): State<DeploymentImpl>,
+    Path(task_id): Path<Uuid>,
+    Json(payload): Json<ResolveStatusConflictRequest>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let task = Task::find_by_id(pool, task_id)
+        .await?
+        .ok_or(ApiError::Database(sqlx::Error::RowNotFound))?;
+    if task.dooray_task_id.is_none() {
+        return Err(ApiError::BadRequest(
+            "Task is not linked to a Dooray post".to_string(),
+        ));
     }
 
-    let api_response: DoorayTemplateDetailApiResponse = response
-        .json()
-        .await
-        .map_err(|e| ApiError::BadRequest(format!("Failed to parse Dooray response: {}", e)))?;
+    let account = DoorayAccount::for_task(pool, &task).await?;
+    status_sync::resolve_conflict(pool, &account.client, &task, payload.resolution).await?;
 
-    match api_response.result {
-        Some(template) => Ok(ResponseJson(ApiResponse::success(template))),
-        None => Ok(ResponseJson(ApiResponse::error("Template not found"))),
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+// ============== Report Settings Endpoints ==============
+
+#[derive(Debug, Serialize, TS)]
+pub struct DoorayReportSettingsResponse {
+    /// `None` until reporting is configured for the project
+    pub settings: Option<DoorayReportSettings>,
+    /// Template used when `comment_template` is empty
+    pub default_template: String,
+}
+
+async fn get_report_settings(
+    State(deployment): State<DeploymentImpl>,
+    Path(project_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<DoorayReportSettingsResponse>>, ApiError> {
+    let settings =
+        DoorayReportSettings::find_by_project_id(&deployment.db().pool, project_id).await?;
+    Ok(ResponseJson(ApiResponse::success(
+        DoorayReportSettingsResponse {
+            settings,
+            default_template: DEFAULT_REPORT_TEMPLATE.to_string(),
+        },
+    )))
+}
+
+async fn update_report_settings(
+    State(deployment): State<DeploymentImpl>,
+    Path(project_id): Path<Uuid>,
+    Json(payload): Json<UpsertDoorayReportSettings>,
+) -> Result<ResponseJson<ApiResponse<DoorayReportSettings>>, ApiError> {
+    let pool = &deployment.db().pool;
+    if Project::find_by_id(pool, project_id).await?.is_none() {
+        return Err(ApiError::Database(sqlx::Error::RowNotFound));
     }
+    let settings = DoorayReportSettings::upsert(pool, project_id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(settings)))
 }
 
 // ============== Helper Functions ==============
 
-fn create_dooray_client(token: &str) -> Result<reqwest::Client, ApiError> {
-    let mut headers = HeaderMap::new();
-
-    // Handle case where user might enter token with or without prefix
-    let clean_token = token.trim();
-    let auth_value = if clean_token.starts_with("dooray-api ") {
-        clean_token.to_string()
-    } else {
-        format!("dooray-api {}", clean_token)
+/// Account for `dooray_project_id`, or for an explicitly selected connection
+async fn account_for(
+    deployment: &DeploymentImpl,
+    connection_id: Option<Uuid>,
+    dooray_project_id: &str,
+) -> Result<DoorayAccount, ApiError> {
+    let pool = &deployment.db().pool;
+    let account = match connection_id {
+        Some(_) => DoorayAccount::for_connection(pool, connection_id).await?,
+        None => DoorayAccount::for_dooray_project(pool, None, dooray_project_id).await?,
     };
+    Ok(account)
+}
+
+/// Link already imported sub-posts to a newly imported post. The import itself
+/// succeeded, so failures are only logged.
+async fn adopt_sub_posts(
+    deployment: &DeploymentImpl,
+    client: &DoorayClient,
+    dooray_project_id: &str,
+    post_id: &str,
+    task_id: Uuid,
+) {
+    if let Err(e) = hierarchy::adopt_sub_posts(
+        &deployment.db().pool,
+        client,
+        dooray_project_id,
+        post_id,
+        task_id,
+    )
+    .await
+    {
+        tracing::warn!("Failed to link sub-posts of Dooray post {}: {}", post_id, e);
+    }
+}
 
-    headers.insert(
-        AUTHORIZATION,
-        HeaderValue::from_str(&auth_value)
-            .map_err(|e| ApiError::BadRequest(format!("Invalid token format: {}", e)))?,
-    );
-    headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+/// Fetch the markdown body of a post; a post that can't be read yields no description.
+async fn fetch_post_description(
+    client: &DoorayClient,
+    dooray_project_id: &str,
+    dooray_task_id: &str,
+) -> Result<Option<String>, ApiError> {
+    match client.get_post(dooray_project_id, dooray_task_id).await {
+        Ok(detail) => Ok(detail.body.and_then(|b| b.content)),
+        Err(e) if e.should_retry() => Err(e.into()),
+        Err(e) => {
+            tracing::warn!(
+                "Failed to fetch Dooray task detail {}: {}",
+                dooray_task_id,
+                e
+            );
+            Ok(None)
+        }
+    }
+}
 
-    reqwest::Client::builder()
-        .default_headers(headers)
-        .build()
-        .map_err(|e| ApiError::BadRequest(format!("Failed to create HTTP client: {}", e)))
+fn masked_connection(connection: DoorayConnection) -> DoorayConnection {
+    DoorayConnection {
+        dooray_token: mask_token(&connection.dooray_token),
+        ..connection
+    }
 }
 
-async fn get_required_settings(deployment: &DeploymentImpl) -> Result<DooraySettings, ApiError> {
-    let result = DooraySettings::get(&deployment.db().pool).await?;
-    match result {
-        Some(settings) => {
-            tracing::debug!("Found dooray settings");
-            Ok(settings)
-        }
-        None => {
-            tracing::warn!("Dooray integration not configured - no settings in DB");
-            Err(ApiError::BadRequest("Dooray integration not configured".to_string()))
-        }
+fn masked(settings: DooraySettings) -> DooraySettings {
+    DooraySettings {
+        dooray_token: mask_token(&settings.dooray_token),
+        ..settings
     }
 }
 
-fn mask_token(token: &str) -> String {
+/// Mask a stored (possibly encrypted) token for display
+fn mask_token(stored: &str) -> String {
+    let token = match reveal_token(stored) {
+        Ok(token) => token,
+        Err(e) => {
+            tracing::warn!("Failed to decrypt stored Dooray token: {}", e);
+            return "********".to_string();
+        }
+    };
     if token.len() <= 8 {
         "*".repeat(token.len())
     } else {
-        format!("{}...{}", &token[..4], &token[token.len()-4..])
+        format!("{}...{}", &token[..4], &token[token.len() - 4..])
     }
 }
@@ -10,7 +10,6 @@ pub mod approvals;
 pub mod config;
 pub mod containers;
 pub mod dooray;
-pub mod dooray_body;
 pub mod filesystem;
 // pub mod github;
 pub mod events;
@@ -19,6 +18,7 @@ pub mod frontend;
 pub mod health;
 pub mod images;
 pub mod migration;
+pub mod model_prices;
 pub mod oauth;
 pub mod organizations;
 pub mod projects;
@@ -52,6 +52,7 @@ pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
         .merge(scratch::router(&deployment))
         .merge(search::router(&deployment))
         .merge(migration::router())
+        .merge(model_prices::router())
         .merge(sessions::router(&deployment))
         .merge(terminal::router())
         .nest("/images", images::routes())
@@ -0,0 +1,78 @@
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+    routing::{delete, get},
+};
+use db::models::model_price::{ModelPrice, UpsertModelPrice};
+use deployment::Deployment;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+pub async fn get_model_prices(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ModelPrice>>>, ApiError> {
+    let prices = ModelPrice::find_all(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(prices)))
+}
+
+/// Add or change the price of the models matching a pattern
+pub async fn upsert_model_price(
+    State(deployment): State<DeploymentImpl>,
+    Json(mut payload): Json<UpsertModelPrice>,
+) -> Result<ResponseJson<ApiResponse<ModelPrice>>, ApiError> {
+    payload.model_pattern = payload.model_pattern.trim().to_string();
+    if payload.model_pattern.is_empty() {
+        return Err(ApiError::BadRequest(
+            "model_pattern must not be empty".to_string(),
+        ));
+    }
+    let prices = [
+        payload.input_price,
+        payload.output_price,
+        payload.cache_read_price,
+        payload.cache_write_price,
+    ];
+    if prices
+        .iter()
+        .any(|price| !price.is_finite() || *price < 0.0)
+    {
+        return Err(ApiError::BadRequest(
+            "Prices must be non-negative numbers".to_string(),
+        ));
+    }
+
+    let price = ModelPrice::upsert(&deployment.db().pool, &payload).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "model_price_updated",
+            serde_json::json!({
+                "model_pattern": price.model_pattern,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(price)))
+}
+
+pub async fn delete_model_price(
+    State(deployment): State<DeploymentImpl>,
+    Path(model_pattern): Path<String>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = ModelPrice::delete(&deployment.db().pool, &model_pattern).await?;
+    if rows_affected == 0 {
+        Err(ApiError::Database(sqlx::Error::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    let inner = Router::new()
+        .route("/", get(get_model_prices).put(upsert_model_price))
+        .route("/{model_pattern}", delete(delete_model_price));
+
+    Router::new().nest("/model-prices", inner)
+}
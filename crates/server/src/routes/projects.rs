@@ -13,13 +13,22 @@ use axum::{
     routing::{get, post},
 };
 use db::models::{
+    approval_rule::{ApprovalRule, UpsertApprovalRule},
+    execution_process_logs::{ExecutionProcessLogs, ProjectLogUsage},
     project::{CreateProject, Project, ProjectError, SearchResult, UpdateProject},
+    project_docker_settings::{ProjectDockerSettings, UpsertProjectDockerSettings},
     project_repo::{CreateProjectRepo, ProjectRepo},
+    project_retry_policy::{ProjectRetryPolicy, UpsertProjectRetryPolicy},
     repo::Repo,
 };
 use deployment::Deployment;
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
-use services::services::{file_search::SearchQuery, project::ProjectServiceError};
+use services::services::{
+    approvals::policy,
+    file_search::SearchQuery,
+    project::ProjectServiceError,
+    token_cost::{self, ProjectCost},
+};
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
@@ -433,6 +442,147 @@ pub async fn get_project_repository(
     }
 }
 
+pub async fn get_approval_rules(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ApprovalRule>>>, ApiError> {
+    let rules = ApprovalRule::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(rules)))
+}
+
+/// Replace the auto-approval rules of a project, in evaluation order
+pub async fn update_approval_rules(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<Vec<UpsertApprovalRule>>,
+) -> Result<ResponseJson<ApiResponse<Vec<ApprovalRule>>>, ApiError> {
+    let rules = policy::prepare_rules(payload)
+        .map_err(|e| ApiError::BadRequest(format!("Invalid approval rule: {e}")))?;
+    let rules =
+        ApprovalRule::replace_for_project(&deployment.db().pool, project.id, &rules).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "approval_rules_updated",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "rule_count": rules.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(rules)))
+}
+
+pub async fn get_retry_policy(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<ProjectRetryPolicy>>>, ApiError> {
+    let policy = ProjectRetryPolicy::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(policy)))
+}
+
+pub async fn update_retry_policy(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpsertProjectRetryPolicy>,
+) -> Result<ResponseJson<ApiResponse<ProjectRetryPolicy>>, ApiError> {
+    if !(0..=10).contains(&payload.max_retries) {
+        return Err(ApiError::BadRequest(
+            "max_retries must be between 0 and 10".to_string(),
+        ));
+    }
+    if !(1..=3600).contains(&payload.backoff_seconds) {
+        return Err(ApiError::BadRequest(
+            "backoff_seconds must be between 1 and 3600".to_string(),
+        ));
+    }
+
+    let policy = ProjectRetryPolicy::upsert(&deployment.db().pool, project.id, &payload).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "retry_policy_updated",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "max_retries": policy.max_retries,
+                "has_fallback": policy.fallback_executor_profile_id.is_some(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(policy)))
+}
+
+pub async fn get_docker_settings(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<ProjectDockerSettings>>>, ApiError> {
+    let settings =
+        ProjectDockerSettings::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(settings)))
+}
+
+/// Run the processes of the project's workspaces in Docker from their next
+/// execution on
+pub async fn update_docker_settings(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpsertProjectDockerSettings>,
+) -> Result<ResponseJson<ApiResponse<ProjectDockerSettings>>, ApiError> {
+    let image = payload.image.trim();
+    if image.is_empty() || image.starts_with('-') || image.contains(char::is_whitespace) {
+        return Err(ApiError::BadRequest(
+            "image must be a Docker image reference".to_string(),
+        ));
+    }
+
+    let settings = ProjectDockerSettings::upsert(
+        &deployment.db().pool,
+        project.id,
+        &UpsertProjectDockerSettings {
+            image: image.to_string(),
+        },
+    )
+    .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "docker_settings_updated",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(settings)))
+}
+
+pub async fn delete_docker_settings(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    ProjectDockerSettings::delete(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Token usage and cost of the coding agent runs of a project, per task
+pub async fn get_project_cost(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ProjectCost>>, ApiError> {
+    let cost = token_cost::cost_of_project(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(cost)))
+}
+
+/// Log storage of every project with logs, largest first
+pub async fn get_log_usage(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ProjectLogUsage>>>, ApiError> {
+    let usage = ExecutionProcessLogs::usage_by_project(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(usage)))
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let project_id_router = Router::new()
         .route(
@@ -445,6 +595,21 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
             "/repositories",
             get(get_project_repositories).post(add_project_repository),
         )
+        .route(
+            "/approval-rules",
+            get(get_approval_rules).put(update_approval_rules),
+        )
+        .route(
+            "/retry-policy",
+            get(get_retry_policy).put(update_retry_policy),
+        )
+        .route(
+            "/docker-settings",
+            get(get_docker_settings)
+                .put(update_docker_settings)
+                .delete(delete_docker_settings),
+        )
+        .route("/cost", get(get_project_cost))
         .layer(from_fn_with_state(
             deployment.clone(),
             load_project_middleware,
@@ -457,6 +622,7 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
             get(get_project_repository).delete(delete_project_repository),
         )
         .route("/stream/ws", get(stream_projects_ws))
+        .route("/log-usage", get(get_log_usage))
         .nest("/{id}", project_id_router);
 
     Router::new().nest("/projects", projects_router)
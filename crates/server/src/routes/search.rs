@@ -4,10 +4,17 @@ use axum::{
     response::Json as ResponseJson,
     routing::get,
 };
-use db::models::{project::SearchResult, repo::Repo};
+use db::models::{
+    execution_log_entry::{ExecutionLogEntry, LogEntryKind, LogSearchHit},
+    project::SearchResult,
+    repo::Repo,
+};
 use deployment::Deployment;
 use serde::Deserialize;
-use services::services::file_search::{SearchMode, SearchQuery};
+use services::services::{
+    file_search::{SearchMode, SearchQuery},
+    log_search,
+};
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
@@ -68,8 +75,55 @@ pub async fn search_files(
     Ok(ResponseJson(ApiResponse::success(results)))
 }
 
+const DEFAULT_LOG_SEARCH_LIMIT: i64 = 50;
+const MAX_LOG_SEARCH_LIMIT: i64 = 200;
+
+#[derive(Debug, Deserialize)]
+pub struct LogSearchQuery {
+    pub q: String,
+    pub project_id: Option<Uuid>,
+    pub kind: Option<LogEntryKind>,
+    pub limit: Option<i64>,
+}
+
+pub async fn search_logs(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<LogSearchQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<LogSearchHit>>>, ApiError> {
+    let fts_query = log_search::fts_query(&query.q).ok_or_else(|| {
+        ApiError::BadRequest("Search for at least one term of 3 or more characters".to_string())
+    })?;
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_LOG_SEARCH_LIMIT)
+        .clamp(1, MAX_LOG_SEARCH_LIMIT);
+
+    let hits = ExecutionLogEntry::search(
+        &deployment.db().pool,
+        &fts_query,
+        query.project_id,
+        query.kind,
+        limit,
+    )
+    .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "logs_searched",
+            serde_json::json!({
+                "project_scoped": query.project_id.is_some(),
+                "kind": query.kind,
+                "hit_count": hits.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(hits)))
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     Router::new()
         .route("/search", get(search_files))
+        .route("/search/logs", get(search_logs))
         .with_state(deployment.clone())
 }
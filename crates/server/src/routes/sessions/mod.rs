@@ -1,5 +1,6 @@
 pub mod queue;
 pub mod review;
+pub mod transcript;
 
 use axum::{
     Extension, Json, Router,
@@ -25,6 +26,7 @@ use executors::{
 };
 use serde::Deserialize;
 use services::services::container::ContainerService;
+use sqlx::SqlitePool;
 use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
@@ -125,22 +127,7 @@ pub async fn follow_up(
 
     let executor_profile_id = payload.executor_profile_id;
 
-    // Validate executor matches session if session has prior executions
-    let expected_executor: Option<String> =
-        ExecutionProcess::latest_executor_profile_for_session(pool, session.id)
-            .await?
-            .map(|profile| profile.executor.to_string())
-            .or_else(|| session.executor.clone());
-
-    if let Some(expected) = expected_executor {
-        let actual = executor_profile_id.executor.to_string();
-        if expected != actual {
-            return Err(ApiError::Session(SessionError::ExecutorMismatch {
-                expected,
-                actual,
-            }));
-        }
-    }
+    ensure_session_executor(pool, &session, &executor_profile_id).await?;
 
     if session.executor.is_none() {
         Session::update_executor(pool, session.id, &executor_profile_id.executor.to_string())
@@ -214,6 +201,31 @@ pub async fn follow_up(
     Ok(ResponseJson(ApiResponse::success(execution_process)))
 }
 
+/// Reject a profile whose executor differs from the one the session already
+/// runs with (its latest execution, else the executor it was created with)
+pub(crate) async fn ensure_session_executor(
+    pool: &SqlitePool,
+    session: &Session,
+    executor_profile_id: &ExecutorProfileId,
+) -> Result<(), ApiError> {
+    let expected_executor: Option<String> =
+        ExecutionProcess::latest_executor_profile_for_session(pool, session.id)
+            .await?
+            .map(|profile| profile.executor.to_string())
+            .or_else(|| session.executor.clone());
+
+    if let Some(expected) = expected_executor {
+        let actual = executor_profile_id.executor.to_string();
+        if expected != actual {
+            return Err(ApiError::Session(SessionError::ExecutorMismatch {
+                expected,
+                actual,
+            }));
+        }
+    }
+    Ok(())
+}
+
 pub async fn reset_process(
     Extension(session): Extension<Session>,
     State(deployment): State<DeploymentImpl>,
@@ -241,6 +253,7 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/follow-up", post(follow_up))
         .route("/reset", post(reset_process))
         .route("/review", post(review::start_review))
+        .route("/transcript", get(transcript::export_transcript))
         .layer(from_fn_with_state(
             deployment.clone(),
             load_session_middleware,
@@ -1,38 +1,84 @@
 use axum::{
-    Extension, Json, Router, extract::State, middleware::from_fn_with_state,
-    response::Json as ResponseJson, routing::get,
+    Extension, Json, Router,
+    extract::{
+        Path, State,
+        ws::{WebSocket, WebSocketUpgrade},
+    },
+    middleware::from_fn_with_state,
+    response::{IntoResponse, Json as ResponseJson},
+    routing::{get, post, put},
+};
+use db::models::{
+    execution_process::ExecutionProcess,
+    scratch::{Scratch, ScratchType},
+    session::Session,
+    workspace::{Workspace, WorkspaceError},
 };
-use db::models::{scratch::DraftFollowUpData, session::Session};
 use deployment::Deployment;
 use executors::profile::ExecutorProfileId;
+use futures_util::{SinkExt, StreamExt, TryStreamExt};
 use serde::Deserialize;
-use services::services::queued_message::QueueStatus;
+use services::services::{container::ContainerService, queued_message::QueueStatus};
 use ts_rs::TS;
 use utils::response::ApiResponse;
+use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError, middleware::load_session_middleware};
+use crate::{
+    DeploymentImpl, error::ApiError, middleware::load_session_middleware,
+    routes::sessions::ensure_session_executor,
+};
 
 /// Request body for queueing a follow-up message
 #[derive(Debug, Deserialize, TS)]
 pub struct QueueMessageRequest {
     pub message: String,
-    pub executor_profile_id: ExecutorProfileId,
+    /// Profile to run the message with; the session's latest profile if unset
+    pub executor_profile_id: Option<ExecutorProfileId>,
+}
+
+/// Request body for editing a queued follow-up message
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateQueuedMessageRequest {
+    pub message: String,
+    pub executor_profile_id: Option<ExecutorProfileId>,
+}
+
+/// Request body for reordering a session's queue
+#[derive(Debug, Deserialize, TS)]
+pub struct ReorderQueueRequest {
+    /// Every queued message of the session, next one first
+    pub message_ids: Vec<Uuid>,
 }
 
-/// Queue a follow-up message to be executed when the current execution finishes
+/// Queue a follow-up message to be executed after the queued messages before it
 pub async fn queue_message(
     Extension(session): Extension<Session>,
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<QueueMessageRequest>,
 ) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
-    let data = DraftFollowUpData {
-        message: payload.message,
-        executor_profile_id: payload.executor_profile_id,
-    };
+    if let Some(profile) = &payload.executor_profile_id {
+        ensure_session_executor(&deployment.db().pool, &session, profile).await?;
+    }
 
-    let queued = deployment
+    let status = deployment
         .queued_message_service()
-        .queue_message(session.id, data);
+        .queue_message(
+            session.id,
+            &payload.message,
+            payload.executor_profile_id.as_ref(),
+        )
+        .await?;
+
+    // The queued message was written in the follow-up draft, which starts over
+    if let Err(e) = Scratch::delete(
+        &deployment.db().pool,
+        session.id,
+        &ScratchType::DraftFollowUp,
+    )
+    .await
+    {
+        tracing::warn!("Failed to delete draft after queueing message: {}", e);
+    }
 
     deployment
         .track_if_analytics_allowed(
@@ -40,23 +86,23 @@ pub async fn queue_message(
             serde_json::json!({
                 "session_id": session.id.to_string(),
                 "workspace_id": session.workspace_id.map(|id| id.to_string()),
+                "queue_length": status.messages.len(),
             }),
         )
         .await;
 
-    Ok(ResponseJson(ApiResponse::success(QueueStatus::Queued {
-        message: queued,
-    })))
+    Ok(ResponseJson(ApiResponse::success(status)))
 }
 
-/// Cancel a queued follow-up message
+/// Clear the queue of a session
 pub async fn cancel_queued_message(
     Extension(session): Extension<Session>,
     State(deployment): State<DeploymentImpl>,
 ) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
-    deployment
+    let status = deployment
         .queued_message_service()
-        .cancel_queued(session.id);
+        .clear(session.id)
+        .await?;
 
     deployment
         .track_if_analytics_allowed(
@@ -68,19 +114,156 @@ pub async fn cancel_queued_message(
         )
         .await;
 
-    Ok(ResponseJson(ApiResponse::success(QueueStatus::Empty)))
+    Ok(ResponseJson(ApiResponse::success(status)))
 }
 
-/// Get the current queue status for a session's workspace
+/// Get the current queue of a session
 pub async fn get_queue_status(
     Extension(session): Extension<Session>,
     State(deployment): State<DeploymentImpl>,
 ) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
-    let status = deployment.queued_message_service().get_status(session.id);
+    let status = deployment
+        .queued_message_service()
+        .get_status(session.id)
+        .await?;
+
+    Ok(ResponseJson(ApiResponse::success(status)))
+}
+
+/// Start the next queued message of a session whose queue is paused by a failed
+/// or stopped run, or by a server restart
+pub async fn resume_queue(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<ExecutionProcess>>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let workspace_id =
+        session
+            .workspace_id
+            .ok_or(ApiError::Workspace(WorkspaceError::ValidationError(
+                "Design sessions cannot use follow-up".to_string(),
+            )))?;
+    let workspace = Workspace::find_by_id(pool, workspace_id)
+        .await?
+        .ok_or(ApiError::Workspace(WorkspaceError::ValidationError(
+            "Workspace not found".to_string(),
+        )))?;
+
+    // A running execution drains the queue itself once it finishes
+    if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, workspace.id)
+        .await?
+    {
+        return Err(ApiError::Conflict(
+            "An execution is still running in this workspace".to_string(),
+        ));
+    }
+
+    deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+
+    let execution_process = deployment
+        .container()
+        .try_start_queued_follow_up(&workspace, &session)
+        .await?;
+
+    Ok(ResponseJson(ApiResponse::success(execution_process)))
+}
+
+pub async fn reorder_queue(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ReorderQueueRequest>,
+) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
+    let status = deployment
+        .queued_message_service()
+        .reorder(session.id, &payload.message_ids)
+        .await?;
+
+    Ok(ResponseJson(ApiResponse::success(status)))
+}
+
+pub async fn update_queued_message(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+    Path((_session_id, message_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<UpdateQueuedMessageRequest>,
+) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
+    if let Some(profile) = &payload.executor_profile_id {
+        ensure_session_executor(&deployment.db().pool, &session, profile).await?;
+    }
+
+    let status = deployment
+        .queued_message_service()
+        .update_message(
+            session.id,
+            message_id,
+            &payload.message,
+            payload.executor_profile_id.as_ref(),
+        )
+        .await?;
 
     Ok(ResponseJson(ApiResponse::success(status)))
 }
 
+pub async fn remove_queued_message(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+    Path((_session_id, message_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
+    let status = deployment
+        .queued_message_service()
+        .remove_message(session.id, message_id)
+        .await?;
+
+    Ok(ResponseJson(ApiResponse::success(status)))
+}
+
+pub async fn stream_queue_ws(
+    ws: WebSocketUpgrade,
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| async move {
+        if let Err(e) = handle_queue_ws(socket, deployment, session.id).await {
+            tracing::warn!("queue WS closed: {}", e);
+        }
+    })
+}
+
+async fn handle_queue_ws(
+    socket: WebSocket,
+    deployment: DeploymentImpl,
+    session_id: Uuid,
+) -> anyhow::Result<()> {
+    let mut stream = deployment
+        .events()
+        .stream_queue_raw(session_id)
+        .await?
+        .map_ok(|msg| msg.to_ws_message_unchecked());
+
+    let (mut sender, mut receiver) = socket.split();
+
+    tokio::spawn(async move { while let Some(Ok(_)) = receiver.next().await {} });
+
+    while let Some(item) = stream.next().await {
+        match item {
+            Ok(msg) => {
+                if sender.send(msg).await.is_err() {
+                    break;
+                }
+            }
+            Err(e) => {
+                tracing::error!("queue stream error: {}", e);
+                break;
+            }
+        }
+    }
+    Ok(())
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     Router::new()
         .route(
@@ -89,6 +272,13 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
                 .post(queue_message)
                 .delete(cancel_queued_message),
         )
+        .route("/reorder", post(reorder_queue))
+        .route("/resume", post(resume_queue))
+        .route("/stream/ws", get(stream_queue_ws))
+        .route(
+            "/{message_id}",
+            put(update_queued_message).delete(remove_queued_message),
+        )
         .layer(from_fn_with_state(
             deployment.clone(),
             load_session_middleware,
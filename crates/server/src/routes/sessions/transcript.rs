@@ -0,0 +1,96 @@
+use axum::{
+    Extension,
+    extract::{Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason},
+    session::Session,
+    workspace::Workspace,
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::{
+    container::ContainerService,
+    transcript::{TRANSCRIPT_SCHEMA_VERSION, Transcript, TranscriptFormat, TranscriptTurn},
+};
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize)]
+pub struct TranscriptQuery {
+    #[serde(default)]
+    pub format: TranscriptFormat,
+}
+
+/// Download the coding agent runs of a session as one document
+pub async fn export_transcript(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<TranscriptQuery>,
+) -> Result<Response, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let workspace = match session.workspace_id {
+        Some(workspace_id) => Workspace::find_by_id(pool, workspace_id).await?,
+        None => None,
+    };
+    let task = match &workspace {
+        Some(workspace) => workspace.parent_task(pool).await?,
+        None => None,
+    };
+
+    let mut turns = Vec::new();
+    for process in ExecutionProcess::find_by_session_id(pool, session.id, false).await? {
+        if process.run_reason != ExecutionProcessRunReason::CodingAgent {
+            continue;
+        }
+        let entries = deployment.container().normalized_entries(&process.id).await;
+        turns.push(TranscriptTurn::new(&process, &entries));
+    }
+
+    let transcript = Transcript {
+        schema_version: TRANSCRIPT_SCHEMA_VERSION,
+        session_id: session.id,
+        executor: session.executor.clone(),
+        workspace_id: workspace.as_ref().map(|w| w.id),
+        branch: workspace.as_ref().map(|w| w.branch.clone()),
+        task_id: task.as_ref().map(|t| t.id),
+        task_title: task.map(|t| t.title),
+        exported_at: Utc::now(),
+        turns,
+    };
+    let body = transcript
+        .render(query.format)
+        .map_err(std::io::Error::other)?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "transcript_exported",
+            serde_json::json!({
+                "session_id": session.id.to_string(),
+                "format": query.format.extension(),
+                "turn_count": transcript.turns.len(),
+            }),
+        )
+        .await;
+
+    let disposition = format!(
+        "attachment; filename=\"transcript-{}.{}\"",
+        session.id,
+        query.format.extension()
+    );
+    Ok((
+        [
+            (
+                header::CONTENT_TYPE,
+                query.format.content_type().to_string(),
+            ),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    )
+        .into_response())
+}
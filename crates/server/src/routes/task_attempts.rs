@@ -1,4 +1,5 @@
 pub mod codex_setup;
+pub mod compare;
 pub mod cursor_setup;
 pub mod gh_cli_setup;
 pub mod images;
@@ -196,11 +197,35 @@ pub async fn update_workspace(
     Ok(ResponseJson(ApiResponse::success(updated)))
 }
 
+/// Most attempts a single request may start
+const MAX_PARALLEL_ATTEMPTS: usize = 5;
+
 #[derive(Debug, Serialize, Deserialize, ts_rs::TS)]
 pub struct CreateTaskAttemptBody {
     pub task_id: Uuid,
     pub executor_profile_id: ExecutorProfileId,
     pub repos: Vec<WorkspaceRepoInput>,
+    /// Start a sibling attempt in its own workspace for each of these profiles,
+    /// to compare with the first one
+    #[ts(optional)]
+    pub additional_executor_profile_ids: Option<Vec<ExecutorProfileId>>,
+}
+
+/// The attempt of `executor_profile_id`, with the siblings started next to it
+#[derive(Debug, Serialize, TS)]
+pub struct CreateTaskAttemptResponse {
+    #[serde(flatten)]
+    pub workspace: Workspace,
+    /// Attempts started for `additional_executor_profile_ids`, in request order
+    pub siblings: Vec<Workspace>,
+    /// Additional profiles whose attempt couldn't be created
+    pub failed_attempts: Vec<FailedAttempt>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct FailedAttempt {
+    pub executor_profile_id: ExecutorProfileId,
+    pub error: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, ts_rs::TS)]
@@ -217,19 +242,45 @@ pub struct RunAgentSetupRequest {
 #[derive(Debug, Serialize, TS)]
 pub struct RunAgentSetupResponse {}
 
+/// Create a workspace per executor profile and start an attempt in each.
+/// Every profile is checked before any workspace is created. Once the first
+/// attempt exists, a sibling that fails is reported in `failed_attempts`
+/// instead of failing the request, so the attempts already running are
+/// always returned.
 #[axum::debug_handler]
 pub async fn create_task_attempt(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<CreateTaskAttemptBody>,
-) -> Result<ResponseJson<ApiResponse<Workspace>>, ApiError> {
-    let executor_profile_id = payload.executor_profile_id.clone();
-
+) -> Result<ResponseJson<ApiResponse<CreateTaskAttemptResponse>>, ApiError> {
     if payload.repos.is_empty() {
         return Err(ApiError::BadRequest(
             "At least one repository is required".to_string(),
         ));
     }
 
+    let profiles: Vec<ExecutorProfileId> = std::iter::once(payload.executor_profile_id.clone())
+        .chain(
+            payload
+                .additional_executor_profile_ids
+                .clone()
+                .unwrap_or_default(),
+        )
+        .collect();
+    if profiles.len() > MAX_PARALLEL_ATTEMPTS {
+        return Err(ApiError::BadRequest(format!(
+            "At most {MAX_PARALLEL_ATTEMPTS} attempts can be started at once"
+        )));
+    }
+    let executor_configs = ExecutorConfigs::get_cached();
+    if let Some(unknown) = profiles
+        .iter()
+        .find(|profile| executor_configs.get_coding_agent(profile).is_none())
+    {
+        return Err(ApiError::BadRequest(format!(
+            "Unknown executor profile: {unknown}"
+        )));
+    }
+
     let pool = &deployment.db().pool;
     let task = Task::find_by_id(&deployment.db().pool, payload.task_id)
         .await?
@@ -253,6 +304,82 @@ pub async fn create_task_attempt(
         None
     };
 
+    let mut workspaces = Vec::with_capacity(profiles.len());
+    let mut failed_attempts = Vec::new();
+    for (index, executor_profile_id) in profiles.iter().enumerate() {
+        let workspace = match start_attempt(
+            &deployment,
+            &task,
+            &payload.repos,
+            agent_working_dir.clone(),
+            executor_profile_id,
+            index,
+        )
+        .await
+        {
+            Ok(workspace) => workspace,
+            // Nothing has been started yet
+            Err(err) if workspaces.is_empty() => return Err(err),
+            Err(err) => {
+                tracing::error!(
+                    "Failed to create attempt {} for task {}: {}",
+                    executor_profile_id,
+                    task.id,
+                    err
+                );
+                failed_attempts.push(FailedAttempt {
+                    executor_profile_id: executor_profile_id.clone(),
+                    error: err.to_string(),
+                });
+                continue;
+            }
+        };
+
+        deployment
+            .track_if_analytics_allowed(
+                "task_attempt_started",
+                serde_json::json!({
+                    "task_id": workspace.task_id.to_string(),
+                    "variant": &executor_profile_id.variant,
+                    "executor": &executor_profile_id.executor,
+                    "workspace_id": workspace.id.to_string(),
+                    "repository_count": payload.repos.len(),
+                    "parallel_attempts": profiles.len(),
+                }),
+            )
+            .await;
+
+        workspaces.push(workspace);
+    }
+
+    tracing::info!(
+        "Created {} attempt(s) for task {}",
+        workspaces.len(),
+        task.id
+    );
+
+    let mut workspaces = workspaces.into_iter();
+    let workspace = workspaces.next().expect("first attempt was created");
+    Ok(ResponseJson(ApiResponse::success(
+        CreateTaskAttemptResponse {
+            workspace,
+            siblings: workspaces.collect(),
+            failed_attempts,
+        },
+    )))
+}
+
+/// Create a workspace for `task` and start an attempt in it. `index` tells
+/// sibling attempts of one request apart.
+async fn start_attempt(
+    deployment: &DeploymentImpl,
+    task: &Task,
+    repos: &[WorkspaceRepoInput],
+    agent_working_dir: Option<String>,
+    executor_profile_id: &ExecutorProfileId,
+    index: usize,
+) -> Result<Workspace, ApiError> {
+    let pool = &deployment.db().pool;
     let attempt_id = Uuid::new_v4();
 
     // Use Dooray task number for branch name if available
@@ -260,12 +387,15 @@ pub async fn create_task_attempt(
         // Extract just the number part (e.g., "Notification-개발/123" -> "123")
         let number = dooray_number.split('/').last().unwrap_or(dooray_number);
         // Use target_branch from first repo (fallback to "develop")
-        let target_branch = payload
-            .repos
+        let target_branch = repos
             .first()
             .map(|r| r.target_branch.as_str())
             .unwrap_or("develop");
-        format!("feature/{}/{}", target_branch, number)
+        // Siblings can't share the branch of the first attempt
+        match index {
+            0 => format!("feature/{}/{}", target_branch, number),
+            _ => format!("feature/{}/{}-{}", target_branch, number, index + 1),
+        }
     } else {
         deployment
             .container()
@@ -280,12 +410,11 @@ pub async fn create_task_attempt(
             agent_working_dir,
         },
         attempt_id,
-        payload.task_id,
+        task.id,
     )
     .await?;
 
-    let workspace_repos: Vec<CreateWorkspaceRepo> = payload
-        .repos
+    let workspace_repos: Vec<CreateWorkspaceRepo> = repos
         .iter()
         .map(|r| CreateWorkspaceRepo {
             repo_id: r.repo_id,
@@ -302,22 +431,7 @@ pub async fn create_task_attempt(
         tracing::error!("Failed to start task attempt: {}", err);
     }
 
-    deployment
-        .track_if_analytics_allowed(
-            "task_attempt_started",
-            serde_json::json!({
-                "task_id": workspace.task_id.to_string(),
-                "variant": &executor_profile_id.variant,
-                "executor": &executor_profile_id.executor,
-                "workspace_id": workspace.id.to_string(),
-                "repository_count": payload.repos.len(),
-            }),
-        )
-        .await;
-
-    tracing::info!("Created attempt for task {}", task.id);
-
-    Ok(ResponseJson(ApiResponse::success(workspace)))
+    Ok(workspace)
 }
 
 #[axum::debug_handler]
@@ -2015,6 +2129,7 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/", get(get_task_attempts).post(create_task_attempt))
         .route("/from-pr", post(pr::create_workspace_from_pr))
         .route("/count", get(get_workspace_count))
+        .route("/compare", post(compare::compare_attempts))
         .route("/stream/ws", get(stream_workspaces_ws))
         .route("/summary", post(workspace_summary::get_workspace_summaries))
         .nest("/{id}", task_attempt_id_router)
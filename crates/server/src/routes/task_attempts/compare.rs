@@ -0,0 +1,83 @@
+use axum::{Json, extract::State, response::Json as ResponseJson};
+use db::models::{task::Task, workspace::Workspace};
+use deployment::Deployment;
+use executors::profile::ExecutorProfileId;
+use serde::Deserialize;
+use services::services::{
+    attempt_comparison::{self, AttemptComparison},
+    read_only_agent::read_only_agent,
+};
+use sqlx::Error as SqlxError;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CompareAttemptsRequest {
+    pub task_id: Uuid,
+    /// Attempts to compare; every unarchived attempt of the task if omitted
+    #[ts(optional)]
+    pub workspace_ids: Option<Vec<Uuid>>,
+    /// Ask the agent of this profile to rank the attempts
+    #[ts(optional)]
+    pub reviewer_executor_profile_id: Option<ExecutorProfileId>,
+}
+
+/// Compare attempts of a task side by side, oldest first
+pub async fn compare_attempts(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CompareAttemptsRequest>,
+) -> Result<ResponseJson<ApiResponse<AttemptComparison>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let task = Task::find_by_id(pool, payload.task_id)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+
+    let mut workspaces = Workspace::fetch_all(pool, Some(task.id)).await?;
+    match &payload.workspace_ids {
+        Some(ids) => workspaces.retain(|workspace| ids.contains(&workspace.id)),
+        None => workspaces.retain(|workspace| !workspace.archived),
+    }
+    if workspaces.len() < 2 {
+        return Err(ApiError::BadRequest(
+            "At least two attempts of the task are needed to compare".to_string(),
+        ));
+    }
+    workspaces.sort_by_key(|workspace| workspace.created_at);
+    // The reviewer runs in an attempt's worktree, so it must be unable to edit it
+    if let Some(reviewer) = &payload.reviewer_executor_profile_id {
+        read_only_agent(reviewer)?;
+    }
+
+    let mut attempts = Vec::with_capacity(workspaces.len());
+    for workspace in workspaces {
+        let summary =
+            attempt_comparison::summarize_attempt(deployment.container(), &workspace).await?;
+        attempts.push((workspace, summary));
+    }
+
+    let ranking = match &payload.reviewer_executor_profile_id {
+        Some(reviewer) => {
+            Some(attempt_comparison::rank_attempts(pool, &task, &attempts, reviewer).await?)
+        }
+        None => None,
+    };
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_attempts_compared",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "attempt_count": attempts.len(),
+                "ranked": ranking.is_some(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(AttemptComparison {
+        attempts: attempts.into_iter().map(|(_, summary)| summary).collect(),
+        ranking,
+    })))
+}
@@ -7,7 +7,6 @@ use axum::{
 };
 use db::models::{
     coding_agent_turn::CodingAgentTurn,
-    dooray_settings::DooraySettings,
     execution_process::{ExecutionProcess, ExecutionProcessRunReason},
     merge::{Merge, MergeStatus},
     project_repo::ProjectRepo,
@@ -26,6 +25,10 @@ use git::{GitCli, GitCliError, GitRemote, GitServiceError};
 use serde::{Deserialize, Serialize};
 use services::services::{
     container::ContainerService,
+    dooray::{
+        account::DoorayAccount,
+        report::{self as dooray_report, DoorayReportEvent, DoorayReportPr},
+    },
     git_host::{
         self, CreatePrRequest, GitHostError, GitHostProvider, ProviderKind, UnifiedPrComment,
         github::{GhCli, extract_host_from_url},
@@ -173,6 +176,17 @@ Then set the PR title and body using the following format:
 
 Use `gh pr edit {pr_number} --title "TITLE" --body "BODY"` to update the PR."#;
 
+/// Dooray account of the task's post, for its project code and domain
+async fn dooray_account_for(
+    pool: &sqlx::SqlitePool,
+    task: Option<&Task>,
+) -> Option<DoorayAccount> {
+    match task {
+        Some(task) => DoorayAccount::for_task(pool, task).await.ok(),
+        None => DoorayAccount::for_connection(pool, None).await.ok(),
+    }
+}
+
 async fn trigger_pr_description_follow_up(
     deployment: &DeploymentImpl,
     workspace: &Workspace,
@@ -183,7 +197,7 @@ async fn trigger_pr_description_follow_up(
 
     // Get task info for Dooray integration
     let task = workspace.parent_task(pool).await?;
-    let dooray_settings = DooraySettings::get(pool).await.ok().flatten();
+    let dooray_account = dooray_account_for(pool, task.as_ref()).await;
 
     // Determine if we have Dooray info
     let has_dooray = task
@@ -220,11 +234,11 @@ async fn trigger_pr_description_follow_up(
         }
     }
 
-    if let Some(settings) = dooray_settings {
-        if let Some(ref project_name) = settings.selected_project_name {
+    if let Some(account) = dooray_account {
+        if let Some(ref project_name) = account.dooray_project_code {
             prompt = prompt.replace("{dooray_project_name}", project_name);
         }
-        if let Some(ref domain) = settings.dooray_domain {
+        if let Some(ref domain) = account.dooray_domain {
             prompt = prompt.replace("{dooray_domain}", domain);
         } else {
             // Default domain if not set
@@ -455,6 +469,36 @@ pub async fn create_pr(
                 });
             }
 
+            // Comment on the linked Dooray post if the project reports new PRs
+            if let Ok(Some(task)) = workspace.parent_task(pool).await {
+                let pool = pool.clone();
+                let git = deployment.git().clone();
+                let workspace = workspace.clone();
+                let pr = DoorayReportPr {
+                    number: pr_info.number,
+                    url: pr_info.url.clone(),
+                };
+                tokio::spawn(async move {
+                    if let Err(e) = dooray_report::report(
+                        &pool,
+                        &git,
+                        &task,
+                        &workspace,
+                        DoorayReportEvent::PrCreated,
+                        Some(&pr),
+                    )
+                    .await
+                    {
+                        tracing::warn!(
+                            "Failed to report PR #{} to Dooray for task {}: {}",
+                            pr.number,
+                            task.id,
+                            e
+                        );
+                    }
+                });
+            }
+
             // Auto-open PR in browser
             if let Err(e) = utils::browser::open_browser(&pr_info.url).await {
                 tracing::warn!("Failed to open PR in browser: {}", e);
@@ -764,7 +808,7 @@ pub async fn preview_pr_description(
 
     // Get task info for Dooray integration
     let task = workspace.parent_task(pool).await?;
-    let dooray_settings = DooraySettings::get(pool).await.ok().flatten();
+    let dooray_account = dooray_account_for(pool, task.as_ref()).await;
 
     // Get git diff info if target_branch is provided
     let git_summary = if let Some(ref target_branch) = request.target_branch {
@@ -791,14 +835,14 @@ pub async fn preview_pr_description(
             .map(|n| n.split('/').last().unwrap_or(n))
             .unwrap_or("");
 
-        let dooray_project_name = dooray_settings
+        let dooray_project_name = dooray_account
             .as_ref()
-            .and_then(|s| s.selected_project_name.as_deref())
+            .and_then(|a| a.dooray_project_code.as_deref())
             .unwrap_or("");
 
-        let dooray_domain = dooray_settings
+        let dooray_domain = dooray_account
             .as_ref()
-            .and_then(|s| s.dooray_domain.as_deref())
+            .and_then(|a| a.dooray_domain.as_deref())
             .unwrap_or("nhnent.dooray.com");
 
         let title = format!(
@@ -1266,6 +1310,7 @@ pub async fn create_workspace_from_pr(
         dooray_project_id: None,
         dooray_task_number: None,
         reference_dooray_url: None,
+        parent_task_id: None,
     };
     let task = Task::create(pool, &create_task, task_id).await?;
 
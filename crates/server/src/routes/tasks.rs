@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::{collections::HashMap, path::PathBuf};
 
 use anyhow;
 use axum::{
@@ -16,7 +16,6 @@ use std::convert::Infallible;
 use db::models::{
     design_message::{CreateDesignMessage, DesignMessage, DesignMessageRole},
     image::TaskImage,
-    project_repo::ProjectRepo,
     repo::{Repo, RepoError},
     session::{CreateSession, Session},
     task::{CreateTask, Task, TaskStatus, TaskWithAttemptStatus, UpdateTask},
@@ -24,13 +23,25 @@ use db::models::{
     workspace_repo::{CreateWorkspaceRepo, WorkspaceRepo},
 };
 use deployment::Deployment;
-use executors::profile::ExecutorProfileId;
+use executors::{
+    logs::{
+        NormalizedEntry, NormalizedEntryType, utils::patch::extract_normalized_entry_from_patch,
+    },
+    profile::ExecutorProfileId,
+};
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
 use serde::{Deserialize, Serialize};
-use services::services::{container::ContainerService, workspace_manager::WorkspaceManager};
-use sqlx::Error as SqlxError;
+use services::services::{
+    container::ContainerService,
+    design_plan::{self, DesignPlanResult},
+    design_session::DesignTurn,
+    read_only_agent,
+    token_cost::{self, TaskCost},
+    workspace_manager::WorkspaceManager,
+};
+use sqlx::{Error as SqlxError, SqlitePool};
 use ts_rs::TS;
-use utils::response::ApiResponse;
+use utils::{log_msg::LogMsg, response::ApiResponse};
 use uuid::Uuid;
 
 use crate::{
@@ -108,6 +119,15 @@ pub async fn get_task(
     Ok(ResponseJson(ApiResponse::success(task)))
 }
 
+/// Token usage and cost of the coding agent runs of a task, per model
+pub async fn get_task_cost(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<TaskCost>>, ApiError> {
+    let cost = token_cost::cost_of_task(&deployment.db().pool, &task).await?;
+    Ok(ResponseJson(ApiResponse::success(cost)))
+}
+
 pub async fn create_task(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<CreateTask>,
@@ -645,6 +665,9 @@ pub struct DesignChatRequest {
     /// Used by changelog generation to avoid context accumulation across steps.
     #[serde(default)]
     pub skip_history: Option<bool>,
+    /// Executor profile to chat with. Defaults to the configured executor profile.
+    #[serde(default)]
+    pub executor_profile_id: Option<ExecutorProfileId>,
 }
 
 /// Response from AI chat in design session
@@ -654,6 +677,31 @@ pub struct DesignChatResponse {
     pub assistant_message: DesignMessage,
 }
 
+/// Design session of the task, created on first use
+async fn ensure_design_session(pool: &SqlitePool, task: &Task) -> Result<Session, ApiError> {
+    if let Some(id) = task.design_session_id
+        && let Some(session) = Session::find_by_id(pool, id).await?
+    {
+        return Ok(session);
+    }
+
+    let session =
+        Session::create_design_session(pool, &CreateSession { executor: None }, Uuid::new_v4())
+            .await?;
+    Task::update_design_session_id(pool, task.id, Some(session.id)).await?;
+    Ok(session)
+}
+
+async fn design_executor_profile(
+    deployment: &DeploymentImpl,
+    requested: Option<ExecutorProfileId>,
+) -> ExecutorProfileId {
+    match requested {
+        Some(executor_profile_id) => executor_profile_id,
+        None => deployment.config().read().await.executor_profile.clone(),
+    }
+}
+
 /// Send a message to the design chat and get AI response
 pub async fn design_chat(
     Extension(task): Extension<Task>,
@@ -661,26 +709,14 @@ pub async fn design_chat(
     Json(payload): Json<DesignChatRequest>,
 ) -> Result<ResponseJson<ApiResponse<DesignChatResponse>>, ApiError> {
     let pool = &deployment.db().pool;
+    let session = ensure_design_session(pool, &task).await?;
+    let executor_profile_id =
+        design_executor_profile(&deployment, payload.executor_profile_id.clone()).await;
 
-    // Get or create design session
-    let design_session_id = if let Some(id) = task.design_session_id {
-        id
-    } else {
-        let session_id = Uuid::new_v4();
-        let session = Session::create_design_session(
-            pool,
-            &CreateSession { executor: None },
-            session_id,
-        )
-        .await?;
-        Task::update_design_session_id(pool, task.id, Some(session.id)).await?;
-        session.id
-    };
-
-    // Save user message
+    let history = DesignMessage::find_by_session_id(pool, session.id).await?;
     let user_message = DesignMessage::create(
         pool,
-        design_session_id,
+        session.id,
         &CreateDesignMessage {
             role: DesignMessageRole::User,
             content: payload.message.clone(),
@@ -688,21 +724,32 @@ pub async fn design_chat(
     )
     .await?;
 
-    // Get existing messages for context
-    let existing_messages = DesignMessage::find_by_session_id(pool, design_session_id).await?;
+    let store = DesignTurn {
+        task: &task,
+        session: &session,
+        executor_profile_id: &executor_profile_id,
+        message: &payload.message,
+        history: &history,
+        persist: true,
+    }
+    .start(pool)
+    .await?;
+    read_only_agent::wait_for_finish(&store).await;
 
-    // Call Claude CLI for AI response
-    let ai_response =
-        call_claude_for_design(&existing_messages, &payload.message, &task, design_session_id)
-            .await?;
+    let reply = read_only_agent::assistant_reply(&store);
+    if reply.is_empty() {
+        return Err(ApiError::BadRequest(format!(
+            "No response received from {}",
+            executor_profile_id
+        )));
+    }
 
-    // Save assistant message
     let assistant_message = DesignMessage::create(
         pool,
-        design_session_id,
+        session.id,
         &CreateDesignMessage {
             role: DesignMessageRole::Assistant,
-            content: ai_response,
+            content: reply,
         },
     )
     .await?;
@@ -713,117 +760,6 @@ pub async fn design_chat(
     })))
 }
 
-/// Call Claude CLI to get AI response for design chat
-async fn call_claude_for_design(
-    messages: &[DesignMessage],
-    user_message: &str,
-    task: &Task,
-    design_session_id: Uuid,
-) -> Result<String, ApiError> {
-    let can_resume = messages
-        .iter()
-        .any(|m| m.role == DesignMessageRole::Assistant);
-
-    let system_prompt = "You are a helpful assistant for software design discussions. \
-        Help the user plan and design their implementation. \
-        Be concise but thorough. Respond in the same language as the user.";
-
-    let task_description = task.description.as_deref().unwrap_or("(no description)");
-
-    // When resuming, CLI already has conversation context — only send new message.
-    let prompt = if can_resume {
-        user_message.to_string()
-    } else {
-        format!(
-            "{}\n\nTask Title: {}\nTask Description: {}\n\nUser: {}",
-            system_prompt, task.title, task_description, user_message
-        )
-    };
-
-    // Call claude CLI with timeout (uses existing authentication from ~/.claude.json)
-    tracing::debug!("Calling claude CLI for design chat (resume={})", can_resume);
-
-    // Build CLI args: use --resume for ongoing sessions, --session-id for new ones
-    let cli_session_id_str = design_session_id.to_string();
-    let mut cli_args = vec![
-        "--print",
-        "--tools=Read,Glob,Grep,Edit,Write,WebSearch,WebFetch,LSP",
-        "--permission-mode=bypassPermissions",
-    ];
-    if can_resume {
-        cli_args.extend(["--resume", &cli_session_id_str]);
-    } else {
-        cli_args.extend(["--session-id", &cli_session_id_str]);
-    }
-
-    // Spawn CLI and write prompt via stdin to avoid ARG_MAX limit (os error 7)
-    let mut child = tokio::process::Command::new("claude")
-        .args(&cli_args)
-        .stdin(std::process::Stdio::piped())
-        .stdout(std::process::Stdio::piped())
-        .stderr(std::process::Stdio::piped())
-        .spawn()
-        .map_err(|e| {
-            tracing::error!("Failed to spawn claude CLI: {}", e);
-            ApiError::BadRequest(format!(
-                "Failed to spawn claude CLI: {}. Make sure claude is installed and authenticated.",
-                e
-            ))
-        })?;
-
-    // Write prompt to stdin and close it
-    {
-        use tokio::io::AsyncWriteExt;
-        let mut stdin = child.stdin.take().ok_or_else(|| {
-            ApiError::BadRequest("Failed to open claude CLI stdin".to_string())
-        })?;
-        stdin.write_all(prompt.as_bytes()).await.map_err(|e| {
-            tracing::error!("Failed to write prompt to claude CLI stdin: {}", e);
-            ApiError::BadRequest(format!("Failed to write prompt to claude CLI: {}", e))
-        })?;
-        drop(stdin); // Close stdin to signal EOF
-    }
-
-    let output = tokio::time::timeout(
-        std::time::Duration::from_secs(300), // 5 minute timeout
-        child.wait_with_output(),
-    )
-    .await
-    .map_err(|_| {
-        tracing::error!("Claude CLI timed out after 300 seconds");
-        ApiError::BadRequest("Claude CLI timed out. Please try again with a shorter message.".to_string())
-    })?
-    .map_err(|e| {
-        tracing::error!("Failed to run claude CLI: {}", e);
-        ApiError::BadRequest(format!(
-            "Failed to run claude CLI: {}. Make sure claude is installed and authenticated.",
-            e
-        ))
-    })?;
-
-    tracing::debug!("Claude CLI completed with status: {}", output.status);
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        tracing::error!("Claude CLI error (stderr): {}", stderr);
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        tracing::error!("Claude CLI error (stdout): {}", stdout);
-        return Err(ApiError::BadRequest(format!(
-            "Claude CLI error: {}",
-            if stderr.trim().is_empty() { stdout.trim() } else { stderr.trim() }
-        )));
-    }
-
-    let response = String::from_utf8(output.stdout).map_err(|e| {
-        tracing::error!("Invalid UTF-8 from claude CLI: {}", e);
-        ApiError::BadRequest(format!("Invalid UTF-8 response from Claude: {}", e))
-    })?;
-
-    tracing::debug!("Claude CLI response length: {} chars", response.len());
-
-    Ok(response.trim().to_string())
-}
-
 /// SSE event types for design chat streaming
 #[derive(Debug, Serialize, TS)]
 #[serde(tag = "type", content = "data")]
@@ -834,74 +770,32 @@ pub enum DesignChatStreamEvent {
     AssistantChunk { content: String },
     /// Assistant response complete
     AssistantComplete { message: DesignMessage },
-    /// Tool use started
-    ToolUse {
-        tool_name: String,
-        tool_input: serde_json::Value,
-    },
-    /// Tool result received
-    ToolResult {
-        tool_name: String,
-        output: String,
+    /// Normalized entry of the agent's conversation was added or replaced,
+    /// as in execution process logs
+    Entry {
+        index: usize,
+        entry: NormalizedEntry,
     },
     /// Error occurred
     Error { message: String },
 }
 
-/// Types for parsing Claude CLI streaming JSON output
-mod cli_protocol {
-    use serde::Deserialize;
-
-    /// Event envelope from CLI stdout (stream-json format)
-    #[derive(Debug, Deserialize)]
-    pub struct SdkEventEnvelope {
-        #[serde(rename = "type")]
-        pub type_: String,
-        #[serde(flatten)]
-        pub properties: serde_json::Value,
-    }
-}
-
-/// Stream design chat response using Server-Sent Events with Claude CLI interactive mode
+/// Stream design chat response using Server-Sent Events
 pub async fn design_chat_stream(
     Extension(task): Extension<Task>,
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<DesignChatRequest>,
 ) -> Result<Sse<impl futures_util::Stream<Item = Result<Event, Infallible>>>, ApiError> {
-    use std::process::Stdio;
-    use tokio::io::{AsyncBufReadExt, BufReader};
-    use tokio::process::Command;
-
     let pool = deployment.db().pool.clone();
-
-    // Get or create design session
-    let design_session_id = if let Some(id) = task.design_session_id {
-        id
-    } else {
-        let session_id = Uuid::new_v4();
-        let session = Session::create_design_session(
-            &pool,
-            &CreateSession { executor: None },
-            session_id,
-        )
-        .await?;
-        Task::update_design_session_id(&pool, task.id, Some(session.id)).await?;
-        session.id
-    };
-
+    let session = ensure_design_session(&pool, &task).await?;
+    let executor_profile_id =
+        design_executor_profile(&deployment, payload.executor_profile_id.clone()).await;
     let skip_history = payload.skip_history.unwrap_or(false);
 
-    // Check if CLI session can be resumed (has any prior messages)
-    // Note: We check for ANY message, not just assistant messages, because
-    // if the stream was interrupted mid-response, the assistant message won't
-    // be saved but the user message will. Using --resume in this case allows
-    // the CLI to continue from its session file context.
-    let can_resume = if !skip_history {
-        let existing_messages =
-            DesignMessage::find_by_session_id(&pool, design_session_id).await?;
-        !existing_messages.is_empty()
+    let history = if skip_history {
+        Vec::new()
     } else {
-        false
+        DesignMessage::find_by_session_id(&pool, session.id).await?
     };
 
     // Save user message (skip when caller provides all context inline, e.g. changelog generation)
@@ -909,7 +803,7 @@ pub async fn design_chat_stream(
         Some(
             DesignMessage::create(
                 &pool,
-                design_session_id,
+                session.id,
                 &CreateDesignMessage {
                     role: DesignMessageRole::User,
                     content: payload.message.clone(),
@@ -921,36 +815,17 @@ pub async fn design_chat_stream(
         None
     };
 
-    let system_prompt = "You are a helpful assistant for software design discussions. \
-        Help the user plan and design their implementation. \
-        Be concise but thorough. Respond in the same language as the user.";
-
-    let task_description = task.description.as_deref().unwrap_or("(no description)");
-
-    // When resuming, CLI already has conversation context — only send new message.
-    // Otherwise, send full context (system prompt + task info + user message).
-    let prompt = if can_resume {
-        payload.message.clone()
-    } else {
-        format!(
-            "{}\n\nTask Title: {}\nTask Description: {}\n\nUser: {}",
-            system_prompt, task.title, task_description, payload.message
-        )
-    };
-
-    // Get repos for the project to determine working directory
-    let repos = ProjectRepo::find_repos_for_project(&pool, task.project_id)
-        .await
-        .unwrap_or_default();
-
-    // Use the first repo's path as working directory, fallback to home
-    let working_dir = if let Some(repo) = repos.first() {
-        repo.path.to_string_lossy().to_string()
-    } else {
-        std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string())
-    };
+    let turn = DesignTurn {
+        task: &task,
+        session: &session,
+        executor_profile_id: &executor_profile_id,
+        message: &payload.message,
+        history: &history,
+        persist: !skip_history,
+    }
+    .start(&pool)
+    .await;
 
-    // Create the SSE stream using Claude CLI --print mode with streaming
     let stream = async_stream::stream! {
         // Send the saved user message (only when history is being tracked)
         if let Some(ref user_msg) = user_message {
@@ -960,310 +835,87 @@ pub async fn design_chat_stream(
             yield Ok(Event::default().json_data(&user_event).unwrap());
         }
 
-        // Build CLI args: use --resume for ongoing sessions, --session-id for new ones
-        let cli_session_id_str = design_session_id.to_string();
-        let mut cli_args = vec![
-            "-y",
-            "@anthropic-ai/claude-code",
-            "--print",
-            "--output-format=stream-json",
-            "--include-partial-messages",
-            "--verbose",
-            "--tools=Read,Glob,Grep,Edit,Write,WebSearch,WebFetch,LSP",
-            "--permission-mode=bypassPermissions",
-        ];
-        if can_resume {
-            cli_args.extend(["--resume", &cli_session_id_str]);
-        } else if !skip_history {
-            cli_args.extend(["--session-id", &cli_session_id_str]);
-        } else {
-            cli_args.push("--no-session-persistence");
-        }
-
-        let mut child = match Command::new("npx")
-            .args(&cli_args)
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .current_dir(&working_dir)
-            .env("NPM_CONFIG_LOGLEVEL", "error")
-            .kill_on_drop(true)
-            .spawn()
-        {
-            Ok(child) => child,
+        let store = match turn {
+            Ok(store) => store,
             Err(e) => {
-                tracing::error!("Failed to spawn Claude CLI: {}", e);
+                tracing::error!("Failed to start design chat with {}: {}", executor_profile_id, e);
                 let error_event = DesignChatStreamEvent::Error {
-                    message: format!("Failed to spawn Claude CLI: {}. Make sure npx is available.", e),
+                    message: format!("Failed to start {}: {}", executor_profile_id, e),
                 };
                 yield Ok(Event::default().json_data(&error_event).unwrap());
                 return;
             }
         };
 
-        // Write prompt via stdin to avoid ARG_MAX limit (os error 7)
-        if let Some(mut stdin) = child.stdin.take() {
-            use tokio::io::AsyncWriteExt;
-            if let Err(e) = stdin.write_all(prompt.as_bytes()).await {
-                tracing::error!("Failed to write prompt to Claude CLI stdin: {}", e);
-                let error_event = DesignChatStreamEvent::Error {
-                    message: format!("Failed to write prompt to Claude CLI: {}", e),
-                };
-                yield Ok(Event::default().json_data(&error_event).unwrap());
-                return;
-            }
-            drop(stdin); // Close stdin to signal EOF
-        }
+        // Forward normalized entries; assistant text is also sent as chunks
+        // for clients that only render the reply
+        let mut assistant_text = HashMap::<usize, String>::new();
+        let mut messages = store.history_plus_stream();
+        while let Some(Ok(msg)) = messages.next().await {
+            let patch = match msg {
+                LogMsg::JsonPatch(patch) => patch,
+                LogMsg::Finished => break,
+                _ => continue,
+            };
+            let Some((index, entry)) = extract_normalized_entry_from_patch(&patch) else {
+                continue;
+            };
 
-        let stdout = match child.stdout.take() {
-            Some(stdout) => stdout,
-            None => {
-                let error_event = DesignChatStreamEvent::Error {
-                    message: "Failed to get Claude CLI stdout".to_string(),
-                };
-                yield Ok(Event::default().json_data(&error_event).unwrap());
-                return;
+            if matches!(entry.entry_type, NormalizedEntryType::AssistantMessage) {
+                let sent = assistant_text.entry(index).or_default();
+                let delta = entry
+                    .content
+                    .strip_prefix(sent.as_str())
+                    .filter(|delta| !delta.is_empty())
+                    .map(str::to_string);
+                *sent = entry.content.clone();
+                if let Some(content) = delta {
+                    let chunk_event = DesignChatStreamEvent::AssistantChunk { content };
+                    yield Ok(Event::default().json_data(&chunk_event).unwrap());
+                }
             }
-        };
 
-        // Spawn a task to consume stderr (prevents buffer fill-up deadlock)
-        if let Some(stderr) = child.stderr.take() {
-            tokio::spawn(async move {
-                let mut reader = BufReader::new(stderr);
-                let mut line = String::new();
-                loop {
-                    line.clear();
-                    match reader.read_line(&mut line).await {
-                        Ok(0) => break, // EOF
-                        Ok(_) => {
-                            let trimmed = line.trim();
-                            if !trimmed.is_empty() {
-                                tracing::debug!("Claude CLI stderr: {}", trimmed);
-                            }
-                        }
-                        Err(e) => {
-                            tracing::warn!("Error reading stderr: {}", e);
-                            break;
-                        }
-                    }
-                }
-            });
+            let entry_event = DesignChatStreamEvent::Entry { index, entry };
+            yield Ok(Event::default().json_data(&entry_event).unwrap());
         }
 
-        // Read streaming output
-        let mut reader = BufReader::new(stdout);
-        let mut line = String::new();
-        // Text accumulated from stream_event deltas (token-by-token)
-        let mut streamed_text = String::new();
-        // Final result text from CLI (complete response for DB save)
-        let mut final_result_text = String::new();
-        // Track emitted tool_use IDs to prevent duplicates from partial messages
-        let mut emitted_tool_ids = std::collections::HashSet::<String>::new();
-
-        loop {
-            line.clear();
-            match tokio::time::timeout(
-                std::time::Duration::from_secs(600),
-                reader.read_line(&mut line)
-            ).await {
-                Ok(Ok(0)) => {
-                    // EOF
-                    tracing::debug!("Claude CLI EOF");
-                    break;
-                }
-                Ok(Ok(_)) => {
-                    let trimmed = line.trim();
-                    if trimmed.is_empty() {
-                        continue;
-                    }
-
-                    tracing::trace!("CLI output: {}", trimmed);
-
-                    // Parse JSON line
-                    if let Ok(envelope) = serde_json::from_str::<cli_protocol::SdkEventEnvelope>(trimmed) {
-                        match envelope.type_.as_str() {
-                            "assistant" => {
-                                // Only process tool_use blocks from assistant messages.
-                                // Text is handled exclusively by stream_event to avoid
-                                // duplication issues with partial messages.
-                                if let Some(message) = envelope.properties.get("message") {
-                                    if let Some(content) = message.get("content").and_then(|c| c.as_array()) {
-                                        for block in content {
-                                            if block.get("type").and_then(|t| t.as_str()) == Some("tool_use") {
-                                                let tool_id = block.get("id")
-                                                    .and_then(|id| id.as_str())
-                                                    .unwrap_or("")
-                                                    .to_string();
-                                                // Only emit complete tool_use (has non-null input)
-                                                // and hasn't been emitted yet
-                                                let has_input = block.get("input")
-                                                    .map_or(false, |i| !i.is_null());
-
-                                                if has_input && (tool_id.is_empty() || !emitted_tool_ids.contains(&tool_id)) {
-                                                    if !tool_id.is_empty() {
-                                                        emitted_tool_ids.insert(tool_id);
-                                                    }
-
-                                                    let tool_name = block.get("name")
-                                                        .and_then(|n| n.as_str())
-                                                        .unwrap_or("unknown")
-                                                        .to_string();
-                                                    let tool_input = block.get("input")
-                                                        .cloned()
-                                                        .unwrap_or(serde_json::Value::Null);
-
-                                                    let tool_event = DesignChatStreamEvent::ToolUse {
-                                                        tool_name,
-                                                        tool_input,
-                                                    };
-                                                    yield Ok(Event::default().json_data(&tool_event).unwrap());
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                            "user" => {
-                                // Handle tool_result blocks in user messages
-                                if let Some(message) = envelope.properties.get("message") {
-                                    if let Some(content) = message.get("content").and_then(|c| c.as_array()) {
-                                        for block in content {
-                                            if block.get("type").and_then(|t| t.as_str()) == Some("tool_result") {
-                                                let tool_name = block.get("tool_use_id")
-                                                    .and_then(|id| id.as_str())
-                                                    .unwrap_or("unknown")
-                                                    .to_string();
-                                                let output = block.get("content")
-                                                    .and_then(|c| {
-                                                        if let Some(s) = c.as_str() {
-                                                            Some(s.to_string())
-                                                        } else if let Some(arr) = c.as_array() {
-                                                            // content is an array - extract text items
-                                                            let texts: Vec<String> = arr.iter()
-                                                                .filter_map(|item| {
-                                                                    if item.get("type").and_then(|t| t.as_str()) == Some("text") {
-                                                                        item.get("text").and_then(|t| t.as_str()).map(|s| s.to_string())
-                                                                    } else {
-                                                                        None
-                                                                    }
-                                                                })
-                                                                .collect();
-                                                            Some(texts.join("\n"))
-                                                        } else {
-                                                            None
-                                                        }
-                                                    })
-                                                    .unwrap_or_default();
-
-                                                let result_event = DesignChatStreamEvent::ToolResult {
-                                                    tool_name,
-                                                    output,
-                                                };
-                                                yield Ok(Event::default().json_data(&result_event).unwrap());
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                            "stream_event" => {
-                                // Token-by-token streaming via stream_event (primary text source)
-                                // event.type = "content_block_delta", event.delta.text contains the token
-                                if let Some(event) = envelope.properties.get("event") {
-                                    if event.get("type").and_then(|t| t.as_str()) == Some("content_block_delta") {
-                                        if let Some(delta) = event.get("delta") {
-                                            if let Some(text) = delta.get("text").and_then(|t| t.as_str()) {
-                                                if !text.is_empty() {
-                                                    let chunk_event = DesignChatStreamEvent::AssistantChunk {
-                                                        content: text.to_string(),
-                                                    };
-                                                    yield Ok(Event::default().json_data(&chunk_event).unwrap());
-                                                    streamed_text.push_str(text);
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                            "result" => {
-                                // CLI is done - extract final text for database save
-                                if let Some(result) = envelope.properties.get("result").and_then(|r| r.as_str()) {
-                                    final_result_text = result.to_string();
-                                }
-                                tracing::debug!("Got result, CLI complete");
-                                break;
-                            }
-                            other => {
-                                tracing::debug!("Unhandled CLI event type: {}", other);
-                            }
-                        }
-                    }
-                }
-                Ok(Err(e)) => {
-                    tracing::error!("Error reading CLI stdout: {}", e);
-                    let error_event = DesignChatStreamEvent::Error {
-                        message: format!("Error reading from Claude CLI: {}", e),
-                    };
-                    yield Ok(Event::default().json_data(&error_event).unwrap());
-                    break;
-                }
-                Err(_) => {
-                    tracing::error!("Claude CLI timed out");
-                    let error_event = DesignChatStreamEvent::Error {
-                        message: "Claude CLI timed out after 600 seconds".to_string(),
-                    };
-                    yield Ok(Event::default().json_data(&error_event).unwrap());
-                    break;
-                }
-            }
+        let reply = read_only_agent::assistant_reply(&store);
+        if reply.is_empty() {
+            tracing::warn!("No design chat response received from {}", executor_profile_id);
+            let error_event = DesignChatStreamEvent::Error {
+                message: format!("No response received from {}", executor_profile_id),
+            };
+            yield Ok(Event::default().json_data(&error_event).unwrap());
+            return;
         }
 
-        // Wait for the process to finish
-        let _ = child.wait().await;
-
-        // Save assistant message to database (skip when skip_history is true)
-        // Prefer final_result_text (complete response from CLI), fall back to streamed text
-        let response_to_save = if !final_result_text.trim().is_empty() {
-            final_result_text.trim().to_string()
-        } else {
-            streamed_text.trim().to_string()
-        };
-
-        if !response_to_save.is_empty() {
-            if !skip_history {
-                let assistant_message = match DesignMessage::create(
-                    &pool,
-                    design_session_id,
-                    &CreateDesignMessage {
-                        role: DesignMessageRole::Assistant,
-                        content: response_to_save,
-                    },
-                )
-                .await
-                {
-                    Ok(msg) => msg,
-                    Err(e) => {
-                        tracing::error!("Failed to save assistant message: {}", e);
-                        let error_event = DesignChatStreamEvent::Error {
-                            message: format!("Failed to save response: {}", e),
-                        };
-                        yield Ok(Event::default().json_data(&error_event).unwrap());
-                        return;
-                    }
-                };
-
-                // Send completion event
+        // skip_history mode: response was streamed but not saved to DB
+        if skip_history {
+            return;
+        }
+        match DesignMessage::create(
+            &pool,
+            session.id,
+            &CreateDesignMessage {
+                role: DesignMessageRole::Assistant,
+                content: reply,
+            },
+        )
+        .await
+        {
+            Ok(assistant_message) => {
                 let complete_event = DesignChatStreamEvent::AssistantComplete {
                     message: assistant_message,
                 };
                 yield Ok(Event::default().json_data(&complete_event).unwrap());
             }
-            // skip_history mode: response was streamed but not saved to DB
-        } else {
-            tracing::warn!("No response received from Claude CLI");
-            let error_event = DesignChatStreamEvent::Error {
-                message: "No response received from Claude CLI".to_string(),
-            };
-            yield Ok(Event::default().json_data(&error_event).unwrap());
+            Err(e) => {
+                tracing::error!("Failed to save assistant message: {}", e);
+                let error_event = DesignChatStreamEvent::Error {
+                    message: format!("Failed to save response: {}", e),
+                };
+                yield Ok(Event::default().json_data(&error_event).unwrap());
+            }
         }
     };
 
@@ -1274,6 +926,52 @@ pub async fn design_chat_stream(
     ))
 }
 
+/// Request to turn a design session into an implementation plan
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateDesignPlanRequest {
+    /// Executor profile to ask for the plan. Defaults to the configured executor profile.
+    #[serde(default)]
+    pub executor_profile_id: Option<ExecutorProfileId>,
+    /// Create a child task (a Dooray sub-post when the task is linked) per step
+    #[serde(default)]
+    pub create_subtasks: bool,
+}
+
+/// Distill the design session into a plan stored on the task
+pub async fn create_design_plan(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateDesignPlanRequest>,
+) -> Result<ResponseJson<ApiResponse<DesignPlanResult>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let session = ensure_design_session(pool, &task).await?;
+    let executor_profile_id =
+        design_executor_profile(&deployment, payload.executor_profile_id).await;
+
+    let result = design_plan::create_plan(
+        pool,
+        &task,
+        &session,
+        &executor_profile_id,
+        payload.create_subtasks,
+    )
+    .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "design_plan_created",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "executor": executor_profile_id.executor.to_string(),
+                "steps": result.plan.steps.len(),
+                "subtasks": result.subtasks.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(result)))
+}
+
 /// Get design session with all messages
 pub async fn get_design_session_with_messages(
     Extension(task): Extension<Task>,
@@ -1334,10 +1032,12 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
             get(get_design_messages).post(add_design_message),
         )
         .route("/design-session/chat", post(design_chat))
-        .route("/design-session/chat/stream", post(design_chat_stream));
+        .route("/design-session/chat/stream", post(design_chat_stream))
+        .route("/design-session/plan", post(create_design_plan));
 
     let task_id_router = Router::new()
         .route("/", get(get_task))
+        .route("/cost", get(get_task_cost))
         .merge(task_actions_router)
         .layer(from_fn_with_state(deployment.clone(), load_task_middleware));
 
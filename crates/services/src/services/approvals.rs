@@ -1,4 +1,5 @@
 pub mod executor_approvals;
+pub mod policy;
 
 use std::{
     collections::{HashMap, HashSet},
@@ -8,7 +9,9 @@ use std::{
 
 use dashmap::DashMap;
 use db::models::{
-    execution_process::ExecutionProcess,
+    approval::{ApprovalRecord, ApprovalResponder},
+    approval_rule::ApprovalRuleAction,
+    execution_process::{ExecutionProcess, ExecutionProcessStatus},
     task::{Task, TaskStatus},
 };
 use executors::{
@@ -29,27 +32,40 @@ use utils::{
 };
 use uuid::Uuid;
 
+use self::policy::ApprovalPolicy;
+
 #[derive(Debug)]
 struct PendingApproval {
-    entry_index: usize,
-    entry: NormalizedEntry,
+    /// Tool use entry the request was linked to, if one was found
+    entry: Option<(usize, NormalizedEntry)>,
     execution_process_id: Uuid,
     tool_name: String,
     response_tx: oneshot::Sender<ApprovalStatus>,
+    waiter: ApprovalWaiter,
 }
 
 type ApprovalWaiter = Shared<BoxFuture<'static, ApprovalStatus>>;
 
+/// A new approval request, answered by a rule of the project or waiting for
+/// the user
+pub enum ApprovalHandle {
+    Decided(ApprovalStatus),
+    Waiting(ApprovalRequest, ApprovalWaiter),
+}
+
 #[derive(Debug)]
 pub struct ToolContext {
     pub tool_name: String,
     pub execution_process_id: Uuid,
 }
 
+/// Tool approval requests of running agents. Requests are stored in the
+/// `approvals` table as they are made and answered; the waiting agents and the
+/// tool use entries their requests belong to are tracked in memory.
 #[derive(Clone)]
 pub struct Approvals {
+    pool: SqlitePool,
     pending: Arc<DashMap<String, PendingApproval>>,
-    completed: Arc<DashMap<String, ApprovalStatus>>,
     msg_stores: Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>,
 }
 
@@ -70,10 +86,10 @@ pub enum ApprovalError {
 }
 
 impl Approvals {
-    pub fn new(msg_stores: Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>) -> Self {
+    pub fn new(pool: SqlitePool, msg_stores: Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>) -> Self {
         Self {
+            pool,
             pending: Arc::new(DashMap::new()),
-            completed: Arc::new(DashMap::new()),
             msg_stores,
         }
     }
@@ -81,7 +97,105 @@ impl Approvals {
     pub async fn create_with_waiter(
         &self,
         request: ApprovalRequest,
-    ) -> Result<(ApprovalRequest, ApprovalWaiter), ApprovalError> {
+    ) -> Result<ApprovalHandle, ApprovalError> {
+        // The agent may ask again about a tool call it is already waiting on,
+        // e.g. after reconnecting; it waits on the same request
+        let existing = ApprovalRecord::find_pending_for_tool_call(
+            &self.pool,
+            request.execution_process_id,
+            &request.tool_call_id,
+        )
+        .await?;
+        let request = match existing {
+            Some(record) => {
+                if let Some(pending) = self.pending.get(&record.id) {
+                    return Ok(ApprovalHandle::Waiting(
+                        record.to_request(),
+                        pending.waiter.clone(),
+                    ));
+                }
+                record.to_request()
+            }
+            None => {
+                ApprovalRecord::create(&self.pool, &request).await?;
+                if let Some(status) = self.apply_policy(&request).await? {
+                    return Ok(ApprovalHandle::Decided(status));
+                }
+                request
+            }
+        };
+
+        let waiter = self.track(&request).await;
+        Ok(ApprovalHandle::Waiting(request, waiter))
+    }
+
+    /// Answer a new request with the first matching approval rule of its
+    /// project. Returns `None` if the user has to decide.
+    async fn apply_policy(
+        &self,
+        request: &ApprovalRequest,
+    ) -> Result<Option<ApprovalStatus>, ApprovalError> {
+        let ctx = ExecutionProcess::load_context(&self.pool, request.execution_process_id).await?;
+        let policy = ApprovalPolicy::load(&self.pool, ctx.project.id).await?;
+        if policy.is_empty() {
+            return Ok(None);
+        }
+
+        let store = self.msg_store_by_id(&request.execution_process_id).await;
+        let entry = store
+            .as_ref()
+            .and_then(|store| find_matching_tool_use(store.clone(), &request.tool_call_id));
+        let action = entry
+            .as_ref()
+            .and_then(|(_, entry)| match &entry.entry_type {
+                NormalizedEntryType::ToolUse { action_type, .. } => Some(action_type),
+                _ => None,
+            });
+        let Some(rule) = policy.evaluate(&request.tool_name, action) else {
+            return Ok(None);
+        };
+
+        let rule_name = rule.to_string();
+        let (status, tool_status) = match rule.action {
+            ApprovalRuleAction::Ask => return Ok(None),
+            ApprovalRuleAction::Allow => (
+                ApprovalStatus::Approved,
+                ToolStatus::AutoApproved {
+                    rule: rule_name.clone(),
+                },
+            ),
+            ApprovalRuleAction::Deny => {
+                let reason = Some(format!("Denied by approval rule {rule_name}"));
+                (
+                    ApprovalStatus::Denied {
+                        reason: reason.clone(),
+                    },
+                    ToolStatus::Denied {
+                        reason,
+                        rule: Some(rule_name.clone()),
+                    },
+                )
+            }
+        };
+        ApprovalRecord::resolve_by_rule(&self.pool, &request.id, &status, &rule_name).await?;
+
+        if let (Some(store), Some((idx, entry))) = (store, entry)
+            && let Some(entry) = entry.with_tool_status(tool_status)
+        {
+            store.push_patch(ConversationPatch::replace(idx, entry));
+        }
+        tracing::info!(
+            "Approval {} for tool '{}' answered by rule {}",
+            request.id,
+            request.tool_name,
+            rule_name
+        );
+        Ok(Some(status))
+    }
+
+    /// Link a stored request to its tool use entry and wait for its answer or
+    /// deadline.
+    async fn track(&self, request: &ApprovalRequest) -> ApprovalWaiter {
         let (tx, rx) = oneshot::channel();
         let waiter: ApprovalWaiter = rx
             .map(|result| result.unwrap_or(ApprovalStatus::TimedOut))
@@ -89,36 +203,30 @@ impl Approvals {
             .shared();
         let req_id = request.id.clone();
 
+        let mut entry = None;
         if let Some(store) = self.msg_store_by_id(&request.execution_process_id).await {
-            // Find the matching tool use entry by name and input
-            let matching_tool = find_matching_tool_use(store.clone(), &request.tool_call_id);
+            // Find the matching tool use entry by name and input, or the one a
+            // restored request was already linked to
+            let matching_tool = find_matching_tool_use(store.clone(), &request.tool_call_id)
+                .or_else(|| find_tool_use_pending_on(store.clone(), &req_id));
 
             if let Some((idx, matching_tool)) = matching_tool {
-                let approval_entry = matching_tool
-                    .with_tool_status(ToolStatus::PendingApproval {
+                if let Some(approval_entry) =
+                    matching_tool.with_tool_status(ToolStatus::PendingApproval {
                         approval_id: req_id.clone(),
                         requested_at: request.created_at,
                         timeout_at: request.timeout_at,
                     })
-                    .ok_or(ApprovalError::NoToolUseEntry)?;
-                store.push_patch(ConversationPatch::replace(idx, approval_entry));
-
-                self.pending.insert(
-                    req_id.clone(),
-                    PendingApproval {
-                        entry_index: idx,
-                        entry: matching_tool,
-                        execution_process_id: request.execution_process_id,
-                        tool_name: request.tool_name.clone(),
-                        response_tx: tx,
-                    },
-                );
+                {
+                    store.push_patch(ConversationPatch::replace(idx, approval_entry));
+                }
                 tracing::debug!(
                     "Created approval {} for tool '{}' at entry index {}",
                     req_id,
                     request.tool_name,
                     idx
                 );
+                entry = Some((idx, matching_tool));
             } else {
                 tracing::warn!(
                     "No matching tool use entry found for approval request: tool='{}', execution_process_id={}",
@@ -133,64 +241,120 @@ impl Approvals {
             );
         }
 
-        self.spawn_timeout_watcher(req_id.clone(), request.timeout_at, waiter.clone());
-        Ok((request, waiter))
+        self.pending.insert(
+            req_id.clone(),
+            PendingApproval {
+                entry,
+                execution_process_id: request.execution_process_id,
+                tool_name: request.tool_name.clone(),
+                response_tx: tx,
+                waiter: waiter.clone(),
+            },
+        );
+        self.spawn_timeout_watcher(req_id, request.timeout_at, waiter.clone());
+        waiter
+    }
+
+    /// Restore the requests that were pending when the server stopped. Those
+    /// of executions still running wait for an answer again, linked to the
+    /// tool use entries of their logs, until their original deadline. The
+    /// agents of any other execution are gone, so their requests are closed.
+    pub async fn restore_pending(&self) -> Result<(), ApprovalError> {
+        for record in ApprovalRecord::find_pending(&self.pool).await? {
+            if self.pending.contains_key(&record.id) {
+                continue;
+            }
+            let running = ExecutionProcess::find_by_id(&self.pool, record.execution_process_id)
+                .await?
+                .is_some_and(|process| process.status == ExecutionProcessStatus::Running);
+            if running {
+                self.track(&record.to_request()).await;
+                tracing::info!(
+                    "Restored approval {} of execution process {}",
+                    record.id,
+                    record.execution_process_id
+                );
+                continue;
+            }
+
+            ApprovalRecord::resolve(
+                &self.pool,
+                &record.id,
+                &ApprovalStatus::TimedOut,
+                ApprovalResponder::Restart,
+            )
+            .await?;
+            tracing::info!(
+                "Closed approval {} of interrupted execution process {}",
+                record.id,
+                record.execution_process_id
+            );
+        }
+        Ok(())
     }
 
     #[tracing::instrument(skip(self, id, req))]
     pub async fn respond(
         &self,
-        pool: &SqlitePool,
         id: &str,
         req: ApprovalResponse,
     ) -> Result<(ApprovalStatus, ToolContext), ApprovalError> {
-        if let Some((_, p)) = self.pending.remove(id) {
-            self.completed.insert(id.to_string(), req.status.clone());
-            let _ = p.response_tx.send(req.status.clone());
+        let Some((_, p)) = self.pending.remove(id) else {
+            return match ApprovalRecord::find_by_id(&self.pool, id).await? {
+                Some(record) if !matches!(record.status, ApprovalStatus::Pending) => {
+                    Err(ApprovalError::AlreadyCompleted)
+                }
+                _ => Err(ApprovalError::NotFound),
+            };
+        };
+
+        let _ = p.response_tx.send(req.status.clone());
+        if let Err(e) =
+            ApprovalRecord::resolve(&self.pool, id, &req.status, ApprovalResponder::User).await
+        {
+            tracing::error!("Failed to record response to approval '{}': {}", id, e);
+        }
 
+        if let Some((entry_index, entry)) = &p.entry {
             if let Some(store) = self.msg_store_by_id(&p.execution_process_id).await {
                 let status = ToolStatus::from_approval_status(&req.status).ok_or(
                     ApprovalError::Custom(anyhow::anyhow!("Invalid approval status")),
                 )?;
-                let updated_entry = p
-                    .entry
+                let updated_entry = entry
                     .with_tool_status(status)
                     .ok_or(ApprovalError::NoToolUseEntry)?;
 
-                store.push_patch(ConversationPatch::replace(p.entry_index, updated_entry));
+                store.push_patch(ConversationPatch::replace(*entry_index, updated_entry));
             } else {
                 tracing::warn!(
                     "No msg_store found for execution_process_id: {}",
                     p.execution_process_id
                 );
             }
+        }
 
-            let tool_ctx = ToolContext {
-                tool_name: p.tool_name,
-                execution_process_id: p.execution_process_id,
-            };
-
-            // If approved or denied, and task is still InReview, move back to InProgress
-            if matches!(
-                req.status,
-                ApprovalStatus::Approved | ApprovalStatus::Denied { .. }
-            ) && let Ok(ctx) =
-                ExecutionProcess::load_context(pool, tool_ctx.execution_process_id).await
-                && ctx.task.status == TaskStatus::InReview
-                && let Err(e) = Task::update_status(pool, ctx.task.id, TaskStatus::InProgress).await
-            {
-                tracing::warn!(
-                    "Failed to update task status to InProgress after approval response: {}",
-                    e
-                );
-            }
-
-            Ok((req.status, tool_ctx))
-        } else if self.completed.contains_key(id) {
-            Err(ApprovalError::AlreadyCompleted)
-        } else {
-            Err(ApprovalError::NotFound)
+        let tool_ctx = ToolContext {
+            tool_name: p.tool_name,
+            execution_process_id: p.execution_process_id,
+        };
+
+        // If approved or denied, and task is still InReview, move back to InProgress
+        if matches!(
+            req.status,
+            ApprovalStatus::Approved | ApprovalStatus::Denied { .. }
+        ) && let Ok(ctx) =
+            ExecutionProcess::load_context(&self.pool, tool_ctx.execution_process_id).await
+            && ctx.task.status == TaskStatus::InReview
+            && let Err(e) =
+                Task::update_status(&self.pool, ctx.task.id, TaskStatus::InProgress).await
+        {
+            tracing::warn!(
+                "Failed to update task status to InProgress after approval response: {}",
+                e
+            );
         }
+
+        Ok((req.status, tool_ctx))
     }
 
     #[tracing::instrument(skip(self, id, timeout_at, waiter))]
@@ -200,8 +364,8 @@ impl Approvals {
         timeout_at: chrono::DateTime<chrono::Utc>,
         waiter: ApprovalWaiter,
     ) {
+        let pool = self.pool.clone();
         let pending = self.pending.clone();
-        let completed = self.completed.clone();
         let msg_stores = self.msg_stores.clone();
 
         let now = chrono::Utc::now();
@@ -219,12 +383,15 @@ impl Approvals {
             };
 
             let is_timeout = matches!(&status, ApprovalStatus::TimedOut);
-            completed.insert(id.clone(), status.clone());
-
             if is_timeout && let Some((_, pending_approval)) = pending.remove(&id) {
                 if pending_approval.response_tx.send(status.clone()).is_err() {
                     tracing::debug!("approval '{}' timeout notification receiver dropped", id);
                 }
+                if let Err(e) =
+                    ApprovalRecord::resolve(&pool, &id, &status, ApprovalResponder::Timeout).await
+                {
+                    tracing::error!("Failed to record timeout of approval '{}': {}", id, e);
+                }
 
                 let store = {
                     let map = msg_stores.read().await;
@@ -232,14 +399,14 @@ impl Approvals {
                 };
 
                 if let Some(store) = store {
-                    if let Some(updated_entry) = pending_approval
-                        .entry
-                        .with_tool_status(ToolStatus::TimedOut)
+                    if let Some((entry_index, updated_entry)) =
+                        pending_approval.entry.and_then(|(idx, entry)| {
+                            entry
+                                .with_tool_status(ToolStatus::TimedOut)
+                                .map(|e| (idx, e))
+                        })
                     {
-                        store.push_patch(ConversationPatch::replace(
-                            pending_approval.entry_index,
-                            updated_entry,
-                        ));
+                        store.push_patch(ConversationPatch::replace(entry_index, updated_entry));
                     } else {
                         tracing::warn!(
                             "Timed out approval '{}' but couldn't update tool status (no tool-use entry).",
@@ -263,24 +430,26 @@ impl Approvals {
 
     pub(crate) async fn cancel(&self, id: &str) {
         if let Some((_, pending_approval)) = self.pending.remove(id) {
-            self.completed.insert(
-                id.to_string(),
-                ApprovalStatus::Denied {
-                    reason: Some("Cancelled".to_string()),
-                },
-            );
+            let status = ApprovalStatus::Denied {
+                reason: Some("Cancelled".to_string()),
+            };
+            if let Err(e) =
+                ApprovalRecord::resolve(&self.pool, id, &status, ApprovalResponder::Cancellation)
+                    .await
+            {
+                tracing::error!("Failed to record cancellation of approval '{}': {}", id, e);
+            }
 
-            if let Some(store) = self
-                .msg_store_by_id(&pending_approval.execution_process_id)
-                .await
-                && let Some(entry) = pending_approval.entry.with_tool_status(ToolStatus::Denied {
+            if let Some((entry_index, entry)) = pending_approval.entry
+                && let Some(store) = self
+                    .msg_store_by_id(&pending_approval.execution_process_id)
+                    .await
+                && let Some(entry) = entry.with_tool_status(ToolStatus::Denied {
                     reason: Some("Cancelled".to_string()),
+                    rule: None,
                 })
             {
-                store.push_patch(ConversationPatch::replace(
-                    pending_approval.entry_index,
-                    entry,
-                ));
+                store.push_patch(ConversationPatch::replace(entry_index, entry));
             }
 
             tracing::debug!("Cancelled approval '{}'", id);
@@ -358,6 +527,27 @@ fn find_matching_tool_use(
     None
 }
 
+/// Find the tool use entry waiting on the given approval
+fn find_tool_use_pending_on(
+    store: Arc<MsgStore>,
+    approval_id: &str,
+) -> Option<(usize, NormalizedEntry)> {
+    store.get_history().iter().rev().find_map(|msg| match msg {
+        LogMsg::JsonPatch(patch) => {
+            extract_normalized_entry_from_patch(patch).filter(|(_, entry)| {
+                matches!(
+                    &entry.entry_type,
+                    NormalizedEntryType::ToolUse {
+                        status: ToolStatus::PendingApproval { approval_id: id, .. },
+                        ..
+                    } if id == approval_id
+                )
+            })
+        }
+        _ => None,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
@@ -8,7 +8,10 @@ use tokio_util::sync::CancellationToken;
 use utils::approvals::{ApprovalRequest, ApprovalStatus, CreateApprovalRequest};
 use uuid::Uuid;
 
-use crate::services::{approvals::Approvals, notification::NotificationService};
+use crate::services::{
+    approvals::{ApprovalHandle, Approvals},
+    notification::NotificationService,
+};
 
 pub struct ExecutorApprovalBridge {
     approvals: Approvals,
@@ -42,8 +45,6 @@ impl ExecutorApprovalService for ExecutorApprovalBridge {
         tool_call_id: &str,
         cancel: CancellationToken,
     ) -> Result<ApprovalStatus, ExecutorApprovalError> {
-        super::ensure_task_in_review(&self.db.pool, self.execution_process_id).await;
-
         let request = ApprovalRequest::from_create(
             CreateApprovalRequest {
                 tool_name: tool_name.to_string(),
@@ -53,11 +54,17 @@ impl ExecutorApprovalService for ExecutorApprovalBridge {
             self.execution_process_id,
         );
 
-        let (request, waiter) = self
+        let (request, waiter) = match self
             .approvals
             .create_with_waiter(request)
             .await
-            .map_err(ExecutorApprovalError::request_failed)?;
+            .map_err(ExecutorApprovalError::request_failed)?
+        {
+            ApprovalHandle::Decided(status) => return Ok(status),
+            ApprovalHandle::Waiting(request, waiter) => (request, waiter),
+        };
+
+        super::ensure_task_in_review(&self.db.pool, self.execution_process_id).await;
 
         let approval_id = request.id.clone();
 
@@ -0,0 +1,455 @@
+//! Per-project auto-approval rules for agent tool calls.
+//!
+//! Rules are checked in order before a request is shown to the user and the
+//! first matching rule decides: `allow` and `deny` answer the request right
+//! away, `ask` (or no matching rule) leaves it to the user. Command globs are
+//! matched against every part of a compound command (`a && b | c & d`),
+//! including command substitutions and subshells; an `allow` rule has to match
+//! all parts while `deny` and `ask` rules match if any part does, so
+//! `cargo test && git push` isn't let through by `cargo test*`. Commands with
+//! background jobs, substitutions, subshells or redirects are never allowed by
+//! a rule, since their parts don't show everything they run or write. Path
+//! globs are matched against file edit paths with `.` and `..` resolved. Agents
+//! report paths relative to the workspace for files inside it, so `/**` and
+//! `../**` match edits outside of it, and an `allow` rule never matches such an
+//! edit.
+
+use db::models::approval_rule::{ApprovalRule, ApprovalRuleAction, UpsertApprovalRule};
+use executors::logs::ActionType;
+use globset::{GlobBuilder, GlobMatcher};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+#[error("Rule {position}: {source}")]
+pub struct InvalidRule {
+    pub position: usize,
+    #[source]
+    pub source: globset::Error,
+}
+
+#[derive(Debug, Default)]
+pub struct ApprovalPolicy {
+    rules: Vec<CompiledRule>,
+}
+
+#[derive(Debug)]
+struct CompiledRule {
+    rule: ApprovalRule,
+    matchers: Matchers,
+}
+
+#[derive(Debug)]
+struct Matchers {
+    tool_name: Option<GlobMatcher>,
+    command: Option<GlobMatcher>,
+    path: Option<GlobMatcher>,
+}
+
+impl Matchers {
+    fn compile(
+        tool_name: Option<&str>,
+        command: Option<&str>,
+        path: Option<&str>,
+    ) -> Result<Self, globset::Error> {
+        let compile = |glob: Option<&str>, configure: fn(&mut GlobBuilder)| {
+            glob.map(|glob| {
+                let mut builder = GlobBuilder::new(glob);
+                configure(&mut builder);
+                builder.build().map(|glob| glob.compile_matcher())
+            })
+            .transpose()
+        };
+        Ok(Self {
+            tool_name: compile(tool_name, |b| {
+                b.case_insensitive(true);
+            })?,
+            command: compile(command, |_| {})?,
+            path: compile(path, |b| {
+                b.literal_separator(true);
+            })?,
+        })
+    }
+}
+
+impl CompiledRule {
+    fn matches(&self, tool_name: &str, action: Option<&ActionType>) -> bool {
+        if let Some(glob) = &self.matchers.tool_name
+            && !glob.is_match(tool_name)
+        {
+            return false;
+        }
+        if let Some(glob) = &self.matchers.command {
+            let Some(ActionType::CommandRun { command, .. }) = action else {
+                return false;
+            };
+            let parts = command_parts(command);
+            let matched = match self.rule.action {
+                ApprovalRuleAction::Allow => {
+                    !has_unsplit_syntax(command)
+                        && !parts.is_empty()
+                        && parts.iter().all(|part| glob.is_match(part))
+                }
+                ApprovalRuleAction::Deny | ApprovalRuleAction::Ask => {
+                    parts.iter().any(|part| glob.is_match(part))
+                }
+            };
+            if !matched {
+                return false;
+            }
+        }
+        if let Some(glob) = &self.matchers.path {
+            let Some(ActionType::FileEdit { path, .. }) = action else {
+                return false;
+            };
+            let path = normalize_path(path);
+            let matched = match self.rule.action {
+                ApprovalRuleAction::Allow => !escapes_workspace(&path) && glob.is_match(&path),
+                ApprovalRuleAction::Deny | ApprovalRuleAction::Ask => glob.is_match(&path),
+            };
+            if !matched {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+impl ApprovalPolicy {
+    /// Rules of a project. Rules whose globs don't compile are skipped.
+    pub async fn load(pool: &SqlitePool, project_id: Uuid) -> Result<Self, sqlx::Error> {
+        let rules = ApprovalRule::find_by_project_id(pool, project_id).await?;
+        Ok(Self::new(rules))
+    }
+
+    pub fn new(rules: Vec<ApprovalRule>) -> Self {
+        let rules = rules
+            .into_iter()
+            .filter_map(|rule| {
+                match Matchers::compile(
+                    rule.tool_name.as_deref(),
+                    rule.command.as_deref(),
+                    rule.path.as_deref(),
+                ) {
+                    Ok(matchers) => Some(CompiledRule { rule, matchers }),
+                    Err(e) => {
+                        tracing::warn!("Skipping invalid approval rule {}: {}", rule.id, e);
+                        None
+                    }
+                }
+            })
+            .collect();
+        Self { rules }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// First rule matching a call of `tool_name`. `action` is the action of
+    /// the call's tool use entry; rules with command or path globs only match
+    /// calls whose action is known.
+    pub fn evaluate(&self, tool_name: &str, action: Option<&ActionType>) -> Option<&ApprovalRule> {
+        self.rules
+            .iter()
+            .find(|rule| rule.matches(tool_name, action))
+            .map(|rule| &rule.rule)
+    }
+}
+
+/// Drop empty globs from `rules` and check that the others compile
+pub fn prepare_rules(
+    rules: Vec<UpsertApprovalRule>,
+) -> Result<Vec<UpsertApprovalRule>, InvalidRule> {
+    let non_empty =
+        |glob: Option<String>| glob.map(|g| g.trim().to_string()).filter(|g| !g.is_empty());
+    rules
+        .into_iter()
+        .enumerate()
+        .map(|(i, rule)| {
+            let rule = UpsertApprovalRule {
+                action: rule.action,
+                tool_name: non_empty(rule.tool_name),
+                command: non_empty(rule.command),
+                path: non_empty(rule.path),
+            };
+            Matchers::compile(
+                rule.tool_name.as_deref(),
+                rule.command.as_deref(),
+                rule.path.as_deref(),
+            )
+            .map_err(|source| InvalidRule {
+                position: i + 1,
+                source,
+            })?;
+            Ok(rule)
+        })
+        .collect()
+}
+
+/// Simple commands of a shell command line, with the commands of
+/// substitutions and subshells split out. Quoting is not parsed.
+fn command_parts(command: &str) -> Vec<&str> {
+    command
+        .split("&&")
+        .flat_map(|part| part.split("||"))
+        .flat_map(|part| part.split("$("))
+        .flat_map(|part| part.split([';', '|', '&', '\n', '(', ')', '`']))
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .collect()
+}
+
+/// Whether `command` uses shell syntax its parts don't account for: background
+/// jobs, command substitutions, subshells and redirects
+fn has_unsplit_syntax(command: &str) -> bool {
+    command
+        .replace("&&", "")
+        .contains(['&', '`', '(', '>', '<'])
+}
+
+/// `path` with `.` and `..` components resolved lexically, keeping the `..`
+/// that climb above a relative path's start
+fn normalize_path(path: &str) -> String {
+    let absolute = path.starts_with('/');
+    let mut parts: Vec<&str> = Vec::new();
+    for part in path.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => match parts.last() {
+                Some(&last) if last != ".." => {
+                    parts.pop();
+                }
+                _ if absolute => {}
+                _ => parts.push(".."),
+            },
+            part => parts.push(part),
+        }
+    }
+    let path = parts.join("/");
+    if absolute { format!("/{path}") } else { path }
+}
+
+/// Whether a normalized edit path points outside the workspace
+fn escapes_workspace(path: &str) -> bool {
+    path.starts_with('/')
+        || path == ".."
+        || path.starts_with("../")
+        || std::path::Path::new(path).is_absolute()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn rule(
+        action: ApprovalRuleAction,
+        tool_name: Option<&str>,
+        command: Option<&str>,
+        path: Option<&str>,
+    ) -> ApprovalRule {
+        ApprovalRule {
+            id: Uuid::new_v4(),
+            project_id: Uuid::nil(),
+            position: 0,
+            action,
+            tool_name: tool_name.map(str::to_string),
+            command: command.map(str::to_string),
+            path: path.map(str::to_string),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn command(command: &str) -> ActionType {
+        ActionType::CommandRun {
+            command: command.to_string(),
+            result: None,
+        }
+    }
+
+    fn edit(path: &str) -> ActionType {
+        ActionType::FileEdit {
+            path: path.to_string(),
+            changes: Vec::new(),
+        }
+    }
+
+    fn decision(
+        policy: &ApprovalPolicy,
+        tool_name: &str,
+        action: &ActionType,
+    ) -> Option<ApprovalRuleAction> {
+        policy
+            .evaluate(tool_name, Some(action))
+            .map(|rule| rule.action)
+    }
+
+    #[test]
+    fn first_matching_rule_decides() {
+        use ApprovalRuleAction::*;
+
+        let policy = ApprovalPolicy::new(vec![
+            rule(Deny, None, Some("git push*"), None),
+            rule(Allow, Some("bash"), Some("cargo test*"), None),
+            rule(Deny, None, None, Some("/**")),
+            rule(Deny, None, None, Some("../**")),
+            rule(Allow, None, None, Some("src/**")),
+        ]);
+
+        assert_eq!(
+            decision(&policy, "Bash", &command("cargo test --workspace")),
+            Some(Allow)
+        );
+        assert_eq!(
+            decision(&policy, "Bash", &command("git push origin main")),
+            Some(Deny)
+        );
+        assert_eq!(
+            decision(&policy, "Bash", &command("cargo test && git push")),
+            Some(Deny)
+        );
+        assert_eq!(
+            decision(&policy, "Bash", &command("cargo test 2>&1 | tail")),
+            None
+        );
+        assert_eq!(decision(&policy, "Shell", &command("cargo test")), None);
+        assert_eq!(
+            decision(&policy, "Edit", &edit("./src/lib/mod.rs")),
+            Some(Allow)
+        );
+        assert_eq!(decision(&policy, "Edit", &edit("/etc/hosts")), Some(Deny));
+        assert_eq!(
+            decision(&policy, "Edit", &edit("../other/Cargo.toml")),
+            Some(Deny)
+        );
+        assert_eq!(decision(&policy, "Edit", &edit("Cargo.toml")), None);
+        assert_eq!(policy.evaluate("Edit", None).map(|r| r.action), None);
+    }
+
+    #[test]
+    fn shell_syntax_does_not_bypass_command_rules() {
+        use ApprovalRuleAction::*;
+
+        let policy = ApprovalPolicy::new(vec![
+            rule(Deny, None, Some("git push*"), None),
+            rule(Allow, None, Some("cargo test*"), None),
+        ]);
+
+        for denied in [
+            "cargo test & git push",
+            "cargo test `git push`",
+            "cargo test $(git push)",
+            "(git push origin main)",
+        ] {
+            assert_eq!(
+                decision(&policy, "Bash", &command(denied)),
+                Some(Deny),
+                "{denied}"
+            );
+        }
+        for asked in [
+            "cargo test & rm -rf ~",
+            "cargo test `rm -rf ~`",
+            "cargo test $(rm -rf ~)",
+            "(cargo test)",
+            "cargo test > ~/.bashrc",
+            "cargo test < /dev/zero",
+            "cargo test &",
+        ] {
+            assert_eq!(decision(&policy, "Bash", &command(asked)), None, "{asked}");
+        }
+        assert_eq!(
+            decision(&policy, "Bash", &command("cargo test && cargo test --doc")),
+            Some(Allow)
+        );
+    }
+
+    #[test]
+    fn path_traversal_does_not_bypass_path_rules() {
+        use ApprovalRuleAction::*;
+
+        let allow_only = ApprovalPolicy::new(vec![rule(Allow, None, None, Some("src/**"))]);
+        for escaping in [
+            "src/../../etc/passwd",
+            "./src/./../../.ssh/authorized_keys",
+            "src/../..",
+        ] {
+            assert_eq!(
+                decision(&allow_only, "Edit", &edit(escaping)),
+                None,
+                "{escaping}"
+            );
+        }
+        assert_eq!(
+            decision(&allow_only, "Edit", &edit("src/../src/main.rs")),
+            Some(Allow)
+        );
+        assert_eq!(
+            decision(&allow_only, "Edit", &edit("lib/../src/main.rs")),
+            Some(Allow)
+        );
+        assert_eq!(
+            decision(&allow_only, "Edit", &edit("src/../Cargo.toml")),
+            None
+        );
+
+        let policy = ApprovalPolicy::new(vec![
+            rule(Deny, None, None, Some("/**")),
+            rule(Deny, None, None, Some("../**")),
+            rule(Allow, None, None, Some("**")),
+        ]);
+        for denied in [
+            "src/../../etc/passwd",
+            "/etc/passwd",
+            "/etc/../etc/passwd",
+            "/../etc/passwd",
+            "../other/Cargo.toml",
+        ] {
+            assert_eq!(
+                decision(&policy, "Edit", &edit(denied)),
+                Some(Deny),
+                "{denied}"
+            );
+        }
+        assert_eq!(
+            decision(&policy, "Edit", &edit("./src/../README.md")),
+            Some(Allow)
+        );
+
+        // An allow rule written for absolute paths still doesn't let edits
+        // outside of the workspace through
+        let allow_absolute = ApprovalPolicy::new(vec![rule(Allow, None, None, Some("/tmp/**"))]);
+        assert_eq!(
+            decision(&allow_absolute, "Edit", &edit("/tmp/scratch.txt")),
+            None
+        );
+    }
+
+    #[test]
+    fn invalid_globs_are_rejected() {
+        let rules = vec![
+            UpsertApprovalRule {
+                action: ApprovalRuleAction::Allow,
+                tool_name: Some(" ".to_string()),
+                command: Some("cargo *".to_string()),
+                path: None,
+            },
+            UpsertApprovalRule {
+                action: ApprovalRuleAction::Deny,
+                tool_name: None,
+                command: None,
+                path: Some("src/[".to_string()),
+            },
+        ];
+
+        let err = prepare_rules(rules.clone()).unwrap_err();
+        assert_eq!(err.position, 2);
+
+        let prepared = prepare_rules(rules[..1].to_vec()).unwrap();
+        assert_eq!(prepared[0].tool_name, None);
+    }
+}
@@ -0,0 +1,335 @@
+//! Side-by-side comparison of attempts of the same task, e.g. the sibling
+//! workspaces started by one best-of-N request.
+//!
+//! Every attempt is summarized from what is already recorded: diff stats of its
+//! worktree, the results of its setup and cleanup scripts, the token usage its
+//! coding agent runs reported and the time they took. Optionally a reviewer
+//! agent is shown the diffs and asked to rank the attempts.
+
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use chrono::Utc;
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+    execution_process_token_usage::ExecutionProcessTokenUsage,
+    session::Session,
+    task::Task,
+    workspace::Workspace,
+    workspace_repo::WorkspaceRepo,
+};
+use executors::{
+    actions::ExecutorActionType,
+    env::{ExecutionEnv, RepoContext},
+    profile::ExecutorProfileId,
+};
+use git::GitCli;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use ts_rs::TS;
+use utils::{msg_store::MsgStore, text::truncate_to_char_boundary};
+use uuid::Uuid;
+
+use super::{
+    container::ContainerService,
+    diff_stream,
+    read_only_agent::{self, ReadOnlyAgentError, ReadOnlyAgentRun},
+};
+
+/// Diff text shown to the reviewer, split between the attempts
+const MAX_REVIEW_DIFF_LEN: usize = 60_000;
+
+const RANKING_PROMPT: &str = "Rank the attempts below from best to worst at solving the task. \
+    Weigh correctness and completeness first, then code quality and the size of the change. \
+    Reply with only a JSON object in a ```json code block, using this shape:\n\
+    {\"workspace_ids\": [\"<best workspace id>\", \"...\"], \"rationale\": \"...\"}\n\
+    Write the rationale in the language of the task.";
+
+#[derive(Debug, Error)]
+pub enum AttemptComparisonError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Agent(#[from] ReadOnlyAgentError),
+    #[error("{0} did not return a ranking")]
+    InvalidRanking(ExecutorProfileId),
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ScriptRunResult {
+    pub run_reason: ExecutionProcessRunReason,
+    pub status: ExecutionProcessStatus,
+    pub exit_code: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct AttemptSummary {
+    pub workspace_id: Uuid,
+    pub branch: String,
+    /// Profile of the first coding agent run
+    pub executor_profile_id: Option<ExecutorProfileId>,
+    /// Status of the latest coding agent run
+    pub agent_status: Option<ExecutionProcessStatus>,
+    /// Number of files changed against the target branches; diff stats are
+    /// unset once the worktree is gone
+    pub files_changed: Option<usize>,
+    pub lines_added: Option<usize>,
+    pub lines_removed: Option<usize>,
+    /// Setup and cleanup script runs, oldest first
+    pub scripts: Vec<ScriptRunResult>,
+    /// Sum of the tokens each coding agent run last reported; unset if the
+    /// executor reports none
+    pub total_tokens: Option<i64>,
+    /// Time spent in coding agent runs
+    pub agent_duration_seconds: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct AttemptRanking {
+    /// Best attempt first
+    pub workspace_ids: Vec<Uuid>,
+    pub rationale: String,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct AttemptComparison {
+    pub attempts: Vec<AttemptSummary>,
+    /// Set when a reviewer was asked to rank the attempts
+    pub ranking: Option<AttemptRanking>,
+}
+
+impl AttemptRanking {
+    /// Ranking in a reviewer reply: the first ```json block, or the outermost
+    /// braces. Unknown and repeated ids are dropped and attempts the reviewer
+    /// left out are appended in their original order.
+    pub fn parse(reply: &str, workspace_ids: &[Uuid]) -> Option<Self> {
+        let json = match reply.split_once("```json") {
+            Some((_, rest)) => rest.split("```").next()?,
+            None => reply.get(reply.find('{')?..=reply.rfind('}')?)?,
+        };
+        let ranking: Self = serde_json::from_str(json.trim()).ok()?;
+
+        let mut seen = HashSet::new();
+        let mut ranked: Vec<Uuid> = ranking
+            .workspace_ids
+            .into_iter()
+            .filter(|id| workspace_ids.contains(id) && seen.insert(*id))
+            .collect();
+        if ranked.is_empty() {
+            return None;
+        }
+        ranked.extend(workspace_ids.iter().filter(|id| !seen.contains(id)));
+
+        Some(Self {
+            workspace_ids: ranked,
+            rationale: ranking.rationale,
+        })
+    }
+}
+
+pub async fn summarize_attempt(
+    container: &impl ContainerService,
+    workspace: &Workspace,
+) -> Result<AttemptSummary, sqlx::Error> {
+    let pool = &container.db().pool;
+    let mut processes = Vec::new();
+    for session in Session::find_by_workspace_id(pool, workspace.id).await? {
+        processes.extend(ExecutionProcess::find_by_session_id(pool, session.id, false).await?);
+    }
+    processes.sort_by_key(|process| process.created_at);
+
+    let agent_runs: Vec<&ExecutionProcess> = processes
+        .iter()
+        .filter(|p| p.run_reason == ExecutionProcessRunReason::CodingAgent)
+        .collect();
+
+    let executor_profile_id = agent_runs.iter().copied().find_map(agent_profile);
+
+    let agent_duration_seconds = agent_runs
+        .iter()
+        .map(|process| {
+            let end = process.completed_at.unwrap_or_else(Utc::now);
+            (end - process.started_at).num_seconds().max(0)
+        })
+        .sum();
+
+    let scripts = processes
+        .iter()
+        .filter(|p| {
+            matches!(
+                p.run_reason,
+                ExecutionProcessRunReason::SetupScript | ExecutionProcessRunReason::CleanupScript
+            )
+        })
+        .map(|p| ScriptRunResult {
+            run_reason: p.run_reason.clone(),
+            status: p.status.clone(),
+            exit_code: p.exit_code,
+        })
+        .collect();
+
+    let token_usage = ExecutionProcessTokenUsage::find_by_workspace_id(pool, workspace.id).await?;
+    let total_tokens =
+        (!token_usage.is_empty()).then(|| token_usage.iter().map(|usage| usage.total_tokens).sum());
+
+    let diff_stats = diff_stream::compute_diff_stats(pool, container.git(), workspace).await;
+
+    Ok(AttemptSummary {
+        workspace_id: workspace.id,
+        branch: workspace.branch.clone(),
+        executor_profile_id,
+        agent_status: agent_runs.last().map(|process| process.status.clone()),
+        files_changed: diff_stats.as_ref().map(|stats| stats.files_changed),
+        lines_added: diff_stats.as_ref().map(|stats| stats.lines_added),
+        lines_removed: diff_stats.as_ref().map(|stats| stats.lines_removed),
+        scripts,
+        total_tokens,
+        agent_duration_seconds,
+    })
+}
+
+fn agent_profile(process: &ExecutionProcess) -> Option<ExecutorProfileId> {
+    match process.executor_action().ok()?.typ() {
+        ExecutorActionType::CodingAgentInitialRequest(request) => {
+            Some(request.executor_profile_id.clone())
+        }
+        ExecutorActionType::CodingAgentFollowUpRequest(request) => {
+            Some(request.executor_profile_id.clone())
+        }
+        _ => None,
+    }
+}
+
+/// Show the diffs of `attempts` to the read-only agent of `reviewer` and ask
+/// it to rank them
+pub async fn rank_attempts(
+    pool: &SqlitePool,
+    task: &Task,
+    attempts: &[(Workspace, AttemptSummary)],
+    reviewer: &ExecutorProfileId,
+) -> Result<AttemptRanking, AttemptComparisonError> {
+    let max_diff_len = MAX_REVIEW_DIFF_LEN / attempts.len().max(1);
+    let mut prompt = format!(
+        "{RANKING_PROMPT}\n\nTask Title: {}\nTask Description: {}\n",
+        task.title,
+        task.description.as_deref().unwrap_or("(no description)")
+    );
+    for (workspace, summary) in attempts {
+        let executor = summary
+            .executor_profile_id
+            .as_ref()
+            .map(ToString::to_string)
+            .unwrap_or_else(|| "unknown".to_string());
+        let diff = workspace_diff(pool, workspace).await?;
+        let diff = if diff.len() > max_diff_len {
+            format!(
+                "{}\n... (diff truncated, {} more bytes)",
+                truncate_to_char_boundary(&diff, max_diff_len),
+                diff.len() - max_diff_len
+            )
+        } else {
+            diff
+        };
+        prompt.push_str(&format!(
+            "\n## Attempt {} ({executor})\n\n```diff\n{diff}\n```\n",
+            workspace.id
+        ));
+    }
+
+    let working_dir = attempts
+        .first()
+        .and_then(|(workspace, _)| workspace.container_ref.as_ref())
+        .map(PathBuf::from)
+        .filter(|dir| dir.exists())
+        .unwrap_or_else(std::env::temp_dir);
+    let store = run_reviewer(reviewer, &working_dir, &prompt).await?;
+
+    let workspace_ids: Vec<Uuid> = attempts.iter().map(|(workspace, _)| workspace.id).collect();
+    AttemptRanking::parse(&read_only_agent::assistant_reply(&store), &workspace_ids)
+        .ok_or_else(|| AttemptComparisonError::InvalidRanking(reviewer.clone()))
+}
+
+/// Run `prompt` on the read-only agent of `reviewer` and wait for it to exit.
+/// The returned store holds its normalized output.
+async fn run_reviewer(
+    reviewer: &ExecutorProfileId,
+    working_dir: &Path,
+    prompt: &str,
+) -> Result<Arc<MsgStore>, ReadOnlyAgentError> {
+    let env = ExecutionEnv::new(
+        RepoContext::new(working_dir.to_path_buf(), Vec::new()),
+        false,
+        String::new(),
+    );
+    let store = ReadOnlyAgentRun::start(reviewer, working_dir, prompt, None, &env)
+        .await?
+        .wait()
+        .await;
+    store.push_finished();
+    Ok(store)
+}
+
+/// Diff of every repository of a workspace against its target branch,
+/// including uncommitted changes
+async fn workspace_diff(pool: &SqlitePool, workspace: &Workspace) -> Result<String, sqlx::Error> {
+    let Some(container_ref) = workspace.container_ref.as_ref() else {
+        return Ok(String::new());
+    };
+    let repos =
+        WorkspaceRepo::find_repos_with_target_branch_for_workspace(pool, workspace.id).await?;
+
+    let mut diff = String::new();
+    for repo in repos {
+        let worktree = PathBuf::from(container_ref).join(&repo.repo.name);
+        let target_branch = repo.target_branch.clone();
+        let repo_diff = tokio::task::spawn_blocking(move || {
+            GitCli::new().git(&worktree, ["diff", target_branch.as_str()])
+        })
+        .await;
+        match repo_diff {
+            Ok(Ok(repo_diff)) => diff.push_str(&repo_diff),
+            Ok(Err(e)) => tracing::warn!(
+                "Failed to diff {} of workspace {}: {}",
+                repo.repo.name,
+                workspace.id,
+                e
+            ),
+            Err(e) => tracing::warn!("Diff task of workspace {} failed: {}", workspace.id, e),
+        }
+    }
+    Ok(diff)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranking_keeps_known_attempts_in_order() {
+        let a = Uuid::new_v4();
+        let b = Uuid::new_v4();
+        let c = Uuid::new_v4();
+        let reply = format!(
+            "Done.\n\n```json\n{{\"workspace_ids\": [\"{c}\", \"{}\", \"{c}\", \"{a}\"], \
+             \"rationale\": \"C passes the tests\"}}\n```",
+            Uuid::new_v4()
+        );
+
+        assert_eq!(
+            AttemptRanking::parse(&reply, &[a, b, c]),
+            Some(AttemptRanking {
+                workspace_ids: vec![c, a, b],
+                rationale: "C passes the tests".to_string(),
+            })
+        );
+        assert_eq!(
+            AttemptRanking::parse("{\"workspace_ids\": [], \"rationale\": \"\"}", &[a]),
+            None
+        );
+        assert_eq!(AttemptRanking::parse("They are all fine.", &[a]), None);
+    }
+}
@@ -28,6 +28,19 @@ pub enum SendMessageShortcut {
     Enter,
 }
 
+/// How long the raw output of finished execution processes is kept. Coding
+/// agent runs keep their normalized conversation either way.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct LogRetentionConfig {
+    /// Prune raw output this many days after the process finished
+    #[serde(default)]
+    pub raw_log_days: Option<u32>,
+    /// Prune the raw output of the oldest processes beyond this many
+    /// megabytes of compressed raw output
+    #[serde(default)]
+    pub raw_log_max_mb: Option<u32>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, TS)]
 pub struct Config {
     pub config_version: String,
@@ -62,6 +75,8 @@ pub struct Config {
     pub commit_reminder_prompt: Option<String>,
     #[serde(default)]
     pub send_message_shortcut: SendMessageShortcut,
+    #[serde(default)]
+    pub log_retention: LogRetentionConfig,
 }
 
 impl Config {
@@ -92,6 +107,7 @@ impl Config {
             commit_reminder_enabled: true,
             commit_reminder_prompt: None,
             send_message_shortcut: SendMessageShortcut::default(),
+            log_retention: LogRetentionConfig::default(),
         }
     }
 
@@ -147,6 +163,7 @@ impl Default for Config {
             commit_reminder_enabled: true,
             commit_reminder_prompt: None,
             send_message_shortcut: SendMessageShortcut::default(),
+            log_retention: LogRetentionConfig::default(),
         }
     }
 }
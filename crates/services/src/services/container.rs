@@ -11,6 +11,7 @@ use db::{
     models::{
         coding_agent_turn::{CodingAgentTurn, CreateCodingAgentTurn},
         design_message::{DesignMessage, DesignMessageRole},
+        execution_log_entry::ExecutionLogEntry,
         execution_process::{
             CreateExecutionProcess, ExecutionContext, ExecutionProcess, ExecutionProcessError,
             ExecutionProcessRunReason, ExecutionProcessStatus,
@@ -19,6 +20,7 @@ use db::{
         execution_process_repo_state::{
             CreateExecutionProcessRepoState, ExecutionProcessRepoState,
         },
+        queued_message::QueuedMessage,
         repo::Repo,
         session::{CreateSession, Session, SessionError},
         task::{Task, TaskStatus},
@@ -33,6 +35,7 @@ use executors::profile::ExecutorConfigs;
 use executors::{
     actions::{
         ExecutorAction, ExecutorActionType,
+        coding_agent_follow_up::CodingAgentFollowUpRequest,
         coding_agent_initial::CodingAgentInitialRequest,
         script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
     },
@@ -54,7 +57,13 @@ use utils::{
 use uuid::Uuid;
 
 use crate::services::{
-    notification::NotificationService, workspace_manager::WorkspaceError as WorkspaceManagerError,
+    dooray::report::{self as dooray_report, DoorayReportEvent},
+    log_retention,
+    log_search::LogIndexer,
+    notification::NotificationService,
+    queued_message::{QueuedMessageService, queued_message_profile},
+    transcript::{self, ConversationFold},
+    workspace_manager::WorkspaceError as WorkspaceManagerError,
     worktree_manager::WorktreeError,
 };
 pub type ContainerRef = String;
@@ -95,6 +104,8 @@ pub trait ContainerService {
 
     fn notification_service(&self) -> &NotificationService;
 
+    fn queued_message_service(&self) -> &QueuedMessageService;
+
     fn workspace_to_current_dir(&self, workspace: &Workspace) -> PathBuf;
 
     async fn available_agent_slash_commands(
@@ -252,6 +263,34 @@ pub trait ContainerService {
             }
         };
         self.notification_service().notify(&title, &message).await;
+
+        // Comment on the linked Dooray post if the project reports agent runs
+        if matches!(
+            ctx.execution_process.run_reason,
+            ExecutionProcessRunReason::CodingAgent
+        ) {
+            let event = DoorayReportEvent::ExecutionFinished {
+                succeeded: matches!(
+                    ctx.execution_process.status,
+                    ExecutionProcessStatus::Completed
+                ),
+            };
+            let pool = self.db().pool.clone();
+            let git = self.git().clone();
+            let task = ctx.task.clone();
+            let workspace = ctx.workspace.clone();
+            tokio::spawn(async move {
+                if let Err(e) =
+                    dooray_report::report(&pool, &git, &task, &workspace, event, None).await
+                {
+                    tracing::warn!(
+                        "Failed to report execution result to Dooray for task {}: {}",
+                        task.id,
+                        e
+                    );
+                }
+            });
+        }
     }
 
     /// Cleanup executions marked as running in the db, call at startup
@@ -408,6 +447,33 @@ pub trait ContainerService {
         Ok(())
     }
 
+    /// Backfill the log search index for coding agent runs logged before it
+    /// existed, by replaying their stored logs through the normalizer. Each
+    /// run is replayed once; an interrupted backfill resumes where it stopped.
+    async fn backfill_log_index(&self) -> Result<(), ContainerError> {
+        let pool = &self.db().pool;
+        let pending = ExecutionLogEntry::find_backfill_pending(pool).await?;
+
+        if pending.is_empty() {
+            return Ok(());
+        }
+
+        tracing::info!("Backfilling log index for {} executions", pending.len());
+
+        for execution_id in pending {
+            if let Some(mut stream) = self.normalize_stored_logs(&execution_id, false).await {
+                let mut indexer = LogIndexer::new(execution_id);
+                while let Some(Ok(msg)) = stream.next().await {
+                    indexer.push(pool, &msg).await;
+                }
+                indexer.flush(pool).await;
+            }
+            ExecutionLogEntry::complete_backfill(pool, execution_id).await?;
+        }
+
+        Ok(())
+    }
+
     fn cleanup_actions_for_repos(&self, repos: &[Repo]) -> Option<ExecutorAction> {
         let repos_with_cleanup: Vec<_> = repos
             .iter()
@@ -653,7 +719,8 @@ pub trait ContainerService {
         let session = Session::find_by_id(pool, session_id)
             .await?
             .ok_or_else(|| ContainerError::Other(anyhow!("Session not found")))?;
-        let workspace_id = session.workspace_id
+        let workspace_id = session
+            .workspace_id
             .ok_or_else(|| ContainerError::Other(anyhow!("Session has no workspace")))?;
         let workspace = Workspace::find_by_id(pool, workspace_id)
             .await?
@@ -866,159 +933,178 @@ pub trait ContainerService {
             )
         } else {
             // Fallback: load from DB and normalize
-            let log_records =
-                match ExecutionProcessLogs::find_by_execution_id(&self.db().pool, *id).await {
-                    Ok(records) if !records.is_empty() => records,
-                    Ok(_) => return None, // No logs exist
-                    Err(e) => {
-                        tracing::error!("Failed to fetch logs for execution {}: {}", id, e);
-                        return None;
-                    }
-                };
+            self.normalize_stored_logs(id, true).await
+        }
+    }
 
-            let raw_messages = match ExecutionProcessLogs::parse_logs(&log_records) {
-                Ok(msgs) => msgs,
+    /// Normalize the logs stored for a process. With `recreate_worktree` a
+    /// deleted worktree is recreated first, so paths are normalized against it.
+    async fn normalize_stored_logs(
+        &self,
+        id: &Uuid,
+        recreate_worktree: bool,
+    ) -> Option<futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>>> {
+        let log_records =
+            match ExecutionProcessLogs::find_by_execution_id(&self.db().pool, *id).await {
+                Ok(records) if !records.is_empty() => records,
+                Ok(_) => return None, // No logs exist
                 Err(e) => {
-                    tracing::error!("Failed to parse logs for execution {}: {}", id, e);
+                    tracing::error!("Failed to fetch logs for execution {}: {}", id, e);
                     return None;
                 }
             };
 
-            // Create temporary store and populate
-            // Include JsonPatch messages (already normalized) and Stdout/Stderr (need normalization)
-            let temp_store = Arc::new(MsgStore::new());
-            for msg in raw_messages {
-                if matches!(
-                    msg,
-                    LogMsg::Stdout(_) | LogMsg::Stderr(_) | LogMsg::JsonPatch(_)
-                ) {
-                    temp_store.push(msg);
-                }
+        let raw_messages = match ExecutionProcessLogs::parse_logs(&log_records) {
+            Ok(msgs) => msgs,
+            Err(e) => {
+                tracing::error!("Failed to parse logs for execution {}: {}", id, e);
+                return None;
             }
-            temp_store.push_finished();
+        };
+
+        // Create temporary store and populate
+        // Include JsonPatch messages (already normalized) and Stdout/Stderr (need normalization)
+        let temp_store = Arc::new(MsgStore::new());
+        for msg in raw_messages {
+            if matches!(
+                msg,
+                LogMsg::Stdout(_) | LogMsg::Stderr(_) | LogMsg::JsonPatch(_)
+            ) {
+                temp_store.push(msg);
+            }
+        }
+        temp_store.push_finished();
 
-            let process = match ExecutionProcess::find_by_id(&self.db().pool, *id).await {
-                Ok(Some(process)) => process,
+        let process = match ExecutionProcess::find_by_id(&self.db().pool, *id).await {
+            Ok(Some(process)) => process,
+            Ok(None) => {
+                tracing::error!("No execution process found for ID: {}", id);
+                return None;
+            }
+            Err(e) => {
+                tracing::error!("Failed to fetch execution process {}: {}", id, e);
+                return None;
+            }
+        };
+
+        // Get the workspace to determine correct directory
+        let (workspace, _session) =
+            match process.parent_workspace_and_session(&self.db().pool).await {
+                Ok(Some((workspace, session))) => (workspace, session),
                 Ok(None) => {
-                    tracing::error!("No execution process found for ID: {}", id);
+                    tracing::error!(
+                        "No workspace/session found for session ID: {}",
+                        process.session_id
+                    );
                     return None;
                 }
                 Err(e) => {
-                    tracing::error!("Failed to fetch execution process {}: {}", id, e);
+                    tracing::error!(
+                        "Failed to fetch workspace for session {}: {}",
+                        process.session_id,
+                        e
+                    );
                     return None;
                 }
             };
 
-            // Get the workspace to determine correct directory
-            let (workspace, _session) =
-                match process.parent_workspace_and_session(&self.db().pool).await {
-                    Ok(Some((workspace, session))) => (workspace, session),
-                    Ok(None) => {
-                        tracing::error!(
-                            "No workspace/session found for session ID: {}",
-                            process.session_id
-                        );
-                        return None;
-                    }
-                    Err(e) => {
-                        tracing::error!(
-                            "Failed to fetch workspace for session {}: {}",
-                            process.session_id,
-                            e
-                        );
-                        return None;
-                    }
-                };
-
-            if let Err(err) = self.ensure_container_exists(&workspace).await {
-                tracing::warn!(
-                    "Failed to recreate worktree before log normalization for workspace {}: {}",
-                    workspace.id,
-                    err
-                );
-            }
+        if recreate_worktree && let Err(err) = self.ensure_container_exists(&workspace).await {
+            tracing::warn!(
+                "Failed to recreate worktree before log normalization for workspace {}: {}",
+                workspace.id,
+                err
+            );
+        }
 
-            let current_dir = self.workspace_to_current_dir(&workspace);
+        let current_dir = self.workspace_to_current_dir(&workspace);
 
-            let executor_action = if let Ok(executor_action) = process.executor_action() {
-                executor_action
-            } else {
-                tracing::error!(
-                    "Failed to parse executor action: {:?}",
-                    process.executor_action()
-                );
-                return None;
-            };
+        let executor_action = if let Ok(executor_action) = process.executor_action() {
+            executor_action
+        } else {
+            tracing::error!(
+                "Failed to parse executor action: {:?}",
+                process.executor_action()
+            );
+            return None;
+        };
 
-            // Spawn normalizer on populated store
-            match executor_action.typ() {
-                ExecutorActionType::CodingAgentInitialRequest(request) => {
-                    #[cfg(feature = "qa-mode")]
-                    {
-                        let executor = QaMockExecutor;
-                        executor.normalize_logs(
-                            temp_store.clone(),
-                            &request.effective_dir(&current_dir),
-                        );
-                    }
-                    #[cfg(not(feature = "qa-mode"))]
-                    {
-                        let executor = ExecutorConfigs::get_cached()
-                            .get_coding_agent_or_default(&request.executor_profile_id);
-                        executor.normalize_logs(
-                            temp_store.clone(),
-                            &request.effective_dir(&current_dir),
-                        );
-                    }
+        // Spawn normalizer on populated store
+        match executor_action.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(request) => {
+                #[cfg(feature = "qa-mode")]
+                {
+                    let executor = QaMockExecutor;
+                    executor
+                        .normalize_logs(temp_store.clone(), &request.effective_dir(&current_dir));
                 }
-                ExecutorActionType::CodingAgentFollowUpRequest(request) => {
-                    #[cfg(feature = "qa-mode")]
-                    {
-                        let executor = QaMockExecutor;
-                        executor.normalize_logs(
-                            temp_store.clone(),
-                            &request.effective_dir(&current_dir),
-                        );
-                    }
-                    #[cfg(not(feature = "qa-mode"))]
-                    {
-                        let executor = ExecutorConfigs::get_cached()
-                            .get_coding_agent_or_default(&request.executor_profile_id);
-                        executor.normalize_logs(
-                            temp_store.clone(),
-                            &request.effective_dir(&current_dir),
-                        );
-                    }
+                #[cfg(not(feature = "qa-mode"))]
+                {
+                    let executor = ExecutorConfigs::get_cached()
+                        .get_coding_agent_or_default(&request.executor_profile_id);
+                    executor
+                        .normalize_logs(temp_store.clone(), &request.effective_dir(&current_dir));
                 }
+            }
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => {
                 #[cfg(feature = "qa-mode")]
-                ExecutorActionType::ReviewRequest(_request) => {
+                {
                     let executor = QaMockExecutor;
-                    executor.normalize_logs(temp_store.clone(), &current_dir);
+                    executor
+                        .normalize_logs(temp_store.clone(), &request.effective_dir(&current_dir));
                 }
                 #[cfg(not(feature = "qa-mode"))]
-                ExecutorActionType::ReviewRequest(request) => {
+                {
                     let executor = ExecutorConfigs::get_cached()
                         .get_coding_agent_or_default(&request.executor_profile_id);
-                    executor.normalize_logs(temp_store.clone(), &current_dir);
-                }
-                _ => {
-                    tracing::debug!(
-                        "Executor action doesn't support log normalization: {:?}",
-                        process.executor_action()
-                    );
-                    return None;
+                    executor
+                        .normalize_logs(temp_store.clone(), &request.effective_dir(&current_dir));
                 }
             }
-            Some(
-                temp_store
-                    .history_plus_stream()
-                    .filter(|msg| future::ready(matches!(msg, Ok(LogMsg::JsonPatch(..)))))
-                    .chain(futures::stream::once(async {
-                        Ok::<_, std::io::Error>(LogMsg::Finished)
-                    }))
-                    .boxed(),
-            )
+            #[cfg(feature = "qa-mode")]
+            ExecutorActionType::ReviewRequest(_request) => {
+                let executor = QaMockExecutor;
+                executor.normalize_logs(temp_store.clone(), &current_dir);
+            }
+            #[cfg(not(feature = "qa-mode"))]
+            ExecutorActionType::ReviewRequest(request) => {
+                let executor = ExecutorConfigs::get_cached()
+                    .get_coding_agent_or_default(&request.executor_profile_id);
+                executor.normalize_logs(temp_store.clone(), &current_dir);
+            }
+            _ => {
+                tracing::debug!(
+                    "Executor action doesn't support log normalization: {:?}",
+                    process.executor_action()
+                );
+                return None;
+            }
         }
+        Some(
+            temp_store
+                .history_plus_stream()
+                .filter(|msg| future::ready(matches!(msg, Ok(LogMsg::JsonPatch(..)))))
+                .chain(futures::stream::once(async {
+                    Ok::<_, std::io::Error>(LogMsg::Finished)
+                }))
+                .boxed(),
+        )
+    }
+
+    /// Normalized entries of a process as they stand now, e.g. for exports
+    async fn normalized_entries(&self, id: &Uuid) -> Vec<NormalizedEntry> {
+        let messages = match self.get_msg_store_by_id(id).await {
+            Some(store) => store.get_history(),
+            None => match self.stream_normalized_logs(id).await {
+                Some(stream) => {
+                    stream
+                        .filter_map(|msg| future::ready(msg.ok()))
+                        .collect()
+                        .await
+                }
+                None => Vec::new(),
+            },
+        };
+        transcript::fold_entries(&messages)
     }
 
     fn spawn_stream_raw_logs_to_db(&self, execution_id: &Uuid) -> JoinHandle<()> {
@@ -1035,6 +1121,9 @@ pub trait ContainerService {
 
             if let Some(store) = store {
                 let mut stream = store.history_plus_stream();
+                let mut indexer = LogIndexer::new(execution_id);
+                let mut conversation = ConversationFold::default();
+                let mut finished = false;
 
                 while let Some(Ok(msg)) = stream.next().await {
                     match &msg {
@@ -1101,12 +1190,26 @@ pub trait ContainerService {
                                 );
                             }
                         }
+                        LogMsg::JsonPatch(patch) => {
+                            conversation.apply(patch);
+                            indexer.push(&db.pool, &msg).await;
+                        }
                         LogMsg::Finished => {
+                            finished = true;
                             break;
                         }
-                        LogMsg::JsonPatch(_) | LogMsg::Ready => continue,
+                        LogMsg::Ready => continue,
                     }
                 }
+                indexer.flush(&db.pool).await;
+                if finished {
+                    log_retention::compact_execution_logs(
+                        &db.pool,
+                        execution_id,
+                        conversation.into_entries(),
+                    )
+                    .await;
+                }
             }
         })
     }
@@ -1144,8 +1247,16 @@ pub trait ContainerService {
 
         let mut prompt = task.to_prompt();
 
-        // If task has design session, append design conversation to prompt
-        if let Some(design_session_id) = task.design_session_id {
+        // Append the task's plan, or else its design conversation, to the prompt
+        if let Some(plan) = task.plan.as_deref().filter(|p| !p.trim().is_empty()) {
+            prompt.push_str("\n\n--- Implementation Plan ---\n\n");
+            prompt.push_str(plan.trim());
+            prompt.push_str("\n\n--- End Implementation Plan ---\n");
+            tracing::info!(
+                task_id = %task.id,
+                "Included implementation plan in coding agent prompt"
+            );
+        } else if let Some(design_session_id) = task.design_session_id {
             match DesignMessage::find_by_session_id(&self.db().pool, design_session_id).await {
                 Ok(messages) if !messages.is_empty() => {
                     prompt.push_str("\n\n--- Design Phase Discussion ---\n\n");
@@ -1425,13 +1536,27 @@ pub trait ContainerService {
         Ok(execution_process)
     }
 
-    async fn try_start_next_action(&self, ctx: &ExecutionContext) -> Result<(), ContainerError> {
+    /// Start the action that follows the one `ctx` ran. Once a chain of
+    /// actions is done, the next queued follow-up of the session is started
+    /// instead, unless the run failed or was stopped, which pauses the queue.
+    /// Returns whether anything was started.
+    async fn try_start_next_action(&self, ctx: &ExecutionContext) -> Result<bool, ContainerError> {
         let action = ctx.execution_process.executor_action()?;
         let next_action = if let Some(next_action) = action.next_action() {
             next_action
         } else {
+            if matches!(
+                ctx.execution_process.status,
+                ExecutionProcessStatus::Completed
+            ) && self.should_finalize(ctx)
+            {
+                return Ok(self
+                    .try_start_queued_follow_up(&ctx.workspace, &ctx.session)
+                    .await?
+                    .is_some());
+            }
             tracing::debug!("No next action configured");
-            return Ok(());
+            return Ok(false);
         };
 
         // Determine the run reason of the next action
@@ -1457,6 +1582,127 @@ pub trait ContainerService {
             .await?;
 
         tracing::debug!("Started next action: {:?}", next_action);
-        Ok(())
+        Ok(true)
+    }
+
+    /// Take the next queued follow-up of a session and start it. Also resumes
+    /// a queue that was paused by a failed or stopped run, or by a restart.
+    /// A message that fails to start is put back at the front of the queue.
+    async fn try_start_queued_follow_up(
+        &self,
+        workspace: &Workspace,
+        session: &Session,
+    ) -> Result<Option<ExecutionProcess>, ContainerError> {
+        let Some(queued_msg) = self
+            .queued_message_service()
+            .take_next(session.id)
+            .await
+            .map_err(|e| ContainerError::Other(anyhow!(e)))?
+        else {
+            return Ok(None);
+        };
+
+        tracing::info!(
+            "Starting queued follow-up {} of session {}",
+            queued_msg.id,
+            session.id
+        );
+        match self
+            .start_queued_follow_up(workspace, session, &queued_msg)
+            .await
+        {
+            Ok(execution_process) => Ok(Some(execution_process)),
+            Err(e) => {
+                if let Err(requeue_err) = self.queued_message_service().requeue(&queued_msg).await {
+                    tracing::error!(
+                        "Failed to requeue follow-up {} of session {}: {}",
+                        queued_msg.id,
+                        session.id,
+                        requeue_err
+                    );
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Start a follow-up execution from a queued message
+    async fn start_queued_follow_up(
+        &self,
+        workspace: &Workspace,
+        session: &Session,
+        queued_msg: &QueuedMessage,
+    ) -> Result<ExecutionProcess, ContainerError> {
+        let pool = &self.db().pool;
+        let latest_profile =
+            ExecutionProcess::latest_executor_profile_for_session(pool, session.id).await?;
+
+        let executor_profile_id = queued_message_profile(
+            queued_msg,
+            latest_profile.as_ref(),
+            session.executor.as_deref(),
+        )
+        .ok_or_else(|| {
+            ContainerError::Other(anyhow!(
+                "No executor profile for queued message of session {}",
+                session.id
+            ))
+        })?;
+
+        // Validate executor matches session if session has prior executions
+        let expected_executor: Option<String> = latest_profile
+            .map(|profile| profile.executor.to_string())
+            .or_else(|| session.executor.clone());
+
+        if let Some(expected) = expected_executor {
+            let actual = executor_profile_id.executor.to_string();
+            if expected != actual {
+                return Err(SessionError::ExecutorMismatch { expected, actual }.into());
+            }
+        }
+
+        if session.executor.is_none() {
+            Session::update_executor(pool, session.id, &executor_profile_id.executor.to_string())
+                .await?;
+        }
+
+        // Get latest agent turn for session continuity (from coding agent turns)
+        let latest_session_info =
+            CodingAgentTurn::find_latest_session_info(pool, session.id).await?;
+
+        let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
+        let cleanup_action = self.cleanup_actions_for_repos(&repos);
+
+        let working_dir = workspace
+            .agent_working_dir
+            .as_ref()
+            .filter(|dir| !dir.is_empty())
+            .cloned();
+
+        let action_type = if let Some(info) = latest_session_info {
+            ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
+                prompt: queued_msg.message.clone(),
+                session_id: info.session_id,
+                reset_to_message_id: None,
+                executor_profile_id: executor_profile_id.clone(),
+                working_dir: working_dir.clone(),
+            })
+        } else {
+            ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                prompt: queued_msg.message.clone(),
+                executor_profile_id: executor_profile_id.clone(),
+                working_dir,
+            })
+        };
+
+        let action = ExecutorAction::new(action_type, cleanup_action.map(Box::new));
+
+        self.start_execution(
+            workspace,
+            session,
+            &action,
+            &ExecutionProcessRunReason::CodingAgent,
+        )
+        .await
     }
 }
@@ -0,0 +1,240 @@
+//! Implementation plans distilled from a task's design session.
+//!
+//! The design agent is asked, in the same session, to summarize the discussion
+//! as a JSON plan (goals, steps, acceptance criteria). The plan is stored on the
+//! task as markdown and handed to every workspace started for it. Steps can be
+//! turned into child tasks, created as Dooray sub-posts when the task is linked
+//! to a post.
+
+use std::collections::HashSet;
+
+use db::models::{
+    design_message::DesignMessage,
+    session::Session,
+    task::{CreateTask, Task},
+};
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::{
+    design_session::{DesignSessionError, DesignTurn},
+    dooray::hierarchy,
+    read_only_agent,
+};
+
+const PLAN_PROMPT: &str = "Summarize the design we agreed on as an implementation plan. \
+    Reply with only a JSON object in a ```json code block, using this shape:\n\
+    {\"goals\": [\"...\"], \"steps\": [{\"title\": \"...\", \"description\": \"...\"}], \
+    \"acceptance_criteria\": [\"...\"]}\n\
+    Each step should be a unit of work that can be implemented and reviewed on its own. \
+    Write the text in the language of the conversation.";
+
+#[derive(Debug, Error)]
+pub enum DesignPlanError {
+    #[error(transparent)]
+    Session(#[from] DesignSessionError),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("The design session has no messages to plan from")]
+    EmptySession,
+    #[error("{0} did not return a plan")]
+    InvalidPlan(ExecutorProfileId),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+pub struct DesignPlan {
+    #[serde(default)]
+    pub goals: Vec<String>,
+    #[serde(default)]
+    pub steps: Vec<PlanStep>,
+    #[serde(default)]
+    pub acceptance_criteria: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct PlanStep {
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct DesignPlanResult {
+    /// The task with its plan
+    pub task: Task,
+    pub plan: DesignPlan,
+    /// Child tasks created for the plan's steps
+    pub subtasks: Vec<Task>,
+}
+
+impl DesignPlan {
+    /// Plan in an agent reply: the first ```json block, or the outermost braces
+    pub fn parse(reply: &str) -> Option<Self> {
+        let json = match reply.split_once("```json") {
+            Some((_, rest)) => rest.split("```").next()?,
+            None => reply.get(reply.find('{')?..=reply.rfind('}')?)?,
+        };
+        let plan: Self = serde_json::from_str(json.trim()).ok()?;
+        (!plan.steps.is_empty() || !plan.goals.is_empty()).then_some(plan)
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut sections = Vec::new();
+        if !self.goals.is_empty() {
+            let goals: Vec<_> = self.goals.iter().map(|g| format!("- {g}")).collect();
+            sections.push(format!("## Goals\n\n{}", goals.join("\n")));
+        }
+        if !self.steps.is_empty() {
+            let steps: Vec<_> = self
+                .steps
+                .iter()
+                .enumerate()
+                .map(|(i, step)| {
+                    let mut item = format!("{}. **{}**", i + 1, step.title);
+                    if let Some(description) = step.description.as_deref().map(str::trim)
+                        && !description.is_empty()
+                    {
+                        for line in description.lines() {
+                            item.push_str(&format!("\n   {line}"));
+                        }
+                    }
+                    item
+                })
+                .collect();
+            sections.push(format!("## Steps\n\n{}", steps.join("\n")));
+        }
+        if !self.acceptance_criteria.is_empty() {
+            let criteria: Vec<_> = self
+                .acceptance_criteria
+                .iter()
+                .map(|c| format!("- [ ] {c}"))
+                .collect();
+            sections.push(format!("## Acceptance Criteria\n\n{}", criteria.join("\n")));
+        }
+        sections.join("\n\n")
+    }
+}
+
+/// Ask the design agent for a plan of the session's discussion, store it on
+/// `task` and, if asked, create a child task per step.
+pub async fn create_plan(
+    pool: &SqlitePool,
+    task: &Task,
+    session: &Session,
+    executor_profile_id: &ExecutorProfileId,
+    create_subtasks: bool,
+) -> Result<DesignPlanResult, DesignPlanError> {
+    let history = DesignMessage::find_by_session_id(pool, session.id).await?;
+    if history.is_empty() {
+        return Err(DesignPlanError::EmptySession);
+    }
+
+    let store = DesignTurn {
+        task,
+        session,
+        executor_profile_id,
+        message: PLAN_PROMPT,
+        history: &history,
+        persist: true,
+    }
+    .start(pool)
+    .await?;
+    read_only_agent::wait_for_finish(&store).await;
+
+    let plan = DesignPlan::parse(&read_only_agent::assistant_reply(&store))
+        .ok_or_else(|| DesignPlanError::InvalidPlan(executor_profile_id.clone()))?;
+    Task::update_plan(pool, task.id, Some(&plan.to_markdown())).await?;
+
+    let subtasks = if create_subtasks {
+        create_step_tasks(pool, task, &plan.steps).await?
+    } else {
+        Vec::new()
+    };
+    let task = Task::find_by_id(pool, task.id)
+        .await?
+        .ok_or(sqlx::Error::RowNotFound)?;
+
+    Ok(DesignPlanResult {
+        task,
+        plan,
+        subtasks,
+    })
+}
+
+/// Create a child task per step, skipping steps that already have one. Tasks
+/// linked to Dooray get a sub-post per step; if that fails the task is kept
+/// local.
+async fn create_step_tasks(
+    pool: &SqlitePool,
+    parent: &Task,
+    steps: &[PlanStep],
+) -> Result<Vec<Task>, sqlx::Error> {
+    let existing: HashSet<String> = Task::find_by_parent_task_id(pool, parent.id)
+        .await?
+        .into_iter()
+        .map(|task| task.title)
+        .collect();
+
+    let mut created = Vec::new();
+    for step in steps.iter().filter(|s| !existing.contains(&s.title)) {
+        let description = step.description.as_deref();
+        match hierarchy::create_sub_post_task(pool, parent, &step.title, description).await {
+            Ok(Some(task)) => {
+                created.push(task);
+                continue;
+            }
+            Ok(None) => {}
+            Err(e) => tracing::warn!(
+                "Failed to create Dooray sub-post for step '{}' of task {}: {}",
+                step.title,
+                parent.id,
+                e
+            ),
+        }
+
+        let create_data = CreateTask {
+            parent_task_id: Some(parent.id),
+            ..CreateTask::from_title_description(
+                parent.project_id,
+                step.title.clone(),
+                step.description.clone(),
+            )
+        };
+        created.push(Task::create(pool, &create_data, Uuid::new_v4()).await?);
+    }
+    Ok(created)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plan_is_read_from_json_block() {
+        let reply = "정리했습니다.\n\n```json\n{\"goals\": [\"파서 분리\"], \
+                     \"steps\": [{\"title\": \"Split lexer\", \"description\": \"Move tokens\\nAdd tests\"}, \
+                     {\"title\": \"Wire parser\"}], \
+                     \"acceptance_criteria\": [\"Existing tests pass\"]}\n```";
+        let plan = DesignPlan::parse(reply).unwrap();
+
+        assert_eq!(plan.steps.len(), 2);
+        assert_eq!(plan.steps[1].description, None);
+        assert_eq!(
+            plan.to_markdown(),
+            "## Goals\n\n- 파서 분리\n\n\
+             ## Steps\n\n1. **Split lexer**\n   Move tokens\n   Add tests\n2. **Wire parser**\n\n\
+             ## Acceptance Criteria\n\n- [ ] Existing tests pass"
+        );
+    }
+
+    #[test]
+    fn replies_without_a_plan_are_rejected() {
+        assert_eq!(DesignPlan::parse("I need more details first."), None);
+        assert_eq!(DesignPlan::parse("{\"goals\": [], \"steps\": []}"), None);
+        assert!(DesignPlan::parse("Plan: {\"steps\": [{\"title\": \"Do it\"}]}").is_some());
+    }
+}
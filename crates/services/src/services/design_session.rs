@@ -0,0 +1,177 @@
+//! Design chat turns run by the coding agent of an executor profile.
+//!
+//! A design session has no workspace, so the agent runs as a
+//! [`ReadOnlyAgentRun`] in the main checkout of the first repository of the
+//! task's project; executors that can't be restricted to reading are refused. The
+//! agent's own session id is kept on the design session so the
+//! next turn resumes the conversation; when it can't (first turn, or the
+//! executor changed) the earlier messages are replayed in the prompt.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use db::models::{
+    design_message::{DesignMessage, DesignMessageRole},
+    project_repo::ProjectRepo,
+    session::Session,
+    task::Task,
+};
+use executors::{
+    env::{ExecutionEnv, RepoContext},
+    profile::ExecutorProfileId,
+};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use utils::{log_msg::LogMsg, msg_store::MsgStore};
+use uuid::Uuid;
+
+use super::read_only_agent::{ReadOnlyAgentError, ReadOnlyAgentRun};
+
+pub const DESIGN_SYSTEM_PROMPT: &str = "You are a helpful assistant for software design discussions. \
+    Help the user plan and design their implementation. \
+    Be concise but thorough. Respond in the same language as the user.";
+
+#[derive(Debug, Error)]
+pub enum DesignSessionError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Agent(#[from] ReadOnlyAgentError),
+}
+
+/// One user message sent to a design session
+#[derive(Debug, Clone, Copy)]
+pub struct DesignTurn<'a> {
+    pub task: &'a Task,
+    pub session: &'a Session,
+    pub executor_profile_id: &'a ExecutorProfileId,
+    pub message: &'a str,
+    /// Earlier messages of the session, replayed when the agent can't resume
+    pub history: &'a [DesignMessage],
+    /// Whether the agent session is kept for the next turn
+    pub persist: bool,
+}
+
+impl DesignTurn<'_> {
+    /// Spawn the agent. Normalized entries are pushed to the returned store,
+    /// which is finished once the agent exits.
+    pub async fn start(&self, pool: &SqlitePool) -> Result<Arc<MsgStore>, DesignSessionError> {
+        let resume_id = self.resume_id(pool).await?;
+        let prompt = match resume_id {
+            Some(_) => self.message.to_string(),
+            None => self.initial_prompt(),
+        };
+
+        let working_dir = working_dir(pool, self.task).await;
+        let run = ReadOnlyAgentRun::start(
+            self.executor_profile_id,
+            &working_dir,
+            &prompt,
+            resume_id.as_deref(),
+            &self.env(&working_dir),
+        )
+        .await?;
+        let store = run.store.clone();
+
+        tokio::spawn(finish_turn(
+            pool.clone(),
+            self.session.id,
+            self.persist,
+            run,
+        ));
+        Ok(store)
+    }
+
+    /// Agent session to continue, if the session's last turn ran on the same executor
+    async fn resume_id(&self, pool: &SqlitePool) -> Result<Option<String>, sqlx::Error> {
+        if !self.persist {
+            return Ok(None);
+        }
+        let executor = self.executor_profile_id.executor.to_string();
+        if self.session.executor.as_deref() == Some(executor.as_str()) {
+            return Session::find_agent_session_id(pool, self.session.id).await;
+        }
+
+        Session::update_executor(pool, self.session.id, &executor).await?;
+        Session::update_agent_session_id(pool, self.session.id, None).await?;
+        Ok(None)
+    }
+
+    fn initial_prompt(&self) -> String {
+        let mut prompt = format!(
+            "{}\n\nTask Title: {}\nTask Description: {}\n\n",
+            DESIGN_SYSTEM_PROMPT,
+            self.task.title,
+            self.task
+                .description
+                .as_deref()
+                .unwrap_or("(no description)")
+        );
+        if !self.history.is_empty() {
+            prompt.push_str("Conversation so far:\n\n");
+            for message in self.history {
+                let speaker = match message.role {
+                    DesignMessageRole::User => "User",
+                    DesignMessageRole::Assistant => "Assistant",
+                };
+                prompt.push_str(&format!("{}: {}\n\n", speaker, message.content));
+            }
+        }
+        prompt.push_str(&format!("User: {}", self.message));
+        prompt
+    }
+
+    fn env(&self, working_dir: &Path) -> ExecutionEnv {
+        let repo_context = RepoContext::new(working_dir.to_path_buf(), Vec::new());
+        let mut env = ExecutionEnv::new(repo_context, false, String::new());
+        env.insert("VK_PROJECT_ID", self.task.project_id.to_string());
+        env.insert("VK_TASK_ID", self.task.id.to_string());
+        env.insert("VK_SESSION_ID", self.session.id.to_string());
+        if let Some(ref dooray_task_number) = self.task.dooray_task_number {
+            env.insert("VK_DOORAY_TASK_NUMBER", dooray_task_number);
+        }
+        if let Some(ref dooray_project_id) = self.task.dooray_project_id {
+            env.insert("VK_DOORAY_PROJECT_ID", dooray_project_id);
+        }
+        env
+    }
+}
+
+/// First repository of the task's project, or the home directory
+async fn working_dir(pool: &SqlitePool, task: &Task) -> PathBuf {
+    let repos = ProjectRepo::find_repos_for_project(pool, task.project_id)
+        .await
+        .unwrap_or_default();
+    match repos.into_iter().next() {
+        Some(repo) => repo.path,
+        None => dirs::home_dir().unwrap_or_else(std::env::temp_dir),
+    }
+}
+
+/// Wait for the agent to exit, keep its session id and finish the store.
+async fn finish_turn(pool: SqlitePool, session_id: Uuid, persist: bool, run: ReadOnlyAgentRun) {
+    let store = run.wait().await;
+
+    let agent_session_id = store
+        .get_history()
+        .into_iter()
+        .rev()
+        .find_map(|msg| match msg {
+            LogMsg::SessionId(id) => Some(id),
+            _ => None,
+        });
+    if persist
+        && let Some(agent_session_id) = agent_session_id
+        && let Err(e) =
+            Session::update_agent_session_id(&pool, session_id, Some(&agent_session_id)).await
+    {
+        tracing::error!(
+            "Failed to save agent session of design session {}: {}",
+            session_id,
+            e
+        );
+    }
+    store.push_finished();
+}
@@ -0,0 +1,123 @@
+//! Picks the Dooray token to use for a request: the connection of the kanban
+//! project's binding to the Dooray project, or the default connection stored in
+//! `dooray_settings`.
+
+use db::models::{
+    dooray_connection::DoorayConnection, dooray_settings::DooraySettings,
+    project_dooray_binding::ProjectDoorayBinding, task::Task,
+};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::{
+    client::DoorayClient,
+    secret::{DooraySecretError, reveal_token},
+    types::DoorayError,
+};
+
+#[derive(Debug, Error)]
+pub enum DoorayAccountError {
+    #[error("Dooray integration not configured")]
+    NotConfigured,
+    #[error("Dooray connection {0} not found")]
+    ConnectionNotFound(Uuid),
+    #[error(transparent)]
+    Dooray(#[from] DoorayError),
+    #[error(transparent)]
+    Secret(#[from] DooraySecretError),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+/// A client for one Dooray tenant plus what we know about the token owner and
+/// the Dooray project it was resolved for
+#[derive(Debug, Clone)]
+pub struct DoorayAccount {
+    pub client: DoorayClient,
+    /// `None` for the default connection in settings
+    pub connection_id: Option<Uuid>,
+    pub dooray_domain: Option<String>,
+    pub member_id: Option<String>,
+    /// Dooray project of the binding (or the project selected in settings)
+    pub dooray_project_id: Option<String>,
+    pub dooray_project_code: Option<String>,
+}
+
+impl DoorayAccount {
+    /// Account of a stored connection, or of the default connection when
+    /// `connection_id` is `None`.
+    pub async fn for_connection(
+        pool: &SqlitePool,
+        connection_id: Option<Uuid>,
+    ) -> Result<Self, DoorayAccountError> {
+        let Some(connection_id) = connection_id else {
+            let settings = DooraySettings::get(pool)
+                .await?
+                .ok_or(DoorayAccountError::NotConfigured)?;
+            return Ok(Self {
+                client: DoorayClient::new(&reveal_token(&settings.dooray_token)?)?,
+                connection_id: None,
+                dooray_domain: settings.dooray_domain,
+                member_id: settings.member_id,
+                dooray_project_id: settings.selected_project_id,
+                dooray_project_code: settings.selected_project_name,
+            });
+        };
+
+        let connection = DoorayConnection::find_by_id(pool, connection_id)
+            .await?
+            .ok_or(DoorayAccountError::ConnectionNotFound(connection_id))?;
+        Ok(Self {
+            client: DoorayClient::new(&reveal_token(&connection.dooray_token)?)?,
+            connection_id: Some(connection.id),
+            dooray_domain: connection.dooray_domain,
+            member_id: connection.member_id,
+            dooray_project_id: None,
+            dooray_project_code: None,
+        })
+    }
+
+    pub async fn for_binding(
+        pool: &SqlitePool,
+        binding: &ProjectDoorayBinding,
+    ) -> Result<Self, DoorayAccountError> {
+        let account = Self::for_connection(pool, binding.connection_id).await?;
+        Ok(Self {
+            dooray_project_id: Some(binding.dooray_project_id.clone()),
+            dooray_project_code: Some(binding.dooray_project_code.clone()),
+            ..account
+        })
+    }
+
+    /// Account that can reach `dooray_project_id`: the connection of a binding to
+    /// it (preferring the binding of `project_id`), else the default connection.
+    pub async fn for_dooray_project(
+        pool: &SqlitePool,
+        project_id: Option<Uuid>,
+        dooray_project_id: &str,
+    ) -> Result<Self, DoorayAccountError> {
+        match ProjectDoorayBinding::find_for_dooray_project(pool, project_id, dooray_project_id)
+            .await?
+        {
+            Some(binding) => Self::for_binding(pool, &binding).await,
+            None => Self::for_connection(pool, None).await,
+        }
+    }
+
+    /// Account for the Dooray post a task is linked to; unlinked tasks get the
+    /// default connection.
+    pub async fn for_task(pool: &SqlitePool, task: &Task) -> Result<Self, DoorayAccountError> {
+        match task.dooray_project_id.as_deref() {
+            Some(dooray_project_id) => {
+                Self::for_dooray_project(pool, Some(task.project_id), dooray_project_id).await
+            }
+            None => Self::for_connection(pool, None).await,
+        }
+    }
+
+    /// Project code for task numbers and mentions
+    pub fn project_code(&self) -> &str {
+        self.dooray_project_code.as_deref().unwrap_or("PROJECT")
+    }
+}
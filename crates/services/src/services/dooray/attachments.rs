@@ -0,0 +1,275 @@
+//! Dooray post images copied into the local image store.
+//!
+//! Image files of a post, attached or pasted into the body, are downloaded with
+//! the Dooray file API and stored through [`ImageService`]. Linked to the task,
+//! they are copied into its workspaces under `.vibe-images/` like uploaded
+//! images. Links to them in the body are rewritten to that path and images the
+//! body doesn't show are appended to it. Other attachments stay on Dooray.
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+    sync::LazyLock,
+};
+
+use db::models::{
+    dooray_file_image::DoorayFileImage,
+    image::{Image, TaskImage},
+};
+use regex::{Captures, Regex};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::{
+    client::DoorayClient,
+    types::{DoorayError, DoorayPostFile},
+};
+use crate::services::image::{ImageError, ImageService};
+
+/// Links to Dooray files in a post body: `/files/{fileId}`, optionally with the
+/// tenant host or a path prefix such as `/project`
+static DOORAY_FILE_LINK_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?m)(?P<prefix>^|[\s("'=])(?:https://[\w.-]+\.dooray\.com)?(?:/[\w-]+)*/files/(?P<id>\d+)(?:\?[^\s)"']*)?"#,
+    )
+    .expect("Invalid regex")
+});
+
+/// Extensions `ImageService` accepts
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp", "bmp", "svg"];
+
+#[derive(Debug, Error)]
+enum AttachmentError {
+    #[error(transparent)]
+    Dooray(#[from] DoorayError),
+    #[error(transparent)]
+    Image(#[from] ImageError),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+/// Post body with the post's images stored locally
+#[derive(Debug, Clone, Default)]
+pub struct LocalizedBody {
+    pub description: Option<String>,
+    pub image_ids: Vec<Uuid>,
+}
+
+impl LocalizedBody {
+    /// Link the images to the task so they are copied into its workspaces.
+    pub async fn attach_to(&self, pool: &SqlitePool, task_id: Uuid) -> Result<(), sqlx::Error> {
+        if self.image_ids.is_empty() {
+            return Ok(());
+        }
+        TaskImage::associate_many_dedup(pool, task_id, &self.image_ids).await
+    }
+}
+
+/// Store the image files of a post locally and point `body` at them. Files
+/// that can't be listed or downloaded are logged and left on Dooray.
+pub async fn localize_post_images(
+    pool: &SqlitePool,
+    images: &ImageService,
+    client: &DoorayClient,
+    dooray_project_id: &str,
+    post_id: &str,
+    body: Option<String>,
+) -> LocalizedBody {
+    let files = match client.list_post_files(dooray_project_id, post_id).await {
+        Ok(files) => files,
+        Err(e) => {
+            tracing::warn!("Failed to list files of Dooray post {}: {}", post_id, e);
+            return LocalizedBody {
+                description: body,
+                image_ids: Vec::new(),
+            };
+        }
+    };
+
+    let mut stored = Vec::new();
+    for file in files {
+        let Some(file_name) = image_file_name(&file) else {
+            continue;
+        };
+        match store_file(
+            pool,
+            images,
+            client,
+            dooray_project_id,
+            post_id,
+            &file,
+            &file_name,
+        )
+        .await
+        {
+            Ok(image) => stored.push((file.id, image)),
+            Err(e) => tracing::warn!(
+                "Failed to copy file '{}' of Dooray post {}: {}",
+                file.name,
+                post_id,
+                e
+            ),
+        }
+    }
+
+    LocalizedBody {
+        image_ids: stored.iter().map(|(_, image)| image.id).collect(),
+        description: rewrite_body(body, &stored),
+    }
+}
+
+async fn store_file(
+    pool: &SqlitePool,
+    images: &ImageService,
+    client: &DoorayClient,
+    dooray_project_id: &str,
+    post_id: &str,
+    file: &DoorayPostFile,
+    file_name: &str,
+) -> Result<Image, AttachmentError> {
+    if let Some(image) = DoorayFileImage::find_image(pool, &file.id).await? {
+        return Ok(image);
+    }
+
+    let data = client
+        .download_post_file(dooray_project_id, post_id, &file.id)
+        .await?;
+    let image = images.store_image(&data, file_name).await?;
+    DoorayFileImage::record(pool, &file.id, image.id).await?;
+    Ok(image)
+}
+
+/// File name `ImageService` will accept for an image file, or `None` if the
+/// file isn't an image.
+fn image_file_name(file: &DoorayPostFile) -> Option<String> {
+    let has_image_extension = Path::new(&file.name)
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| IMAGE_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()));
+    if has_image_extension {
+        return Some(file.name.clone());
+    }
+
+    let extension = match file.mime_type.as_deref()? {
+        "image/png" => "png",
+        "image/jpeg" => "jpg",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        "image/bmp" => "bmp",
+        "image/svg+xml" => "svg",
+        _ => return None,
+    };
+    Some(format!("{}.{}", file.name, extension))
+}
+
+fn local_path(image: &Image) -> String {
+    format!("{}/{}", utils::path::VIBE_IMAGES_DIR, image.file_path)
+}
+
+/// Point links to stored files at their local copies and append the stored
+/// images the body doesn't link to.
+fn rewrite_body(body: Option<String>, stored: &[(String, Image)]) -> Option<String> {
+    if stored.is_empty() {
+        return body;
+    }
+
+    let local_paths: HashMap<&str, String> = stored
+        .iter()
+        .map(|(file_id, image)| (file_id.as_str(), local_path(image)))
+        .collect();
+    let mut linked = HashSet::new();
+    let mut body = body
+        .map(|body| {
+            DOORAY_FILE_LINK_RE
+                .replace_all(&body, |caps: &Captures| {
+                    match local_paths.get(&caps["id"]) {
+                        Some(path) => {
+                            linked.insert(caps["id"].to_string());
+                            format!("{}{}", &caps["prefix"], path)
+                        }
+                        None => caps[0].to_string(),
+                    }
+                })
+                .into_owned()
+        })
+        .unwrap_or_default();
+
+    for (file_id, image) in stored {
+        if linked.contains(file_id) {
+            continue;
+        }
+        if !body.is_empty() {
+            body.push_str("\n\n");
+        }
+        body.push_str(&format!(
+            "![{}]({})",
+            image.original_name,
+            local_paths[file_id.as_str()]
+        ));
+    }
+    Some(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn image(file_path: &str, original_name: &str) -> Image {
+        Image {
+            id: Uuid::new_v4(),
+            file_path: file_path.to_string(),
+            original_name: original_name.to_string(),
+            mime_type: Some("image/png".to_string()),
+            size_bytes: 1,
+            hash: String::new(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn linked_files_are_rewritten_and_unlinked_images_appended() {
+        let stored = vec![
+            ("111".to_string(), image("a_shot.png", "shot.png")),
+            ("222".to_string(), image("b_log.png", "log.png")),
+        ];
+        let body = "재현 화면\n![shot.png](/files/111)\n\
+                    [원본](https://nhnent.dooray.com/project/files/111?size=original)\n\
+                    다른 파일 /files/999"
+            .to_string();
+
+        assert_eq!(
+            rewrite_body(Some(body), &stored).unwrap(),
+            "재현 화면\n![shot.png](.vibe-images/a_shot.png)\n\
+             [원본](.vibe-images/a_shot.png)\n\
+             다른 파일 /files/999\n\n\
+             ![log.png](.vibe-images/b_log.png)"
+        );
+    }
+
+    #[test]
+    fn only_image_files_are_stored() {
+        let file = |name: &str, mime_type: Option<&str>| DoorayPostFile {
+            id: "1".to_string(),
+            name: name.to_string(),
+            size: None,
+            mime_type: mime_type.map(str::to_string),
+        };
+
+        assert_eq!(
+            image_file_name(&file("Screen.PNG", None)).as_deref(),
+            Some("Screen.PNG")
+        );
+        assert_eq!(
+            image_file_name(&file("clipboard", Some("image/jpeg"))).as_deref(),
+            Some("clipboard.jpg")
+        );
+        assert_eq!(
+            image_file_name(&file("report.pdf", Some("application/pdf"))),
+            None
+        );
+    }
+}
@@ -0,0 +1,598 @@
+use std::time::Duration;
+
+use reqwest::{
+    Client, Method, RequestBuilder, StatusCode,
+    header::{AUTHORIZATION, CONTENT_TYPE, HeaderMap, HeaderValue, LOCATION},
+    redirect,
+};
+use serde::{Serialize, de::DeserializeOwned};
+use url::{Host, Url};
+
+use super::types::{
+    CreatePostRequest, DoorayApiHeader, DoorayCreatedPost, DoorayEnvelope, DoorayError,
+    DoorayMemberDetail, DoorayPage, DoorayPostFile, DoorayPostLog, DoorayProjectDetail,
+    DoorayProjectMember, DoorayProjectSummary, DoorayTagItem, DoorayTask, DoorayTaskBody,
+    DoorayTaskDetail, DoorayTemplate, DoorayTemplateDetail, DoorayWorkflow, PostListQuery,
+};
+
+pub const DEFAULT_DOORAY_API_BASE: &str = "https://api.dooray.com";
+
+/// Environment variable that overrides the Dooray API base URL (e.g. for a local mock server)
+pub const DOORAY_API_BASE_ENV: &str = "VK_DOORAY_API_BASE";
+
+/// Returns the Dooray API base URL, honouring `VK_DOORAY_API_BASE` when set.
+pub fn default_api_base() -> String {
+    std::env::var(DOORAY_API_BASE_ENV)
+        .ok()
+        .filter(|s| !s.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_DOORAY_API_BASE.to_string())
+}
+
+/// Typed HTTP client for the Dooray public API.
+#[derive(Clone)]
+pub struct DoorayClient {
+    base: Url,
+    http: Client,
+    /// Doesn't follow redirects, so file downloads can resend the token
+    downloads: Client,
+}
+
+impl std::fmt::Debug for DoorayClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DoorayClient")
+            .field("base", &self.base)
+            .field("token", &"<redacted>")
+            .finish()
+    }
+}
+
+impl DoorayClient {
+    const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+    /// Page size used by the `list_all_*` helpers (Dooray's maximum)
+    pub const PAGE_SIZE: u32 = 100;
+    /// Upper bound on pages fetched when walking a listing
+    pub const MAX_PAGES: u32 = 50;
+    /// Upper bound on redirects followed when downloading a file
+    const MAX_DOWNLOAD_REDIRECTS: usize = 3;
+    /// File hosts a download may be redirected to, named by the label that
+    /// replaces the first one of the API host
+    const FILE_HOST_LABELS: &[&str] = &["file-api"];
+
+    /// Create a client against the default (or `VK_DOORAY_API_BASE`) API base URL.
+    pub fn new(token: &str) -> Result<Self, DoorayError> {
+        Self::with_base_url(&default_api_base(), token)
+    }
+
+    pub fn with_base_url(base_url: &str, token: &str) -> Result<Self, DoorayError> {
+        let base = Url::parse(base_url).map_err(|e| DoorayError::InvalidBaseUrl(e.to_string()))?;
+
+        // Accept tokens entered with or without the `dooray-api ` prefix
+        let clean_token = token.trim();
+        let auth_value = if clean_token.starts_with("dooray-api ") {
+            clean_token.to_string()
+        } else {
+            format!("dooray-api {}", clean_token)
+        };
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            AUTHORIZATION,
+            HeaderValue::from_str(&auth_value)
+                .map_err(|e| DoorayError::InvalidToken(e.to_string()))?,
+        );
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+
+        let http = Client::builder()
+            .default_headers(headers.clone())
+            .timeout(Self::REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| DoorayError::Transport(e.to_string()))?;
+        let downloads = Client::builder()
+            .default_headers(headers)
+            .timeout(Self::REQUEST_TIMEOUT)
+            .redirect(redirect::Policy::none())
+            .build()
+            .map_err(|e| DoorayError::Transport(e.to_string()))?;
+
+        Ok(Self {
+            base,
+            http,
+            downloads,
+        })
+    }
+
+    pub fn base_url(&self) -> &str {
+        self.base.as_str().trim_end_matches('/')
+    }
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        self.http
+            .request(method, format!("{}{}", self.base_url(), path))
+    }
+
+    async fn send<T: DeserializeOwned>(
+        &self,
+        builder: RequestBuilder,
+    ) -> Result<DoorayEnvelope<T>, DoorayError> {
+        let response = builder
+            .send()
+            .await
+            .map_err(|e| DoorayError::Transport(e.to_string()))?;
+        let status = response.status();
+        let text = response
+            .text()
+            .await
+            .map_err(|e| DoorayError::Transport(e.to_string()))?;
+
+        match status {
+            StatusCode::UNAUTHORIZED => return Err(DoorayError::Unauthorized),
+            StatusCode::FORBIDDEN => return Err(DoorayError::Forbidden),
+            StatusCode::NOT_FOUND => return Err(DoorayError::NotFound),
+            s if !s.is_success() => {
+                // Dooray usually still returns its header envelope on failures
+                if let Ok(envelope) =
+                    serde_json::from_str::<DoorayEnvelope<serde_json::Value>>(&text)
+                {
+                    return Err(header_error(&envelope.header));
+                }
+                return Err(DoorayError::Http {
+                    status: s.as_u16(),
+                    body: text,
+                });
+            }
+            _ => {}
+        }
+
+        let envelope: DoorayEnvelope<T> = serde_json::from_str(&text).map_err(|e| {
+            tracing::debug!(
+                "Unparseable Dooray response: {}",
+                text.chars().take(500).collect::<String>()
+            );
+            DoorayError::Serde(e.to_string())
+        })?;
+
+        if !envelope.header.is_successful {
+            return Err(header_error(&envelope.header));
+        }
+
+        Ok(envelope)
+    }
+
+    async fn get_result<T: DeserializeOwned>(&self, path: &str) -> Result<T, DoorayError> {
+        self.send::<T>(self.request(Method::GET, path))
+            .await?
+            .result
+            .ok_or(DoorayError::EmptyResult)
+    }
+
+    async fn send_json<B: Serialize + ?Sized, T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        body: &B,
+    ) -> Result<Option<T>, DoorayError> {
+        Ok(self
+            .send::<T>(self.request(method, path).json(body))
+            .await?
+            .result)
+    }
+
+    /// Fetch a single page of a listing endpoint.
+    pub async fn get_page<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        params: &[(&str, String)],
+        page: u32,
+        size: u32,
+    ) -> Result<DoorayPage<T>, DoorayError> {
+        let envelope = self
+            .send::<Vec<T>>(
+                self.request(Method::GET, path)
+                    .query(params)
+                    .query(&[("page", page), ("size", size)]),
+            )
+            .await?;
+        Ok(DoorayPage {
+            items: envelope.result.unwrap_or_default(),
+            page,
+            size,
+            total_count: envelope.total_count,
+        })
+    }
+
+    /// Walk every page of a listing endpoint and collect the items.
+    pub async fn get_all_pages<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        params: &[(&str, String)],
+    ) -> Result<Vec<T>, DoorayError> {
+        let mut items = Vec::new();
+        for page in 0..Self::MAX_PAGES {
+            let current = self
+                .get_page::<T>(path, params, page, Self::PAGE_SIZE)
+                .await?;
+            let has_more = current.has_more();
+            items.extend(current.items);
+            if !has_more {
+                return Ok(items);
+            }
+        }
+        tracing::warn!(
+            "Stopped paginating {} after {} pages",
+            path,
+            Self::MAX_PAGES
+        );
+        Ok(items)
+    }
+
+    // ============== Projects ==============
+
+    pub async fn list_my_projects_page(
+        &self,
+        page: u32,
+        size: u32,
+    ) -> Result<DoorayPage<DoorayProjectSummary>, DoorayError> {
+        self.get_page(
+            "/project/v1/projects",
+            &[("member", "me".to_string())],
+            page,
+            size,
+        )
+        .await
+    }
+
+    /// Projects the token owner is a member of.
+    pub async fn list_my_projects(&self) -> Result<Vec<DoorayProjectSummary>, DoorayError> {
+        self.get_all_pages("/project/v1/projects", &[("member", "me".to_string())])
+            .await
+    }
+
+    pub async fn get_project(&self, project_id: &str) -> Result<DoorayProjectDetail, DoorayError> {
+        self.get_result(&format!("/project/v1/projects/{}", project_id))
+            .await
+    }
+
+    // ============== Posts ==============
+
+    pub async fn list_posts_page(
+        &self,
+        project_id: &str,
+        query: &PostListQuery,
+        page: u32,
+        size: u32,
+    ) -> Result<DoorayPage<DoorayTask>, DoorayError> {
+        self.get_page(
+            &format!("/project/v1/projects/{}/posts", project_id),
+            &query.to_params(),
+            page,
+            size,
+        )
+        .await
+    }
+
+    pub async fn list_all_posts(
+        &self,
+        project_id: &str,
+        query: &PostListQuery,
+    ) -> Result<Vec<DoorayTask>, DoorayError> {
+        self.get_all_pages(
+            &format!("/project/v1/projects/{}/posts", project_id),
+            &query.to_params(),
+        )
+        .await
+    }
+
+    /// Look up a post by its project-local number (e.g. the `123` in `PROJ/123`).
+    pub async fn find_post_by_number(
+        &self,
+        project_id: &str,
+        number: i64,
+    ) -> Result<Option<DoorayTask>, DoorayError> {
+        let query = PostListQuery {
+            post_number: Some(number),
+            ..Default::default()
+        };
+        let page = self.list_posts_page(project_id, &query, 0, 1).await?;
+        Ok(page.items.into_iter().next())
+    }
+
+    pub async fn get_post(
+        &self,
+        project_id: &str,
+        post_id: &str,
+    ) -> Result<DoorayTaskDetail, DoorayError> {
+        self.get_result(&format!(
+            "/project/v1/projects/{}/posts/{}",
+            project_id, post_id
+        ))
+        .await
+    }
+
+    pub async fn create_post(
+        &self,
+        project_id: &str,
+        request: &CreatePostRequest,
+    ) -> Result<DoorayCreatedPost, DoorayError> {
+        self.send_json(
+            Method::POST,
+            &format!("/project/v1/projects/{}/posts", project_id),
+            request,
+        )
+        .await?
+        .ok_or(DoorayError::EmptyResult)
+    }
+
+    /// Replace the body of a post (markdown).
+    pub async fn update_post_body(
+        &self,
+        project_id: &str,
+        post_id: &str,
+        content: &str,
+    ) -> Result<(), DoorayError> {
+        self.send_json::<_, serde_json::Value>(
+            Method::PUT,
+            &format!("/project/v1/projects/{}/posts/{}", project_id, post_id),
+            &serde_json::json!({ "body": DoorayTaskBody::markdown(content) }),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Move a post to another workflow (status column).
+    pub async fn set_post_workflow(
+        &self,
+        project_id: &str,
+        post_id: &str,
+        workflow_id: &str,
+    ) -> Result<(), DoorayError> {
+        self.send_json::<_, serde_json::Value>(
+            Method::POST,
+            &format!(
+                "/project/v1/projects/{}/posts/{}/set-workflow",
+                project_id, post_id
+            ),
+            &serde_json::json!({ "workflowId": workflow_id }),
+        )
+        .await?;
+        Ok(())
+    }
+
+    // ============== Workflows ==============
+
+    pub async fn list_workflows(
+        &self,
+        project_id: &str,
+    ) -> Result<Vec<DoorayWorkflow>, DoorayError> {
+        self.get_result(&format!("/project/v1/projects/{}/workflows", project_id))
+            .await
+    }
+
+    // ============== Comments (post logs) ==============
+
+    pub async fn list_post_logs(
+        &self,
+        project_id: &str,
+        post_id: &str,
+    ) -> Result<Vec<DoorayPostLog>, DoorayError> {
+        self.get_all_pages(
+            &format!("/project/v1/projects/{}/posts/{}/logs", project_id, post_id),
+            &[],
+        )
+        .await
+    }
+
+    /// Add a markdown comment to a post.
+    pub async fn create_post_log(
+        &self,
+        project_id: &str,
+        post_id: &str,
+        content: &str,
+    ) -> Result<(), DoorayError> {
+        self.send_json::<_, serde_json::Value>(
+            Method::POST,
+            &format!("/project/v1/projects/{}/posts/{}/logs", project_id, post_id),
+            &serde_json::json!({ "body": DoorayTaskBody::markdown(content) }),
+        )
+        .await?;
+        Ok(())
+    }
+
+    // ============== Files ==============
+
+    /// Files attached to a post, including images pasted into its body.
+    pub async fn list_post_files(
+        &self,
+        project_id: &str,
+        post_id: &str,
+    ) -> Result<Vec<DoorayPostFile>, DoorayError> {
+        self.get_result(&format!(
+            "/project/v1/projects/{}/posts/{}/files",
+            project_id, post_id
+        ))
+        .await
+    }
+
+    /// Download the contents of a file attached to a post.
+    pub async fn download_post_file(
+        &self,
+        project_id: &str,
+        post_id: &str,
+        file_id: &str,
+    ) -> Result<Vec<u8>, DoorayError> {
+        let mut url = Url::parse(&format!(
+            "{}/project/v1/projects/{}/posts/{}/files/{}?media=raw",
+            self.base_url(),
+            project_id,
+            post_id,
+            file_id
+        ))
+        .map_err(|e| DoorayError::InvalidBaseUrl(e.to_string()))?;
+
+        // Dooray redirects to its file server, which wants the token as well.
+        // reqwest drops the Authorization header on cross-host redirects, so
+        // follow them by hand, but only within the API's own domain.
+        let mut redirects = 0;
+        loop {
+            let response = self
+                .downloads
+                .get(url.clone())
+                .send()
+                .await
+                .map_err(|e| DoorayError::Transport(e.to_string()))?;
+            let status = response.status();
+
+            if status.is_redirection() && redirects < Self::MAX_DOWNLOAD_REDIRECTS {
+                redirects += 1;
+                let location = response
+                    .headers()
+                    .get(LOCATION)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|location| url.join(location).ok())
+                    .filter(|target| self.is_trusted_redirect(target))
+                    .ok_or_else(|| DoorayError::Http {
+                        status: status.as_u16(),
+                        body: "unexpected file download redirect".to_string(),
+                    })?;
+                url = location;
+                continue;
+            }
+
+            return match status {
+                StatusCode::UNAUTHORIZED => Err(DoorayError::Unauthorized),
+                StatusCode::FORBIDDEN => Err(DoorayError::Forbidden),
+                StatusCode::NOT_FOUND => Err(DoorayError::NotFound),
+                s if !s.is_success() => Err(DoorayError::Http {
+                    status: s.as_u16(),
+                    body: response.text().await.unwrap_or_default(),
+                }),
+                _ => Ok(response
+                    .bytes()
+                    .await
+                    .map_err(|e| DoorayError::Transport(e.to_string()))?
+                    .to_vec()),
+            };
+        }
+    }
+
+    /// Whether the token may be sent to `target`: an https URL on the API host
+    /// itself or on one of its file hosts, e.g. `file-api.dooray.com` next to
+    /// `api.dooray.com`. IP hosts are never trusted.
+    fn is_trusted_redirect(&self, target: &Url) -> bool {
+        if target.scheme() != "https" {
+            return false;
+        }
+        let (Some(Host::Domain(base_host)), Some(Host::Domain(target_host))) =
+            (self.base.host(), target.host())
+        else {
+            return false;
+        };
+        if base_host == target_host {
+            return true;
+        }
+        let Some((_, domain)) = base_host.split_once('.') else {
+            return false;
+        };
+        Self::FILE_HOST_LABELS
+            .iter()
+            .any(|label| target_host == format!("{label}.{domain}"))
+    }
+
+    // ============== Tags ==============
+
+    pub async fn list_tags(&self, project_id: &str) -> Result<Vec<DoorayTagItem>, DoorayError> {
+        self.get_all_pages(&format!("/project/v1/projects/{}/tags", project_id), &[])
+            .await
+    }
+
+    // ============== Templates ==============
+
+    pub async fn list_templates(
+        &self,
+        project_id: &str,
+    ) -> Result<Vec<DoorayTemplate>, DoorayError> {
+        self.get_all_pages(
+            &format!("/project/v1/projects/{}/templates", project_id),
+            &[],
+        )
+        .await
+    }
+
+    pub async fn get_template(
+        &self,
+        project_id: &str,
+        template_id: &str,
+    ) -> Result<DoorayTemplateDetail, DoorayError> {
+        self.get_result(&format!(
+            "/project/v1/projects/{}/templates/{}",
+            project_id, template_id
+        ))
+        .await
+    }
+
+    // ============== Members ==============
+
+    /// The organization member that owns the API token.
+    pub async fn get_me(&self) -> Result<DoorayMemberDetail, DoorayError> {
+        self.get_result("/common/v1/members/me").await
+    }
+
+    pub async fn get_member(&self, member_id: &str) -> Result<DoorayMemberDetail, DoorayError> {
+        self.get_result(&format!("/common/v1/members/{}", member_id))
+            .await
+    }
+
+    pub async fn list_project_members(
+        &self,
+        project_id: &str,
+    ) -> Result<Vec<DoorayProjectMember>, DoorayError> {
+        self.get_all_pages(&format!("/project/v1/projects/{}/members", project_id), &[])
+            .await
+    }
+
+    /// Resolve member ids to display names in parallel; unknown members are skipped.
+    pub async fn resolve_member_names(&self, member_ids: Vec<String>) -> Vec<(String, String)> {
+        let lookups = member_ids.into_iter().map(|id| async move {
+            let name = self.get_member(&id).await.ok()?.name?;
+            Some((id, name))
+        });
+        futures_util::future::join_all(lookups)
+            .await
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+}
+
+fn header_error(header: &DoorayApiHeader) -> DoorayError {
+    DoorayError::Api {
+        code: header.result_code,
+        message: header
+            .result_message
+            .clone()
+            .unwrap_or_else(|| "unknown error".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redirects_keep_the_token_only_over_https_to_dooray_file_hosts() {
+        let client = DoorayClient::with_base_url("https://api.dooray.com", "token").unwrap();
+        let trusted = |target: &str| client.is_trusted_redirect(&Url::parse(target).unwrap());
+
+        assert!(trusted("https://api.dooray.com/files/1"));
+        assert!(trusted("https://file-api.dooray.com/files/1"));
+        assert!(!trusted("http://api.dooray.com/files/1"));
+        assert!(!trusted("http://file-api.dooray.com/files/1"));
+        assert!(!trusted("https://api.dooray.com.evil.example/files/1"));
+        assert!(!trusted("https://example.com/files/1"));
+        // Not every host of the parent domain
+        assert!(!trusted("https://nhnent.dooray.com/files/1"));
+
+        let client = DoorayClient::with_base_url("https://10.0.0.1", "token").unwrap();
+        assert!(!client.is_trusted_redirect(&Url::parse("https://0.0.1/files/1").unwrap()));
+        assert!(!client.is_trusted_redirect(&Url::parse("https://10.0.0.1/files/1").unwrap()));
+    }
+}
@@ -0,0 +1,273 @@
+//! Dooray sub-posts as a kanban task hierarchy.
+//!
+//! A task's `parent_task_id` points at the task of its post's parent post. Links
+//! are made from whichever side is imported last: a new sub-post task looks up
+//! the task of its parent post, and a new parent task adopts the tasks of its
+//! already imported sub-posts.
+
+use db::models::{
+    dooray_status_sync::DoorayTaskSyncState,
+    project_dooray_binding::ProjectDoorayBinding,
+    task::{CreateTask, Task, TaskStatus},
+};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::{
+    account::DoorayAccount,
+    attachments,
+    client::DoorayClient,
+    status_sync::StatusMapping,
+    sync::DooraySyncError,
+    types::{CreatePostRequest, DoorayTaskBody, DoorayTaskDetail, PostListQuery},
+};
+use crate::services::image::ImageService;
+
+/// Kanban project and Dooray project imported posts belong to
+#[derive(Debug, Clone, Copy)]
+pub struct ImportTarget<'a> {
+    pub project_id: Uuid,
+    pub dooray_project_id: &'a str,
+    /// Used to format task numbers (e.g. `PROJECT/123`)
+    pub dooray_project_code: &'a str,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+pub struct SubPostImportResult {
+    /// Task of the parent post
+    pub parent_task_id: Option<Uuid>,
+    pub created: i32,
+    /// Sub-posts that were already imported and are now linked to the parent
+    pub linked: i32,
+    pub skipped: i32,
+}
+
+/// Task of the parent post `parent_post_id`, if that post has been imported.
+pub async fn parent_task_id_for(
+    pool: &SqlitePool,
+    parent_post_id: Option<&str>,
+) -> Result<Option<Uuid>, sqlx::Error> {
+    let Some(parent_post_id) = parent_post_id else {
+        return Ok(None);
+    };
+    Ok(Task::find_by_dooray_task_id(pool, parent_post_id)
+        .await?
+        .map(|task| task.id))
+}
+
+/// Point the tasks of the already imported sub-posts of `post_id` at `task_id`.
+/// Returns how many tasks were linked.
+pub async fn adopt_sub_posts(
+    pool: &SqlitePool,
+    client: &DoorayClient,
+    dooray_project_id: &str,
+    post_id: &str,
+    task_id: Uuid,
+) -> Result<i32, DooraySyncError> {
+    let sub_posts = client
+        .list_all_posts(dooray_project_id, &PostListQuery::sub_posts_of(post_id))
+        .await?;
+
+    let mut linked = 0;
+    for sub_post in sub_posts {
+        if let Some(sub_task) = Task::find_by_dooray_task_id(pool, &sub_post.id).await?
+            && sub_task.parent_task_id != Some(task_id)
+        {
+            Task::update_parent_task_id(pool, sub_task.id, Some(task_id)).await?;
+            linked += 1;
+        }
+    }
+    Ok(linked)
+}
+
+/// Import `parent_post_id` (unless it already is) and all of its sub-posts, with
+/// the sub-post tasks linked to the parent task.
+pub async fn import_with_sub_posts(
+    pool: &SqlitePool,
+    images: &ImageService,
+    client: &DoorayClient,
+    target: ImportTarget<'_>,
+    parent_post_id: &str,
+) -> Result<SubPostImportResult, DooraySyncError> {
+    let mapping = StatusMapping::load(pool, client, target.dooray_project_id).await?;
+    let mut result = SubPostImportResult::default();
+
+    let parent_task_id = match Task::find_by_dooray_task_id(pool, parent_post_id).await? {
+        Some(task) => task.id,
+        None => {
+            let detail = client
+                .get_post(target.dooray_project_id, parent_post_id)
+                .await?;
+            let grandparent_task_id =
+                parent_task_id_for(pool, detail.parent.as_ref().map(|p| p.id.as_str())).await?;
+            result.created += 1;
+            create_task(
+                pool,
+                images,
+                client,
+                &mapping,
+                target,
+                detail,
+                grandparent_task_id,
+            )
+            .await?
+        }
+    };
+    result.parent_task_id = Some(parent_task_id);
+
+    let sub_posts = client
+        .list_all_posts(
+            target.dooray_project_id,
+            &PostListQuery::sub_posts_of(parent_post_id),
+        )
+        .await?;
+    for sub_post in sub_posts {
+        match Task::find_by_dooray_task_id(pool, &sub_post.id).await? {
+            Some(task) if task.parent_task_id == Some(parent_task_id) => result.skipped += 1,
+            Some(task) => {
+                Task::update_parent_task_id(pool, task.id, Some(parent_task_id)).await?;
+                result.linked += 1;
+            }
+            None => {
+                let detail = client
+                    .get_post(target.dooray_project_id, &sub_post.id)
+                    .await?;
+                create_task(
+                    pool,
+                    images,
+                    client,
+                    &mapping,
+                    target,
+                    detail,
+                    Some(parent_task_id),
+                )
+                .await?;
+                result.created += 1;
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+/// Create a sub-post of `parent`'s post and a task for it, linked to `parent`.
+/// Returns `None` if `parent` isn't linked to a Dooray post.
+pub async fn create_sub_post_task(
+    pool: &SqlitePool,
+    parent: &Task,
+    subject: &str,
+    body: Option<&str>,
+) -> Result<Option<Task>, DooraySyncError> {
+    let (Some(dooray_project_id), Some(parent_post_id)) = (
+        parent.dooray_project_id.as_deref(),
+        parent.dooray_task_id.as_deref(),
+    ) else {
+        return Ok(None);
+    };
+    let account = DoorayAccount::for_task(pool, parent).await?;
+
+    let mut request = CreatePostRequest::new(subject);
+    request.body = body.map(DoorayTaskBody::markdown);
+    request.parent_post_id = Some(parent_post_id.to_string());
+    if let Some(member_id) = &account.member_id {
+        request = request.assign_to(member_id);
+    }
+    // Keep the sub-post inside the binding's sync filter
+    if let Some(binding) = ProjectDoorayBinding::find_for_dooray_project(
+        pool,
+        Some(parent.project_id),
+        dooray_project_id,
+    )
+    .await?
+    {
+        request.tag_ids = binding.tag_ids.0;
+    }
+    let created = account
+        .client
+        .create_post(dooray_project_id, &request)
+        .await?;
+
+    // The create response carries no workflow; read it back so the sync baseline
+    // holds the workflow the post actually starts in
+    let detail = account
+        .client
+        .get_post(dooray_project_id, &created.id)
+        .await
+        .ok();
+    let workflow_id = detail
+        .as_ref()
+        .and_then(|d| d.workflow.as_ref())
+        .map(|w| w.id.clone());
+
+    let number = created
+        .number
+        .or_else(|| detail.as_ref().and_then(|d| d.number))
+        .map(|n| n.to_string())
+        .unwrap_or_else(|| created.id.clone());
+    let create_data = CreateTask {
+        project_id: parent.project_id,
+        title: subject.to_string(),
+        description: body.map(str::to_string),
+        status: Some(TaskStatus::Todo),
+        parent_workspace_id: None,
+        image_ids: None,
+        dooray_task_id: Some(created.id),
+        dooray_project_id: Some(dooray_project_id.to_string()),
+        dooray_task_number: Some(format!("{}/{}", account.project_code(), number)),
+        reference_dooray_url: None,
+        parent_task_id: Some(parent.id),
+    };
+    let task = Task::create(pool, &create_data, Uuid::new_v4()).await?;
+    DoorayTaskSyncState::mark_synced(pool, task.id, &TaskStatus::Todo, workflow_id.as_deref())
+        .await?;
+    Ok(Some(task))
+}
+
+async fn create_task(
+    pool: &SqlitePool,
+    images: &ImageService,
+    client: &DoorayClient,
+    mapping: &StatusMapping,
+    target: ImportTarget<'_>,
+    detail: DoorayTaskDetail,
+    parent_task_id: Option<Uuid>,
+) -> Result<Uuid, DooraySyncError> {
+    let workflow_id = detail.workflow.as_ref().map(|w| w.id.clone());
+    let status = mapping.status_for(workflow_id.as_deref(), detail.workflow_class.as_deref());
+    let task_number = match detail.number {
+        Some(number) => format!("{}/{}", target.dooray_project_code, number),
+        None => format!("{}/{}", target.dooray_project_code, detail.id),
+    };
+
+    let localized = attachments::localize_post_images(
+        pool,
+        images,
+        client,
+        target.dooray_project_id,
+        &detail.id,
+        detail.body.and_then(|b| b.content),
+    )
+    .await;
+
+    let create_data = CreateTask {
+        project_id: target.project_id,
+        title: detail.subject,
+        description: localized.description.clone(),
+        status: Some(status.clone()),
+        parent_workspace_id: None,
+        image_ids: None,
+        dooray_task_id: Some(detail.id),
+        dooray_project_id: Some(target.dooray_project_id.to_string()),
+        dooray_task_number: Some(task_number),
+        reference_dooray_url: None,
+        parent_task_id,
+    };
+
+    let task_id = Uuid::new_v4();
+    Task::create(pool, &create_data, task_id).await?;
+    localized.attach_to(pool, task_id).await?;
+    DoorayTaskSyncState::mark_synced(pool, task_id, &status, workflow_id.as_deref()).await?;
+    Ok(task_id)
+}
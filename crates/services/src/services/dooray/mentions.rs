@@ -0,0 +1,154 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::LazyLock,
+};
+
+use regex::Regex;
+
+use super::client::DoorayClient;
+
+/// Dooray task URL pattern: https://{domain}/project/tasks/{taskId} or https://{domain}/task/{projectId}/{taskId}
+pub static DOORAY_TASK_URL_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"https://[\w.-]+\.dooray\.com/(?:project/tasks/(\d+)|task/\d+/(\d+))")
+        .expect("Invalid regex")
+});
+
+/// Extract the Dooray post id from a task URL, if the URL is one.
+pub fn task_id_from_url(url: &str) -> Option<String> {
+    DOORAY_TASK_URL_RE
+        .captures(url)
+        .and_then(|cap| cap.get(1).or_else(|| cap.get(2)))
+        .map(|m| m.as_str().to_string())
+}
+
+/// Task info needed to build a rich mention
+#[derive(Debug, Clone)]
+pub struct TaskMentionInfo {
+    pub task_id: String,
+    pub number: i64,
+    pub subject: String,
+    pub workflow_class: String,
+    pub project_id: String,
+    pub project_code: String,
+}
+
+/// Process task body and convert Dooray task URLs to rich inline HTML mentions.
+///
+/// Returns processed body with URLs replaced by `dooray-flavored-html-mention` `<a>` tags.
+/// The mimeType remains `text/x-markdown` (caller handles); Dooray's markdown renderer
+/// passes through inline HTML tags. URLs whose post cannot be fetched are kept as-is.
+pub async fn process_body_with_mentions(
+    client: &DoorayClient,
+    body: &str,
+    default_project_id: &str,
+    project_code: &str,
+) -> String {
+    // Find all Dooray task URLs in the body
+    let task_ids: HashSet<String> = DOORAY_TASK_URL_RE
+        .captures_iter(body)
+        .filter_map(|cap| {
+            cap.get(1)
+                .or_else(|| cap.get(2))
+                .map(|m| m.as_str().to_string())
+        })
+        .collect();
+
+    if task_ids.is_empty() {
+        return body.to_string();
+    }
+
+    // Fetch task details in parallel
+    let fetches = task_ids
+        .iter()
+        .map(|task_id| fetch_mention_info(client, default_project_id, task_id, project_code));
+
+    let mention_map: HashMap<String, TaskMentionInfo> = futures_util::future::join_all(fetches)
+        .await
+        .into_iter()
+        .flatten()
+        .map(|info| (info.task_id.clone(), info))
+        .collect();
+
+    if mention_map.is_empty() {
+        // Could not fetch any task details, return body as-is
+        return body.to_string();
+    }
+
+    // Replace task URLs with rich inline HTML mentions directly in the markdown body
+    DOORAY_TASK_URL_RE
+        .replace_all(body, |caps: &regex::Captures| {
+            let task_id = caps
+                .get(1)
+                .or_else(|| caps.get(2))
+                .map(|m| m.as_str())
+                .unwrap_or("");
+
+            if let Some(info) = mention_map.get(task_id) {
+                build_mention_html(info)
+            } else {
+                // Keep original URL if we couldn't fetch details
+                caps[0].to_string()
+            }
+        })
+        .to_string()
+}
+
+/// Fetch the post details needed to render a mention. Returns `None` when the post
+/// cannot be fetched or has no number yet.
+pub async fn fetch_mention_info(
+    client: &DoorayClient,
+    project_id: &str,
+    task_id: &str,
+    default_project_code: &str,
+) -> Option<TaskMentionInfo> {
+    let task = match client.get_post(project_id, task_id).await {
+        Ok(task) => task,
+        Err(e) => {
+            tracing::debug!("Failed to fetch Dooray post {} for mention: {}", task_id, e);
+            return None;
+        }
+    };
+
+    let number = task.number?;
+    let proj_id = task
+        .project
+        .as_ref()
+        .and_then(|p| p.id.clone())
+        .unwrap_or_else(|| project_id.to_string());
+    let proj_code = task
+        .project
+        .as_ref()
+        .and_then(|p| p.code.clone())
+        .unwrap_or_else(|| default_project_code.to_string());
+
+    Some(TaskMentionInfo {
+        task_id: task.id,
+        number,
+        subject: task.subject,
+        workflow_class: task
+            .workflow_class
+            .unwrap_or_else(|| "registered".to_string()),
+        project_id: proj_id,
+        project_code: proj_code,
+    })
+}
+
+pub fn build_mention_html(info: &TaskMentionInfo) -> String {
+    let escaped_subject = html_escape(&info.subject);
+    format!(
+        r#"<a class="dooray-flavored-html-mention task-reference {wf}" href="/project/posts/{tid}" title="{wf}" target="_blank" rel="noopener noreferrer" data-dooray-href="dooray://{pid}/tasks/{tid}" data-id="{tid}">{code}/{num} | {subj}</a>"#,
+        wf = html_escape(&info.workflow_class),
+        tid = info.task_id,
+        pid = info.project_id,
+        code = html_escape(&info.project_code),
+        num = info.number,
+        subj = escaped_subject,
+    )
+}
+
+pub fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,25 @@
+//! Typed client for the Dooray project API, shared by the HTTP routes, background
+//! sync and the MCP task server.
+
+pub mod account;
+pub mod attachments;
+mod client;
+pub mod hierarchy;
+pub mod mentions;
+pub mod report;
+pub mod secret;
+pub mod status_sync;
+pub mod sync;
+pub mod token;
+pub mod webhook;
+mod types;
+
+pub use client::{DEFAULT_DOORAY_API_BASE, DOORAY_API_BASE_ENV, DoorayClient, default_api_base};
+pub use types::{
+    CreatePostRequest, DoorayApiHeader, DoorayCreatedPost, DoorayError, DoorayLogCreator,
+    DoorayMemberDetail, DoorayOrganizationRef, DoorayPage, DoorayPostFile, DoorayPostLog,
+    DoorayPostProjectRef, DoorayPostRef, DoorayProjectDetail, DoorayProjectMember,
+    DoorayProjectSummary, DoorayTagGroupInfo, DoorayTagItem, DoorayTask, DoorayTaskBody,
+    DoorayTaskDetail, DoorayTemplate, DoorayTemplateDetail, DoorayWorkflow, DoorayWorkflowRef,
+    PostListQuery,
+};
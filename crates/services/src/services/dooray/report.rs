@@ -0,0 +1,253 @@
+//! Opt-in comments on the linked Dooray post when a coding agent run finishes or
+//! the attempt's PR is created or merged.
+//!
+//! Reporting is configured per kanban project in [`DoorayReportSettings`]. The
+//! comment is rendered from a template with `{placeholders}` and task URLs in it
+//! become Dooray mentions, like in task bodies.
+
+use db::models::{
+    coding_agent_turn::CodingAgentTurn, dooray_report_settings::DoorayReportSettings, task::Task,
+    workspace::Workspace,
+};
+use git::GitService;
+use sqlx::SqlitePool;
+use thiserror::Error;
+
+use super::{
+    account::{DoorayAccount, DoorayAccountError},
+    mentions::process_body_with_mentions,
+    types::DoorayError,
+};
+use crate::services::diff_stream::{DiffStats, compute_diff_stats};
+
+/// Built-in comment template. Placeholders: `{event}`, `{task_title}`, `{branch}`,
+/// `{diff_stats}`, `{pr_number}`, `{pr_url}`, `{summary}`.
+pub const DEFAULT_REPORT_TEMPLATE: &str = "### {event}
+- 작업: {task_title}
+- 브랜치: `{branch}`
+- 변경: {diff_stats}
+- PR: {pr_url}
+
+{summary}";
+
+/// Longest agent summary included in a comment, in characters
+const MAX_SUMMARY_CHARS: usize = 4000;
+
+#[derive(Debug, Error)]
+pub enum DoorayReportError {
+    #[error(transparent)]
+    Dooray(#[from] DoorayError),
+    #[error(transparent)]
+    Account(#[from] DoorayAccountError),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DoorayReportEvent {
+    ExecutionFinished { succeeded: bool },
+    PrCreated,
+    PrMerged,
+}
+
+impl DoorayReportEvent {
+    fn enabled_in(&self, settings: &DoorayReportSettings) -> bool {
+        match self {
+            Self::ExecutionFinished { .. } => settings.on_execution_finished,
+            Self::PrCreated => settings.on_pr_created,
+            Self::PrMerged => settings.on_pr_merged,
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::ExecutionFinished { succeeded: true } => "🤖 에이전트 작업 완료",
+            Self::ExecutionFinished { succeeded: false } => "⚠️ 에이전트 작업 실패",
+            Self::PrCreated => "🔀 PR 생성",
+            Self::PrMerged => "✅ PR 머지",
+        }
+    }
+}
+
+/// Pull request the report is about
+#[derive(Debug, Clone)]
+pub struct DoorayReportPr {
+    pub number: i64,
+    pub url: String,
+}
+
+/// Values substituted into the comment template
+#[derive(Debug, Clone)]
+pub struct DoorayReport<'a> {
+    pub event: DoorayReportEvent,
+    pub task_title: &'a str,
+    pub branch: &'a str,
+    pub diff_stats: Option<DiffStats>,
+    pub pr: Option<&'a DoorayReportPr>,
+    pub summary: Option<&'a str>,
+}
+
+impl DoorayReport<'_> {
+    pub fn render(&self, template: &str) -> String {
+        let diff_stats = self
+            .diff_stats
+            .as_ref()
+            .map(|s| {
+                format!(
+                    "파일 {}개, +{} / -{}",
+                    s.files_changed, s.lines_added, s.lines_removed
+                )
+            })
+            .unwrap_or_else(|| "-".to_string());
+        let summary = self
+            .summary
+            .map(|s| truncate_chars(s.trim(), MAX_SUMMARY_CHARS))
+            .unwrap_or_default();
+
+        template
+            .replace("{event}", self.event.label())
+            .replace("{task_title}", self.task_title)
+            .replace("{branch}", self.branch)
+            .replace("{diff_stats}", &diff_stats)
+            .replace(
+                "{pr_number}",
+                &self
+                    .pr
+                    .map(|pr| format!("#{}", pr.number))
+                    .unwrap_or_else(|| "-".to_string()),
+            )
+            .replace("{pr_url}", self.pr.map(|pr| pr.url.as_str()).unwrap_or("-"))
+            .replace("{summary}", &summary)
+            .trim_end()
+            .to_string()
+    }
+}
+
+/// Comment on the Dooray post linked to `task` if its project reports `event`.
+/// Returns whether a comment was posted.
+pub async fn report(
+    pool: &SqlitePool,
+    git: &GitService,
+    task: &Task,
+    workspace: &Workspace,
+    event: DoorayReportEvent,
+    pr: Option<&DoorayReportPr>,
+) -> Result<bool, DoorayReportError> {
+    let (Some(dooray_project_id), Some(dooray_task_id)) = (
+        task.dooray_project_id.as_deref(),
+        task.dooray_task_id.as_deref(),
+    ) else {
+        return Ok(false);
+    };
+    let Some(settings) = DoorayReportSettings::find_by_project_id(pool, task.project_id).await?
+    else {
+        return Ok(false);
+    };
+    if !event.enabled_in(&settings) {
+        return Ok(false);
+    }
+
+    let account = match DoorayAccount::for_task(pool, task).await {
+        Ok(account) => account,
+        Err(DoorayAccountError::NotConfigured) => return Ok(false),
+        Err(e) => return Err(e.into()),
+    };
+
+    let diff_stats = compute_diff_stats(pool, git, workspace).await;
+    let summary = CodingAgentTurn::find_latest_summary_for_workspace(pool, workspace.id).await?;
+    let content = DoorayReport {
+        event,
+        task_title: &task.title,
+        branch: &workspace.branch,
+        diff_stats,
+        pr,
+        summary: summary.as_deref(),
+    }
+    .render(
+        settings
+            .comment_template
+            .as_deref()
+            .unwrap_or(DEFAULT_REPORT_TEMPLATE),
+    );
+    let content = process_body_with_mentions(
+        &account.client,
+        &content,
+        dooray_project_id,
+        account.project_code(),
+    )
+    .await;
+
+    account
+        .client
+        .create_post_log(dooray_project_id, dooray_task_id, &content)
+        .await?;
+    tracing::info!(
+        "Reported {:?} for task {} to Dooray post {}",
+        event,
+        task.id,
+        dooray_task_id
+    );
+    Ok(true)
+}
+
+fn truncate_chars(s: &str, max: usize) -> String {
+    match s.char_indices().nth(max) {
+        Some((idx, _)) => format!("{}…", &s[..idx]),
+        None => s.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_template_fills_placeholders_and_dashes_missing_values() {
+        let report = DoorayReport {
+            event: DoorayReportEvent::ExecutionFinished { succeeded: true },
+            task_title: "로그인 수정",
+            branch: "vk/1234-login",
+            diff_stats: Some(DiffStats {
+                files_changed: 3,
+                lines_added: 10,
+                lines_removed: 2,
+            }),
+            pr: None,
+            summary: Some("Fixed the login redirect.\n"),
+        };
+
+        assert_eq!(
+            report.render(DEFAULT_REPORT_TEMPLATE),
+            "### 🤖 에이전트 작업 완료
+- 작업: 로그인 수정
+- 브랜치: `vk/1234-login`
+- 변경: 파일 3개, +10 / -2
+- PR: -
+
+Fixed the login redirect."
+        );
+    }
+
+    #[test]
+    fn custom_template_gets_pr_and_truncated_summary() {
+        let pr = DoorayReportPr {
+            number: 7,
+            url: "https://github.com/org/repo/pull/7".to_string(),
+        };
+        let summary = "가".repeat(MAX_SUMMARY_CHARS + 10);
+        let report = DoorayReport {
+            event: DoorayReportEvent::PrMerged,
+            task_title: "t",
+            branch: "b",
+            diff_stats: None,
+            pr: Some(&pr),
+            summary: Some(&summary),
+        };
+
+        let rendered = report.render("{event} {pr_number} {pr_url} {diff_stats}\n{summary}");
+        let (head, body) = rendered.split_once('\n').unwrap();
+        assert_eq!(head, "✅ PR 머지 #7 https://github.com/org/repo/pull/7 -");
+        assert_eq!(body.chars().count(), MAX_SUMMARY_CHARS + 1);
+        assert!(body.ends_with('…'));
+    }
+}
@@ -0,0 +1,302 @@
+//! Encryption at rest for stored Dooray API tokens.
+//!
+//! Tokens are kept in SQLite as `enc:v1:` followed by the URL-safe base64 of
+//! `nonce || ciphertext` (AES-256-GCM). The key lives in a local secret file in
+//! the asset directory, or in the Secret Service keyring on Linux when
+//! `VK_DOORAY_SECRET_BACKEND=keyring`. Values without the prefix were saved before
+//! encryption was introduced; [`seal_stored_tokens`] re-saves them on startup.
+
+use std::{io::Write, path::Path};
+
+use aes_gcm::{
+    Aes256Gcm, Key, Nonce,
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+};
+use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
+use db::models::{dooray_connection::DoorayConnection, dooray_settings::DooraySettings};
+use once_cell::sync::OnceCell;
+use sqlx::SqlitePool;
+use thiserror::Error;
+use utils::assets::dooray_secret_key_path;
+
+/// Environment variable selecting where the token encryption key is kept
+pub const SECRET_BACKEND_ENV: &str = "VK_DOORAY_SECRET_BACKEND";
+
+const SEALED_PREFIX: &str = "enc:v1:";
+const NONCE_SIZE: usize = 12; // 96 bits for AES-256-GCM
+const KEY_SIZE: usize = 32;
+
+#[cfg(target_os = "linux")]
+const KEYRING_SERVICE: &str = "vibe-kanban";
+#[cfg(target_os = "linux")]
+const KEYRING_USER: &str = "dooray-token-key";
+
+static CIPHER: OnceCell<TokenCipher> = OnceCell::new();
+
+#[derive(Debug, Error)]
+pub enum DooraySecretError {
+    #[error("unknown VK_DOORAY_SECRET_BACKEND value '{0}' (expected 'file' or 'keyring')")]
+    UnknownBackend(String),
+    #[error("keyring backend unavailable: {0}")]
+    Keyring(String),
+    #[error("failed to access the Dooray secret key file: {0}")]
+    KeyFile(#[from] std::io::Error),
+    #[error("Dooray secret key is malformed")]
+    InvalidKey,
+    #[error("failed to encrypt the Dooray token")]
+    Encrypt,
+    #[error(
+        "stored Dooray token could not be decrypted; the secret key may have changed, please enter the token again"
+    )]
+    Decrypt,
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+/// Where the token encryption key is kept
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecretBackend {
+    /// `dooray_secret.key` in the asset directory (default)
+    File,
+    /// Secret Service keyring (Linux only)
+    Keyring,
+}
+
+impl SecretBackend {
+    pub fn from_env() -> Result<Self, DooraySecretError> {
+        let value = std::env::var(SECRET_BACKEND_ENV).unwrap_or_default();
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" | "file" => Ok(Self::File),
+            "keyring" => Ok(Self::Keyring),
+            other => Err(DooraySecretError::UnknownBackend(other.to_string())),
+        }
+    }
+}
+
+/// AES-256-GCM cipher for stored Dooray tokens
+#[derive(Clone)]
+pub struct TokenCipher {
+    cipher: Aes256Gcm,
+}
+
+impl std::fmt::Debug for TokenCipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TokenCipher")
+            .field("key", &"<redacted>")
+            .finish()
+    }
+}
+
+impl TokenCipher {
+    pub fn new(key: &[u8; KEY_SIZE]) -> Self {
+        Self {
+            cipher: Aes256Gcm::new(&Key::<Aes256Gcm>::from(*key)),
+        }
+    }
+
+    /// Process-wide cipher; the key is loaded (or created) on first use.
+    pub fn global() -> Result<&'static Self, DooraySecretError> {
+        CIPHER.get_or_try_init(|| {
+            let backend = SecretBackend::from_env()?;
+            let key = match backend {
+                SecretBackend::File => load_or_create_file_key(&dooray_secret_key_path())?,
+                SecretBackend::Keyring => load_or_create_keyring_key()?,
+            };
+            tracing::debug!(?backend, "Loaded Dooray token encryption key");
+            Ok(Self::new(&key))
+        })
+    }
+
+    pub fn seal(&self, token: &str) -> Result<String, DooraySecretError> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, token.as_bytes())
+            .map_err(|_| DooraySecretError::Encrypt)?;
+
+        let mut combined = nonce.to_vec();
+        combined.extend_from_slice(&ciphertext);
+        Ok(format!(
+            "{SEALED_PREFIX}{}",
+            URL_SAFE_NO_PAD.encode(combined)
+        ))
+    }
+
+    /// Decrypt a stored token. Tokens saved before encryption are returned as-is.
+    pub fn reveal(&self, stored: &str) -> Result<String, DooraySecretError> {
+        let Some(encoded) = stored.strip_prefix(SEALED_PREFIX) else {
+            return Ok(stored.to_string());
+        };
+        let decoded = URL_SAFE_NO_PAD
+            .decode(encoded)
+            .map_err(|_| DooraySecretError::Decrypt)?;
+        if decoded.len() < NONCE_SIZE {
+            return Err(DooraySecretError::Decrypt);
+        }
+
+        let (nonce, ciphertext) = decoded.split_at(NONCE_SIZE);
+        let plaintext = self
+            .cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| DooraySecretError::Decrypt)?;
+        String::from_utf8(plaintext).map_err(|_| DooraySecretError::Decrypt)
+    }
+}
+
+/// Whether a stored token is already encrypted
+pub fn is_sealed(stored: &str) -> bool {
+    stored.starts_with(SEALED_PREFIX)
+}
+
+/// Encrypt a token for storage with the process-wide key
+pub fn seal_token(token: &str) -> Result<String, DooraySecretError> {
+    TokenCipher::global()?.seal(token)
+}
+
+/// Decrypt a stored token with the process-wide key
+pub fn reveal_token(stored: &str) -> Result<String, DooraySecretError> {
+    if !is_sealed(stored) {
+        return Ok(stored.to_string());
+    }
+    TokenCipher::global()?.reveal(stored)
+}
+
+/// Encrypt tokens that are still stored in plain text. Returns how many were re-saved.
+pub async fn seal_stored_tokens(pool: &SqlitePool) -> Result<usize, DooraySecretError> {
+    let mut sealed = 0;
+
+    if let Some(settings) = DooraySettings::get(pool).await?
+        && !is_sealed(&settings.dooray_token)
+    {
+        let token = seal_token(&settings.dooray_token)?;
+        DooraySettings::update_token(pool, &token, settings.member_id.as_deref()).await?;
+        sealed += 1;
+    }
+
+    for connection in DoorayConnection::find_all(pool).await? {
+        if is_sealed(&connection.dooray_token) {
+            continue;
+        }
+        let token = seal_token(&connection.dooray_token)?;
+        DoorayConnection::update_token(pool, connection.id, &token).await?;
+        sealed += 1;
+    }
+
+    Ok(sealed)
+}
+
+fn new_key() -> [u8; KEY_SIZE] {
+    let mut key = [0u8; KEY_SIZE];
+    key.copy_from_slice(&Aes256Gcm::generate_key(OsRng));
+    key
+}
+
+fn decode_key(encoded: &str) -> Result<[u8; KEY_SIZE], DooraySecretError> {
+    URL_SAFE_NO_PAD
+        .decode(encoded.trim())
+        .ok()
+        .and_then(|bytes| <[u8; KEY_SIZE]>::try_from(bytes.as_slice()).ok())
+        .ok_or(DooraySecretError::InvalidKey)
+}
+
+fn load_or_create_file_key(path: &Path) -> Result<[u8; KEY_SIZE], DooraySecretError> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => return decode_key(&contents),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e.into()),
+    }
+
+    let mut opts = std::fs::OpenOptions::new();
+    opts.write(true).create_new(true);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        opts.mode(0o600);
+    }
+
+    match opts.open(path) {
+        Ok(mut file) => {
+            let key = new_key();
+            file.write_all(URL_SAFE_NO_PAD.encode(key).as_bytes())?;
+            file.sync_all()?;
+            tracing::info!("Created Dooray token encryption key at {}", path.display());
+            Ok(key)
+        }
+        // Another process created the key first
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+            decode_key(&std::fs::read_to_string(path)?)
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn load_or_create_keyring_key() -> Result<[u8; KEY_SIZE], DooraySecretError> {
+    let keyring_error = |e: keyring::Error| DooraySecretError::Keyring(e.to_string());
+    let entry = keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER).map_err(keyring_error)?;
+
+    match entry.get_password() {
+        Ok(encoded) => decode_key(&encoded),
+        Err(keyring::Error::NoEntry) => {
+            let key = new_key();
+            entry
+                .set_password(&URL_SAFE_NO_PAD.encode(key))
+                .map_err(keyring_error)?;
+            tracing::info!("Stored new Dooray token encryption key in the keyring");
+            Ok(key)
+        }
+        Err(e) => Err(keyring_error(e)),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn load_or_create_keyring_key() -> Result<[u8; KEY_SIZE], DooraySecretError> {
+    Err(DooraySecretError::Keyring(
+        "the keyring backend is only supported on Linux".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sealed_token_round_trips_and_legacy_token_passes_through() {
+        let cipher = TokenCipher::new(&new_key());
+
+        let sealed = cipher.seal("dooray-api secret").unwrap();
+        assert!(is_sealed(&sealed));
+        assert!(!sealed.contains("secret"));
+        assert_eq!(cipher.reveal(&sealed).unwrap(), "dooray-api secret");
+
+        assert_eq!(cipher.reveal("plain-token").unwrap(), "plain-token");
+    }
+
+    #[test]
+    fn token_sealed_with_another_key_does_not_decrypt() {
+        let sealed = TokenCipher::new(&new_key()).seal("secret").unwrap();
+
+        assert!(matches!(
+            TokenCipher::new(&new_key()).reveal(&sealed),
+            Err(DooraySecretError::Decrypt)
+        ));
+    }
+
+    #[test]
+    fn file_key_is_created_once_and_reused() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dooray_secret.key");
+
+        let created = load_or_create_file_key(&path).unwrap();
+        let loaded = load_or_create_file_key(&path).unwrap();
+        assert_eq!(created, loaded);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+}
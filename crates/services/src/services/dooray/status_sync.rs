@@ -0,0 +1,660 @@
+//! Two-way sync between kanban task statuses and Dooray workflows.
+//!
+//! Each linked task keeps a [`DoorayTaskSyncState`] with the status and workflow
+//! both sides last agreed on. Comparing the current values against it tells which
+//! side changed: a local-only change is pushed to Dooray, a remote-only change is
+//! pulled into the task, and a change on both sides is recorded as a conflict
+//! that the user resolves explicitly.
+
+use std::{sync::Arc, time::Duration};
+
+use db::{
+    DBService,
+    models::{
+        dooray_status_sync::{DoorayTaskSyncState, DoorayWorkflowMapping},
+        task::{Task, TaskStatus},
+    },
+};
+use json_patch::PatchOperation;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use tokio::{sync::broadcast::error::RecvError, time::interval};
+use tracing::{debug, error, info, warn};
+use ts_rs::TS;
+use utils::{log_msg::LogMsg, msg_store::MsgStore};
+use uuid::Uuid;
+
+use super::{
+    account::{DoorayAccount, DoorayAccountError},
+    client::DoorayClient,
+    types::{DoorayError, DoorayWorkflow},
+};
+
+#[derive(Debug, Error)]
+pub enum StatusSyncError {
+    #[error(transparent)]
+    Dooray(#[from] DoorayError),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Account(#[from] DoorayAccountError),
+}
+
+/// Status a post in the given workflow class maps to when no explicit mapping exists
+pub fn default_status_for_class(workflow_class: Option<&str>) -> TaskStatus {
+    match workflow_class {
+        Some("working") => TaskStatus::InProgress,
+        Some("closed") => TaskStatus::Done,
+        _ => TaskStatus::Todo,
+    }
+}
+
+/// Workflow class a task status is pushed as when no explicit mapping exists
+fn default_class_for_status(status: &TaskStatus) -> &'static str {
+    match status {
+        TaskStatus::Todo => "registered",
+        TaskStatus::InProgress | TaskStatus::InReview => "working",
+        TaskStatus::Done | TaskStatus::Cancelled => "closed",
+    }
+}
+
+/// Resolved mapping between task statuses and the workflows of one Dooray project.
+///
+/// Explicit [`DoorayWorkflowMapping`] rows win; anything unmapped falls back to the
+/// workflow class defaults.
+#[derive(Debug, Clone, Default)]
+pub struct StatusMapping {
+    rules: Vec<DoorayWorkflowMapping>,
+    workflows: Vec<DoorayWorkflow>,
+}
+
+impl StatusMapping {
+    pub fn new(rules: Vec<DoorayWorkflowMapping>, mut workflows: Vec<DoorayWorkflow>) -> Self {
+        workflows.sort_by_key(|w| w.order.unwrap_or(i32::MAX));
+        Self { rules, workflows }
+    }
+
+    /// Load the configured rules and the project's workflows.
+    pub async fn load(
+        pool: &SqlitePool,
+        client: &DoorayClient,
+        dooray_project_id: &str,
+    ) -> Result<Self, StatusSyncError> {
+        let rules =
+            DoorayWorkflowMapping::find_by_dooray_project_id(pool, dooray_project_id).await?;
+        let workflows = client.list_workflows(dooray_project_id).await?;
+        Ok(Self::new(rules, workflows))
+    }
+
+    /// Task status for a post in the given workflow.
+    pub fn status_for(
+        &self,
+        workflow_id: Option<&str>,
+        workflow_class: Option<&str>,
+    ) -> TaskStatus {
+        if let Some(workflow_id) = workflow_id
+            && let Some(rule) = self.rules.iter().find(|r| r.workflow_id == workflow_id)
+        {
+            return rule.task_status.clone();
+        }
+        let class = workflow_class.or_else(|| {
+            workflow_id.and_then(|id| {
+                self.workflows
+                    .iter()
+                    .find(|w| w.id == id)
+                    .map(|w| w.class.as_str())
+            })
+        });
+        default_status_for_class(class)
+    }
+
+    /// Workflow a post should move to when its task enters `status`.
+    pub fn workflow_for(&self, status: &TaskStatus) -> Option<&str> {
+        let mut candidates = self.rules.iter().filter(|r| &r.task_status == status);
+        if let Some(rule) = candidates
+            .clone()
+            .find(|r| r.is_push_target)
+            .or_else(|| candidates.next())
+        {
+            return Some(rule.workflow_id.as_str());
+        }
+        let class = default_class_for_status(status);
+        self.workflows
+            .iter()
+            .find(|w| w.class == class)
+            .map(|w| w.id.as_str())
+    }
+
+    /// Whether the task status and the post workflow already say the same thing.
+    pub fn agrees(
+        &self,
+        status: &TaskStatus,
+        workflow_id: Option<&str>,
+        workflow_class: Option<&str>,
+    ) -> bool {
+        &self.status_for(workflow_id, workflow_class) == status
+            || (workflow_id.is_some() && self.workflow_for(status) == workflow_id)
+    }
+}
+
+/// What to do to bring a task and its post back in agreement
+#[derive(Debug, Clone, PartialEq)]
+pub enum StatusSyncAction {
+    /// Neither side changed
+    None,
+    /// Both sides agree; record the current values as the new baseline
+    Baseline,
+    /// Only the post changed; apply its status locally
+    Pull(TaskStatus),
+    /// Only the task changed; move the post to this workflow
+    Push(String),
+    /// Both sides changed to different states
+    Conflict,
+}
+
+/// Decide how to reconcile a task with its post given the last agreed state.
+pub fn reconcile(
+    mapping: &StatusMapping,
+    local: &TaskStatus,
+    remote_workflow_id: Option<&str>,
+    remote_workflow_class: Option<&str>,
+    state: Option<&DoorayTaskSyncState>,
+) -> StatusSyncAction {
+    let agrees = mapping.agrees(local, remote_workflow_id, remote_workflow_class);
+
+    // Without a baseline (or with an open conflict) we cannot tell which side moved
+    let Some(state) = state.filter(|s| s.conflict_workflow_id.is_none()) else {
+        return if agrees {
+            StatusSyncAction::Baseline
+        } else {
+            StatusSyncAction::Conflict
+        };
+    };
+
+    let local_changed = &state.task_status != local;
+    let remote_changed = match state.workflow_id.as_deref() {
+        Some(workflow_id) => Some(workflow_id) != remote_workflow_id,
+        // Baseline recorded without the post's workflow: a local change is taken
+        // as the only change, otherwise a disagreeing post counts as moved
+        None => !local_changed && !agrees,
+    };
+
+    match (local_changed, remote_changed) {
+        (false, false) => StatusSyncAction::None,
+        (_, _) if agrees => StatusSyncAction::Baseline,
+        (true, false) => match mapping.workflow_for(local) {
+            Some(workflow_id) => StatusSyncAction::Push(workflow_id.to_string()),
+            // Nothing to push to; accept the local status as-is
+            None => StatusSyncAction::Baseline,
+        },
+        (false, true) => {
+            StatusSyncAction::Pull(mapping.status_for(remote_workflow_id, remote_workflow_class))
+        }
+        (true, true) => StatusSyncAction::Conflict,
+    }
+}
+
+/// Result of syncing one task
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusSyncOutcome {
+    Unchanged,
+    Pulled,
+    Pushed,
+    Conflict,
+}
+
+/// Reconcile `task` with the current workflow of its post and apply the result.
+pub async fn sync_task_status(
+    pool: &SqlitePool,
+    client: &DoorayClient,
+    mapping: &StatusMapping,
+    task: &Task,
+    remote_workflow_id: Option<&str>,
+    remote_workflow_class: Option<&str>,
+) -> Result<StatusSyncOutcome, StatusSyncError> {
+    let state = DoorayTaskSyncState::find_by_task_id(pool, task.id).await?;
+    let action = reconcile(
+        mapping,
+        &task.status,
+        remote_workflow_id,
+        remote_workflow_class,
+        state.as_ref(),
+    );
+
+    match action {
+        StatusSyncAction::None => Ok(StatusSyncOutcome::Unchanged),
+        StatusSyncAction::Baseline => {
+            DoorayTaskSyncState::mark_synced(pool, task.id, &task.status, remote_workflow_id)
+                .await?;
+            Ok(StatusSyncOutcome::Unchanged)
+        }
+        StatusSyncAction::Pull(status) => {
+            // Record the new baseline first so the resulting task update is not
+            // mistaken for a local change and pushed back
+            DoorayTaskSyncState::mark_synced(pool, task.id, &status, remote_workflow_id).await?;
+            Task::update_status(pool, task.id, status).await?;
+            Ok(StatusSyncOutcome::Pulled)
+        }
+        StatusSyncAction::Push(workflow_id) => {
+            let (Some(project_id), Some(post_id)) = (
+                task.dooray_project_id.as_deref(),
+                task.dooray_task_id.as_deref(),
+            ) else {
+                return Ok(StatusSyncOutcome::Unchanged);
+            };
+            client
+                .set_post_workflow(project_id, post_id, &workflow_id)
+                .await?;
+            DoorayTaskSyncState::mark_synced(pool, task.id, &task.status, Some(&workflow_id))
+                .await?;
+            Ok(StatusSyncOutcome::Pushed)
+        }
+        StatusSyncAction::Conflict => {
+            match remote_workflow_id {
+                Some(workflow_id) => {
+                    DoorayTaskSyncState::mark_conflict(pool, task.id, &task.status, workflow_id)
+                        .await?
+                }
+                // A conflict is recorded against a remote workflow, so it can't
+                // be stored; the next sync reports it again
+                None => warn!(
+                    "Status {} of task {} conflicts with its Dooray post, which has no workflow",
+                    task.status, task.id
+                ),
+            }
+            Ok(StatusSyncOutcome::Conflict)
+        }
+    }
+}
+
+/// Fetch the post of a linked task and sync its status.
+pub async fn sync_linked_task(
+    pool: &SqlitePool,
+    client: &DoorayClient,
+    task: &Task,
+) -> Result<StatusSyncOutcome, StatusSyncError> {
+    let (Some(project_id), Some(post_id)) = (
+        task.dooray_project_id.as_deref(),
+        task.dooray_task_id.as_deref(),
+    ) else {
+        return Ok(StatusSyncOutcome::Unchanged);
+    };
+
+    let post = client.get_post(project_id, post_id).await?;
+    let mapping = StatusMapping::load(pool, client, project_id).await?;
+    sync_task_status(
+        pool,
+        client,
+        &mapping,
+        task,
+        post.workflow.as_ref().map(|w| w.id.as_str()),
+        post.workflow_class.as_deref(),
+    )
+    .await
+}
+
+/// Which side wins when resolving a status conflict
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum StatusConflictResolution {
+    /// Keep the kanban status and push it to Dooray
+    Local,
+    /// Take the Dooray workflow and apply it to the task
+    Remote,
+}
+
+/// Resolve a status conflict of a linked task by picking one side.
+pub async fn resolve_conflict(
+    pool: &SqlitePool,
+    client: &DoorayClient,
+    task: &Task,
+    resolution: StatusConflictResolution,
+) -> Result<(), StatusSyncError> {
+    let (Some(project_id), Some(post_id)) = (
+        task.dooray_project_id.as_deref(),
+        task.dooray_task_id.as_deref(),
+    ) else {
+        return Ok(());
+    };
+
+    let mapping = StatusMapping::load(pool, client, project_id).await?;
+    match resolution {
+        StatusConflictResolution::Local => {
+            let workflow_id = mapping.workflow_for(&task.status);
+            if let Some(workflow_id) = workflow_id {
+                client
+                    .set_post_workflow(project_id, post_id, workflow_id)
+                    .await?;
+            }
+            DoorayTaskSyncState::mark_synced(pool, task.id, &task.status, workflow_id).await?;
+        }
+        StatusConflictResolution::Remote => {
+            let post = client.get_post(project_id, post_id).await?;
+            let workflow_id = post.workflow.as_ref().map(|w| w.id.as_str());
+            let status = mapping.status_for(workflow_id, post.workflow_class.as_deref());
+            DoorayTaskSyncState::mark_synced(pool, task.id, &status, workflow_id).await?;
+            Task::update_status(pool, task.id, status).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Pushes local status changes of Dooray-linked tasks to Dooray.
+///
+/// Task updates are picked up from the events stream, so every status change
+/// (drag and drop, `Task::update_status` from executions, PR merges, ...) is
+/// covered. A periodic sweep retries pushes that failed.
+pub struct DoorayStatusSyncService {
+    db: DBService,
+    events: Arc<MsgStore>,
+    retry_interval: Duration,
+}
+
+impl DoorayStatusSyncService {
+    pub async fn spawn(db: DBService, events: Arc<MsgStore>) -> tokio::task::JoinHandle<()> {
+        let service = Self {
+            db,
+            events,
+            retry_interval: Duration::from_secs(300),
+        };
+        tokio::spawn(async move {
+            service.start().await;
+        })
+    }
+
+    async fn start(&self) {
+        info!(
+            "Starting Dooray status sync service with retry interval {:?}",
+            self.retry_interval
+        );
+
+        let mut receiver = self.events.get_receiver();
+        let mut retry = interval(self.retry_interval);
+
+        loop {
+            tokio::select! {
+                msg = receiver.recv() => match msg {
+                    Ok(LogMsg::JsonPatch(patch)) => {
+                        for task_id in updated_linked_task_ids(&patch) {
+                            self.push_if_changed(task_id).await;
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!("Dooray status sync lagged behind {} events", skipped);
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+                _ = retry.tick() => {
+                    if let Err(e) = self.push_pending().await {
+                        error!("Error pushing pending Dooray status changes: {}", e);
+                    }
+                }
+            }
+        }
+    }
+
+    async fn push_pending(&self) -> Result<(), StatusSyncError> {
+        let task_ids = DoorayTaskSyncState::find_locally_changed_task_ids(&self.db.pool).await?;
+        if task_ids.is_empty() {
+            return Ok(());
+        }
+
+        debug!("Pushing {} pending Dooray status changes", task_ids.len());
+        for task_id in task_ids {
+            self.push_if_changed(task_id).await;
+        }
+        Ok(())
+    }
+
+    async fn push_if_changed(&self, task_id: Uuid) {
+        if let Err(e) = self.try_push(task_id).await {
+            error!("Failed to sync Dooray status for task {}: {}", task_id, e);
+        }
+    }
+
+    async fn try_push(&self, task_id: Uuid) -> Result<(), StatusSyncError> {
+        let pool = &self.db.pool;
+        let Some(task) = Task::find_by_id(pool, task_id).await? else {
+            return Ok(());
+        };
+        if task.dooray_task_id.is_none() {
+            return Ok(());
+        }
+
+        // Most task updates are not status changes; skip the round trip to Dooray
+        if let Some(state) = DoorayTaskSyncState::find_by_task_id(pool, task.id).await?
+            && (state.task_status == task.status || state.conflict_workflow_id.is_some())
+        {
+            return Ok(());
+        }
+
+        let account = match DoorayAccount::for_task(pool, &task).await {
+            Ok(account) => account,
+            Err(DoorayAccountError::NotConfigured) => return Ok(()),
+            Err(e) => return Err(e.into()),
+        };
+
+        match sync_linked_task(pool, &account.client, &task).await? {
+            StatusSyncOutcome::Pushed => {
+                info!(
+                    "Pushed status {} of task {} to Dooray",
+                    task.status, task.id
+                )
+            }
+            StatusSyncOutcome::Conflict => warn!(
+                "Status of task {} conflicts with its Dooray post; waiting for resolution",
+                task.id
+            ),
+            StatusSyncOutcome::Pulled | StatusSyncOutcome::Unchanged => {}
+        }
+        Ok(())
+    }
+}
+
+/// IDs of Dooray-linked tasks replaced by a task patch
+fn updated_linked_task_ids(patch: &json_patch::Patch) -> Vec<Uuid> {
+    patch
+        .0
+        .iter()
+        .filter_map(|op| match op {
+            PatchOperation::Replace(replace) => Some(replace),
+            _ => None,
+        })
+        .filter(|replace| {
+            replace
+                .value
+                .get("dooray_task_id")
+                .is_some_and(|id| !id.is_null())
+        })
+        .filter_map(|replace| {
+            replace
+                .path
+                .as_str()
+                .strip_prefix("/tasks/")
+                .and_then(|id| Uuid::parse_str(id).ok())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn workflow(id: &str, class: &str, order: i32) -> DoorayWorkflow {
+        DoorayWorkflow {
+            id: id.to_string(),
+            name: id.to_string(),
+            order: Some(order),
+            class: class.to_string(),
+        }
+    }
+
+    fn rule(workflow_id: &str, status: TaskStatus, push: bool) -> DoorayWorkflowMapping {
+        DoorayWorkflowMapping {
+            id: Uuid::new_v4(),
+            dooray_project_id: "p1".to_string(),
+            workflow_id: workflow_id.to_string(),
+            workflow_name: None,
+            task_status: status,
+            is_push_target: push,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn state(status: TaskStatus, workflow_id: &str) -> DoorayTaskSyncState {
+        DoorayTaskSyncState {
+            task_id: Uuid::new_v4(),
+            task_status: status,
+            workflow_id: Some(workflow_id.to_string()),
+            conflict_workflow_id: None,
+            synced_at: Utc::now(),
+        }
+    }
+
+    fn mapping() -> StatusMapping {
+        StatusMapping::new(
+            vec![
+                rule("review", TaskStatus::InReview, true),
+                rule("qa", TaskStatus::InReview, false),
+            ],
+            vec![
+                workflow("done", "closed", 4),
+                workflow("todo", "registered", 1),
+                workflow("doing", "working", 2),
+                workflow("review", "working", 3),
+                workflow("qa", "working", 5),
+            ],
+        )
+    }
+
+    #[test]
+    fn explicit_rules_override_class_defaults() {
+        let mapping = mapping();
+        assert_eq!(
+            mapping.status_for(Some("review"), Some("working")),
+            TaskStatus::InReview
+        );
+        assert_eq!(
+            mapping.status_for(Some("doing"), Some("working")),
+            TaskStatus::InProgress
+        );
+        assert_eq!(mapping.workflow_for(&TaskStatus::InReview), Some("review"));
+        assert_eq!(mapping.workflow_for(&TaskStatus::InProgress), Some("doing"));
+        assert_eq!(mapping.workflow_for(&TaskStatus::Done), Some("done"));
+    }
+
+    #[test]
+    fn reconcile_pushes_local_and_pulls_remote_changes() {
+        let mapping = mapping();
+        let synced = state(TaskStatus::InProgress, "doing");
+
+        assert_eq!(
+            reconcile(
+                &mapping,
+                &TaskStatus::InProgress,
+                Some("doing"),
+                None,
+                Some(&synced)
+            ),
+            StatusSyncAction::None
+        );
+        assert_eq!(
+            reconcile(
+                &mapping,
+                &TaskStatus::InReview,
+                Some("doing"),
+                None,
+                Some(&synced)
+            ),
+            StatusSyncAction::Push("review".to_string())
+        );
+        assert_eq!(
+            reconcile(
+                &mapping,
+                &TaskStatus::InProgress,
+                Some("done"),
+                Some("closed"),
+                Some(&synced)
+            ),
+            StatusSyncAction::Pull(TaskStatus::Done)
+        );
+    }
+
+    #[test]
+    fn reconcile_detects_conflicts() {
+        let mapping = mapping();
+        let synced = state(TaskStatus::InProgress, "doing");
+
+        assert_eq!(
+            reconcile(
+                &mapping,
+                &TaskStatus::InReview,
+                Some("todo"),
+                None,
+                Some(&synced)
+            ),
+            StatusSyncAction::Conflict
+        );
+        // Both sides moved to the same place
+        assert_eq!(
+            reconcile(
+                &mapping,
+                &TaskStatus::InReview,
+                Some("qa"),
+                None,
+                Some(&synced)
+            ),
+            StatusSyncAction::Baseline
+        );
+        // No baseline and disagreeing sides
+        assert_eq!(
+            reconcile(&mapping, &TaskStatus::Done, Some("doing"), None, None),
+            StatusSyncAction::Conflict
+        );
+    }
+
+    #[test]
+    fn baseline_without_workflow_pushes_local_changes() {
+        let mapping = mapping();
+        let synced = DoorayTaskSyncState {
+            workflow_id: None,
+            ..state(TaskStatus::Todo, "todo")
+        };
+
+        assert_eq!(
+            reconcile(
+                &mapping,
+                &TaskStatus::InProgress,
+                Some("todo"),
+                Some("registered"),
+                Some(&synced)
+            ),
+            StatusSyncAction::Push("doing".to_string())
+        );
+        assert_eq!(
+            reconcile(
+                &mapping,
+                &TaskStatus::Todo,
+                Some("done"),
+                Some("closed"),
+                Some(&synced)
+            ),
+            StatusSyncAction::Pull(TaskStatus::Done)
+        );
+        assert_eq!(
+            reconcile(
+                &mapping,
+                &TaskStatus::Todo,
+                Some("todo"),
+                Some("registered"),
+                Some(&synced)
+            ),
+            StatusSyncAction::None
+        );
+    }
+}
@@ -0,0 +1,646 @@
+//! Pulls posts of a bound Dooray project into kanban tasks, either on demand
+//! (`POST /dooray/sync`) or periodically from [`DooraySyncService`].
+//!
+//! Syncs remember the newest post `updatedAt` they saw as a watermark on the
+//! [`ProjectDoorayBinding`]. Incremental syncs only walk posts updated since then,
+//! and post bodies are only fetched for posts that changed.
+
+use std::{
+    collections::HashSet,
+    sync::{Arc, LazyLock},
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use db::{
+    DBService,
+    models::{
+        dooray_status_sync::DoorayTaskSyncState,
+        project_dooray_binding::ProjectDoorayBinding,
+        task::{CreateTask, Task, TaskStatus},
+    },
+};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use tokio::{sync::Mutex, time::interval};
+use tracing::{debug, error, info, warn};
+use ts_rs::TS;
+use utils::msg_store::MsgStore;
+use uuid::Uuid;
+
+use super::{
+    account::{DoorayAccount, DoorayAccountError},
+    attachments::{self, LocalizedBody},
+    client::DoorayClient,
+    hierarchy,
+    status_sync::{self, StatusMapping, StatusSyncError, StatusSyncOutcome},
+    types::{DoorayError, DoorayTask, DoorayTaskDetail, PostListQuery},
+};
+use crate::services::{events::dooray_sync_patch, image::ImageService};
+
+/// Environment variable with the scheduled sync interval in seconds (`0` disables it)
+pub const DOORAY_SYNC_INTERVAL_ENV: &str = "VK_DOORAY_SYNC_INTERVAL_SECS";
+const DEFAULT_SYNC_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Publish a progress update every this many posts
+const PROGRESS_EVERY: i32 = 10;
+
+/// Manual and scheduled syncs must not run concurrently or they would both
+/// create tasks for the same new posts
+static SYNC_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+#[derive(Debug, Error)]
+pub enum DooraySyncError {
+    #[error(transparent)]
+    Dooray(#[from] DoorayError),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Account(#[from] DoorayAccountError),
+}
+
+impl From<StatusSyncError> for DooraySyncError {
+    fn from(err: StatusSyncError) -> Self {
+        match err {
+            StatusSyncError::Dooray(e) => Self::Dooray(e),
+            StatusSyncError::Database(e) => Self::Database(e),
+            StatusSyncError::Account(e) => Self::Account(e),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+pub struct SyncResult {
+    pub created: i32,
+    pub updated: i32,
+    pub skipped: i32,
+    /// Tasks whose status changed on both sides since the last sync
+    pub conflicts: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum DooraySyncTrigger {
+    Manual,
+    Scheduled,
+    /// A webhook about a post that has no task yet
+    Webhook,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum DooraySyncPhase {
+    Running,
+    Completed,
+    Failed,
+}
+
+/// Progress of the current (or last) sync, published on the events stream at
+/// `/dooray_sync`
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct DooraySyncProgress {
+    pub binding_id: Uuid,
+    pub project_id: Uuid,
+    pub dooray_project_id: String,
+    pub trigger: DooraySyncTrigger,
+    pub phase: DooraySyncPhase,
+    /// Whether only posts changed since the previous sync were considered
+    pub incremental: bool,
+    pub processed: i32,
+    pub total: Option<i32>,
+    pub result: SyncResult,
+    pub error: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncMode {
+    /// Walk every open post
+    Full,
+    /// Only walk posts updated since the stored watermark (falls back to a full
+    /// sync when there is none)
+    Incremental,
+}
+
+/// Task a post was synced into; sub-post links are resolved once every post of
+/// the run has a task
+struct SyncedPost {
+    post_id: String,
+    parent_post_id: Option<String>,
+    task_id: Uuid,
+    parent_task_id: Option<Uuid>,
+    created: bool,
+}
+
+fn is_open_workflow_class(workflow_class: Option<&str>) -> bool {
+    matches!(
+        workflow_class,
+        None | Some("backlog") | Some("registered") | Some("working")
+    )
+}
+
+/// A single sync run of one kanban project / Dooray project binding
+pub struct DooraySync<'a> {
+    pool: &'a SqlitePool,
+    client: &'a DoorayClient,
+    trigger: DooraySyncTrigger,
+    events: Option<&'a MsgStore>,
+    images: Option<&'a ImageService>,
+}
+
+impl<'a> DooraySync<'a> {
+    pub fn new(pool: &'a SqlitePool, client: &'a DoorayClient, trigger: DooraySyncTrigger) -> Self {
+        Self {
+            pool,
+            client,
+            trigger,
+            events: None,
+            images: None,
+        }
+    }
+
+    /// Publish progress on the given events store
+    pub fn with_events(mut self, events: &'a MsgStore) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Copy post images into the given image store
+    pub fn with_images(mut self, images: &'a ImageService) -> Self {
+        self.images = Some(images);
+        self
+    }
+
+    pub async fn run(
+        &self,
+        binding: &ProjectDoorayBinding,
+        mode: SyncMode,
+    ) -> Result<SyncResult, DooraySyncError> {
+        let _guard = SYNC_LOCK.lock().await;
+
+        // Re-read the watermark under the lock; a concurrent sync may have moved
+        // it and changing the tag filter clears it
+        let watermark = ProjectDoorayBinding::find_by_id(self.pool, binding.id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?
+            .sync_watermark;
+        let since = match mode {
+            SyncMode::Incremental => watermark,
+            SyncMode::Full => None,
+        };
+
+        let mut progress = DooraySyncProgress {
+            binding_id: binding.id,
+            project_id: binding.project_id,
+            dooray_project_id: binding.dooray_project_id.clone(),
+            trigger: self.trigger,
+            phase: DooraySyncPhase::Running,
+            incremental: since.is_some(),
+            processed: 0,
+            total: None,
+            result: SyncResult::default(),
+            error: None,
+            started_at: Utc::now(),
+            finished_at: None,
+        };
+        self.publish(&progress);
+
+        let outcome = self.sync(binding, since, watermark, &mut progress).await;
+
+        progress.finished_at = Some(Utc::now());
+        match outcome {
+            Ok(()) => {
+                progress.phase = DooraySyncPhase::Completed;
+                self.publish(&progress);
+                Ok(progress.result)
+            }
+            Err(e) => {
+                progress.phase = DooraySyncPhase::Failed;
+                progress.error = Some(e.to_string());
+                self.publish(&progress);
+                Err(e)
+            }
+        }
+    }
+
+    async fn sync(
+        &self,
+        binding: &ProjectDoorayBinding,
+        since: Option<DateTime<Utc>>,
+        watermark: Option<DateTime<Utc>>,
+        progress: &mut DooraySyncProgress,
+    ) -> Result<(), DooraySyncError> {
+        let posts = match since {
+            Some(since) => self.changed_posts(binding, since).await?,
+            None => {
+                let query = PostListQuery::open().with_tag_ids(binding.tag_ids.0.clone());
+                self.client
+                    .list_all_posts(&binding.dooray_project_id, &query)
+                    .await?
+            }
+        };
+        let mapping =
+            StatusMapping::load(self.pool, self.client, &binding.dooray_project_id).await?;
+
+        progress.total = Some(posts.len() as i32);
+        self.publish(progress);
+
+        let mut new_watermark = watermark;
+        let mut seen_post_ids = HashSet::new();
+        let mut synced_posts = Vec::new();
+        for post in posts {
+            new_watermark = new_watermark.max(post.updated_at);
+            seen_post_ids.insert(post.id.clone());
+            if let Some(synced) = self
+                .sync_post(binding, &mapping, post, watermark, &mut progress.result)
+                .await?
+            {
+                synced_posts.push(synced);
+            }
+
+            progress.processed += 1;
+            if progress.processed % PROGRESS_EVERY == 0 {
+                self.publish(progress);
+            }
+        }
+
+        self.link_sub_posts(binding, &synced_posts, since.is_some())
+            .await?;
+
+        // A full sync only lists open posts; linked tasks whose post was closed
+        // still need their status pulled
+        if since.is_none() {
+            self.sync_unlisted_tasks(binding, &seen_post_ids, &mut progress.result)
+                .await?;
+        }
+
+        ProjectDoorayBinding::update_sync_watermark(self.pool, binding.id, new_watermark).await?;
+        Ok(())
+    }
+
+    /// Posts updated at or after `since`, newest first. Posts exactly at the
+    /// watermark are included again since several posts can share a timestamp.
+    async fn changed_posts(
+        &self,
+        binding: &ProjectDoorayBinding,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<DoorayTask>, DooraySyncError> {
+        let query = PostListQuery::default()
+            .with_tag_ids(binding.tag_ids.0.clone())
+            .newest_first();
+
+        let mut posts = Vec::new();
+        for page in 0..DoorayClient::MAX_PAGES {
+            let current = self
+                .client
+                .list_posts_page(
+                    &binding.dooray_project_id,
+                    &query,
+                    page,
+                    DoorayClient::PAGE_SIZE,
+                )
+                .await?;
+            let has_more = current.has_more();
+
+            let mut reached_watermark = false;
+            for post in current.items {
+                if post.updated_at.is_some_and(|updated_at| updated_at < since) {
+                    reached_watermark = true;
+                    break;
+                }
+                posts.push(post);
+            }
+            if reached_watermark || !has_more {
+                break;
+            }
+        }
+        Ok(posts)
+    }
+
+    async fn sync_post(
+        &self,
+        binding: &ProjectDoorayBinding,
+        mapping: &StatusMapping,
+        post: DoorayTask,
+        watermark: Option<DateTime<Utc>>,
+        result: &mut SyncResult,
+    ) -> Result<Option<SyncedPost>, DooraySyncError> {
+        let workflow_id = post.workflow.as_ref().map(|w| w.id.clone());
+        let post_id = post.id.clone();
+        let parent_post_id = post.parent.as_ref().map(|p| p.id.clone());
+
+        let Some(existing) = Task::find_by_dooray_task_id(self.pool, &post.id).await? else {
+            if !is_open_workflow_class(post.workflow_class.as_deref()) {
+                // Closed posts are never imported, only kept in sync once linked
+                result.skipped += 1;
+                return Ok(None);
+            }
+
+            let body = self
+                .fetch_detail(binding, &post.id)
+                .await?
+                .and_then(|detail| detail.body)
+                .and_then(|body| body.content);
+            let localized = self.localize_body(binding, &post.id, body).await;
+            let status = mapping.status_for(workflow_id.as_deref(), post.workflow_class.as_deref());
+            let create_data = CreateTask {
+                project_id: binding.project_id,
+                title: post.subject,
+                description: localized.description.clone(),
+                status: Some(status.clone()),
+                parent_workspace_id: None,
+                image_ids: None,
+                dooray_task_id: Some(post.id),
+                dooray_project_id: Some(binding.dooray_project_id.clone()),
+                dooray_task_number: Some(format!(
+                    "{}/{}",
+                    binding.dooray_project_code, post.number
+                )),
+                reference_dooray_url: None,
+                parent_task_id: None,
+            };
+
+            let task_id = Uuid::new_v4();
+            Task::create(self.pool, &create_data, task_id).await?;
+            localized.attach_to(self.pool, task_id).await?;
+            DoorayTaskSyncState::mark_synced(self.pool, task_id, &status, workflow_id.as_deref())
+                .await?;
+            result.created += 1;
+            return Ok(Some(SyncedPost {
+                post_id,
+                parent_post_id,
+                task_id,
+                parent_task_id: None,
+                created: true,
+            }));
+        };
+
+        // Only fetch the body of posts that changed since the last sync
+        let unchanged = matches!(
+            (post.updated_at, watermark),
+            (Some(updated_at), Some(watermark)) if updated_at < watermark
+        );
+        let mut task = existing;
+        let detail = if unchanged {
+            None
+        } else {
+            self.fetch_detail(binding, &post.id).await?
+        };
+
+        match detail {
+            Some(detail) => {
+                let body = detail.body.and_then(|b| b.content);
+                let localized = self.localize_body(binding, &post.id, body).await;
+                localized.attach_to(self.pool, task.id).await?;
+                let description = localized.description;
+                if task.title != post.subject || task.description != description {
+                    task = Task::update(
+                        self.pool,
+                        task.id,
+                        task.project_id,
+                        post.subject,
+                        description,
+                        task.status,
+                        task.parent_workspace_id,
+                    )
+                    .await?;
+                    result.updated += 1;
+                } else {
+                    result.skipped += 1;
+                }
+            }
+            None => result.skipped += 1,
+        }
+
+        let outcome = status_sync::sync_task_status(
+            self.pool,
+            self.client,
+            mapping,
+            &task,
+            workflow_id.as_deref(),
+            post.workflow_class.as_deref(),
+        )
+        .await?;
+        if outcome == StatusSyncOutcome::Conflict {
+            result.conflicts += 1;
+        }
+        Ok(Some(SyncedPost {
+            post_id,
+            parent_post_id,
+            task_id: task.id,
+            parent_task_id: task.parent_task_id,
+            created: false,
+        }))
+    }
+
+    /// Link the tasks of this run's posts to the tasks of their parent posts. An
+    /// incremental run doesn't see unchanged sub-posts, so new tasks also adopt
+    /// the sub-posts that were imported before them.
+    async fn link_sub_posts(
+        &self,
+        binding: &ProjectDoorayBinding,
+        synced_posts: &[SyncedPost],
+        incremental: bool,
+    ) -> Result<(), DooraySyncError> {
+        for synced in synced_posts {
+            let parent_task_id =
+                hierarchy::parent_task_id_for(self.pool, synced.parent_post_id.as_deref()).await?;
+            if parent_task_id != synced.parent_task_id {
+                Task::update_parent_task_id(self.pool, synced.task_id, parent_task_id).await?;
+            }
+
+            if incremental && synced.created {
+                hierarchy::adopt_sub_posts(
+                    self.pool,
+                    self.client,
+                    &binding.dooray_project_id,
+                    &synced.post_id,
+                    synced.task_id,
+                )
+                .await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn sync_unlisted_tasks(
+        &self,
+        binding: &ProjectDoorayBinding,
+        seen_post_ids: &HashSet<String>,
+        result: &mut SyncResult,
+    ) -> Result<(), DooraySyncError> {
+        let linked_tasks =
+            Task::find_by_project_id_with_attempt_status(self.pool, binding.project_id)
+                .await?
+                .into_iter()
+                .map(|t| t.task)
+                .filter(|t| {
+                    t.dooray_project_id.as_deref() == Some(binding.dooray_project_id.as_str())
+                        && t.dooray_task_id
+                            .as_ref()
+                            .is_some_and(|id| !seen_post_ids.contains(id))
+                        && !matches!(t.status, TaskStatus::Done | TaskStatus::Cancelled)
+                });
+
+        for task in linked_tasks {
+            match status_sync::sync_linked_task(self.pool, self.client, &task).await {
+                Ok(StatusSyncOutcome::Conflict) => result.conflicts += 1,
+                Ok(_) => {}
+                Err(StatusSyncError::Dooray(e)) if !e.should_retry() => {
+                    warn!("Failed to sync status of task {}: {}", task.id, e);
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(())
+    }
+
+    /// Post detail, or `None` if it cannot be fetched for a non-transient reason
+    async fn fetch_detail(
+        &self,
+        binding: &ProjectDoorayBinding,
+        post_id: &str,
+    ) -> Result<Option<DoorayTaskDetail>, DooraySyncError> {
+        match self
+            .client
+            .get_post(&binding.dooray_project_id, post_id)
+            .await
+        {
+            Ok(detail) => Ok(Some(detail)),
+            Err(e) if e.should_retry() => Err(e.into()),
+            Err(e) => {
+                warn!("Failed to fetch Dooray task detail {}: {}", post_id, e);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Store the post's images locally when an image store was given.
+    async fn localize_body(
+        &self,
+        binding: &ProjectDoorayBinding,
+        post_id: &str,
+        body: Option<String>,
+    ) -> LocalizedBody {
+        match self.images {
+            Some(images) => {
+                attachments::localize_post_images(
+                    self.pool,
+                    images,
+                    self.client,
+                    &binding.dooray_project_id,
+                    post_id,
+                    body,
+                )
+                .await
+            }
+            None => LocalizedBody {
+                description: body,
+                image_ids: Vec::new(),
+            },
+        }
+    }
+
+    fn publish(&self, progress: &DooraySyncProgress) {
+        if let Some(events) = self.events {
+            events.push_patch(dooray_sync_patch::replace(progress));
+        }
+    }
+}
+
+/// Periodically runs an incremental sync of every project binding.
+pub struct DooraySyncService {
+    db: DBService,
+    events: Arc<MsgStore>,
+    images: ImageService,
+    poll_interval: Duration,
+}
+
+impl DooraySyncService {
+    pub async fn spawn(
+        db: DBService,
+        events: Arc<MsgStore>,
+        images: ImageService,
+    ) -> Option<tokio::task::JoinHandle<()>> {
+        let poll_interval = match std::env::var(DOORAY_SYNC_INTERVAL_ENV)
+            .ok()
+            .and_then(|s| s.trim().parse::<u64>().ok())
+        {
+            Some(0) => {
+                info!(
+                    "Scheduled Dooray sync disabled via {}",
+                    DOORAY_SYNC_INTERVAL_ENV
+                );
+                return None;
+            }
+            Some(secs) => Duration::from_secs(secs),
+            None => DEFAULT_SYNC_INTERVAL,
+        };
+
+        let service = Self {
+            db,
+            events,
+            images,
+            poll_interval,
+        };
+        Some(tokio::spawn(async move {
+            service.start().await;
+        }))
+    }
+
+    async fn start(&self) {
+        info!(
+            "Starting Dooray sync service with interval {:?}",
+            self.poll_interval
+        );
+
+        let mut interval = interval(self.poll_interval);
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.sync_all_bindings().await {
+                error!("Error running scheduled Dooray sync: {}", e);
+            }
+        }
+    }
+
+    async fn sync_all_bindings(&self) -> Result<(), DooraySyncError> {
+        for binding in ProjectDoorayBinding::find_all(&self.db.pool).await? {
+            if let Err(e) = self.sync_binding(&binding).await {
+                error!(
+                    "Scheduled Dooray sync of {} into project {} failed: {}",
+                    binding.dooray_project_code, binding.project_id, e
+                );
+            }
+        }
+        Ok(())
+    }
+
+    async fn sync_binding(&self, binding: &ProjectDoorayBinding) -> Result<(), DooraySyncError> {
+        let pool = &self.db.pool;
+        let account = match DoorayAccount::for_binding(pool, binding).await {
+            Ok(account) => account,
+            Err(DoorayAccountError::NotConfigured) => {
+                debug!("Dooray integration not configured; skipping scheduled sync");
+                return Ok(());
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let result = DooraySync::new(pool, &account.client, DooraySyncTrigger::Scheduled)
+            .with_events(&self.events)
+            .with_images(&self.images)
+            .run(binding, SyncMode::Incremental)
+            .await?;
+
+        if result.created > 0 || result.updated > 0 || result.conflicts > 0 {
+            info!(
+                "Scheduled Dooray sync of {}: {} created, {} updated, {} conflicts",
+                binding.dooray_project_code, result.created, result.updated, result.conflicts
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,117 @@
+//! Checks a Dooray API token against `members/me` and the project API and
+//! explains what is wrong with it.
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::{client::DoorayClient, types::DoorayError};
+
+const REJECTED_MESSAGE: &str = "Dooray rejected the token. It may have expired or been revoked; create a new API token in Dooray.";
+const MISSING_PROJECT_SCOPE_MESSAGE: &str = "The token is valid but may not access Dooray projects. Create a token with project permissions.";
+const MISSING_MEMBER_SCOPE_MESSAGE: &str = "The token may not read its owner's member profile, so new Dooray tasks won't be assigned automatically.";
+
+/// What is wrong with a Dooray token, as far as the API lets us tell
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum DoorayTokenProblem {
+    /// Not usable as a header value (e.g. pasted with line breaks)
+    Malformed,
+    /// Dooray rejected the token: expired, revoked or mistyped
+    Rejected,
+    /// The token may not read its owner's member profile; assignee auto-set is disabled
+    MissingMemberScope,
+    /// The token may not use the project API
+    MissingProjectScope,
+    /// Dooray could not be reached or answered with a server error
+    Unreachable,
+    /// Dooray answered with an error we don't recognise
+    Unexpected,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct DoorayTokenValidation {
+    /// Whether the token can be used for the integration
+    pub valid: bool,
+    /// Token owner's Dooray organizationMemberId
+    pub member_id: Option<String>,
+    pub member_name: Option<String>,
+    pub problem: Option<DoorayTokenProblem>,
+    /// Explanation of `problem`
+    pub message: Option<String>,
+}
+
+impl DoorayTokenValidation {
+    fn failed(problem: DoorayTokenProblem, message: impl Into<String>) -> Self {
+        Self {
+            valid: false,
+            member_id: None,
+            member_name: None,
+            problem: Some(problem),
+            message: Some(message.into()),
+        }
+    }
+}
+
+/// Validate a raw token against the default API base URL.
+pub async fn validate_token(token: &str) -> DoorayTokenValidation {
+    match DoorayClient::new(token) {
+        Ok(client) => validate_client(&client).await,
+        Err(e) => DoorayTokenValidation::failed(
+            DoorayTokenProblem::Malformed,
+            format!("The token is malformed: {}", e),
+        ),
+    }
+}
+
+/// Validate the token a client was built with.
+pub async fn validate_client(client: &DoorayClient) -> DoorayTokenValidation {
+    let (member_id, member_name, member_problem) = match client.get_me().await {
+        Ok(me) => (Some(me.id), me.name, None),
+        Err(DoorayError::Forbidden) => (None, None, Some(DoorayTokenProblem::MissingMemberScope)),
+        Err(e) => return DoorayTokenValidation::failed(problem_for(&e), message_for(&e)),
+    };
+
+    if let Err(e) = client.list_my_projects_page(0, 1).await {
+        let problem = match &e {
+            DoorayError::Unauthorized | DoorayError::Forbidden => {
+                DoorayTokenProblem::MissingProjectScope
+            }
+            other => problem_for(other),
+        };
+        let message = match problem {
+            DoorayTokenProblem::MissingProjectScope => MISSING_PROJECT_SCOPE_MESSAGE.to_string(),
+            _ => message_for(&e),
+        };
+        return DoorayTokenValidation {
+            member_id,
+            member_name,
+            ..DoorayTokenValidation::failed(problem, message)
+        };
+    }
+
+    DoorayTokenValidation {
+        valid: true,
+        member_id,
+        member_name,
+        problem: member_problem,
+        message: member_problem.map(|_| MISSING_MEMBER_SCOPE_MESSAGE.to_string()),
+    }
+}
+
+fn problem_for(err: &DoorayError) -> DoorayTokenProblem {
+    match err {
+        DoorayError::InvalidToken(_) => DoorayTokenProblem::Malformed,
+        DoorayError::Unauthorized => DoorayTokenProblem::Rejected,
+        DoorayError::Forbidden => DoorayTokenProblem::MissingProjectScope,
+        e if e.should_retry() => DoorayTokenProblem::Unreachable,
+        _ => DoorayTokenProblem::Unexpected,
+    }
+}
+
+fn message_for(err: &DoorayError) -> String {
+    match err {
+        DoorayError::Unauthorized => REJECTED_MESSAGE.to_string(),
+        e if e.should_retry() => format!("Could not reach Dooray: {}", e),
+        e => format!("Dooray could not validate the token: {}", e),
+    }
+}
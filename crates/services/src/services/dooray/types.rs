@@ -0,0 +1,397 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use ts_rs::TS;
+
+#[derive(Debug, Error)]
+pub enum DoorayError {
+    #[error("invalid Dooray token: {0}")]
+    InvalidToken(String),
+    #[error("invalid Dooray API base URL: {0}")]
+    InvalidBaseUrl(String),
+    #[error("network error: {0}")]
+    Transport(String),
+    #[error("Dooray rejected the API token")]
+    Unauthorized,
+    #[error("Dooray API token is not allowed to access this resource")]
+    Forbidden,
+    #[error("Dooray resource not found")]
+    NotFound,
+    #[error("http {status}: {body}")]
+    Http { status: u16, body: String },
+    #[error("Dooray API error {code}: {message}")]
+    Api { code: i32, message: String },
+    #[error("failed to parse Dooray response: {0}")]
+    Serde(String),
+    #[error("Dooray response did not contain a result")]
+    EmptyResult,
+}
+
+impl DoorayError {
+    /// Returns true if the error is transient and the request may be retried.
+    pub fn should_retry(&self) -> bool {
+        match self {
+            Self::Transport(_) => true,
+            Self::Http { status, .. } => *status == 429 || (500..=599).contains(status),
+            _ => false,
+        }
+    }
+}
+
+/// Common `header` object returned with every Dooray API response.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DoorayApiHeader {
+    pub result_code: i32,
+    pub result_message: Option<String>,
+    pub is_successful: bool,
+}
+
+/// Envelope shared by all Dooray API responses.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DoorayEnvelope<T> {
+    pub header: DoorayApiHeader,
+    pub result: Option<T>,
+    pub total_count: Option<i64>,
+}
+
+/// A single page of a paginated Dooray listing.
+#[derive(Debug, Clone)]
+pub struct DoorayPage<T> {
+    pub items: Vec<T>,
+    pub page: u32,
+    pub size: u32,
+    pub total_count: Option<i64>,
+}
+
+impl<T> DoorayPage<T> {
+    /// Whether another page should be requested after this one.
+    pub fn has_more(&self) -> bool {
+        match self.total_count {
+            Some(total) => ((self.page as i64 + 1) * self.size as i64) < total,
+            None => self.items.len() as u32 >= self.size,
+        }
+    }
+}
+
+// ============== Projects ==============
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DoorayProjectSummary {
+    pub id: String,
+    pub code: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DoorayProjectDetail {
+    pub id: String,
+    pub code: Option<String>,
+    pub organization: Option<DoorayOrganizationRef>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DoorayOrganizationRef {
+    pub id: String,
+}
+
+// ============== Posts ==============
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct DoorayTask {
+    pub id: String,
+    pub number: i64,
+    pub subject: String,
+    #[serde(rename = "workflowClass")]
+    pub workflow_class: Option<String>,
+    #[serde(default)]
+    pub workflow: Option<DoorayWorkflowRef>,
+    pub body: Option<DoorayTaskBody>,
+    #[serde(rename = "updatedAt", default)]
+    pub updated_at: Option<DateTime<Utc>>,
+    /// Parent post, for sub-posts
+    #[serde(default)]
+    pub parent: Option<DoorayPostRef>,
+}
+
+/// Workflow reference embedded in posts
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct DoorayWorkflowRef {
+    pub id: String,
+    pub name: Option<String>,
+}
+
+/// Post reference embedded in posts (e.g. the parent of a sub-post)
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct DoorayPostRef {
+    pub id: String,
+    #[serde(default)]
+    pub number: Option<i64>,
+    #[serde(default)]
+    pub subject: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct DoorayTaskBody {
+    #[serde(rename = "mimeType")]
+    pub mime_type: Option<String>,
+    pub content: Option<String>,
+}
+
+impl DoorayTaskBody {
+    pub fn markdown(content: impl Into<String>) -> Self {
+        Self {
+            mime_type: Some("text/x-markdown".to_string()),
+            content: Some(content.into()),
+        }
+    }
+}
+
+/// Full post returned by `GET /project/v1/projects/{projectId}/posts/{postId}`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DoorayTaskDetail {
+    pub id: String,
+    pub subject: String,
+    #[serde(default)]
+    pub number: Option<i64>,
+    pub workflow_class: Option<String>,
+    #[serde(default)]
+    pub workflow: Option<DoorayWorkflowRef>,
+    pub body: Option<DoorayTaskBody>,
+    #[serde(default)]
+    pub project: Option<DoorayPostProjectRef>,
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub parent: Option<DoorayPostRef>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DoorayPostProjectRef {
+    pub id: Option<String>,
+    pub code: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DoorayCreatedPost {
+    pub id: String,
+    #[serde(default)]
+    pub number: Option<i64>,
+}
+
+/// Filters for `GET /project/v1/projects/{projectId}/posts`
+#[derive(Debug, Clone, Default)]
+pub struct PostListQuery {
+    pub workflow_classes: Vec<String>,
+    pub tag_ids: Vec<String>,
+    pub post_number: Option<i64>,
+    /// Keyword matched against post subjects
+    pub subject: Option<String>,
+    /// Only sub-posts of this post
+    pub parent_post_id: Option<String>,
+    /// Sort field, prefixed with `-` for descending (e.g. `-postUpdatedAt`)
+    pub order: Option<String>,
+}
+
+impl PostListQuery {
+    /// Posts that are not closed yet (backlog, registered, working)
+    pub fn open() -> Self {
+        Self {
+            workflow_classes: vec![
+                "backlog".to_string(),
+                "registered".to_string(),
+                "working".to_string(),
+            ],
+            ..Default::default()
+        }
+    }
+
+    pub fn with_tag_ids(mut self, tag_ids: Vec<String>) -> Self {
+        self.tag_ids = tag_ids;
+        self
+    }
+
+    pub fn with_subject(mut self, subject: impl Into<String>) -> Self {
+        self.subject = Some(subject.into());
+        self
+    }
+
+    /// Sub-posts of `parent_post_id`
+    pub fn sub_posts_of(parent_post_id: impl Into<String>) -> Self {
+        Self {
+            parent_post_id: Some(parent_post_id.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Most recently updated posts first
+    pub fn newest_first(mut self) -> Self {
+        self.order = Some("-postUpdatedAt".to_string());
+        self
+    }
+
+    pub(crate) fn to_params(&self) -> Vec<(&'static str, String)> {
+        let mut params = Vec::new();
+        if !self.workflow_classes.is_empty() {
+            params.push(("postWorkflowClasses", self.workflow_classes.join(",")));
+        }
+        if !self.tag_ids.is_empty() {
+            params.push(("tagIds", self.tag_ids.join(",")));
+        }
+        if let Some(number) = self.post_number {
+            params.push(("postNumber", number.to_string()));
+        }
+        if let Some(subject) = &self.subject {
+            params.push(("subjects", subject.clone()));
+        }
+        if let Some(parent_post_id) = &self.parent_post_id {
+            params.push(("parentPostId", parent_post_id.clone()));
+        }
+        if let Some(order) = &self.order {
+            params.push(("order", order.clone()));
+        }
+        params
+    }
+}
+
+/// Body for `POST /project/v1/projects/{projectId}/posts`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatePostRequest {
+    pub subject: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<DoorayTaskBody>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub users: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tag_ids: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_post_id: Option<String>,
+}
+
+impl CreatePostRequest {
+    pub fn new(subject: impl Into<String>) -> Self {
+        Self {
+            subject: subject.into(),
+            body: None,
+            users: None,
+            tag_ids: Vec::new(),
+            parent_post_id: None,
+        }
+    }
+
+    /// Assign the post to a single organization member
+    pub fn assign_to(mut self, organization_member_id: &str) -> Self {
+        self.users = Some(serde_json::json!({
+            "to": [{
+                "type": "member",
+                "member": {
+                    "organizationMemberId": organization_member_id
+                }
+            }]
+        }));
+        self
+    }
+}
+
+// ============== Workflows ==============
+
+/// A workflow (status column) of a Dooray project
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct DoorayWorkflow {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub order: Option<i32>,
+    /// One of `backlog`, `registered`, `working` or `closed`
+    pub class: String,
+}
+
+// ============== Comments (post logs) ==============
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DoorayPostLog {
+    pub id: String,
+    pub created_at: Option<String>,
+    pub body: Option<DoorayTaskBody>,
+    pub creator_member: Option<DoorayLogCreator>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DoorayLogCreator {
+    pub name: Option<String>,
+    pub organization_member_id: Option<String>,
+}
+
+// ============== Files ==============
+
+/// File attached to a post (including images pasted into the body)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DoorayPostFile {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub size: Option<i64>,
+    #[serde(default)]
+    pub mime_type: Option<String>,
+}
+
+// ============== Tags ==============
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DoorayTagItem {
+    pub id: String,
+    pub name: String,
+    pub tag_group: Option<DoorayTagGroupInfo>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DoorayTagGroupInfo {
+    pub id: String,
+    pub name: Option<String>,
+    pub mandatory: Option<bool>,
+    pub select_one: Option<bool>,
+}
+
+// ============== Templates ==============
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DoorayTemplate {
+    pub id: String,
+    #[serde(rename = "templateName")]
+    pub template_name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DoorayTemplateDetail {
+    pub id: String,
+    #[serde(rename = "templateName")]
+    pub template_name: String,
+    pub body: Option<DoorayTaskBody>,
+    pub guide: Option<DoorayTaskBody>,
+    pub subject: Option<String>,
+}
+
+// ============== Members ==============
+
+/// Response of `GET /common/v1/members/me` and `GET /common/v1/members/{memberId}`
+#[derive(Debug, Clone, Deserialize)]
+pub struct DoorayMemberDetail {
+    pub id: String,
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DoorayProjectMember {
+    pub organization_member_id: Option<String>,
+}
@@ -0,0 +1,307 @@
+//! Dooray project webhooks (`POST /dooray/webhook`), applied to the linked task
+//! as they arrive instead of waiting for the next scheduled sync.
+//!
+//! Dooray doesn't sign webhook requests, so the hook URL carries a shared
+//! secret (`VK_DOORAY_WEBHOOK_SECRET`) as a `token` query parameter or the
+//! `X-Dooray-Webhook-Secret` header. A change to a linked post is pulled into
+//! its task; a post without a task is picked up by an incremental sync of the
+//! bindings of its project. Task changes reach clients through the usual task
+//! patches; every handled hook is also published at `/dooray_webhook`.
+
+use chrono::{DateTime, Utc};
+use db::models::{project_dooray_binding::ProjectDoorayBinding, task::Task};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use utils::msg_store::MsgStore;
+use uuid::Uuid;
+
+use super::{
+    account::DoorayAccount,
+    attachments, hierarchy,
+    status_sync::{self, StatusMapping},
+    sync::{DooraySync, DooraySyncError, DooraySyncTrigger, SyncMode},
+};
+use crate::services::{events::dooray_webhook_patch, image::ImageService};
+
+/// Environment variable with the shared secret webhook requests must carry.
+/// The endpoint is disabled while it is unset.
+pub const WEBHOOK_SECRET_ENV: &str = "VK_DOORAY_WEBHOOK_SECRET";
+
+/// Header that may carry the shared secret instead of the `token` query parameter
+pub const WEBHOOK_SECRET_HEADER: &str = "x-dooray-webhook-secret";
+
+/// Configured webhook secret, if webhooks are enabled
+pub fn webhook_secret() -> Option<String> {
+    std::env::var(WEBHOOK_SECRET_ENV)
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// Compare the provided secret with the configured one in constant time.
+pub fn verify_secret(expected: &str, provided: Option<&str>) -> bool {
+    let Some(provided) = provided else {
+        return false;
+    };
+    let (expected, provided) = (expected.as_bytes(), provided.trim().as_bytes());
+    expected.len() == provided.len()
+        && expected
+            .iter()
+            .zip(provided)
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Project webhook request body. Only the fields needed to find the post are
+/// read; the post itself is fetched again from the API.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DoorayWebhookPayload {
+    pub hook_event_type: String,
+    #[serde(default)]
+    pub project: Option<DoorayWebhookProject>,
+    #[serde(default)]
+    pub post: Option<DoorayWebhookPost>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DoorayWebhookProject {
+    pub id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DoorayWebhookPost {
+    pub id: String,
+    #[serde(default)]
+    pub number: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum DoorayWebhookEvent {
+    PostCreated,
+    PostUpdated,
+    PostCommented,
+    WorkflowChanged,
+    /// Hooks that don't concern posts
+    Other,
+}
+
+impl DoorayWebhookEvent {
+    /// Classify a Dooray `hookEventType` (e.g. `postCreated`,
+    /// `postCommentCreated`, `postWorkflowChanged`, `postSubjectChanged`).
+    pub fn from_hook_event_type(event_type: &str) -> Self {
+        let event_type = event_type.to_ascii_lowercase();
+        if !event_type.starts_with("post") {
+            Self::Other
+        } else if event_type.contains("comment") {
+            Self::PostCommented
+        } else if event_type.contains("workflow") {
+            Self::WorkflowChanged
+        } else if event_type == "postcreated" {
+            Self::PostCreated
+        } else {
+            Self::PostUpdated
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum DoorayWebhookAction {
+    /// The linked task was refreshed from the post
+    Updated,
+    /// The post had no task; its project's bindings were synced
+    Synced,
+    /// A comment was added to a linked post; clients may reload its comments
+    Commented,
+    Ignored,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct DoorayWebhookResult {
+    pub event: DoorayWebhookEvent,
+    pub action: DoorayWebhookAction,
+    pub dooray_post_id: Option<String>,
+    pub task_id: Option<Uuid>,
+    pub received_at: DateTime<Utc>,
+}
+
+/// Apply a webhook to the task of its post and publish the result.
+pub async fn handle_webhook(
+    pool: &SqlitePool,
+    images: &ImageService,
+    events: &MsgStore,
+    payload: DoorayWebhookPayload,
+) -> Result<DoorayWebhookResult, DooraySyncError> {
+    let event = DoorayWebhookEvent::from_hook_event_type(&payload.hook_event_type);
+    let mut result = DoorayWebhookResult {
+        event,
+        action: DoorayWebhookAction::Ignored,
+        dooray_post_id: payload.post.as_ref().map(|p| p.id.clone()),
+        task_id: None,
+        received_at: Utc::now(),
+    };
+    let Some(post) = payload.post.filter(|_| event != DoorayWebhookEvent::Other) else {
+        return Ok(result);
+    };
+
+    match Task::find_by_dooray_task_id(pool, &post.id).await? {
+        Some(task) => {
+            result.task_id = Some(task.id);
+            result.action = if event == DoorayWebhookEvent::PostCommented {
+                DoorayWebhookAction::Commented
+            } else {
+                refresh_task(pool, images, &task).await?;
+                DoorayWebhookAction::Updated
+            };
+        }
+        None => {
+            let Some(project) = payload.project else {
+                return Ok(result);
+            };
+            if sync_bindings(pool, images, events, &project.id).await? {
+                result.action = DoorayWebhookAction::Synced;
+                result.task_id = Task::find_by_dooray_task_id(pool, &post.id)
+                    .await?
+                    .map(|task| task.id);
+            }
+        }
+    }
+
+    events.push_patch(dooray_webhook_patch::replace(&result));
+    Ok(result)
+}
+
+/// Pull title, body, parent and status of `task` from its post.
+async fn refresh_task(
+    pool: &SqlitePool,
+    images: &ImageService,
+    task: &Task,
+) -> Result<(), DooraySyncError> {
+    let (Some(dooray_project_id), Some(post_id)) = (
+        task.dooray_project_id.as_deref(),
+        task.dooray_task_id.as_deref(),
+    ) else {
+        return Ok(());
+    };
+    let account = DoorayAccount::for_task(pool, task).await?;
+    let client = &account.client;
+    let detail = client.get_post(dooray_project_id, post_id).await?;
+
+    let localized = attachments::localize_post_images(
+        pool,
+        images,
+        client,
+        dooray_project_id,
+        post_id,
+        detail.body.and_then(|b| b.content),
+    )
+    .await;
+    localized.attach_to(pool, task.id).await?;
+
+    let mut task = task.clone();
+    if task.title != detail.subject || task.description != localized.description {
+        task = Task::update(
+            pool,
+            task.id,
+            task.project_id,
+            detail.subject,
+            localized.description,
+            task.status,
+            task.parent_workspace_id,
+        )
+        .await?;
+    }
+
+    let parent_task_id =
+        hierarchy::parent_task_id_for(pool, detail.parent.as_ref().map(|p| p.id.as_str())).await?;
+    if parent_task_id != task.parent_task_id {
+        Task::update_parent_task_id(pool, task.id, parent_task_id).await?;
+    }
+
+    let mapping = StatusMapping::load(pool, client, dooray_project_id).await?;
+    let workflow_id = detail.workflow.as_ref().map(|w| w.id.as_str());
+    status_sync::sync_task_status(
+        pool,
+        client,
+        &mapping,
+        &task,
+        workflow_id,
+        detail.workflow_class.as_deref(),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Incrementally sync every binding of a Dooray project. Returns whether any
+/// binding exists.
+async fn sync_bindings(
+    pool: &SqlitePool,
+    images: &ImageService,
+    events: &MsgStore,
+    dooray_project_id: &str,
+) -> Result<bool, DooraySyncError> {
+    let bindings: Vec<_> = ProjectDoorayBinding::find_all(pool)
+        .await?
+        .into_iter()
+        .filter(|binding| binding.dooray_project_id == dooray_project_id)
+        .collect();
+
+    for binding in &bindings {
+        let account = DoorayAccount::for_binding(pool, binding).await?;
+        DooraySync::new(pool, &account.client, DooraySyncTrigger::Webhook)
+            .with_events(events)
+            .with_images(images)
+            .run(binding, SyncMode::Incremental)
+            .await?;
+    }
+    Ok(!bindings.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hook_event_types_are_classified() {
+        use DoorayWebhookEvent::*;
+
+        for (event_type, expected) in [
+            ("postCreated", PostCreated),
+            ("postCommentCreated", PostCommented),
+            ("postWorkflowChanged", WorkflowChanged),
+            ("postSubjectChanged", PostUpdated),
+            ("postBodyChanged", PostUpdated),
+            ("projectMemberAdded", Other),
+        ] {
+            assert_eq!(
+                DoorayWebhookEvent::from_hook_event_type(event_type),
+                expected,
+                "{event_type}"
+            );
+        }
+    }
+
+    #[test]
+    fn secret_must_match_exactly() {
+        assert!(verify_secret("s3cret", Some("s3cret")));
+        assert!(verify_secret("s3cret", Some(" s3cret\n")));
+        assert!(!verify_secret("s3cret", Some("s3cre")));
+        assert!(!verify_secret("s3cret", Some("s3creT")));
+        assert!(!verify_secret("s3cret", None));
+    }
+
+    #[test]
+    fn sample_payload_parses() {
+        let payload: DoorayWebhookPayload = serde_json::from_str(include_str!(
+            "../../../tests/fixtures/dooray_webhook/post_workflow_changed.json"
+        ))
+        .unwrap();
+
+        assert_eq!(payload.hook_event_type, "postWorkflowChanged");
+        assert_eq!(payload.project.unwrap().id, "1234567890123456789");
+        assert_eq!(payload.post.unwrap().number, Some(42));
+    }
+}
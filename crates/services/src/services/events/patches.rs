@@ -5,6 +5,11 @@ use db::models::{
 use json_patch::{AddOperation, Patch, PatchOperation, RemoveOperation, ReplaceOperation};
 use uuid::Uuid;
 
+use crate::services::{
+    dooray::{sync::DooraySyncProgress, webhook::DoorayWebhookResult},
+    queued_message::QueueStatus,
+};
+
 // Shared helper to escape JSON Pointer segments
 fn escape_pointer_segment(s: &str) -> String {
     s.replace('~', "~0").replace('/', "~1")
@@ -214,3 +219,58 @@ pub mod scratch_patch {
         })])
     }
 }
+
+/// Helper functions for creating Dooray sync progress patches.
+/// Only the latest progress is kept, at path "/dooray_sync".
+pub mod dooray_sync_patch {
+    use super::*;
+
+    const DOORAY_SYNC_PATH: &str = "/dooray_sync";
+
+    /// Create patch replacing the current sync progress
+    pub fn replace(progress: &DooraySyncProgress) -> Patch {
+        Patch(vec![PatchOperation::Replace(ReplaceOperation {
+            path: DOORAY_SYNC_PATH
+                .try_into()
+                .expect("Dooray sync path should be valid"),
+            value: serde_json::to_value(progress)
+                .expect("Dooray sync progress serialization should not fail"),
+        })])
+    }
+}
+
+/// Helper functions for creating Dooray webhook patches.
+/// Only the latest handled webhook is kept, at path "/dooray_webhook".
+pub mod dooray_webhook_patch {
+    use super::*;
+
+    const DOORAY_WEBHOOK_PATH: &str = "/dooray_webhook";
+
+    /// Create patch replacing the last handled webhook
+    pub fn replace(result: &DoorayWebhookResult) -> Patch {
+        Patch(vec![PatchOperation::Replace(ReplaceOperation {
+            path: DOORAY_WEBHOOK_PATH
+                .try_into()
+                .expect("Dooray webhook path should be valid"),
+            value: serde_json::to_value(result)
+                .expect("Dooray webhook result serialization should not fail"),
+        })])
+    }
+}
+
+/// Helper functions for creating follow-up queue patches.
+/// The queue of a session replaces the value at path "/queue".
+pub mod queue_patch {
+    use super::*;
+
+    pub const QUEUE_PATH: &str = "/queue";
+
+    /// Create patch replacing the queue of a session
+    pub fn replace(status: &QueueStatus) -> Patch {
+        Patch(vec![PatchOperation::Replace(ReplaceOperation {
+            path: QUEUE_PATH.try_into().expect("Queue path should be valid"),
+            value: serde_json::to_value(status)
+                .expect("Queue status serialization should not fail"),
+        })])
+    }
+}
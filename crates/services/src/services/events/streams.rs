@@ -1,6 +1,7 @@
 use db::models::{
     execution_process::ExecutionProcess,
     project::Project,
+    queued_message::QueuedMessage,
     scratch::Scratch,
     task::{Task, TaskWithAttemptStatus},
     workspace::Workspace,
@@ -13,9 +14,10 @@ use uuid::Uuid;
 
 use super::{
     EventService,
-    patches::execution_process_patch,
+    patches::{execution_process_patch, queue_patch},
     types::{EventError, EventPatch, RecordTypes},
 };
+use crate::services::queued_message::QueueStatus;
 
 impl EventService {
     /// Stream raw task messages for a specific project with initial snapshot
@@ -434,6 +436,47 @@ impl EventService {
         Ok(combined_stream)
     }
 
+    /// Stream the follow-up queue of a session with initial snapshot (raw LogMsg format for WebSocket)
+    pub async fn stream_queue_raw(
+        &self,
+        session_id: Uuid,
+    ) -> Result<futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>>, EventError>
+    {
+        let status = QueueStatus {
+            session_id,
+            messages: QueuedMessage::find_by_session_id(&self.db.pool, session_id).await?,
+        };
+        let initial_patch = queue_patch::replace(&status);
+        let initial_msg = LogMsg::JsonPatch(initial_patch);
+
+        // Filter to only this session's queue by matching session_id in the patch value
+        let filtered_stream =
+            BroadcastStream::new(self.msg_store.get_receiver()).filter_map(move |msg_result| {
+                let id_str = session_id.to_string();
+                async move {
+                    match msg_result {
+                        Ok(LogMsg::JsonPatch(patch)) => {
+                            if let Some(op) = patch.0.first()
+                                && op.path() == queue_patch::QUEUE_PATH
+                                && let json_patch::PatchOperation::Replace(replace) = op
+                                && replace.value.get("session_id").and_then(|v| v.as_str())
+                                    == Some(&id_str)
+                            {
+                                return Some(Ok(LogMsg::JsonPatch(patch)));
+                            }
+                            None
+                        }
+                        Ok(other) => Some(Ok(other)),
+                        Err(_) => None,
+                    }
+                }
+            });
+
+        let initial_stream = futures::stream::iter(vec![Ok(initial_msg), Ok(LogMsg::Ready)]);
+        let combined_stream = initial_stream.chain(filtered_stream).boxed();
+        Ok(combined_stream)
+    }
+
     pub async fn stream_workspaces_raw(
         &self,
         archived: Option<bool>,
@@ -0,0 +1,302 @@
+//! Recovery of failed coding agent runs according to the project's retry
+//! policy.
+//!
+//! A failed run is classified from its stderr and error entries. Transient
+//! failures (rate limits, overloaded or unreachable APIs) are retried with the
+//! same executor, waiting `backoff_seconds` doubled for every further retry.
+//! Failures that can't be retried, or still fail after `max_retries`, continue
+//! with the fallback profile in a new session, whose prompt carries what the
+//! failed session already did.
+
+use std::{fmt, time::Duration};
+
+use db::models::project_retry_policy::ProjectRetryPolicy;
+use executors::{
+    logs::{
+        NormalizedEntryError, NormalizedEntryType,
+        utils::patch::extract_normalized_entry_from_patch,
+    },
+    profile::ExecutorProfileId,
+};
+use utils::log_msg::LogMsg;
+
+/// Longest wait between two retries
+const MAX_BACKOFF: Duration = Duration::from_secs(60 * 60);
+
+const AUTH_MARKERS: &[&str] = &[
+    "auth required",
+    "authentication required",
+    "not logged in",
+    "please run /login",
+    "invalid api key",
+    "unauthorized",
+];
+
+const RATE_LIMIT_MARKERS: &[&str] = &[
+    "rate limit",
+    "rate_limit",
+    "too many requests",
+    "usage limit",
+    "quota exceeded",
+    " 429",
+];
+
+const TRANSIENT_MARKERS: &[&str] = &[
+    "overloaded",
+    "service unavailable",
+    "bad gateway",
+    "internal server error",
+    "econnreset",
+    "econnrefused",
+    "etimedout",
+    "connection reset",
+    "network error",
+    "socket hang up",
+    "timed out",
+    " 500",
+    " 502",
+    " 503",
+    " 529",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureKind {
+    /// The executor isn't logged in or its credentials were rejected
+    AuthRequired,
+    RateLimited,
+    /// Overloaded or unreachable API
+    Transient,
+    /// Any other non-zero exit
+    Other,
+}
+
+impl FailureKind {
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, FailureKind::RateLimited | FailureKind::Transient)
+    }
+}
+
+impl fmt::Display for FailureKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            FailureKind::AuthRequired => "authentication required",
+            FailureKind::RateLimited => "rate limited",
+            FailureKind::Transient => "transient API error",
+            FailureKind::Other => "non-zero exit",
+        })
+    }
+}
+
+/// Classify a failed run from the log history of its execution process.
+/// Only stderr and error entries are looked at, so an agent talking about
+/// rate limits in its answer doesn't count.
+pub fn classify_failure(history: &[LogMsg]) -> FailureKind {
+    let mut kind = FailureKind::Other;
+    for msg in history {
+        let text = match msg {
+            LogMsg::Stderr(text) => text.clone(),
+            LogMsg::JsonPatch(patch) => match extract_normalized_entry_from_patch(patch) {
+                Some((_, entry)) => match entry.entry_type {
+                    NormalizedEntryType::ErrorMessage {
+                        error_type: NormalizedEntryError::SetupRequired,
+                    } => return FailureKind::AuthRequired,
+                    NormalizedEntryType::ErrorMessage { .. } => entry.content,
+                    _ => continue,
+                },
+                None => continue,
+            },
+            _ => continue,
+        };
+        let text = text.to_lowercase();
+        let has = |markers: &[&str]| markers.iter().any(|m| text.contains(m));
+        if has(AUTH_MARKERS) {
+            return FailureKind::AuthRequired;
+        } else if has(RATE_LIMIT_MARKERS) {
+            kind = FailureKind::RateLimited;
+        } else if kind == FailureKind::Other && has(TRANSIENT_MARKERS) {
+            kind = FailureKind::Transient;
+        }
+    }
+    kind
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Recovery {
+    /// Run the failed action again after `delay`; `attempt` counts from 1
+    Retry { attempt: u32, delay: Duration },
+    /// Continue in a new session with this profile
+    Fallback(ExecutorProfileId),
+}
+
+/// What to do about a failed run of `current`, which has already been retried
+/// `retries` times. `None` leaves the run failed.
+pub fn plan_recovery(
+    policy: &ProjectRetryPolicy,
+    failure: FailureKind,
+    retries: u32,
+    current: &ExecutorProfileId,
+) -> Option<Recovery> {
+    if failure.is_retryable() && i64::from(retries) < policy.max_retries {
+        let backoff = Duration::from_secs(policy.backoff_seconds.max(0) as u64);
+        let delay = backoff
+            .checked_mul(2u32.saturating_pow(retries))
+            .unwrap_or(MAX_BACKOFF)
+            .min(MAX_BACKOFF);
+        return Some(Recovery::Retry {
+            attempt: retries + 1,
+            delay,
+        });
+    }
+
+    // Falling back once is enough; a failing fallback executor stays failed
+    policy
+        .fallback_executor_profile_id
+        .as_ref()
+        .filter(|fallback| fallback.executor != current.executor)
+        .map(|fallback| Recovery::Fallback(fallback.clone()))
+}
+
+/// Prompt of the fallback session: the prompt the failed session started with,
+/// what it did before it failed, and the follow-up the failed run was answering
+pub fn fallback_prompt(
+    initial_prompt: &str,
+    follow_up: Option<&str>,
+    failed: &ExecutorProfileId,
+    failure: FailureKind,
+    summaries: &[String],
+) -> String {
+    let mut prompt = format!(
+        "{initial_prompt}\n\n---\n\
+         This task was started by another coding agent ({}) whose run failed ({failure}). \
+         Its changes so far are already in the workspace; check the current state before \
+         continuing.",
+        failed.executor
+    );
+    if !summaries.is_empty() {
+        prompt.push_str("\n\nWhat the previous agent reported so far:");
+        for summary in summaries {
+            prompt.push_str("\n\n");
+            prompt.push_str(summary.trim());
+        }
+    }
+    if let Some(follow_up) = follow_up {
+        prompt.push_str("\n\nThe run that failed was working on this follow-up request:\n\n");
+        prompt.push_str(follow_up);
+    }
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use executors::executors::BaseCodingAgent;
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn policy(max_retries: i64, fallback: Option<BaseCodingAgent>) -> ProjectRetryPolicy {
+        ProjectRetryPolicy {
+            project_id: Uuid::nil(),
+            max_retries,
+            backoff_seconds: 30,
+            fallback_executor_profile_id: fallback.map(ExecutorProfileId::new),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn classifies_stderr() {
+        let stderr = |s: &str| LogMsg::Stderr(s.to_string());
+
+        assert_eq!(
+            classify_failure(&[stderr("API Error: 429 Too Many Requests")]),
+            FailureKind::RateLimited
+        );
+        assert_eq!(
+            classify_failure(&[stderr("Error: socket hang up"), stderr("rate limit hit")]),
+            FailureKind::RateLimited
+        );
+        assert_eq!(
+            classify_failure(&[stderr("API Error: 529 Overloaded")]),
+            FailureKind::Transient
+        );
+        assert_eq!(
+            classify_failure(&[stderr("Invalid API key · Please run /login")]),
+            FailureKind::AuthRequired
+        );
+        assert_eq!(
+            classify_failure(&[
+                LogMsg::Stdout("rate limit".to_string()),
+                stderr("error: tests failed")
+            ]),
+            FailureKind::Other
+        );
+    }
+
+    #[test]
+    fn retries_then_falls_back() {
+        let claude = ExecutorProfileId::new(BaseCodingAgent::ClaudeCode);
+        let with_fallback = policy(2, Some(BaseCodingAgent::Codex));
+
+        assert_eq!(
+            plan_recovery(&with_fallback, FailureKind::RateLimited, 0, &claude),
+            Some(Recovery::Retry {
+                attempt: 1,
+                delay: Duration::from_secs(30)
+            })
+        );
+        assert_eq!(
+            plan_recovery(&with_fallback, FailureKind::Transient, 1, &claude),
+            Some(Recovery::Retry {
+                attempt: 2,
+                delay: Duration::from_secs(60)
+            })
+        );
+        let fallback = Some(Recovery::Fallback(ExecutorProfileId::new(
+            BaseCodingAgent::Codex,
+        )));
+        assert_eq!(
+            plan_recovery(&with_fallback, FailureKind::RateLimited, 2, &claude),
+            fallback
+        );
+        assert_eq!(
+            plan_recovery(&with_fallback, FailureKind::AuthRequired, 0, &claude),
+            fallback
+        );
+        assert_eq!(
+            plan_recovery(
+                &with_fallback,
+                FailureKind::Other,
+                0,
+                &ExecutorProfileId::new(BaseCodingAgent::Codex)
+            ),
+            None
+        );
+        assert_eq!(
+            plan_recovery(&policy(0, None), FailureKind::Other, 0, &claude),
+            None
+        );
+    }
+
+    #[test]
+    fn fallback_prompt_keeps_the_task_and_the_failed_follow_up() {
+        let claude = ExecutorProfileId::new(BaseCodingAgent::ClaudeCode);
+        let prompt = fallback_prompt(
+            "Fix the login form",
+            Some("Also cover it with a test"),
+            &claude,
+            FailureKind::RateLimited,
+            &["Fixed the validation".to_string()],
+        );
+
+        assert!(prompt.starts_with("Fix the login form\n\n---\n"));
+        let summary = prompt.find("Fixed the validation").unwrap();
+        let follow_up = prompt.find("Also cover it with a test").unwrap();
+        assert!(summary < follow_up);
+        assert!(
+            !fallback_prompt("Fix the login form", None, &claude, FailureKind::Other, &[])
+                .contains("follow-up")
+        );
+    }
+}
@@ -0,0 +1,113 @@
+//! Compaction and retention of execution process logs.
+//!
+//! Lines are appended to `execution_process_logs` while a process runs. Once
+//! it finishes they move into zstd-compressed chunks, together with the
+//! normalized conversation of coding agent runs, so that the raw output can
+//! be pruned by [`LogRetentionConfig`] without losing the conversation.
+
+use std::{sync::Arc, time::Duration};
+
+use chrono::Utc;
+use db::{DBService, models::execution_process_logs::ExecutionProcessLogs};
+use executors::logs::{NormalizedEntry, utils::ConversationPatch};
+use sqlx::SqlitePool;
+use tokio::{sync::RwLock, time::interval};
+use utils::log_msg::LogMsg;
+use uuid::Uuid;
+
+use crate::services::config::{Config, LogRetentionConfig};
+
+/// Compact the logs of a finished process, storing `entries` as its
+/// conversation
+pub async fn compact_execution_logs(
+    pool: &SqlitePool,
+    execution_id: Uuid,
+    entries: Vec<NormalizedEntry>,
+) {
+    let conversation: Vec<String> = entries
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, entry)| {
+            let patch = ConversationPatch::add_normalized_entry(index, entry);
+            serde_json::to_string(&LogMsg::JsonPatch(patch))
+                .ok()
+                .map(|line| format!("{line}\n"))
+        })
+        .collect();
+
+    if let Err(e) = ExecutionProcessLogs::compact(pool, execution_id, &conversation).await {
+        tracing::error!(
+            "Failed to compact logs of execution {}: {}",
+            execution_id,
+            e
+        );
+    }
+}
+
+/// Compacts logs left behind by earlier runs of the app and prunes raw
+/// output as configured
+pub struct LogRetentionService {
+    db: DBService,
+    config: Arc<RwLock<Config>>,
+    poll_interval: Duration,
+}
+
+impl LogRetentionService {
+    pub async fn spawn(db: DBService, config: Arc<RwLock<Config>>) -> tokio::task::JoinHandle<()> {
+        let service = Self {
+            db,
+            config,
+            poll_interval: Duration::from_secs(60 * 60),
+        };
+        tokio::spawn(async move {
+            service.start().await;
+        })
+    }
+
+    async fn start(&self) {
+        let mut interval = interval(self.poll_interval);
+        loop {
+            interval.tick().await;
+            self.compact_leftovers().await;
+            let retention = self.config.read().await.log_retention.clone();
+            if let Err(e) = self.prune(&retention).await {
+                tracing::error!("Failed to prune execution logs: {}", e);
+            }
+        }
+    }
+
+    /// Logs of processes that finished while the app was stopped, or before
+    /// logs were compacted. Their conversation is not stored.
+    async fn compact_leftovers(&self) {
+        let pool = &self.db.pool;
+        let execution_ids = match ExecutionProcessLogs::find_uncompacted_finished(pool).await {
+            Ok(ids) => ids,
+            Err(e) => {
+                tracing::error!("Failed to find uncompacted execution logs: {}", e);
+                return;
+            }
+        };
+        for execution_id in execution_ids {
+            compact_execution_logs(pool, execution_id, Vec::new()).await;
+        }
+    }
+
+    async fn prune(&self, retention: &LogRetentionConfig) -> Result<(), sqlx::Error> {
+        let pool = &self.db.pool;
+        if let Some(days) = retention.raw_log_days {
+            let cutoff = Utc::now() - chrono::Duration::days(days.into());
+            let pruned = ExecutionProcessLogs::prune_raw_before(pool, cutoff).await?;
+            if pruned > 0 {
+                tracing::info!("Pruned {} raw log chunks older than {} days", pruned, days);
+            }
+        }
+        if let Some(max_mb) = retention.raw_log_max_mb {
+            let max_bytes = i64::from(max_mb) * 1024 * 1024;
+            let pruned = ExecutionProcessLogs::prune_raw_over(pool, max_bytes).await?;
+            if pruned > 0 {
+                tracing::info!("Pruned {} raw log chunks beyond {} MB", pruned, max_mb);
+            }
+        }
+        Ok(())
+    }
+}
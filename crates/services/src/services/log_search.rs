@@ -0,0 +1,192 @@
+//! Full-text search over the conversations of execution processes.
+//!
+//! The normalized entries of a process are indexed while its logs are
+//! persisted: messages, errors and the commands, paths and queries of tool
+//! calls. Searches go through the FTS5 trigram index of
+//! `execution_log_entries`.
+
+use std::collections::BTreeMap;
+
+use db::models::execution_log_entry::{ExecutionLogEntry, LogEntryKind};
+use executors::logs::{
+    ActionType, FileChange, NormalizedEntry, NormalizedEntryType,
+    utils::patch::extract_normalized_entry_from_patch,
+};
+use sqlx::SqlitePool;
+use utils::log_msg::LogMsg;
+use uuid::Uuid;
+
+/// Shortest term the trigram index can match
+const MIN_TERM_CHARS: usize = 3;
+
+/// Kind and searchable text of an entry, if it is worth indexing
+pub fn searchable_entry(entry: &NormalizedEntry) -> Option<(LogEntryKind, String)> {
+    let (kind, details) = match &entry.entry_type {
+        NormalizedEntryType::UserMessage => (LogEntryKind::UserMessage, vec![]),
+        NormalizedEntryType::AssistantMessage => (LogEntryKind::AssistantMessage, vec![]),
+        NormalizedEntryType::ErrorMessage { .. } => (LogEntryKind::ErrorMessage, vec![]),
+        NormalizedEntryType::ToolUse { action_type, .. } => {
+            (LogEntryKind::ToolUse, action_details(action_type))
+        }
+        _ => return None,
+    };
+
+    let mut parts: Vec<&str> = Vec::new();
+    for part in std::iter::once(entry.content.as_str()).chain(details) {
+        let part = part.trim();
+        if !part.is_empty() && !parts.contains(&part) {
+            parts.push(part);
+        }
+    }
+    (!parts.is_empty()).then(|| (kind, parts.join("\n")))
+}
+
+fn action_details(action: &ActionType) -> Vec<&str> {
+    match action {
+        ActionType::FileRead { path } => vec![path],
+        ActionType::FileEdit { path, changes } => std::iter::once(path.as_str())
+            .chain(changes.iter().filter_map(|change| match change {
+                FileChange::Rename { new_path } => Some(new_path.as_str()),
+                _ => None,
+            }))
+            .collect(),
+        ActionType::CommandRun { command, .. } => vec![command],
+        ActionType::Search { query } => vec![query],
+        ActionType::WebFetch { url } => vec![url],
+        ActionType::Tool { tool_name, .. } => vec![tool_name],
+        ActionType::TaskCreate { description, .. } => vec![description],
+        ActionType::PlanPresentation { plan } => vec![plan],
+        ActionType::Other { description } => vec![description],
+        ActionType::TodoManagement { .. } => vec![],
+    }
+}
+
+/// FTS5 query matching entries that contain every term of `query`. Terms
+/// are quoted so that FTS5 syntax is searched for literally; terms too short
+/// for the trigram index are dropped. `None` if no term is left.
+pub fn fts_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .filter(|term| term.chars().count() >= MIN_TERM_CHARS)
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+/// Indexes the normalized entries of one execution process as they stream.
+/// An entry is rewritten while the agent works on it, so it is written once
+/// a later entry shows up, and the rest when the process finishes.
+pub struct LogIndexer {
+    execution_id: Uuid,
+    pending: BTreeMap<usize, (LogEntryKind, String)>,
+}
+
+impl LogIndexer {
+    pub fn new(execution_id: Uuid) -> Self {
+        Self {
+            execution_id,
+            pending: BTreeMap::new(),
+        }
+    }
+
+    pub async fn push(&mut self, pool: &SqlitePool, msg: &LogMsg) {
+        let LogMsg::JsonPatch(patch) = msg else {
+            return;
+        };
+        let Some((index, entry)) = extract_normalized_entry_from_patch(patch) else {
+            return;
+        };
+        if let Some(searchable) = searchable_entry(&entry) {
+            self.pending.insert(index, searchable);
+        }
+
+        let settled = self.pending.split_off(&index);
+        let earlier = std::mem::replace(&mut self.pending, settled);
+        self.write(pool, earlier).await;
+    }
+
+    pub async fn flush(&mut self, pool: &SqlitePool) {
+        let pending = std::mem::take(&mut self.pending);
+        self.write(pool, pending).await;
+    }
+
+    async fn write(&self, pool: &SqlitePool, entries: BTreeMap<usize, (LogEntryKind, String)>) {
+        for (index, (kind, content)) in entries {
+            if let Err(e) =
+                ExecutionLogEntry::upsert(pool, self.execution_id, index as i64, kind, &content)
+                    .await
+            {
+                tracing::error!(
+                    "Failed to index log entry {} of execution {}: {}",
+                    index,
+                    self.execution_id,
+                    e
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::logs::ToolStatus;
+
+    use super::*;
+
+    #[test]
+    fn indexes_tool_calls_by_command_and_path() {
+        let entry = |entry_type, content: &str| NormalizedEntry {
+            timestamp: None,
+            entry_type,
+            content: content.to_string(),
+            metadata: None,
+        };
+        let command = entry(
+            NormalizedEntryType::ToolUse {
+                tool_name: "Bash".to_string(),
+                action_type: ActionType::CommandRun {
+                    command: "sqlx migrate run".to_string(),
+                    result: None,
+                },
+                status: ToolStatus::Success,
+            },
+            "Run migrations",
+        );
+        let edit = entry(
+            NormalizedEntryType::ToolUse {
+                tool_name: "Edit".to_string(),
+                action_type: ActionType::FileEdit {
+                    path: "src/db.rs".to_string(),
+                    changes: vec![],
+                },
+                status: ToolStatus::Success,
+            },
+            "src/db.rs",
+        );
+
+        assert_eq!(
+            searchable_entry(&command),
+            Some((
+                LogEntryKind::ToolUse,
+                "Run migrations\nsqlx migrate run".to_string()
+            ))
+        );
+        assert_eq!(
+            searchable_entry(&edit),
+            Some((LogEntryKind::ToolUse, "src/db.rs".to_string()))
+        );
+        assert_eq!(
+            searchable_entry(&entry(NormalizedEntryType::Thinking, "hmm")),
+            None
+        );
+    }
+
+    #[test]
+    fn quotes_search_terms() {
+        assert_eq!(
+            fts_query("add_column  \"users\" OR id"),
+            Some("\"add_column\" \"\"\"users\"\"\" \"OR\"".to_string())
+        );
+        assert_eq!(fts_query("a b"), None);
+    }
+}
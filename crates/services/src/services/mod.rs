@@ -1,16 +1,23 @@
 pub mod analytics;
 pub mod approvals;
+pub mod attempt_comparison;
 pub mod auth;
 pub mod config;
 pub mod container;
+pub mod design_plan;
+pub mod design_session;
 pub mod diff_stream;
+pub mod dooray;
 pub mod events;
+pub mod execution_retry;
 pub mod file_ranker;
 pub mod file_search;
 pub mod filesystem;
 pub mod filesystem_watcher;
 pub mod git_host;
 pub mod image;
+pub mod log_retention;
+pub mod log_search;
 pub mod migration;
 pub mod notification;
 pub mod oauth_credentials;
@@ -19,8 +26,11 @@ pub mod project;
 #[cfg(feature = "qa-mode")]
 pub mod qa_repos;
 pub mod queued_message;
+pub mod read_only_agent;
 pub mod remote_client;
 pub mod remote_sync;
 pub mod repo;
+pub mod token_cost;
+pub mod transcript;
 pub mod workspace_manager;
 pub mod worktree_manager;
@@ -19,6 +19,7 @@ use utils::api::pull_requests::{PullRequestStatus, UpsertPullRequestRequest};
 use crate::services::{
     analytics::AnalyticsContext,
     container::ContainerService,
+    dooray::report::{self, DoorayReportEvent, DoorayReportPr},
     git_host::{self, GitHostError, GitHostProvider},
     remote_client::RemoteClient,
     remote_sync,
@@ -134,6 +135,8 @@ impl<C: ContainerService + Send + Sync + 'static> PrMonitorService<C> {
                     pr_merge.pr_info.number, workspace.task_id
                 );
                 Task::update_status(&self.db.pool, workspace.task_id, TaskStatus::Done).await?;
+                // Report before archiving, which removes the worktree the diff stats come from
+                self.report_merge_to_dooray(pr_merge, &workspace).await;
                 if !workspace.pinned
                     && let Err(e) = self.container.archive_workspace(workspace.id).await
                 {
@@ -160,6 +163,37 @@ impl<C: ContainerService + Send + Sync + 'static> PrMonitorService<C> {
         Ok(())
     }
 
+    /// Comment on the linked Dooray post if the project reports merged PRs
+    async fn report_merge_to_dooray(&self, pr_merge: &PrMerge, workspace: &Workspace) {
+        let task = match Task::find_by_id(&self.db.pool, workspace.task_id).await {
+            Ok(Some(task)) => task,
+            Ok(None) => return,
+            Err(e) => {
+                error!("Failed to load task {}: {}", workspace.task_id, e);
+                return;
+            }
+        };
+        let pr = DoorayReportPr {
+            number: pr_merge.pr_info.number,
+            url: pr_merge.pr_info.url.clone(),
+        };
+        if let Err(e) = report::report(
+            &self.db.pool,
+            self.container.git(),
+            &task,
+            workspace,
+            DoorayReportEvent::PrMerged,
+            Some(&pr),
+        )
+        .await
+        {
+            error!(
+                "Failed to report PR #{} merge to Dooray for task {}: {}",
+                pr.number, task.id, e
+            );
+        }
+    }
+
     /// Sync PR status to remote server
     async fn sync_pr_to_remote(
         &self,
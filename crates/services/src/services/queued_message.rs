@@ -1,92 +1,175 @@
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 
-use chrono::{DateTime, Utc};
-use dashmap::DashMap;
-use db::models::scratch::DraftFollowUpData;
+use db::models::queued_message::QueuedMessage;
+use executors::profile::ExecutorProfileId;
 use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use thiserror::Error;
 use ts_rs::TS;
+use utils::msg_store::MsgStore;
 use uuid::Uuid;
 
-/// Represents a queued follow-up message for a session
+use crate::services::events::queue_patch;
+
+#[derive(Debug, Error)]
+pub enum QueuedMessageError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Queued message not found")]
+    NotFound,
+    #[error("The new order must list every queued message of the session once")]
+    InvalidOrder,
+}
+
+/// Follow-up queue of a session (for frontend display)
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export)]
-pub struct QueuedMessage {
-    /// The session this message is queued for
+pub struct QueueStatus {
     pub session_id: Uuid,
-    /// The follow-up data (message + variant)
-    pub data: DraftFollowUpData,
-    /// Timestamp when the message was queued
-    pub queued_at: DateTime<Utc>,
+    /// Queued messages, next one first
+    pub messages: Vec<QueuedMessage>,
 }
 
-/// Status of the queue for a session (for frontend display)
-#[derive(Debug, Clone, Serialize, Deserialize, TS)]
-#[serde(tag = "status", rename_all = "snake_case")]
-#[ts(export)]
-pub enum QueueStatus {
-    /// No message queued
-    Empty,
-    /// Message is queued and waiting for execution to complete
-    Queued { message: QueuedMessage },
+impl QueueStatus {
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
 }
 
-/// In-memory service for managing queued follow-up messages.
-/// One queued message per session.
+/// Profile to run a queued message with: its own profile (variant included) if
+/// it has one, else the session's latest profile, else the default profile of
+/// the session's executor
+pub fn queued_message_profile(
+    message: &QueuedMessage,
+    latest_profile: Option<&ExecutorProfileId>,
+    session_executor: Option<&str>,
+) -> Option<ExecutorProfileId> {
+    message
+        .executor_profile_id
+        .as_ref()
+        .or(latest_profile)
+        .cloned()
+        .or_else(|| {
+            session_executor
+                .and_then(|executor| executor.parse().ok())
+                .map(ExecutorProfileId::new)
+        })
+}
+
+/// Persisted follow-up queues, one per session. Every change is published on
+/// the events stream at `/queue`.
 #[derive(Clone)]
 pub struct QueuedMessageService {
-    queue: Arc<DashMap<Uuid, QueuedMessage>>,
+    pool: SqlitePool,
+    events: Arc<MsgStore>,
 }
 
 impl QueuedMessageService {
-    pub fn new() -> Self {
-        Self {
-            queue: Arc::new(DashMap::new()),
+    pub fn new(pool: SqlitePool, events: Arc<MsgStore>) -> Self {
+        Self { pool, events }
+    }
+
+    /// Add a message to the end of a session's queue
+    pub async fn queue_message(
+        &self,
+        session_id: Uuid,
+        message: &str,
+        executor_profile_id: Option<&ExecutorProfileId>,
+    ) -> Result<QueueStatus, QueuedMessageError> {
+        QueuedMessage::create(&self.pool, session_id, message, executor_profile_id).await?;
+        self.publish(session_id).await
+    }
+
+    pub async fn update_message(
+        &self,
+        session_id: Uuid,
+        id: Uuid,
+        message: &str,
+        executor_profile_id: Option<&ExecutorProfileId>,
+    ) -> Result<QueueStatus, QueuedMessageError> {
+        if !QueuedMessage::update(&self.pool, session_id, id, message, executor_profile_id).await? {
+            return Err(QueuedMessageError::NotFound);
         }
+        self.publish(session_id).await
     }
 
-    /// Queue a message for a session. Replaces any existing queued message.
-    pub fn queue_message(&self, session_id: Uuid, data: DraftFollowUpData) -> QueuedMessage {
-        let queued = QueuedMessage {
-            session_id,
-            data,
-            queued_at: Utc::now(),
-        };
-        self.queue.insert(session_id, queued.clone());
-        queued
+    pub async fn remove_message(
+        &self,
+        session_id: Uuid,
+        id: Uuid,
+    ) -> Result<QueueStatus, QueuedMessageError> {
+        if !QueuedMessage::delete(&self.pool, session_id, id).await? {
+            return Err(QueuedMessageError::NotFound);
+        }
+        self.publish(session_id).await
     }
 
-    /// Cancel/remove a queued message for a session
-    pub fn cancel_queued(&self, session_id: Uuid) -> Option<QueuedMessage> {
-        self.queue.remove(&session_id).map(|(_, v)| v)
+    /// Put a session's queue in the order of `ids`
+    pub async fn reorder(
+        &self,
+        session_id: Uuid,
+        ids: &[Uuid],
+    ) -> Result<QueueStatus, QueuedMessageError> {
+        let queued: HashSet<Uuid> = QueuedMessage::find_by_session_id(&self.pool, session_id)
+            .await?
+            .into_iter()
+            .map(|message| message.id)
+            .collect();
+        let requested: HashSet<Uuid> = ids.iter().copied().collect();
+        if requested.len() != ids.len() || requested != queued {
+            return Err(QueuedMessageError::InvalidOrder);
+        }
+
+        QueuedMessage::reorder(&self.pool, session_id, ids).await?;
+        self.publish(session_id).await
     }
 
-    /// Get the queued message for a session (if any)
-    pub fn get_queued(&self, session_id: Uuid) -> Option<QueuedMessage> {
-        self.queue.get(&session_id).map(|r| r.clone())
+    /// Remove every queued message of a session
+    pub async fn clear(&self, session_id: Uuid) -> Result<QueueStatus, QueuedMessageError> {
+        QueuedMessage::delete_by_session_id(&self.pool, session_id).await?;
+        self.publish(session_id).await
     }
 
-    /// Take (remove and return) the queued message for a session.
-    /// Used by finalization flow to consume the queued message.
-    pub fn take_queued(&self, session_id: Uuid) -> Option<QueuedMessage> {
-        self.queue.remove(&session_id).map(|(_, v)| v)
+    /// Take (remove and return) the next queued message of a session.
+    /// Used by the next-action flow to consume the queue one message at a time.
+    pub async fn take_next(
+        &self,
+        session_id: Uuid,
+    ) -> Result<Option<QueuedMessage>, QueuedMessageError> {
+        let next = QueuedMessage::take_next(&self.pool, session_id).await?;
+        if next.is_some() {
+            self.publish(session_id).await?;
+        }
+        Ok(next)
     }
 
-    /// Check if a session has a queued message
-    pub fn has_queued(&self, session_id: Uuid) -> bool {
-        self.queue.contains_key(&session_id)
+    /// Put a message back at the front of its session's queue, e.g. when it
+    /// was taken but could not be started
+    pub async fn requeue(
+        &self,
+        message: &QueuedMessage,
+    ) -> Result<QueueStatus, QueuedMessageError> {
+        QueuedMessage::requeue(&self.pool, message).await?;
+        self.publish(message.session_id).await
+    }
+
+    /// Check if a session has queued messages
+    pub async fn has_queued(&self, session_id: Uuid) -> Result<bool, QueuedMessageError> {
+        Ok(QueuedMessage::exists_for_session(&self.pool, session_id).await?)
     }
 
     /// Get queue status for frontend display
-    pub fn get_status(&self, session_id: Uuid) -> QueueStatus {
-        match self.get_queued(session_id) {
-            Some(msg) => QueueStatus::Queued { message: msg },
-            None => QueueStatus::Empty,
-        }
+    pub async fn get_status(&self, session_id: Uuid) -> Result<QueueStatus, QueuedMessageError> {
+        let messages = QueuedMessage::find_by_session_id(&self.pool, session_id).await?;
+        Ok(QueueStatus {
+            session_id,
+            messages,
+        })
     }
-}
 
-impl Default for QueuedMessageService {
-    fn default() -> Self {
-        Self::new()
+    async fn publish(&self, session_id: Uuid) -> Result<QueueStatus, QueuedMessageError> {
+        let status = self.get_status(session_id).await?;
+        self.events.push_patch(queue_patch::replace(&status));
+        Ok(status)
     }
 }
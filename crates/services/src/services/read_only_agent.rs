@@ -0,0 +1,242 @@
+//! One-off runs of a coding agent outside any workspace, restricted to reading
+//! the directory it runs in. Executors without a read-only mode are refused,
+//! since they would run with the profile's full permissions.
+//!
+//! Design chat turns and the attempt reviewer both run an agent this way: its
+//! output is normalized into a [`MsgStore`] like a workspace execution, and it
+//! is stopped if it runs longer than [`RUN_TIMEOUT`].
+
+use std::{collections::BTreeMap, path::Path, sync::Arc, time::Duration};
+
+use executors::{
+    env::ExecutionEnv,
+    executors::{
+        BaseCodingAgent, CodingAgent, ExecutorError, SpawnedChild, StandardCodingAgentExecutor,
+    },
+    logs::{
+        NormalizedEntry, NormalizedEntryType, utils::patch::extract_normalized_entry_from_patch,
+    },
+    profile::{ExecutorConfigs, ExecutorProfileId},
+};
+use futures::{FutureExt, StreamExt, TryStreamExt, future, stream::select};
+use thiserror::Error;
+use tokio::task::JoinHandle;
+use tokio_util::io::ReaderStream;
+use utils::{log_msg::LogMsg, msg_store::MsgStore, process::kill_process_group};
+
+const SPAWN_TIMEOUT: Duration = Duration::from_secs(30);
+/// Longest a single run may take before the agent is stopped
+const RUN_TIMEOUT: Duration = Duration::from_secs(20 * 60);
+const NORMALIZE_GRACE: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Error)]
+pub enum ReadOnlyAgentError {
+    #[error(transparent)]
+    Executor(#[from] ExecutorError),
+    #[error("Executor profile {0} is not configured")]
+    UnknownProfile(ExecutorProfileId),
+    #[error("{0} has no read-only mode, so it can't be used for this")]
+    NoReadOnlyMode(BaseCodingAgent),
+    #[error("{0} took more than 30 seconds to start")]
+    SpawnTimeout(BaseCodingAgent),
+}
+
+/// The read-only configuration of `profile`'s agent
+pub fn read_only_agent(profile: &ExecutorProfileId) -> Result<CodingAgent, ReadOnlyAgentError> {
+    ExecutorConfigs::get_cached()
+        .get_coding_agent(profile)
+        .ok_or_else(|| ReadOnlyAgentError::UnknownProfile(profile.clone()))?
+        .read_only()
+        .ok_or(ReadOnlyAgentError::NoReadOnlyMode(profile.executor))
+}
+
+/// A running agent whose normalized output is pushed to `store`
+pub struct ReadOnlyAgentRun {
+    pub store: Arc<MsgStore>,
+    profile: ExecutorProfileId,
+    spawned: SpawnedChild,
+    forwarder: JoinHandle<()>,
+}
+
+impl ReadOnlyAgentRun {
+    /// Spawn the agent of `profile` in `working_dir`, continuing the agent
+    /// session `resume_id` if set
+    pub async fn start(
+        profile: &ExecutorProfileId,
+        working_dir: &Path,
+        prompt: &str,
+        resume_id: Option<&str>,
+        env: &ExecutionEnv,
+    ) -> Result<Self, ReadOnlyAgentError> {
+        let agent = read_only_agent(profile)?;
+
+        let spawn = async {
+            match resume_id {
+                Some(id) => {
+                    agent
+                        .spawn_follow_up(working_dir, prompt, id, None, env)
+                        .await
+                }
+                None => agent.spawn(working_dir, prompt, env).await,
+            }
+        };
+        let mut spawned = tokio::time::timeout(SPAWN_TIMEOUT, spawn)
+            .await
+            .map_err(|_| ReadOnlyAgentError::SpawnTimeout(profile.executor))??;
+
+        let store = Arc::new(MsgStore::new());
+        let forwarder = forward_output(&store, &mut spawned)?;
+        agent.normalize_logs(store.clone(), working_dir);
+
+        Ok(Self {
+            store,
+            profile: profile.clone(),
+            spawned,
+            forwarder,
+        })
+    }
+
+    /// Wait for the agent to exit, stopping it after [`RUN_TIMEOUT`], and let
+    /// the normalizer catch up with its last output. The store is left for the
+    /// caller to finish.
+    pub async fn wait(self) -> Arc<MsgStore> {
+        let SpawnedChild {
+            mut child,
+            exit_signal,
+            cancel,
+        } = self.spawned;
+        let exit_signal = exit_signal
+            .map(|rx| rx.map(|_| ()).boxed())
+            .unwrap_or_else(|| std::future::pending().boxed());
+
+        let exited = tokio::select! {
+            _ = child.wait() => true,
+            _ = exit_signal => false,
+            _ = tokio::time::sleep(RUN_TIMEOUT) => {
+                tracing::warn!("{} timed out", self.profile);
+                self.store.push_stderr(format!(
+                    "The agent timed out after {} minutes",
+                    RUN_TIMEOUT.as_secs() / 60
+                ));
+                false
+            }
+        };
+        if !exited {
+            if let Some(cancel) = cancel {
+                cancel.cancel();
+            }
+            if let Err(e) = kill_process_group(&mut child).await {
+                tracing::warn!("Failed to stop {}: {}", self.profile, e);
+            }
+        }
+        let _ = tokio::time::timeout(Duration::from_secs(5), self.forwarder).await;
+        tokio::time::sleep(NORMALIZE_GRACE).await;
+        self.store
+    }
+}
+
+fn forward_output(
+    store: &Arc<MsgStore>,
+    spawned: &mut SpawnedChild,
+) -> Result<JoinHandle<()>, ExecutorError> {
+    let missing =
+        |stream: &str| ExecutorError::Io(std::io::Error::other(format!("Missing {stream}")));
+    let out = spawned
+        .child
+        .inner()
+        .stdout
+        .take()
+        .ok_or_else(|| missing("stdout"))?;
+    let err = spawned
+        .child
+        .inner()
+        .stderr
+        .take()
+        .ok_or_else(|| missing("stderr"))?;
+
+    let out = ReaderStream::new(out)
+        .map_ok(|chunk| LogMsg::Stdout(String::from_utf8_lossy(&chunk).into_owned()));
+    let err = ReaderStream::new(err)
+        .map_ok(|chunk| LogMsg::Stderr(String::from_utf8_lossy(&chunk).into_owned()));
+    Ok(store.clone().spawn_forwarder(select(out, err)))
+}
+
+/// Wait until the run pushing to `store` has finished
+pub async fn wait_for_finish(store: &MsgStore) {
+    store
+        .history_plus_stream()
+        .any(|msg| future::ready(matches!(msg, Ok(LogMsg::Finished))))
+        .await;
+}
+
+/// Latest version of every normalized entry pushed to `store`, by index
+pub fn normalized_entries(store: &MsgStore) -> BTreeMap<usize, NormalizedEntry> {
+    store
+        .get_history()
+        .iter()
+        .filter_map(|msg| match msg {
+            LogMsg::JsonPatch(patch) => extract_normalized_entry_from_patch(patch),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Assistant messages of a finished run, joined into one reply
+pub fn assistant_reply(store: &MsgStore) -> String {
+    normalized_entries(store)
+        .into_values()
+        .filter(|entry| matches!(entry.entry_type, NormalizedEntryType::AssistantMessage))
+        .map(|entry| entry.content.trim().to_string())
+        .filter(|content| !content.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::logs::utils::patch::ConversationPatch;
+
+    use super::*;
+
+    fn entry(entry_type: NormalizedEntryType, content: &str) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type,
+            content: content.to_string(),
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn reply_joins_latest_assistant_messages() {
+        let store = MsgStore::new();
+        store.push_patch(ConversationPatch::add_normalized_entry(
+            0,
+            entry(NormalizedEntryType::UserMessage, "설계 부탁"),
+        ));
+        store.push_patch(ConversationPatch::add_normalized_entry(
+            1,
+            entry(NormalizedEntryType::AssistantMessage, "Let me"),
+        ));
+        store.push_patch(ConversationPatch::replace(
+            1,
+            entry(NormalizedEntryType::AssistantMessage, "Let me look."),
+        ));
+        store.push_patch(ConversationPatch::add_normalized_entry(
+            2,
+            entry(NormalizedEntryType::Thinking, "hmm"),
+        ));
+        store.push_patch(ConversationPatch::add_normalized_entry(
+            3,
+            entry(
+                NormalizedEntryType::AssistantMessage,
+                "Plan:\n1. Split the parser\n",
+            ),
+        ));
+
+        assert_eq!(
+            assistant_reply(&store),
+            "Let me look.\n\nPlan:\n1. Split the parser"
+        );
+    }
+}
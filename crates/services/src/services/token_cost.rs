@@ -0,0 +1,174 @@
+//! Spend of coding agent runs per task and project.
+//!
+//! The billed tokens each run reported are summed per task and model and
+//! priced with the model price table. Models without a matching price are
+//! still listed with their tokens, but don't add to the cost.
+
+use std::collections::HashMap;
+
+use db::models::{
+    execution_process_token_usage::{ExecutionProcessTokenUsage, TaskModelTokenUsage},
+    model_price::ModelPrice,
+    task::Task,
+};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use uuid::Uuid;
+
+const TOKENS_PER_PRICE_UNIT: f64 = 1_000_000.0;
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ModelCost {
+    /// Unset for runs whose executor didn't name the model
+    pub model: Option<String>,
+    pub runs: i64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cache_read_tokens: i64,
+    pub cache_write_tokens: i64,
+    /// Unset when no price matches the model
+    pub cost_usd: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct TaskCost {
+    pub task_id: Uuid,
+    pub title: String,
+    pub dooray_task_id: Option<String>,
+    pub dooray_task_number: Option<String>,
+    pub models: Vec<ModelCost>,
+    /// Sum of the priced models
+    pub cost_usd: f64,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ProjectCost {
+    pub project_id: Uuid,
+    /// Tasks with recorded usage, most expensive first
+    pub tasks: Vec<TaskCost>,
+    pub cost_usd: f64,
+}
+
+/// Cost of `usage` in USD, if a price matches its model
+pub fn model_cost(prices: &[ModelPrice], usage: &TaskModelTokenUsage) -> ModelCost {
+    let cost_usd = usage
+        .model
+        .as_deref()
+        .and_then(|model| ModelPrice::find_for_model(prices, model))
+        .map(|price| {
+            (usage.input_tokens as f64 * price.input_price
+                + usage.output_tokens as f64 * price.output_price
+                + usage.cache_read_tokens as f64 * price.cache_read_price
+                + usage.cache_write_tokens as f64 * price.cache_write_price)
+                / TOKENS_PER_PRICE_UNIT
+        });
+
+    ModelCost {
+        model: usage.model.clone(),
+        runs: usage.runs,
+        input_tokens: usage.input_tokens,
+        output_tokens: usage.output_tokens,
+        cache_read_tokens: usage.cache_read_tokens,
+        cache_write_tokens: usage.cache_write_tokens,
+        cost_usd,
+    }
+}
+
+fn task_cost(task: &Task, models: Vec<ModelCost>) -> TaskCost {
+    TaskCost {
+        task_id: task.id,
+        title: task.title.clone(),
+        dooray_task_id: task.dooray_task_id.clone(),
+        dooray_task_number: task.dooray_task_number.clone(),
+        cost_usd: models.iter().filter_map(|model| model.cost_usd).sum(),
+        models,
+    }
+}
+
+pub async fn cost_of_task(pool: &SqlitePool, task: &Task) -> Result<TaskCost, sqlx::Error> {
+    let prices = ModelPrice::find_all(pool).await?;
+    let models = ExecutionProcessTokenUsage::sum_by_task_id(pool, task.id)
+        .await?
+        .iter()
+        .map(|usage| model_cost(&prices, usage))
+        .collect();
+    Ok(task_cost(task, models))
+}
+
+pub async fn cost_of_project(
+    pool: &SqlitePool,
+    project_id: Uuid,
+) -> Result<ProjectCost, sqlx::Error> {
+    let prices = ModelPrice::find_all(pool).await?;
+
+    let mut models_by_task: HashMap<Uuid, Vec<ModelCost>> = HashMap::new();
+    for usage in ExecutionProcessTokenUsage::sum_by_project_id(pool, project_id).await? {
+        models_by_task
+            .entry(usage.task_id)
+            .or_default()
+            .push(model_cost(&prices, &usage));
+    }
+
+    let mut tasks = Vec::with_capacity(models_by_task.len());
+    for (task_id, models) in models_by_task {
+        if let Some(task) = Task::find_by_id(pool, task_id).await? {
+            tasks.push(task_cost(&task, models));
+        }
+    }
+    tasks.sort_by(|a, b| b.cost_usd.total_cmp(&a.cost_usd));
+
+    Ok(ProjectCost {
+        project_id,
+        cost_usd: tasks.iter().map(|task| task.cost_usd).sum(),
+        tasks,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn price(model_pattern: &str, input_price: f64, output_price: f64) -> ModelPrice {
+        ModelPrice {
+            model_pattern: model_pattern.to_string(),
+            input_price,
+            output_price,
+            cache_read_price: input_price / 10.0,
+            cache_write_price: input_price * 1.25,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn usage(model: Option<&str>) -> TaskModelTokenUsage {
+        TaskModelTokenUsage {
+            task_id: Uuid::nil(),
+            model: model.map(str::to_string),
+            runs: 2,
+            input_tokens: 200_000,
+            output_tokens: 100_000,
+            cache_read_tokens: 1_000_000,
+            cache_write_tokens: 0,
+        }
+    }
+
+    #[test]
+    fn prices_by_longest_matching_pattern() {
+        let prices = [
+            price("claude-opus-4", 15.0, 75.0),
+            price("claude-opus-4-5", 5.0, 25.0),
+        ];
+
+        let opus_4_5 = model_cost(&prices, &usage(Some("claude-opus-4-5-20251101")));
+        assert_eq!(opus_4_5.cost_usd, Some(1.0 + 2.5 + 0.5));
+
+        let opus_4_1 = model_cost(&prices, &usage(Some("claude-opus-4-1-20250805")));
+        assert_eq!(opus_4_1.cost_usd, Some(3.0 + 7.5 + 1.5));
+
+        assert_eq!(model_cost(&prices, &usage(Some("gpt-5"))).cost_usd, None);
+        assert_eq!(model_cost(&prices, &usage(None)).cost_usd, None);
+    }
+}
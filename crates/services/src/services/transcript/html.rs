@@ -0,0 +1,172 @@
+use std::fmt::Write;
+
+use super::{Transcript, TranscriptEntry, TranscriptEntryKind, TranscriptToolCall};
+
+const STYLE: &str = r#"
+body { font: 14px/1.5 system-ui, sans-serif; max-width: 960px; margin: 2rem auto; padding: 0 1rem; color: #1f2328; }
+h1 { font-size: 1.5rem; }
+h2 { font-size: 1.15rem; margin-top: 2rem; border-bottom: 1px solid #d0d7de; padding-bottom: .25rem; }
+dl.meta { display: grid; grid-template-columns: max-content 1fr; gap: .1rem 1rem; color: #59636e; }
+dl.meta dd { margin: 0; }
+.entry { margin: .75rem 0; }
+.label { font-weight: 600; font-size: .8rem; text-transform: uppercase; color: #59636e; }
+.text { white-space: pre-wrap; word-break: break-word; }
+.error .text { color: #cf222e; }
+details { border: 1px solid #d0d7de; border-radius: 6px; padding: .25rem .75rem; }
+details.thinking { color: #59636e; }
+summary { cursor: pointer; }
+.status { font-size: .75rem; padding: 0 .4rem; border-radius: 1rem; background: #eaeef2; }
+.status.success { background: #dafbe1; } .status.failed, .status.denied, .status.timed-out { background: #ffebe9; }
+pre { background: #f6f8fa; padding: .5rem; overflow-x: auto; white-space: pre-wrap; }
+.add { color: #116329; background: #dafbe1; } .del { color: #82071e; background: #ffebe9; } .hunk { color: #8250df; }
+"#;
+
+pub(super) fn render(transcript: &Transcript) -> String {
+    let title = escape(&transcript.title());
+    let mut out = String::new();
+    let _ = write!(
+        out,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>{STYLE}</style>\n</head>\n<body>\n<h1>{title}</h1>\n<dl class=\"meta\">\n"
+    );
+    let mut meta = |name: &str, value: &str| {
+        let _ = writeln!(out, "<dt>{name}</dt><dd>{}</dd>", escape(value));
+    };
+    meta("Session", &transcript.session_id.to_string());
+    if let Some(task_id) = transcript.task_id {
+        meta("Task", &task_id.to_string());
+    }
+    if let Some(branch) = &transcript.branch {
+        meta("Branch", branch);
+    }
+    if let Some(executor) = &transcript.executor {
+        meta("Executor", executor);
+    }
+    meta("Exported", &transcript.exported_at.to_rfc3339());
+    out.push_str("</dl>\n");
+
+    for (i, turn) in transcript.turns.iter().enumerate() {
+        let _ = writeln!(
+            out,
+            "<section>\n<h2>Turn {} · {} <span class=\"status\">{}</span></h2>",
+            i + 1,
+            escape(turn.executor_profile.as_deref().unwrap_or("Agent")),
+            turn.status_label()
+        );
+        for entry in &turn.entries {
+            render_entry(&mut out, entry);
+        }
+        out.push_str("</section>\n");
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+fn render_entry(out: &mut String, entry: &TranscriptEntry) {
+    let (class, label) = match entry.kind {
+        TranscriptEntryKind::UserMessage => ("user", "User"),
+        TranscriptEntryKind::UserFeedback => ("user", "User feedback"),
+        TranscriptEntryKind::AssistantMessage => ("assistant", "Assistant"),
+        TranscriptEntryKind::SystemMessage => ("system", "System"),
+        TranscriptEntryKind::ErrorMessage => ("error", "Error"),
+        TranscriptEntryKind::Thinking => {
+            let _ = writeln!(
+                out,
+                "<details class=\"entry thinking\"><summary>Thinking</summary><div class=\"text\">{}</div></details>",
+                escape(entry.content.trim())
+            );
+            return;
+        }
+        TranscriptEntryKind::ToolCall => {
+            if let Some(call) = &entry.tool_call {
+                render_tool_call(out, &entry.content, call);
+            }
+            return;
+        }
+    };
+    let _ = writeln!(
+        out,
+        "<div class=\"entry {class}\"><div class=\"label\">{label}</div><div class=\"text\">{}</div></div>",
+        escape(entry.content.trim())
+    );
+}
+
+fn render_tool_call(out: &mut String, summary: &str, call: &TranscriptToolCall) {
+    let status = call.status.label();
+    let summary = summary.trim();
+    let summary = if summary.is_empty() {
+        call.input.as_deref().unwrap_or_default()
+    } else {
+        summary
+    };
+    let _ = write!(
+        out,
+        "<details class=\"entry tool\"><summary><b>{}</b> {} <span class=\"status {}\">{status}</span></summary>",
+        escape(&call.tool_name),
+        escape(summary.lines().next().unwrap_or_default()),
+        status.replace(' ', "-")
+    );
+    if let Some(input) = &call.input {
+        let _ = write!(out, "<pre>{}</pre>", escape(input));
+    }
+    if let Some(output) = &call.output {
+        let _ = write!(
+            out,
+            "<div class=\"label\">Output</div><pre>{}</pre>",
+            escape(output)
+        );
+    }
+    if let Some(diff) = &call.diff {
+        out.push_str("<pre class=\"diff\">");
+        for line in diff.lines() {
+            let class = if line.starts_with("+++") || line.starts_with("---") {
+                None
+            } else if line.starts_with('+') {
+                Some("add")
+            } else if line.starts_with('-') {
+                Some("del")
+            } else if line.starts_with("@@") {
+                Some("hunk")
+            } else {
+                None
+            };
+            match class {
+                Some(class) => {
+                    let _ = writeln!(out, "<span class=\"{class}\">{}</span>", escape(line));
+                }
+                None => {
+                    let _ = writeln!(out, "{}", escape(line));
+                }
+            }
+        }
+        out.push_str("</pre>");
+    }
+    out.push_str("</details>\n");
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_markup() {
+        assert_eq!(
+            escape(r#"<script>alert("x & y")</script>"#),
+            "&lt;script&gt;alert(&quot;x &amp; y&quot;)&lt;/script&gt;"
+        );
+    }
+}
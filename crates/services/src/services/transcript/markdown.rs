@@ -0,0 +1,110 @@
+use std::fmt::Write;
+
+use super::{Transcript, TranscriptEntry, TranscriptEntryKind, TranscriptToolCall};
+
+pub(super) fn render(transcript: &Transcript) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# {}\n", transcript.title());
+    let _ = writeln!(out, "- Session: `{}`", transcript.session_id);
+    if let Some(task_id) = transcript.task_id {
+        let _ = writeln!(out, "- Task: `{task_id}`");
+    }
+    if let Some(branch) = &transcript.branch {
+        let _ = writeln!(out, "- Branch: `{branch}`");
+    }
+    if let Some(executor) = &transcript.executor {
+        let _ = writeln!(out, "- Executor: {executor}");
+    }
+    let _ = writeln!(out, "- Exported: {}", transcript.exported_at.to_rfc3339());
+
+    for (i, turn) in transcript.turns.iter().enumerate() {
+        let profile = turn.executor_profile.as_deref().unwrap_or("Agent");
+        let _ = writeln!(
+            out,
+            "\n## Turn {} · {} ({})\n",
+            i + 1,
+            profile,
+            turn.status_label()
+        );
+        let _ = writeln!(out, "_Started {}_", turn.started_at.to_rfc3339());
+        for entry in &turn.entries {
+            out.push('\n');
+            render_entry(&mut out, entry);
+        }
+    }
+    out
+}
+
+fn render_entry(out: &mut String, entry: &TranscriptEntry) {
+    let label = match entry.kind {
+        TranscriptEntryKind::UserMessage => "User",
+        TranscriptEntryKind::UserFeedback => "User feedback",
+        TranscriptEntryKind::AssistantMessage => "Assistant",
+        TranscriptEntryKind::SystemMessage => "System",
+        TranscriptEntryKind::ErrorMessage => "Error",
+        TranscriptEntryKind::Thinking => {
+            let _ = writeln!(out, "> _Thinking_\n>");
+            for line in entry.content.lines() {
+                let _ = writeln!(out, "> {line}");
+            }
+            return;
+        }
+        TranscriptEntryKind::ToolCall => {
+            if let Some(call) = &entry.tool_call {
+                render_tool_call(out, &entry.content, call);
+            }
+            return;
+        }
+    };
+    let _ = writeln!(out, "**{label}**\n\n{}", entry.content.trim_end());
+}
+
+fn render_tool_call(out: &mut String, summary: &str, call: &TranscriptToolCall) {
+    let _ = write!(
+        out,
+        "**Tool · {}** ({})",
+        call.tool_name,
+        call.status.label()
+    );
+    let summary = summary.trim();
+    if !summary.is_empty() && Some(summary) != call.input.as_deref().map(str::trim) {
+        let _ = write!(out, " — {}", summary.replace('\n', " "));
+    }
+    out.push('\n');
+    if let Some(input) = &call.input {
+        out.push('\n');
+        out.push_str(&fenced(input, "text"));
+    }
+    if let Some(output) = &call.output {
+        out.push_str("\nOutput:\n\n");
+        out.push_str(&fenced(output, "text"));
+    }
+    if let Some(diff) = &call.diff {
+        out.push('\n');
+        out.push_str(&fenced(diff, "diff"));
+    }
+}
+
+/// Code block whose fence is longer than any backtick run in `content`
+fn fenced(content: &str, lang: &str) -> String {
+    let longest_run = content.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    format!(
+        "{fence}{lang}\n{}\n{fence}\n",
+        content.trim_end_matches('\n')
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fence_outgrows_backticks_in_content() {
+        assert_eq!(
+            fenced("a ```b``` c", "text"),
+            "````text\na ```b``` c\n````\n"
+        );
+        assert_eq!(fenced("plain\n", "diff"), "```diff\nplain\n```\n");
+    }
+}
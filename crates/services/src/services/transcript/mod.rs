@@ -0,0 +1,469 @@
+//! Exports of a session's agent conversation.
+//!
+//! The normalized entries of each coding agent run are mapped onto a
+//! [`Transcript`], which is the JSON export and the input of the Markdown and
+//! HTML renderers. The transcript types only change in a backwards compatible
+//! way; anything else bumps [`TRANSCRIPT_SCHEMA_VERSION`].
+
+mod html;
+mod markdown;
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use db::models::execution_process::{ExecutionProcess, ExecutionProcessStatus};
+use executors::{
+    actions::ExecutorActionType,
+    logs::{
+        ActionType, FileChange, NormalizedEntry, NormalizedEntryType, ToolResult,
+        ToolResultValueType, ToolStatus,
+    },
+};
+use json_patch::Patch;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, from_value, to_value};
+use ts_rs::TS;
+use utils::log_msg::LogMsg;
+use uuid::Uuid;
+
+pub const TRANSCRIPT_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum TranscriptFormat {
+    #[default]
+    Markdown,
+    Html,
+    Json,
+}
+
+impl TranscriptFormat {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Markdown => "text/markdown; charset=utf-8",
+            Self::Html => "text/html; charset=utf-8",
+            Self::Json => "application/json",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Html => "html",
+            Self::Json => "json",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct Transcript {
+    pub schema_version: u32,
+    pub session_id: Uuid,
+    pub executor: Option<String>,
+    pub workspace_id: Option<Uuid>,
+    pub branch: Option<String>,
+    pub task_id: Option<Uuid>,
+    pub task_title: Option<String>,
+    pub exported_at: DateTime<Utc>,
+    pub turns: Vec<TranscriptTurn>,
+}
+
+/// One coding agent run of the session
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct TranscriptTurn {
+    pub execution_id: Uuid,
+    /// Executor profile of the run, e.g. `CLAUDE_CODE:PLAN`
+    pub executor_profile: Option<String>,
+    pub status: ExecutionProcessStatus,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub entries: Vec<TranscriptEntry>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum TranscriptEntryKind {
+    UserMessage,
+    UserFeedback,
+    AssistantMessage,
+    Thinking,
+    SystemMessage,
+    ErrorMessage,
+    ToolCall,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct TranscriptEntry {
+    pub kind: TranscriptEntryKind,
+    pub content: String,
+    pub timestamp: Option<String>,
+    /// Set for `tool_call` entries
+    pub tool_call: Option<TranscriptToolCall>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum TranscriptToolStatus {
+    Pending,
+    Success,
+    Failed,
+    Denied,
+    TimedOut,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct TranscriptToolCall {
+    pub tool_name: String,
+    /// `pending` if no outcome was logged, e.g. the run stopped during the call
+    pub status: TranscriptToolStatus,
+    /// Command, path, query, URL or arguments of the call
+    pub input: Option<String>,
+    pub output: Option<String>,
+    /// Unified diff of the files the call changed
+    pub diff: Option<String>,
+}
+
+impl TranscriptToolStatus {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Success => "success",
+            Self::Failed => "failed",
+            Self::Denied => "denied",
+            Self::TimedOut => "timed out",
+        }
+    }
+}
+
+impl Transcript {
+    pub fn render(&self, format: TranscriptFormat) -> Result<String, serde_json::Error> {
+        Ok(match format {
+            TranscriptFormat::Markdown => markdown::render(self),
+            TranscriptFormat::Html => html::render(self),
+            TranscriptFormat::Json => serde_json::to_string_pretty(self)?,
+        })
+    }
+
+    /// Heading of the exported documents
+    fn title(&self) -> String {
+        match &self.task_title {
+            Some(title) => title.clone(),
+            None => format!("Session {}", self.session_id),
+        }
+    }
+}
+
+impl TranscriptTurn {
+    fn status_label(&self) -> &'static str {
+        match self.status {
+            ExecutionProcessStatus::Running => "running",
+            ExecutionProcessStatus::Completed => "completed",
+            ExecutionProcessStatus::Failed => "failed",
+            ExecutionProcessStatus::Killed => "stopped",
+        }
+    }
+
+    /// Turn of a coding agent run, starting with its prompt unless the
+    /// executor logged the prompt itself
+    pub fn new(process: &ExecutionProcess, entries: &[NormalizedEntry]) -> Self {
+        let (executor_profile, prompt) = match process.executor_action().map(|a| a.typ()) {
+            Ok(ExecutorActionType::CodingAgentInitialRequest(request)) => (
+                Some(request.executor_profile_id.to_string()),
+                Some(&request.prompt),
+            ),
+            Ok(ExecutorActionType::CodingAgentFollowUpRequest(request)) => (
+                Some(request.executor_profile_id.to_string()),
+                Some(&request.prompt),
+            ),
+            Ok(ExecutorActionType::ReviewRequest(request)) => (
+                Some(request.executor_profile_id.to_string()),
+                Some(&request.prompt),
+            ),
+            _ => (None, None),
+        };
+
+        let mut entries: Vec<TranscriptEntry> =
+            entries.iter().filter_map(TranscriptEntry::new).collect();
+        if let Some(prompt) = prompt
+            && entries
+                .first()
+                .is_none_or(|entry| entry.kind != TranscriptEntryKind::UserMessage)
+        {
+            entries.insert(
+                0,
+                TranscriptEntry {
+                    kind: TranscriptEntryKind::UserMessage,
+                    content: prompt.clone(),
+                    timestamp: None,
+                    tool_call: None,
+                },
+            );
+        }
+
+        Self {
+            execution_id: process.id,
+            executor_profile,
+            status: process.status.clone(),
+            started_at: process.started_at,
+            completed_at: process.completed_at,
+            entries,
+        }
+    }
+}
+
+impl TranscriptEntry {
+    /// `None` for entries that only matter while the run is shown live
+    fn new(entry: &NormalizedEntry) -> Option<Self> {
+        let (kind, tool_call) = match &entry.entry_type {
+            NormalizedEntryType::UserMessage => (TranscriptEntryKind::UserMessage, None),
+            NormalizedEntryType::UserFeedback { .. } => (TranscriptEntryKind::UserFeedback, None),
+            NormalizedEntryType::AssistantMessage => (TranscriptEntryKind::AssistantMessage, None),
+            NormalizedEntryType::Thinking => (TranscriptEntryKind::Thinking, None),
+            NormalizedEntryType::SystemMessage => (TranscriptEntryKind::SystemMessage, None),
+            NormalizedEntryType::ErrorMessage { .. } => (TranscriptEntryKind::ErrorMessage, None),
+            NormalizedEntryType::ToolUse {
+                tool_name,
+                action_type,
+                status,
+            } => (
+                TranscriptEntryKind::ToolCall,
+                Some(TranscriptToolCall::new(tool_name, action_type, status)),
+            ),
+            NormalizedEntryType::Loading
+            | NormalizedEntryType::NextAction { .. }
+            | NormalizedEntryType::TokenUsageInfo(_) => return None,
+        };
+        Some(Self {
+            kind,
+            content: entry.content.clone(),
+            timestamp: entry.timestamp.clone(),
+            tool_call,
+        })
+    }
+}
+
+impl TranscriptToolCall {
+    fn new(tool_name: &str, action: &ActionType, status: &ToolStatus) -> Self {
+        let status = match status {
+            ToolStatus::Success => TranscriptToolStatus::Success,
+            ToolStatus::Failed => TranscriptToolStatus::Failed,
+            ToolStatus::Denied { .. } => TranscriptToolStatus::Denied,
+            ToolStatus::TimedOut => TranscriptToolStatus::TimedOut,
+            ToolStatus::Created
+            | ToolStatus::AutoApproved { .. }
+            | ToolStatus::PendingApproval { .. } => TranscriptToolStatus::Pending,
+        };
+        let (input, output, diff) = match action {
+            ActionType::FileRead { path } => (Some(path.clone()), None, None),
+            ActionType::FileEdit { path, changes } => {
+                (Some(path.clone()), None, Some(file_diff(path, changes)))
+            }
+            ActionType::CommandRun { command, result } => (
+                Some(command.clone()),
+                result.as_ref().and_then(|r| r.output.clone()),
+                None,
+            ),
+            ActionType::Search { query } => (Some(query.clone()), None, None),
+            ActionType::WebFetch { url } => (Some(url.clone()), None, None),
+            ActionType::Tool {
+                arguments, result, ..
+            } => (
+                arguments.as_ref().map(json_text),
+                result.as_ref().map(tool_result_text),
+                None,
+            ),
+            ActionType::TaskCreate {
+                description,
+                result,
+                ..
+            } => (
+                Some(description.clone()),
+                result.as_ref().map(tool_result_text),
+                None,
+            ),
+            ActionType::PlanPresentation { plan } => (Some(plan.clone()), None, None),
+            ActionType::TodoManagement { todos, .. } => {
+                let list = todos
+                    .iter()
+                    .map(|todo| {
+                        let done = if todo.status == "completed" { "x" } else { " " };
+                        format!("- [{done}] {}", todo.content)
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                (Some(list), None, None)
+            }
+            ActionType::Other { description } => (Some(description.clone()), None, None),
+        };
+
+        Self {
+            tool_name: tool_name.to_string(),
+            status,
+            input: input.filter(|s| !s.trim().is_empty()),
+            output: output.filter(|s| !s.trim().is_empty()),
+            diff: diff.filter(|s| !s.is_empty()),
+        }
+    }
+}
+
+fn json_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        _ => serde_json::to_string_pretty(value).unwrap_or_default(),
+    }
+}
+
+fn tool_result_text(result: &ToolResult) -> String {
+    match result.r#type {
+        ToolResultValueType::Markdown => json_text(&result.value),
+        ToolResultValueType::Json => {
+            serde_json::to_string_pretty(&result.value).unwrap_or_default()
+        }
+    }
+}
+
+/// Unified diff of the changes a tool made to one file
+fn file_diff(path: &str, changes: &[FileChange]) -> String {
+    let mut diff = String::new();
+    for change in changes {
+        match change {
+            FileChange::Edit { unified_diff, .. } => diff.push_str(unified_diff),
+            FileChange::Write { content } => {
+                diff.push_str(&format!("--- /dev/null\n+++ b/{path}\n"));
+                for line in content.lines() {
+                    diff.push_str(&format!("+{line}\n"));
+                }
+            }
+            FileChange::Delete => diff.push_str(&format!("--- a/{path}\n+++ /dev/null\n")),
+            FileChange::Rename { new_path } => {
+                diff.push_str(&format!("rename from {path}\nrename to {new_path}\n"))
+            }
+        }
+        if !diff.is_empty() && !diff.ends_with('\n') {
+            diff.push('\n');
+        }
+    }
+    diff
+}
+
+/// Conversation left behind by the entry patches of a process, folded as
+/// they arrive
+#[derive(Debug, Default)]
+pub struct ConversationFold {
+    entries: BTreeMap<usize, NormalizedEntry>,
+}
+
+impl ConversationFold {
+    pub fn apply(&mut self, patch: &Patch) {
+        let Ok(Value::Array(ops)) = to_value(patch) else {
+            return;
+        };
+        for op in ops {
+            let Some(index) = op
+                .get("path")
+                .and_then(Value::as_str)
+                .and_then(|path| path.strip_prefix("/entries/"))
+                .and_then(|index| index.parse::<usize>().ok())
+            else {
+                continue;
+            };
+            if op.get("op").and_then(Value::as_str) == Some("remove") {
+                self.entries.remove(&index);
+                continue;
+            }
+            let entry = op
+                .get("value")
+                .filter(|value| {
+                    value.get("type").and_then(Value::as_str) == Some("NORMALIZED_ENTRY")
+                })
+                .and_then(|value| value.get("content"))
+                .and_then(|content| from_value::<NormalizedEntry>(content.clone()).ok());
+            if let Some(entry) = entry {
+                self.entries.insert(index, entry);
+            }
+        }
+    }
+
+    pub fn into_entries(self) -> Vec<NormalizedEntry> {
+        self.entries.into_values().collect()
+    }
+}
+
+pub fn fold_entries(messages: &[LogMsg]) -> Vec<NormalizedEntry> {
+    let mut fold = ConversationFold::default();
+    for msg in messages {
+        if let LogMsg::JsonPatch(patch) = msg {
+            fold.apply(patch);
+        }
+    }
+    fold.into_entries()
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::logs::utils::ConversationPatch;
+
+    use super::*;
+
+    fn entry(entry_type: NormalizedEntryType, content: &str) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type,
+            content: content.to_string(),
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn folds_entry_patches_in_order() {
+        let bash = |status| NormalizedEntryType::ToolUse {
+            tool_name: "Bash".to_string(),
+            action_type: ActionType::CommandRun {
+                command: "cargo test".to_string(),
+                result: None,
+            },
+            status,
+        };
+        let messages = vec![
+            LogMsg::JsonPatch(ConversationPatch::add_normalized_entry(
+                1,
+                entry(bash(ToolStatus::Created), "Run tests"),
+            )),
+            LogMsg::JsonPatch(ConversationPatch::add_normalized_entry(
+                0,
+                entry(NormalizedEntryType::AssistantMessage, "Running the tests"),
+            )),
+            LogMsg::JsonPatch(ConversationPatch::add_stdout(2, "noise".to_string())),
+            LogMsg::JsonPatch(ConversationPatch::replace(
+                1,
+                entry(bash(ToolStatus::Success), "Run tests"),
+            )),
+            LogMsg::JsonPatch(ConversationPatch::add_normalized_entry(
+                3,
+                entry(NormalizedEntryType::Loading, ""),
+            )),
+            LogMsg::JsonPatch(ConversationPatch::remove(3)),
+        ];
+
+        let entries = fold_entries(&messages);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].content, "Running the tests");
+        let tool = TranscriptEntry::new(&entries[1])
+            .unwrap()
+            .tool_call
+            .unwrap();
+        assert_eq!(tool.status, TranscriptToolStatus::Success);
+        assert_eq!(tool.input.as_deref(), Some("cargo test"));
+    }
+
+    #[test]
+    fn renders_written_files_as_additions() {
+        let diff = file_diff(
+            "src/lib.rs",
+            &[FileChange::Write {
+                content: "fn main() {}".to_string(),
+            }],
+        );
+        assert_eq!(diff, "--- /dev/null\n+++ b/src/lib.rs\n+fn main() {}\n");
+    }
+}
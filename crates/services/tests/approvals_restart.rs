@@ -0,0 +1,179 @@
+mod common;
+
+use std::{collections::HashMap, sync::Arc};
+
+use chrono::{Duration, Utc};
+use db::models::{
+    approval::{ApprovalRecord, ApprovalResponder},
+    execution_process::{CreateExecutionProcess, ExecutionProcess, ExecutionProcessRunReason},
+};
+use executors::{
+    actions::{
+        ExecutorAction, ExecutorActionType, coding_agent_initial::CodingAgentInitialRequest,
+    },
+    approvals::ToolCallMetadata,
+    executors::BaseCodingAgent,
+    logs::{
+        ActionType, NormalizedEntry, NormalizedEntryType, ToolStatus,
+        utils::patch::{ConversationPatch, extract_normalized_entry_from_patch},
+    },
+    profile::ExecutorProfileId,
+};
+use services::services::approvals::{ApprovalError, Approvals};
+use sqlx::SqlitePool;
+use tokio::sync::RwLock;
+use utils::{
+    approvals::{ApprovalRequest, ApprovalResponse, ApprovalStatus},
+    log_msg::LogMsg,
+    msg_store::MsgStore,
+};
+use uuid::Uuid;
+
+fn pending_request(execution_process_id: Uuid) -> ApprovalRequest {
+    let now = Utc::now();
+    ApprovalRequest {
+        id: Uuid::new_v4().to_string(),
+        tool_name: "Bash".to_string(),
+        tool_input: serde_json::json!({ "command": "cargo test" }),
+        tool_call_id: "toolu_01".to_string(),
+        execution_process_id,
+        created_at: now,
+        timeout_at: now + Duration::hours(1),
+    }
+}
+
+async fn running_execution(pool: &SqlitePool) -> Uuid {
+    let action = ExecutorAction::new(
+        ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+            prompt: "Run the tests".to_string(),
+            executor_profile_id: ExecutorProfileId::new(BaseCodingAgent::ClaudeCode),
+            working_dir: None,
+        }),
+        None,
+    );
+    let data = CreateExecutionProcess {
+        session_id: Uuid::new_v4(),
+        executor_action: action,
+        run_reason: ExecutionProcessRunReason::CodingAgent,
+    };
+    ExecutionProcess::create(pool, &data, Uuid::new_v4(), &[])
+        .await
+        .unwrap()
+        .id
+}
+
+fn tool_status(store: &MsgStore) -> Option<ToolStatus> {
+    store
+        .get_history()
+        .iter()
+        .rev()
+        .find_map(|msg| match msg {
+            LogMsg::JsonPatch(patch) => extract_normalized_entry_from_patch(patch),
+            _ => None,
+        })
+        .and_then(|(_, entry)| match entry.entry_type {
+            NormalizedEntryType::ToolUse { status, .. } => Some(status),
+            _ => None,
+        })
+}
+
+#[tokio::test]
+async fn restart_closes_approvals_of_stopped_executions() {
+    let pool = common::migrated_pool(false).await;
+    let execution_process_id = Uuid::new_v4();
+    let request = pending_request(execution_process_id);
+    ApprovalRecord::create(&pool, &request).await.unwrap();
+
+    // A fresh service, as after a restart: nothing is tracked in memory
+    let approvals = Approvals::new(pool.clone(), Arc::new(RwLock::new(HashMap::new())));
+    approvals.restore_pending().await.unwrap();
+
+    let record = ApprovalRecord::find_by_id(&pool, &request.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(matches!(record.status, ApprovalStatus::TimedOut));
+    assert_eq!(record.responded_by, Some(ApprovalResponder::Restart));
+    assert!(record.responded_at.is_some());
+    assert!(
+        ApprovalRecord::find_pending(&pool)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    let response = ApprovalResponse {
+        execution_process_id,
+        status: ApprovalStatus::Approved,
+    };
+    assert!(matches!(
+        approvals.respond(&request.id, response).await,
+        Err(ApprovalError::AlreadyCompleted)
+    ));
+}
+
+#[tokio::test]
+async fn restart_restores_approvals_of_running_executions() {
+    let pool = common::migrated_pool(false).await;
+    let execution_process_id = running_execution(&pool).await;
+    let request = pending_request(execution_process_id);
+    ApprovalRecord::create(&pool, &request).await.unwrap();
+
+    let store = Arc::new(MsgStore::new());
+    store.push_patch(ConversationPatch::add_normalized_entry(
+        0,
+        NormalizedEntry {
+            timestamp: None,
+            entry_type: NormalizedEntryType::ToolUse {
+                tool_name: "Bash".to_string(),
+                action_type: ActionType::CommandRun {
+                    command: "cargo test".to_string(),
+                    result: None,
+                },
+                status: ToolStatus::Created,
+            },
+            content: "cargo test".to_string(),
+            metadata: Some(
+                serde_json::to_value(ToolCallMetadata {
+                    tool_call_id: request.tool_call_id.clone(),
+                })
+                .unwrap(),
+            ),
+        },
+    ));
+    let msg_stores = HashMap::from([(execution_process_id, store.clone())]);
+    let approvals = Approvals::new(pool.clone(), Arc::new(RwLock::new(msg_stores)));
+    approvals.restore_pending().await.unwrap();
+
+    let record = ApprovalRecord::find_by_id(&pool, &request.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(matches!(record.status, ApprovalStatus::Pending));
+    assert!(matches!(
+        tool_status(&store),
+        Some(ToolStatus::PendingApproval { approval_id, .. }) if approval_id == request.id
+    ));
+    assert_eq!(
+        approvals.get_pending_execution_process_ids(&[execution_process_id]),
+        [execution_process_id].into()
+    );
+
+    let response = ApprovalResponse {
+        execution_process_id,
+        status: ApprovalStatus::Approved,
+    };
+    let (status, _) = approvals.respond(&request.id, response).await.unwrap();
+    assert!(matches!(status, ApprovalStatus::Approved));
+
+    let record = ApprovalRecord::find_by_id(&pool, &request.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(matches!(record.status, ApprovalStatus::Approved));
+    assert_eq!(record.responded_by, Some(ApprovalResponder::User));
+    assert!(!matches!(
+        tool_status(&store),
+        Some(ToolStatus::PendingApproval { .. })
+    ));
+}
@@ -0,0 +1,35 @@
+use std::{path::Path, str::FromStr};
+
+use sqlx::{
+    SqlitePool,
+    migrate::Migrator,
+    sqlite::{SqliteConnectOptions, SqlitePoolOptions},
+};
+
+/// In-memory database with every migration applied. Without `foreign_keys`
+/// they are turned off once the migrations have run, so rows can be stored
+/// without the rows they reference.
+pub async fn migrated_pool(foreign_keys: bool) -> SqlitePool {
+    let options = SqliteConnectOptions::from_str("sqlite::memory:")
+        .unwrap()
+        .foreign_keys(true);
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect_with(options)
+        .await
+        .unwrap();
+    let migrations = Path::new(env!("CARGO_MANIFEST_DIR")).join("../db/migrations");
+    Migrator::new(migrations)
+        .await
+        .unwrap()
+        .run(&pool)
+        .await
+        .unwrap();
+    if !foreign_keys {
+        sqlx::query("PRAGMA foreign_keys = OFF")
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+    pool
+}
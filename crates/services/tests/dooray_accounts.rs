@@ -0,0 +1,190 @@
+mod common;
+
+use db::models::{
+    dooray_connection::{CreateDoorayConnection, DoorayConnection},
+    dooray_settings::{CreateDooraySettings, DooraySettings},
+    project::{CreateProject, Project},
+    project_dooray_binding::{CreateProjectDoorayBinding, ProjectDoorayBinding},
+};
+use services::services::dooray::account::{DoorayAccount, DoorayAccountError};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+async fn create_project(pool: &SqlitePool, name: &str) -> Uuid {
+    let data = CreateProject {
+        name: name.to_string(),
+        repositories: Vec::new(),
+    };
+    Project::create(pool, &data, Uuid::new_v4())
+        .await
+        .unwrap()
+        .id
+}
+
+async fn create_default_connection(pool: &SqlitePool) {
+    let data = CreateDooraySettings {
+        dooray_token: "default-token".to_string(),
+        selected_project_id: Some("default-project".to_string()),
+        selected_project_name: Some("DEF".to_string()),
+        selected_tag_ids: None,
+        dooray_domain: Some("default.dooray.com".to_string()),
+        member_id: Some("default-member".to_string()),
+    };
+    DooraySettings::upsert(pool, &data).await.unwrap();
+}
+
+async fn create_connection(pool: &SqlitePool, name: &str) -> DoorayConnection {
+    let data = CreateDoorayConnection {
+        name: name.to_string(),
+        dooray_token: format!("{name}-token"),
+        dooray_domain: Some(format!("{name}.dooray.com")),
+        member_id: Some(format!("{name}-member")),
+    };
+    DoorayConnection::create(pool, &data, Uuid::new_v4())
+        .await
+        .unwrap()
+}
+
+async fn bind(
+    pool: &SqlitePool,
+    project_id: Uuid,
+    connection_id: Option<Uuid>,
+    dooray_project_id: &str,
+    dooray_project_code: &str,
+) -> ProjectDoorayBinding {
+    let data = CreateProjectDoorayBinding {
+        project_id,
+        connection_id,
+        dooray_project_id: dooray_project_id.to_string(),
+        dooray_project_code: dooray_project_code.to_string(),
+        tag_ids: Vec::new(),
+        is_default: false,
+    };
+    ProjectDoorayBinding::create(pool, &data, Uuid::new_v4())
+        .await
+        .unwrap()
+}
+
+fn assert_default_connection(account: &DoorayAccount) {
+    assert_eq!(account.connection_id, None);
+    assert_eq!(account.dooray_domain.as_deref(), Some("default.dooray.com"));
+    assert_eq!(account.member_id.as_deref(), Some("default-member"));
+    assert_eq!(
+        account.dooray_project_id.as_deref(),
+        Some("default-project")
+    );
+    assert_eq!(account.project_code(), "DEF");
+}
+
+#[tokio::test]
+async fn bound_project_uses_its_binding_connection() {
+    let pool = common::migrated_pool(true).await;
+    create_default_connection(&pool).await;
+    let project_id = create_project(&pool, "kanban").await;
+    let connection = create_connection(&pool, "tenant").await;
+    bind(&pool, project_id, Some(connection.id), "dooray-1", "PROJ").await;
+
+    let account = DoorayAccount::for_dooray_project(&pool, Some(project_id), "dooray-1")
+        .await
+        .unwrap();
+
+    assert_eq!(account.connection_id, Some(connection.id));
+    assert_eq!(account.dooray_domain.as_deref(), Some("tenant.dooray.com"));
+    assert_eq!(account.member_id.as_deref(), Some("tenant-member"));
+    assert_eq!(account.dooray_project_id.as_deref(), Some("dooray-1"));
+    assert_eq!(account.project_code(), "PROJ");
+}
+
+#[tokio::test]
+async fn binding_of_the_requesting_project_is_preferred() {
+    let pool = common::migrated_pool(true).await;
+    create_default_connection(&pool).await;
+    let first = create_project(&pool, "first").await;
+    let second = create_project(&pool, "second").await;
+    let first_connection = create_connection(&pool, "first").await;
+    let second_connection = create_connection(&pool, "second").await;
+    bind(&pool, first, Some(first_connection.id), "shared", "FIRST").await;
+    bind(
+        &pool,
+        second,
+        Some(second_connection.id),
+        "shared",
+        "SECOND",
+    )
+    .await;
+
+    let account = DoorayAccount::for_dooray_project(&pool, Some(second), "shared")
+        .await
+        .unwrap();
+    assert_eq!(account.connection_id, Some(second_connection.id));
+    assert_eq!(account.project_code(), "SECOND");
+
+    let account = DoorayAccount::for_dooray_project(&pool, Some(first), "shared")
+        .await
+        .unwrap();
+    assert_eq!(account.connection_id, Some(first_connection.id));
+    assert_eq!(account.project_code(), "FIRST");
+}
+
+#[tokio::test]
+async fn unbound_project_falls_back_to_default_connection() {
+    let pool = common::migrated_pool(true).await;
+    create_default_connection(&pool).await;
+    let project_id = create_project(&pool, "kanban").await;
+
+    let account = DoorayAccount::for_dooray_project(&pool, Some(project_id), "unbound")
+        .await
+        .unwrap();
+    assert_default_connection(&account);
+}
+
+#[tokio::test]
+async fn unbound_project_without_default_connection_is_not_configured() {
+    let pool = common::migrated_pool(true).await;
+    let project_id = create_project(&pool, "kanban").await;
+
+    let result = DoorayAccount::for_dooray_project(&pool, Some(project_id), "unbound").await;
+    assert!(matches!(result, Err(DoorayAccountError::NotConfigured)));
+}
+
+#[tokio::test]
+async fn deleting_a_bound_connection_removes_its_bindings() {
+    let pool = common::migrated_pool(true).await;
+    create_default_connection(&pool).await;
+    let project_id = create_project(&pool, "kanban").await;
+    let connection = create_connection(&pool, "tenant").await;
+    let binding = bind(&pool, project_id, Some(connection.id), "dooray-1", "PROJ").await;
+
+    assert_eq!(
+        DoorayConnection::delete(&pool, connection.id)
+            .await
+            .unwrap(),
+        1
+    );
+
+    // The binding goes with the connection, so the project no longer resolves
+    // to the deleted token and falls back to the default connection
+    assert!(
+        ProjectDoorayBinding::find_by_id(&pool, binding.id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        ProjectDoorayBinding::find_by_project_id(&pool, project_id)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    let account = DoorayAccount::for_dooray_project(&pool, Some(project_id), "dooray-1")
+        .await
+        .unwrap();
+    assert_default_connection(&account);
+
+    // Resolving the deleted connection directly reports it as missing
+    let result = DoorayAccount::for_connection(&pool, Some(connection.id)).await;
+    assert!(matches!(
+        result,
+        Err(DoorayAccountError::ConnectionNotFound(id)) if id == connection.id
+    ));
+}
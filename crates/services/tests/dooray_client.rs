@@ -0,0 +1,353 @@
+use std::collections::HashMap;
+
+use axum::{
+    Json, Router,
+    extract::{Path, Query},
+    http::{HeaderMap, StatusCode},
+    routing::{get, post},
+};
+use serde_json::{Value, json};
+use services::services::dooray::{
+    DoorayClient, DoorayError, PostListQuery,
+    token::{DoorayTokenProblem, validate_client},
+};
+
+/// Serve `app` on an ephemeral local port and return its base URL.
+async fn spawn_mock(app: Router) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{}", addr)
+}
+
+fn ok_header() -> Value {
+    json!({ "resultCode": 0, "resultMessage": "", "isSuccessful": true })
+}
+
+#[tokio::test]
+async fn get_post_sends_token_and_parses_detail() {
+    let app = Router::new().route(
+        "/project/v1/projects/{project_id}/posts/{post_id}",
+        get(
+            |Path((project_id, post_id)): Path<(String, String)>, headers: HeaderMap| async move {
+                let auth = headers
+                    .get("authorization")
+                    .and_then(|v| v.to_str().ok())
+                    .unwrap_or_default()
+                    .to_string();
+                if auth != "dooray-api secret-token" {
+                    return (StatusCode::UNAUTHORIZED, Json(json!({})));
+                }
+                (
+                    StatusCode::OK,
+                    Json(json!({
+                        "header": ok_header(),
+                        "result": {
+                            "id": post_id,
+                            "subject": "Fix login",
+                            "number": 42,
+                            "workflowClass": "working",
+                            "body": { "mimeType": "text/x-markdown", "content": "details" },
+                            "project": { "id": project_id, "code": "PROJ" }
+                        }
+                    })),
+                )
+            },
+        ),
+    );
+    let base = spawn_mock(app).await;
+
+    let client = DoorayClient::with_base_url(&base, "secret-token").unwrap();
+    let post = client.get_post("p1", "1001").await.unwrap();
+
+    assert_eq!(post.id, "1001");
+    assert_eq!(post.number, Some(42));
+    assert_eq!(post.workflow_class.as_deref(), Some("working"));
+    assert_eq!(
+        post.body.and_then(|b| b.content).as_deref(),
+        Some("details")
+    );
+    assert_eq!(post.project.and_then(|p| p.code).as_deref(), Some("PROJ"));
+
+    let unauthorized = DoorayClient::with_base_url(&base, "wrong").unwrap();
+    assert!(matches!(
+        unauthorized.get_post("p1", "1001").await,
+        Err(DoorayError::Unauthorized)
+    ));
+}
+
+#[tokio::test]
+async fn list_all_posts_follows_pagination() {
+    let app = Router::new().route(
+        "/project/v1/projects/{project_id}/posts",
+        get(|Query(params): Query<HashMap<String, String>>| async move {
+            assert_eq!(
+                params.get("postWorkflowClasses").map(String::as_str),
+                Some("backlog,registered,working")
+            );
+            let page: usize = params["page"].parse().unwrap();
+            let size: usize = params["size"].parse().unwrap();
+            let total: usize = 130;
+            let start = page * size;
+            let posts: Vec<Value> = (start..total.min(start + size))
+                .map(|i| json!({ "id": i.to_string(), "number": i, "subject": format!("Post {}", i) }))
+                .collect();
+            Json(json!({ "header": ok_header(), "result": posts, "totalCount": total }))
+        }),
+    );
+    let base = spawn_mock(app).await;
+
+    let client = DoorayClient::with_base_url(&base, "token").unwrap();
+    let posts = client
+        .list_all_posts("p1", &PostListQuery::open())
+        .await
+        .unwrap();
+
+    assert_eq!(posts.len(), 130);
+    assert_eq!(posts.last().unwrap().number, 129);
+}
+
+#[tokio::test]
+async fn unsuccessful_header_maps_to_api_error() {
+    let app = Router::new().route(
+        "/common/v1/members/me",
+        get(|| async {
+            Json(json!({
+                "header": { "resultCode": -10001, "resultMessage": "Invalid member", "isSuccessful": false },
+                "result": null
+            }))
+        }),
+    );
+    let base = spawn_mock(app).await;
+
+    let client = DoorayClient::with_base_url(&base, "token").unwrap();
+    match client.get_me().await {
+        Err(DoorayError::Api { code, message }) => {
+            assert_eq!(code, -10001);
+            assert_eq!(message, "Invalid member");
+        }
+        other => panic!("expected API error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn set_post_workflow_sends_workflow_id() {
+    let app = Router::new()
+        .route(
+            "/project/v1/projects/{project_id}/workflows",
+            get(|| async {
+                Json(json!({
+                    "header": ok_header(),
+                    "result": [
+                        { "id": "w1", "name": "할 일", "order": 1, "class": "registered" },
+                        { "id": "w2", "name": "진행 중", "order": 2, "class": "working" }
+                    ]
+                }))
+            }),
+        )
+        .route(
+            "/project/v1/projects/{project_id}/posts/{post_id}/set-workflow",
+            post(|Json(body): Json<Value>| async move {
+                assert_eq!(body, json!({ "workflowId": "w2" }));
+                Json(json!({ "header": ok_header(), "result": null }))
+            }),
+        );
+    let base = spawn_mock(app).await;
+
+    let client = DoorayClient::with_base_url(&base, "token").unwrap();
+    let workflows = client.list_workflows("p1").await.unwrap();
+    assert_eq!(workflows.len(), 2);
+    assert_eq!(workflows[1].class, "working");
+
+    client.set_post_workflow("p1", "1001", "w2").await.unwrap();
+}
+
+#[tokio::test]
+async fn newest_first_orders_by_update_time_and_parses_updated_at() {
+    let app = Router::new().route(
+        "/project/v1/projects/{project_id}/posts",
+        get(|Query(params): Query<HashMap<String, String>>| async move {
+            assert_eq!(
+                params.get("order").map(String::as_str),
+                Some("-postUpdatedAt")
+            );
+            assert!(!params.contains_key("postWorkflowClasses"));
+            Json(json!({
+                "header": ok_header(),
+                "result": [
+                    { "id": "2", "number": 2, "subject": "Newer", "updatedAt": "2026-10-16T10:00:00+09:00" },
+                    { "id": "1", "number": 1, "subject": "Older" }
+                ],
+                "totalCount": 2
+            }))
+        }),
+    );
+    let base = spawn_mock(app).await;
+
+    let client = DoorayClient::with_base_url(&base, "token").unwrap();
+    let page = client
+        .list_posts_page(
+            "p1",
+            &PostListQuery::default().newest_first(),
+            0,
+            DoorayClient::PAGE_SIZE,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        page.items[0].updated_at.map(|t| t.to_rfc3339()).as_deref(),
+        Some("2026-10-16T01:00:00+00:00")
+    );
+    assert!(page.items[1].updated_at.is_none());
+}
+
+#[tokio::test]
+async fn validation_tells_expired_tokens_from_missing_project_scope() {
+    let app = Router::new()
+        .route(
+            "/common/v1/members/me",
+            get(|headers: HeaderMap| async move {
+                if headers.get("authorization").and_then(|v| v.to_str().ok())
+                    == Some("dooray-api expired")
+                {
+                    return (StatusCode::UNAUTHORIZED, Json(json!({})));
+                }
+                (
+                    StatusCode::OK,
+                    Json(json!({
+                        "header": ok_header(),
+                        "result": { "id": "m1", "name": "Kim" }
+                    })),
+                )
+            }),
+        )
+        .route(
+            "/project/v1/projects",
+            get(|| async { (StatusCode::FORBIDDEN, Json(json!({}))) }),
+        );
+    let base = spawn_mock(app).await;
+
+    let expired = DoorayClient::with_base_url(&base, "expired").unwrap();
+    let validation = validate_client(&expired).await;
+    assert!(!validation.valid);
+    assert_eq!(validation.problem, Some(DoorayTokenProblem::Rejected));
+
+    let limited = DoorayClient::with_base_url(&base, "limited").unwrap();
+    let validation = validate_client(&limited).await;
+    assert!(!validation.valid);
+    assert_eq!(
+        validation.problem,
+        Some(DoorayTokenProblem::MissingProjectScope)
+    );
+    assert_eq!(validation.member_id.as_deref(), Some("m1"));
+    assert!(matches!(
+        limited.list_my_projects_page(0, 1).await,
+        Err(DoorayError::Forbidden)
+    ));
+}
+
+#[tokio::test]
+async fn subject_filter_is_sent_as_subjects() {
+    let app = Router::new().route(
+        "/project/v1/projects/{project_id}/posts",
+        get(|Query(params): Query<HashMap<String, String>>| async move {
+            assert_eq!(params.get("subjects").map(String::as_str), Some("로그인"));
+            assert!(!params.contains_key("postNumber"));
+            Json(json!({
+                "header": ok_header(),
+                "result": [{ "id": "7", "number": 7, "subject": "로그인 오류 수정" }],
+                "totalCount": 1
+            }))
+        }),
+    );
+    let base = spawn_mock(app).await;
+
+    let client = DoorayClient::with_base_url(&base, "token").unwrap();
+    let page = client
+        .list_posts_page(
+            "p1",
+            &PostListQuery::default().with_subject("로그인"),
+            0,
+            20,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(page.items.len(), 1);
+    assert_eq!(page.items[0].number, 7);
+}
+
+#[tokio::test]
+async fn sub_post_query_filters_by_parent_and_parses_parent_ref() {
+    let app = Router::new().route(
+        "/project/v1/projects/{project_id}/posts",
+        get(|Query(params): Query<HashMap<String, String>>| async move {
+            assert_eq!(params.get("parentPostId").map(String::as_str), Some("100"));
+            assert!(!params.contains_key("postWorkflowClasses"));
+            Json(json!({
+                "header": ok_header(),
+                "result": [{
+                    "id": "101",
+                    "number": 12,
+                    "subject": "하위 업무",
+                    "parent": { "id": "100", "number": 11, "subject": "상위 업무" }
+                }],
+                "totalCount": 1
+            }))
+        }),
+    );
+    let base = spawn_mock(app).await;
+
+    let client = DoorayClient::with_base_url(&base, "token").unwrap();
+    let posts = client
+        .list_all_posts("p1", &PostListQuery::sub_posts_of("100"))
+        .await
+        .unwrap();
+
+    assert_eq!(posts.len(), 1);
+    let parent = posts[0].parent.as_ref().unwrap();
+    assert_eq!(parent.id, "100");
+    assert_eq!(parent.number, Some(11));
+}
+
+#[tokio::test]
+async fn file_download_refuses_plain_http_redirect() {
+    let app = Router::new()
+        .route(
+            "/project/v1/projects/{project_id}/posts/{post_id}/files",
+            get(|| async {
+                Json(json!({
+                    "header": ok_header(),
+                    "result": [{ "id": "f1", "name": "screen.png", "size": 3, "mimeType": "image/png" }]
+                }))
+            }),
+        )
+        .route(
+            "/project/v1/projects/{project_id}/posts/{post_id}/files/{file_id}",
+            get(
+                |Path((_, _, file_id)): Path<(String, String, String)>,
+                 Query(params): Query<HashMap<String, String>>| async move {
+                    assert_eq!(params.get("media").map(String::as_str), Some("raw"));
+                    (
+                        StatusCode::TEMPORARY_REDIRECT,
+                        [("location", format!("/download/{}", file_id))],
+                    )
+                },
+            ),
+        );
+    let base = spawn_mock(app).await;
+
+    let client = DoorayClient::with_base_url(&base, "token").unwrap();
+    let files = client.list_post_files("p1", "1001").await.unwrap();
+    assert_eq!(files[0].mime_type.as_deref(), Some("image/png"));
+
+    // The mock only speaks http, so even a same-host redirect isn't followed
+    let err = client
+        .download_post_file("p1", "1001", &files[0].id)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, DoorayError::Http { status: 307, .. }));
+}
@@ -0,0 +1,130 @@
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use axum::{
+    Json, Router,
+    extract::Path,
+    routing::{get, post},
+};
+use db::models::{
+    dooray_settings::{CreateDooraySettings, DooraySettings},
+    task::{CreateTask, Task, TaskStatus},
+};
+use serde_json::{Value, json};
+use services::services::dooray::{
+    DOORAY_API_BASE_ENV, DoorayClient,
+    hierarchy::create_sub_post_task,
+    status_sync::{StatusSyncOutcome, sync_linked_task},
+};
+use uuid::Uuid;
+
+fn ok_header() -> Value {
+    json!({ "resultCode": 0, "resultMessage": "", "isSuccessful": true })
+}
+
+/// Dooray project `p1` whose posts start in workflow `w1`; workflows moved to
+/// through `set-workflow` are recorded in the returned list.
+async fn spawn_mock() -> (String, Arc<Mutex<Vec<String>>>) {
+    let moved = Arc::new(Mutex::new(Vec::new()));
+    let recorder = moved.clone();
+    let app = Router::new()
+        .route(
+            "/project/v1/projects/{project_id}/posts",
+            post(|| async { Json(json!({ "header": ok_header(), "result": { "id": "2001" } })) }),
+        )
+        .route(
+            "/project/v1/projects/{project_id}/posts/{post_id}",
+            get(|Path((_, post_id)): Path<(String, String)>| async move {
+                Json(json!({
+                    "header": ok_header(),
+                    "result": {
+                        "id": post_id,
+                        "subject": "Sub",
+                        "number": 7,
+                        "workflowClass": "registered",
+                        "workflow": { "id": "w1", "name": "할 일" }
+                    }
+                }))
+            }),
+        )
+        .route(
+            "/project/v1/projects/{project_id}/workflows",
+            get(|| async {
+                Json(json!({
+                    "header": ok_header(),
+                    "result": [
+                        { "id": "w1", "name": "할 일", "order": 1, "class": "registered" },
+                        { "id": "w2", "name": "진행 중", "order": 2, "class": "working" }
+                    ]
+                }))
+            }),
+        )
+        .route(
+            "/project/v1/projects/{project_id}/posts/{post_id}/set-workflow",
+            post(move |Json(body): Json<Value>| {
+                let recorder = recorder.clone();
+                async move {
+                    let workflow_id = body["workflowId"].as_str().unwrap_or_default();
+                    recorder.lock().unwrap().push(workflow_id.to_string());
+                    Json(json!({ "header": ok_header(), "result": null }))
+                }
+            }),
+        );
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    (format!("http://{}", addr), moved)
+}
+
+#[tokio::test]
+async fn local_status_change_of_a_created_post_is_pushed() {
+    let (base, moved) = spawn_mock().await;
+    // The only test in this binary, so nothing else reads the variable meanwhile
+    unsafe { std::env::set_var(DOORAY_API_BASE_ENV, &base) };
+
+    let pool = common::migrated_pool(false).await;
+    let settings = CreateDooraySettings {
+        dooray_token: "token".to_string(),
+        selected_project_id: Some("p1".to_string()),
+        selected_project_name: Some("PROJ".to_string()),
+        selected_tag_ids: None,
+        dooray_domain: None,
+        member_id: None,
+    };
+    DooraySettings::upsert(&pool, &settings).await.unwrap();
+
+    let parent = CreateTask {
+        project_id: Uuid::new_v4(),
+        title: "Parent".to_string(),
+        description: None,
+        status: Some(TaskStatus::Todo),
+        parent_workspace_id: None,
+        image_ids: None,
+        dooray_task_id: Some("1000".to_string()),
+        dooray_project_id: Some("p1".to_string()),
+        dooray_task_number: Some("PROJ/1".to_string()),
+        reference_dooray_url: None,
+        parent_task_id: None,
+    };
+    let parent = Task::create(&pool, &parent, Uuid::new_v4()).await.unwrap();
+
+    let task = create_sub_post_task(&pool, &parent, "Sub", None)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(task.dooray_task_number.as_deref(), Some("PROJ/7"));
+
+    Task::update_status(&pool, task.id, TaskStatus::InProgress)
+        .await
+        .unwrap();
+    let task = Task::find_by_id(&pool, task.id).await.unwrap().unwrap();
+    let client = DoorayClient::with_base_url(&base, "token").unwrap();
+    let outcome = sync_linked_task(&pool, &client, &task).await.unwrap();
+
+    assert_eq!(outcome, StatusSyncOutcome::Pushed);
+    assert_eq!(*moved.lock().unwrap(), vec!["w2".to_string()]);
+}
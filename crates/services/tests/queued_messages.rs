@@ -0,0 +1,201 @@
+mod common;
+
+use std::sync::Arc;
+
+use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
+use services::services::queued_message::{
+    QueuedMessageError, QueuedMessageService, queued_message_profile,
+};
+use utils::msg_store::MsgStore;
+use uuid::Uuid;
+
+async fn queue_service() -> QueuedMessageService {
+    QueuedMessageService::new(
+        common::migrated_pool(false).await,
+        Arc::new(MsgStore::new()),
+    )
+}
+
+async fn queue_all(queue: &QueuedMessageService, session_id: Uuid, messages: &[&str]) -> Vec<Uuid> {
+    let mut ids = Vec::new();
+    for message in messages {
+        let status = queue
+            .queue_message(session_id, message, None)
+            .await
+            .unwrap();
+        ids.push(status.messages.last().unwrap().id);
+    }
+    ids
+}
+
+async fn queued_texts(queue: &QueuedMessageService, session_id: Uuid) -> Vec<String> {
+    queue
+        .get_status(session_id)
+        .await
+        .unwrap()
+        .messages
+        .into_iter()
+        .map(|queued| queued.message)
+        .collect()
+}
+
+#[tokio::test]
+async fn messages_keep_their_order_across_inserts_and_deletes() {
+    let queue = queue_service().await;
+    let session_id = Uuid::new_v4();
+    let other_session_id = Uuid::new_v4();
+
+    let ids = queue_all(&queue, session_id, &["first", "second", "third"]).await;
+    queue_all(&queue, other_session_id, &["elsewhere"]).await;
+
+    let status = queue.get_status(session_id).await.unwrap();
+    let positions: Vec<i64> = status.messages.iter().map(|m| m.position).collect();
+    assert_eq!(positions, vec![0, 1, 2]);
+    assert_eq!(
+        queued_texts(&queue, session_id).await,
+        ["first", "second", "third"]
+    );
+
+    queue.remove_message(session_id, ids[1]).await.unwrap();
+    assert_eq!(queued_texts(&queue, session_id).await, ["first", "third"]);
+
+    // New messages go after the last one, not into the gap
+    queue_all(&queue, session_id, &["fourth"]).await;
+    assert_eq!(
+        queued_texts(&queue, session_id).await,
+        ["first", "third", "fourth"]
+    );
+
+    queue.remove_message(session_id, ids[0]).await.unwrap();
+    queue_all(&queue, session_id, &["fifth"]).await;
+    assert_eq!(
+        queued_texts(&queue, session_id).await,
+        ["third", "fourth", "fifth"]
+    );
+
+    // Messages of another session can't be removed through this one
+    assert!(matches!(
+        queue.remove_message(other_session_id, ids[2]).await,
+        Err(QueuedMessageError::NotFound)
+    ));
+    assert_eq!(queued_texts(&queue, other_session_id).await, ["elsewhere"]);
+}
+
+#[tokio::test]
+async fn reorder_rejects_duplicate_or_missing_ids() {
+    let queue = queue_service().await;
+    let session_id = Uuid::new_v4();
+    let ids = queue_all(&queue, session_id, &["a", "b", "c"]).await;
+
+    for invalid in [
+        vec![ids[0], ids[0], ids[2]],
+        vec![ids[0], ids[1]],
+        vec![ids[0], ids[1], ids[2], Uuid::new_v4()],
+        vec![ids[0], ids[1], Uuid::new_v4()],
+        Vec::new(),
+    ] {
+        assert!(
+            matches!(
+                queue.reorder(session_id, &invalid).await,
+                Err(QueuedMessageError::InvalidOrder)
+            ),
+            "{invalid:?}"
+        );
+        assert_eq!(queued_texts(&queue, session_id).await, ["a", "b", "c"]);
+    }
+
+    let status = queue
+        .reorder(session_id, &[ids[2], ids[0], ids[1]])
+        .await
+        .unwrap();
+    let reordered: Vec<Uuid> = status.messages.iter().map(|m| m.id).collect();
+    assert_eq!(reordered, vec![ids[2], ids[0], ids[1]]);
+}
+
+#[tokio::test]
+async fn take_next_drains_the_queue_in_order() {
+    let queue = queue_service().await;
+    let session_id = Uuid::new_v4();
+    let ids = queue_all(&queue, session_id, &["a", "b", "c"]).await;
+    queue
+        .reorder(session_id, &[ids[1], ids[2], ids[0]])
+        .await
+        .unwrap();
+
+    let mut taken = Vec::new();
+    while let Some(next) = queue.take_next(session_id).await.unwrap() {
+        assert_eq!(next.session_id, session_id);
+        taken.push(next.message);
+    }
+
+    assert_eq!(taken, ["b", "c", "a"]);
+    assert!(!queue.has_queued(session_id).await.unwrap());
+    assert!(queue.take_next(session_id).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn requeued_message_goes_back_to_the_front() {
+    let queue = queue_service().await;
+    let session_id = Uuid::new_v4();
+    let ids = queue_all(&queue, session_id, &["a", "b"]).await;
+
+    let taken = queue.take_next(session_id).await.unwrap().unwrap();
+    queue_all(&queue, session_id, &["c"]).await;
+    let status = queue.requeue(&taken).await.unwrap();
+
+    let requeued = &status.messages[0];
+    assert_eq!(requeued.id, ids[0]);
+    assert_eq!(requeued.created_at, taken.created_at);
+    assert_eq!(queued_texts(&queue, session_id).await, ["a", "b", "c"]);
+    assert_eq!(
+        queue.take_next(session_id).await.unwrap().unwrap().message,
+        "a"
+    );
+}
+
+#[tokio::test]
+async fn queued_profile_variant_is_honored() {
+    let queue = queue_service().await;
+    let session_id = Uuid::new_v4();
+    let plan = ExecutorProfileId::with_variant(BaseCodingAgent::ClaudeCode, "PLAN".to_string());
+    let latest = ExecutorProfileId::new(BaseCodingAgent::ClaudeCode);
+
+    queue
+        .queue_message(session_id, "plan it", Some(&plan))
+        .await
+        .unwrap();
+    queue_all(&queue, session_id, &["build it"]).await;
+
+    let with_variant = queue.take_next(session_id).await.unwrap().unwrap();
+    assert_eq!(with_variant.executor_profile_id.as_ref(), Some(&plan));
+    assert_eq!(
+        queued_message_profile(&with_variant, Some(&latest), Some("CLAUDE_CODE")),
+        Some(plan.clone())
+    );
+
+    // Without its own profile a message runs with the session's latest one,
+    // or the default profile of the session's executor
+    let without = queue.take_next(session_id).await.unwrap().unwrap();
+    assert_eq!(without.executor_profile_id, None);
+    assert_eq!(
+        queued_message_profile(&without, Some(&plan), Some("CLAUDE_CODE")),
+        Some(plan)
+    );
+    assert_eq!(
+        queued_message_profile(&without, None, Some("CLAUDE_CODE")),
+        Some(latest)
+    );
+    assert_eq!(queued_message_profile(&without, None, None), None);
+
+    // Editing a message can change its variant
+    let ids = queue_all(&queue, session_id, &["review it"]).await;
+    let review = ExecutorProfileId::with_variant(BaseCodingAgent::ClaudeCode, "REVIEW".to_string());
+    let status = queue
+        .update_message(session_id, ids[0], "review it", Some(&review))
+        .await
+        .unwrap();
+    assert_eq!(
+        status.messages[0].executor_profile_id.as_ref(),
+        Some(&review)
+    );
+}
@@ -36,6 +36,10 @@ pub fn credentials_path() -> std::path::PathBuf {
     asset_dir().join("credentials.json")
 }
 
+pub fn dooray_secret_key_path() -> std::path::PathBuf {
+    asset_dir().join("dooray_secret.key")
+}
+
 #[derive(RustEmbed)]
 #[folder = "../../assets/sounds"]
 pub struct SoundAssets;
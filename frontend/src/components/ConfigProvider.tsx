@@ -12,6 +12,7 @@ import {
   type Environment,
   type UserSystemInfo,
   type BaseAgentCapability,
+  type AcpAgentInfo,
   type LoginStatus,
 } from 'shared/types';
 import type { ExecutorConfig } from 'shared/types';
@@ -23,6 +24,7 @@ interface UserSystemState {
   environment: Environment | null;
   profiles: Record<string, ExecutorConfig> | null;
   capabilities: Record<string, BaseAgentCapability[]> | null;
+  acpCapabilities: Record<string, Record<string, AcpAgentInfo>> | null;
   analyticsUserId: string | null;
   loginStatus: LoginStatus | null;
 }
@@ -41,6 +43,7 @@ interface UserSystemContextType {
   environment: Environment | null;
   profiles: Record<string, ExecutorConfig> | null;
   capabilities: Record<string, BaseAgentCapability[]> | null;
+  acpCapabilities: Record<string, Record<string, AcpAgentInfo>> | null;
   analyticsUserId: string | null;
   loginStatus: LoginStatus | null;
   setEnvironment: (env: Environment | null) => void;
@@ -83,6 +86,11 @@ export function UserSystemProvider({ children }: UserSystemProviderProps) {
       string,
       BaseAgentCapability[]
     > | null) || null;
+  const acpCapabilities =
+    (userSystemInfo?.acp_capabilities as Record<
+      string,
+      Record<string, AcpAgentInfo>
+    > | null) || null;
 
   // Sync language with i18n when config changes
   useEffect(() => {
@@ -186,6 +194,7 @@ export function UserSystemProvider({ children }: UserSystemProviderProps) {
         environment,
         profiles,
         capabilities,
+        acpCapabilities,
         analyticsUserId,
         loginStatus,
       },
@@ -193,6 +202,7 @@ export function UserSystemProvider({ children }: UserSystemProviderProps) {
       environment,
       profiles,
       capabilities,
+      acpCapabilities,
       analyticsUserId,
       loginStatus,
       updateConfig,
@@ -209,6 +219,7 @@ export function UserSystemProvider({ children }: UserSystemProviderProps) {
       environment,
       profiles,
       capabilities,
+      acpCapabilities,
       analyticsUserId,
       loginStatus,
       updateConfig,
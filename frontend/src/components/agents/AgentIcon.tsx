@@ -38,6 +38,10 @@ export function getAgentName(
       return 'Copilot';
     case BaseCodingAgent.DROID:
       return 'Droid';
+    case BaseCodingAgent.CUSTOM_AGENT:
+      return 'Custom agent';
+    case BaseCodingAgent.ACP_AGENT:
+      return 'ACP agent';
   }
 }
 
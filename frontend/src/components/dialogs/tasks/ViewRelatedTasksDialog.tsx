@@ -42,6 +42,17 @@ const ViewRelatedTasksDialogImpl =
       if (relationships?.children) {
         relatedTasks.push(...relationships.children);
       }
+      // Tasks linked through the Dooray parent / sub-post hierarchy
+      if (relationships?.dooray_parent_task) {
+        relatedTasks.push(relationships.dooray_parent_task);
+      }
+      if (relationships?.dooray_subtasks) {
+        relatedTasks.push(...relationships.dooray_subtasks);
+      }
+      const uniqueRelatedTasks = relatedTasks.filter(
+        (task, index) =>
+          relatedTasks.findIndex((other) => other.id === task.id) === index
+      );
 
       const taskColumns: ColumnDef<Task>[] = [
         {
@@ -136,7 +147,7 @@ const ViewRelatedTasksDialogImpl =
 
               {!isError && (
                 <DataTable
-                  data={relatedTasks}
+                  data={uniqueRelatedTasks}
                   columns={taskColumns}
                   keyExtractor={(task) => task.id}
                   onRowClick={(task) => handleClickTask(task.id)}
@@ -146,7 +157,7 @@ const ViewRelatedTasksDialogImpl =
                     <div className="w-full flex text-left">
                       <span className="flex-1">
                         {t('viewRelatedTasksDialog.tasksCount', {
-                          count: relatedTasks.length,
+                          count: uniqueRelatedTasks.length,
                         })}
                       </span>
                       <span>
@@ -1,8 +1,17 @@
-import { useCallback, useMemo, useRef } from 'react';
+import { useCallback, useMemo, useRef, useState } from 'react';
 import { useTranslation } from 'react-i18next';
 import type { TaskWithAttemptStatus, NormalizedEntry } from 'shared/types';
-import { Loader2, ExternalLink, Bot, RefreshCw, StopCircle } from 'lucide-react';
+import {
+  Loader2,
+  ExternalLink,
+  Bot,
+  RefreshCw,
+  StopCircle,
+  ListChecks,
+} from 'lucide-react';
 import { Button } from '../ui/button';
+import { Checkbox } from '../ui/checkbox';
+import { Label } from '../ui/label';
 import { CreateDoorayTaskDialog } from '@/components/dialogs/tasks/CreateDoorayTaskDialog';
 import { UpdateDoorayBodyDialog } from '@/components/dialogs/tasks/UpdateDoorayBodyDialog';
 import { useProject } from '@/contexts/ProjectContext';
@@ -12,6 +21,7 @@ import { formatDesignMessagesToMarkdown } from '@/utils/formatDesignMessages';
 import {
   useDesignSessionFull,
   useDesignChatStream,
+  useDesignSessionMutations,
 } from '@/hooks/useDesignSession';
 import { DesignChatBox } from '@/components/tasks/DesignChatBox';
 import {
@@ -26,7 +36,6 @@ import {
   designMessageToNormalizedEntry,
   createStreamingEntry,
   createLoadingEntry,
-  createToolUseEntry,
 } from '@/utils/designMessageAdapter';
 
 interface TaskDesignPanelProps {
@@ -107,6 +116,16 @@ const TaskDesignPanel = ({ task }: TaskDesignPanelProps) => {
     currentUserMessage,
   } = useDesignChatStream(task.id);
 
+  // Distill the conversation into a plan stored on the task
+  const { createPlan } = useDesignSessionMutations(task.id);
+  const [createSubtasks, setCreateSubtasks] = useState(false);
+  const handleCreatePlan = useCallback(() => {
+    createPlan.mutate({
+      executor_profile_id: null,
+      create_subtasks: createSubtasks,
+    });
+  }, [createPlan, createSubtasks]);
+
   // Handle sending a message (with AI streaming)
   const handleSendMessage = useCallback(
     async (content: string) => {
@@ -173,7 +192,6 @@ const TaskDesignPanel = ({ task }: TaskDesignPanelProps) => {
       // Then, add streaming events in order (preserves text/tool interleaving)
       if (streamingEvents.length > 0) {
         let textSegmentIndex = 0;
-        let toolIndex = 0;
 
         streamingEvents.forEach((event) => {
           if (event.type === 'text') {
@@ -183,13 +201,12 @@ const TaskDesignPanel = ({ task }: TaskDesignPanelProps) => {
               content: createStreamingEntry(event.content),
             });
             textSegmentIndex++;
-          } else if (event.type === 'tool_use') {
+          } else if (event.type === 'entry' && event.entry) {
             entries.push({
-              patchKey: `tool-${event.toolName}-${toolIndex}`,
+              patchKey: `entry-${event.index}`,
               type: 'NORMALIZED_ENTRY' as const,
-              content: createToolUseEntry(event.toolName!, event.content),
+              content: event.entry,
             });
-            toolIndex++;
           }
         });
       } else {
@@ -310,8 +327,53 @@ const TaskDesignPanel = ({ task }: TaskDesignPanelProps) => {
       </div>
 
       {/* Actions footer */}
-      {(canCreateDoorayTask || hasDoorayIntegration) && (
+      {(hasMessages || canCreateDoorayTask || hasDoorayIntegration) && (
         <div className="border-t p-4 space-y-2">
+          {hasMessages && (
+            <>
+              <Button
+                onClick={handleCreatePlan}
+                variant="outline"
+                className="w-full"
+                disabled={isStreaming || createPlan.isPending}
+              >
+                {createPlan.isPending ? (
+                  <Loader2 className="h-4 w-4 mr-2 animate-spin" />
+                ) : (
+                  <ListChecks className="h-4 w-4 mr-2" />
+                )}
+                {t('taskPanel.design.createPlan')}
+              </Button>
+              <div className="flex items-center gap-2">
+                <Checkbox
+                  id="design-plan-subtasks"
+                  checked={createSubtasks}
+                  onCheckedChange={setCreateSubtasks}
+                  disabled={createPlan.isPending}
+                />
+                <Label
+                  htmlFor="design-plan-subtasks"
+                  className="text-xs text-muted-foreground"
+                >
+                  {hasDoorayIntegration
+                    ? t('taskPanel.design.createSubPosts')
+                    : t('taskPanel.design.createSubtasks')}
+                </Label>
+              </div>
+              {createPlan.isError ? (
+                <div className="text-xs text-destructive text-center">
+                  {createPlan.error.message}
+                </div>
+              ) : (
+                task.plan && (
+                  <div className="text-xs text-muted-foreground text-center">
+                    {t('taskPanel.design.planSaved')}
+                  </div>
+                )
+              )}
+            </>
+          )}
+
           {canCreateDoorayTask && (
             <Button
               onClick={handleCreateDoorayTask}
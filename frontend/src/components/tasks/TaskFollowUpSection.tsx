@@ -47,18 +47,13 @@ import WYSIWYGEditor from '@/components/ui/wysiwyg';
 import { useRetryUi } from '@/contexts/RetryUiContext';
 import { useFollowUpSend } from '@/hooks/useFollowUpSend';
 import { useVariant } from '@/hooks/useVariant';
-import type {
-  DraftFollowUpData,
-  ExecutorProfileId,
-  QueueStatus,
-} from 'shared/types';
+import type { DraftFollowUpData } from 'shared/types';
 import { getLatestProfileFromProcesses } from '@/utils/executor';
 import { buildResolveConflictsInstructions } from '@/lib/conflicts';
 import { useTranslation } from 'react-i18next';
 import { useScratch } from '@/hooks/useScratch';
 import { useDebouncedCallback } from '@/hooks/useDebouncedCallback';
-import { useQuery, useMutation, useQueryClient } from '@tanstack/react-query';
-import { queueApi } from '@/lib/api';
+import { useSessionQueueInteraction } from '@/hooks/useSessionQueueInteraction';
 import { imagesApi, attemptsApi } from '@/lib/api';
 import { PrCommentsDialog } from '@/components/dialogs/tasks/PrCommentsDialog';
 import type { NormalizedComment } from '@/components/ui/wysiwyg/nodes/pr-comment-node';
@@ -253,96 +248,10 @@ export function TaskFollowUpSection({
   const { activeRetryProcessId } = useRetryUi();
   const isRetryActive = !!activeRetryProcessId;
 
-  // Queue status for queuing follow-up messages while agent is running
-  const queryClient = useQueryClient();
-  const QUEUE_STATUS_KEY = 'queue-status';
-
-  const {
-    data: queueStatus = { status: 'empty' as const },
-    refetch: refreshQueueStatus,
-  } = useQuery<QueueStatus>({
-    queryKey: [QUEUE_STATUS_KEY, sessionId],
-    queryFn: () => queueApi.getStatus(sessionId!),
-    enabled: !!sessionId,
-  });
-
-  const isQueued = queueStatus.status === 'queued';
-  const queuedMessage = isQueued
-    ? (queueStatus as Extract<QueueStatus, { status: 'queued' }>).message
-    : null;
-
-  const queueMutation = useMutation({
-    mutationFn: ({
-      message,
-      executor_profile_id,
-    }: {
-      message: string;
-      executor_profile_id: ExecutorProfileId;
-    }) => queueApi.queue(sessionId!, { message, executor_profile_id }),
-    onSuccess: (status) => {
-      queryClient.setQueryData([QUEUE_STATUS_KEY, sessionId], status);
-    },
-  });
-
-  const cancelMutation = useMutation({
-    mutationFn: () => queueApi.cancel(sessionId!),
-    onSuccess: (status) => {
-      queryClient.setQueryData([QUEUE_STATUS_KEY, sessionId], status);
-    },
-  });
-
-  const queueMessage = useCallback(
-    async (message: string, executorProfileId: ExecutorProfileId) => {
-      if (!sessionId) return;
-      await queueMutation.mutateAsync({
-        message,
-        executor_profile_id: executorProfileId,
-      });
-    },
-    [sessionId, queueMutation]
-  );
-
-  const cancelQueue = useCallback(async () => {
-    if (!sessionId) return;
-    await cancelMutation.mutateAsync();
-  }, [sessionId, cancelMutation]);
-
-  const isQueueLoading = queueMutation.isPending || cancelMutation.isPending;
-
-  // Track previous process count to detect new processes
-  const prevProcessCountRef = useRef(processes.length);
-
-  // Refresh queue status when execution stops OR when a new process starts
-  useEffect(() => {
-    const prevCount = prevProcessCountRef.current;
-    prevProcessCountRef.current = processes.length;
-
-    if (!workspaceId) return;
-
-    // Refresh when execution stops
-    if (!isAttemptRunning) {
-      refreshQueueStatus();
-      return;
-    }
-
-    // Refresh when a new process starts (could be queued message consumption or follow-up)
-    if (processes.length > prevCount) {
-      refreshQueueStatus();
-      // Re-sync local message from current scratch state
-      // If scratch was deleted, scratchData will be undefined, so localMessage becomes ''
-      setLocalMessage(scratchData?.message ?? '');
-    }
-  }, [
-    isAttemptRunning,
-    workspaceId,
-    processes.length,
-    refreshQueueStatus,
-    scratchData?.message,
-  ]);
-
-  // When queued, display the queued message content so user can edit it
-  const displayMessage =
-    isQueued && queuedMessage ? queuedMessage.data.message : localMessage;
+  // Queue for follow-up messages sent while the agent is running
+  const { queuedMessages, isQueueLoading, queueMessage, cancelQueue } =
+    useSessionQueueInteraction({ sessionId });
+  const isQueued = queuedMessages.length > 0;
 
   // Check if there's a pending approval - users shouldn't be able to type during approvals
   const { entries } = useEntries();
@@ -384,7 +293,6 @@ export function TaskFollowUpSection({
 
     if (isRetryActive) return false; // disable typing while retry editor is active
     if (hasPendingApproval) return false; // disable typing during approval
-    // Note: isQueued no longer blocks typing - editing auto-cancels the queue
     return true;
   }, [
     workspaceId,
@@ -447,10 +355,8 @@ export function TaskFollowUpSection({
       return;
     }
 
-    // Cancel any pending debounced save and save immediately before queueing
-    // This prevents the race condition where the debounce fires after queueing
+    // Cancel any pending debounced save so it can't recreate the draft
     cancelDebouncedSave();
-    await saveToScratch(localMessage, selectedVariant);
 
     // Combine all the content that would be sent (same as follow-up send)
     const { prompt } = buildAgentPrompt(
@@ -464,6 +370,8 @@ export function TaskFollowUpSection({
         executor: latestProfileId.executor,
         variant: selectedVariant,
       });
+      // The server clears the draft once the message is queued
+      setLocalMessage('');
     }
   }, [
     localMessage,
@@ -474,7 +382,6 @@ export function TaskFollowUpSection({
     selectedVariant,
     queueMessage,
     cancelDebouncedSave,
-    saveToScratch,
   ]);
 
   // Keyboard shortcut handler - send follow-up or queue depending on state
@@ -482,15 +389,13 @@ export function TaskFollowUpSection({
     (e?: KeyboardEvent) => {
       e?.preventDefault();
       if (isAttemptRunning) {
-        // When running, CMD+Enter queues the message (if not already queued)
-        if (!isQueued) {
-          handleQueueMessage();
-        }
+        // When running, CMD+Enter adds the message to the queue
+        handleQueueMessage();
       } else {
         onSendFollowUp();
       }
     },
-    [isAttemptRunning, isQueued, handleQueueMessage, onSendFollowUp]
+    [isAttemptRunning, handleQueueMessage, onSendFollowUp]
   );
 
   // Ref to access setFollowUpMessage without adding it as a dependency
@@ -505,19 +410,6 @@ export function TaskFollowUpSection({
     followUpErrorRef.current = followUpError;
   }, [followUpError]);
 
-  // Helper to get current queue state from cache (avoids ref-sync pattern)
-  const getQueueState = useCallback(() => {
-    const status = queryClient.getQueryData<QueueStatus>([
-      QUEUE_STATUS_KEY,
-      sessionId,
-    ]);
-    const queued = status?.status === 'queued';
-    const message = queued
-      ? (status as Extract<QueueStatus, { status: 'queued' }>).message
-      : null;
-    return { isQueued: queued, queuedMessage: message };
-  }, [queryClient, sessionId]);
-
   // Handle image paste - upload to container and insert markdown
   const handlePasteFiles = useCallback(
     async (files: File[]) => {
@@ -529,34 +421,19 @@ export function TaskFollowUpSection({
           // Append markdown image to current message
           const imageMarkdown = `![${response.original_name}](${response.file_path})`;
 
-          // If queued, cancel queue and use queued message as base (same as editor change behavior)
-          const {
-            isQueued: currentlyQueued,
-            queuedMessage: currentQueuedMessage,
-          } = getQueueState();
-          if (currentlyQueued && currentQueuedMessage) {
-            cancelMutation.mutate();
-            const base = currentQueuedMessage.data.message;
-            const newMessage = base
-              ? `${base}\n\n${imageMarkdown}`
+          setLocalMessage((prev) => {
+            const newMessage = prev
+              ? `${prev}\n\n${imageMarkdown}`
               : imageMarkdown;
-            setLocalMessage(newMessage);
-            setFollowUpMessageRef.current(newMessage);
-          } else {
-            setLocalMessage((prev) => {
-              const newMessage = prev
-                ? `${prev}\n\n${imageMarkdown}`
-                : imageMarkdown;
-              setFollowUpMessageRef.current(newMessage); // Debounced save to scratch
-              return newMessage;
-            });
-          }
+            setFollowUpMessageRef.current(newMessage); // Debounced save to scratch
+            return newMessage;
+          });
         } catch (error) {
           console.error('Failed to upload image:', error);
         }
       }
     },
-    [workspaceId, getQueueState, cancelMutation]
+    [workspaceId]
   );
 
   // Attachment button - file input ref and handlers
@@ -614,37 +491,22 @@ export function TaskFollowUpSection({
       const markdown = markdownBlocks.join('\n\n');
 
       // Same pattern as image paste
-      const { isQueued: currentlyQueued, queuedMessage: currentQueuedMessage } =
-        getQueueState();
-      if (currentlyQueued && currentQueuedMessage) {
-        cancelMutation.mutate();
-        const base = currentQueuedMessage.data.message;
-        const newMessage = base ? `${base}\n\n${markdown}` : markdown;
-        setLocalMessage(newMessage);
+      setLocalMessage((prev) => {
+        const newMessage = prev ? `${prev}\n\n${markdown}` : markdown;
         setFollowUpMessageRef.current(newMessage);
-      } else {
-        setLocalMessage((prev) => {
-          const newMessage = prev ? `${prev}\n\n${markdown}` : markdown;
-          setFollowUpMessageRef.current(newMessage);
-          return newMessage;
-        });
-      }
+        return newMessage;
+      });
     }
-  }, [workspaceId, getSelectedRepoId, getQueueState, cancelMutation]);
+  }, [workspaceId, getSelectedRepoId]);
 
   // Stable onChange handler for WYSIWYGEditor
   const handleEditorChange = useCallback(
     (value: string) => {
-      // Auto-cancel queue when user starts editing
-      const { isQueued: currentlyQueued } = getQueueState();
-      if (currentlyQueued) {
-        cancelMutation.mutate();
-      }
       setLocalMessage(value); // Immediate update for UI responsiveness
       setFollowUpMessageRef.current(value); // Debounced save to scratch
       if (followUpErrorRef.current) setFollowUpError(null);
     },
-    [setFollowUpError, getQueueState, cancelMutation]
+    [setFollowUpError]
   );
 
   // Memoize placeholder to avoid re-renders
@@ -761,15 +623,23 @@ export function TaskFollowUpSection({
             <ClickedElementsBanner />
 
             {/* Queued message indicator */}
-            {isQueued && queuedMessage && (
+            {isQueued && (
               <div className="flex items-center gap-2 text-sm text-muted-foreground bg-muted p-3 rounded-md border">
                 <Clock className="h-4 w-4 flex-shrink-0" />
-                <div className="font-medium">
-                  {t(
-                    'followUp.queuedMessage',
-                    'Message queued - will execute when current run finishes'
-                  )}
+                <div className="font-medium flex-1">
+                  {t('followUp.queuedMessages', {
+                    count: queuedMessages.length,
+                  })}
                 </div>
+                <Button
+                  onClick={cancelQueue}
+                  disabled={isQueueLoading}
+                  size="sm"
+                  variant="ghost"
+                >
+                  <X className="h-4 w-4 mr-2" />
+                  {t('followUp.cancelQueue', 'Cancel Queue')}
+                </Button>
               </div>
             )}
 
@@ -785,7 +655,7 @@ export function TaskFollowUpSection({
             >
               <WYSIWYGEditor
                 placeholder={editorPlaceholder}
-                value={displayMessage}
+                value={localMessage}
                 onChange={handleEditorChange}
                 disabled={!isEditable}
                 onPasteFiles={handlePasteFiles}
@@ -907,45 +777,27 @@ export function TaskFollowUpSection({
 
           {isAttemptRunning ? (
             <div className="flex items-center gap-2">
-              {/* Queue/Cancel Queue button when running */}
-              {isQueued ? (
-                <Button
-                  onClick={cancelQueue}
-                  disabled={isQueueLoading}
-                  size="sm"
-                  variant="outline"
-                >
-                  {isQueueLoading ? (
-                    <Loader2 className="animate-spin h-4 w-4 mr-2" />
-                  ) : (
-                    <>
-                      <X className="h-4 w-4 mr-2" />
-                      {t('followUp.cancelQueue', 'Cancel Queue')}
-                    </>
-                  )}
-                </Button>
-              ) : (
-                <Button
-                  onClick={handleQueueMessage}
-                  disabled={
-                    isQueueLoading ||
-                    (!localMessage.trim() &&
-                      !conflictResolutionInstructions &&
-                      !reviewMarkdown &&
-                      !clickedMarkdown)
-                  }
-                  size="sm"
-                >
-                  {isQueueLoading ? (
-                    <Loader2 className="animate-spin h-4 w-4 mr-2" />
-                  ) : (
-                    <>
-                      <Clock className="h-4 w-4 mr-2" />
-                      {t('followUp.queue', 'Queue')}
-                    </>
-                  )}
-                </Button>
-              )}
+              {/* Queue button when running */}
+              <Button
+                onClick={handleQueueMessage}
+                disabled={
+                  isQueueLoading ||
+                  (!localMessage.trim() &&
+                    !conflictResolutionInstructions &&
+                    !reviewMarkdown &&
+                    !clickedMarkdown)
+                }
+                size="sm"
+              >
+                {isQueueLoading ? (
+                  <Loader2 className="animate-spin h-4 w-4 mr-2" />
+                ) : (
+                  <>
+                    <Clock className="h-4 w-4 mr-2" />
+                    {t('followUp.queue', 'Queue')}
+                  </>
+                )}
+              </Button>
               <Button
                 onClick={stopExecution}
                 disabled={isStopping}
@@ -49,7 +49,6 @@ function computeExecutionStatus(params: {
   isStopping: boolean;
   isQueueLoading: boolean;
   isSendingFollowUp: boolean;
-  isQueued: boolean;
   isAttemptRunning: boolean;
 }): ExecutionStatus {
   if (params.isInFeedbackMode) return 'feedback';
@@ -57,7 +56,6 @@ function computeExecutionStatus(params: {
   if (params.isStopping) return 'stopping';
   if (params.isQueueLoading) return 'queue-loading';
   if (params.isSendingFollowUp) return 'sending';
-  if (params.isQueued) return 'queued';
   if (params.isAttemptRunning) return 'running';
   return 'idle';
 }
@@ -363,12 +361,13 @@ export function SessionChatBoxContainer(props: SessionChatBoxContainerProps) {
 
   // Queue interaction
   const {
-    isQueued,
-    queuedMessage,
+    queuedMessages,
     isQueueLoading,
     queueMessage,
+    removeQueuedMessage,
+    moveQueuedMessageUp,
+    resumeQueue,
     cancelQueue,
-    refreshQueueStatus,
   } = useSessionQueueInteraction({ sessionId });
 
   // Send actions
@@ -415,26 +414,6 @@ export function SessionChatBoxContainer(props: SessionChatBoxContainerProps) {
     onScrollToBottom,
   ]);
 
-  // Track previous process count for queue refresh
-  const prevProcessCountRef = useRef(processes.length);
-
-  // Refresh queue status when execution stops or new process starts
-  useEffect(() => {
-    const prevCount = prevProcessCountRef.current;
-    prevProcessCountRef.current = processes.length;
-
-    if (!workspaceId) return;
-
-    if (!isAttemptRunning) {
-      refreshQueueStatus();
-      return;
-    }
-
-    if (processes.length > prevCount) {
-      refreshQueueStatus();
-    }
-  }, [isAttemptRunning, workspaceId, processes.length, refreshQueueStatus]);
-
   // Queue message handler
   const handleQueueMessage = useCallback(async () => {
     // Allow queueing if there's a message OR review comments, and we have an executor
@@ -442,11 +421,8 @@ export function SessionChatBoxContainer(props: SessionChatBoxContainerProps) {
 
     const { prompt } = buildAgentPrompt(localMessage, [reviewMarkdown]);
 
+    // The server clears the draft once the message is queued
     cancelDebouncedSave();
-    await saveToScratch(localMessage, {
-      executor: effectiveExecutor,
-      variant: selectedVariant,
-    });
     await queueMessage(prompt, {
       executor: effectiveExecutor,
       variant: selectedVariant,
@@ -463,7 +439,6 @@ export function SessionChatBoxContainer(props: SessionChatBoxContainerProps) {
     selectedVariant,
     queueMessage,
     cancelDebouncedSave,
-    saveToScratch,
     setLocalMessage,
     clearUploadedImages,
     reviewContext,
@@ -472,7 +447,6 @@ export function SessionChatBoxContainer(props: SessionChatBoxContainerProps) {
   // Editor change handler
   const handleEditorChange = useCallback(
     (value: string) => {
-      if (isQueued) cancelQueue();
       if (effectiveExecutor) {
         handleMessageChange(value, {
           executor: effectiveExecutor,
@@ -484,8 +458,6 @@ export function SessionChatBoxContainer(props: SessionChatBoxContainerProps) {
       if (sendError) clearError();
     },
     [
-      isQueued,
-      cancelQueue,
       handleMessageChange,
       effectiveExecutor,
       selectedVariant,
@@ -519,13 +491,20 @@ export function SessionChatBoxContainer(props: SessionChatBoxContainerProps) {
     feedbackContext?.exitFeedbackMode();
   }, [feedbackContext]);
 
-  // Handle cancel queue - restore message to editor
-  const handleCancelQueue = useCallback(async () => {
-    if (queuedMessage) {
-      setLocalMessage(queuedMessage);
-    }
-    await cancelQueue();
-  }, [queuedMessage, setLocalMessage, cancelQueue]);
+  // Handle edit of a queued message - move it back into the editor
+  const handleEditQueuedMessage = useCallback(
+    async (messageId: string) => {
+      const queued = queuedMessages.find((m) => m.id === messageId);
+      if (!queued) return;
+      setLocalMessage(
+        localMessage.trim()
+          ? `${localMessage}\n\n${queued.message}`
+          : queued.message
+      );
+      await removeQueuedMessage(messageId);
+    },
+    [queuedMessages, localMessage, setLocalMessage, removeQueuedMessage]
+  );
 
   // Message edit retry mutation
   const editRetryMutation = useMessageEditRetry(sessionId ?? '', () => {
@@ -663,23 +642,14 @@ export function SessionChatBoxContainer(props: SessionChatBoxContainerProps) {
     isStopping,
     isQueueLoading,
     isSendingFollowUp: isSending,
-    isQueued,
     isAttemptRunning,
   });
 
   // During loading, render with empty editor to preserve container UI
-  // In approval mode, don't show queued message - it's for follow-up, not approval response
   const editorValue = useMemo(() => {
     if (isScratchLoading || !hasInitialValue) return '';
-    if (pendingApproval) return localMessage;
-    return queuedMessage ?? localMessage;
-  }, [
-    isScratchLoading,
-    hasInitialValue,
-    pendingApproval,
-    queuedMessage,
-    localMessage,
-  ]);
+    return localMessage;
+  }, [isScratchLoading, hasInitialValue, localMessage]);
 
   // In placeholder mode, render a disabled version to maintain visual structure
   if (mode === 'placeholder') {
@@ -696,7 +666,6 @@ export function SessionChatBoxContainer(props: SessionChatBoxContainerProps) {
         actions={{
           onSend: () => {},
           onQueue: () => {},
-          onCancelQueue: () => {},
           onStop: () => {},
           onPasteFiles: () => {},
         }}
@@ -732,7 +701,6 @@ export function SessionChatBoxContainer(props: SessionChatBoxContainerProps) {
       actions={{
         onSend: handleSend,
         onQueue: handleQueueMessage,
-        onCancelQueue: handleCancelQueue,
         onStop: stopExecution,
         onPasteFiles: uploadFiles,
       }}
@@ -813,6 +781,19 @@ export function SessionChatBoxContainer(props: SessionChatBoxContainerProps) {
             }
           : undefined
       }
+      queue={
+        queuedMessages.length > 0
+          ? {
+              messages: queuedMessages,
+              isPaused: !isAttemptRunning,
+              onEdit: handleEditQueuedMessage,
+              onMoveUp: moveQueuedMessageUp,
+              onRemove: removeQueuedMessage,
+              onResume: resumeQueue,
+              onClear: cancelQueue,
+            }
+          : undefined
+      }
       localImages={localImages}
       dropzone={{ getRootProps, getInputProps, isDragActive }}
     />
@@ -10,11 +10,14 @@ import {
   TrashIcon,
   WarningIcon,
   ArrowUpIcon,
+  PencilSimpleIcon,
+  PlayIcon,
 } from '@phosphor-icons/react';
 import { useTranslation } from 'react-i18next';
 import {
   BaseAgentCapability,
   type BaseCodingAgent,
+  type QueuedMessage,
   type Session,
   type TodoItem,
   type TokenUsageInfo,
@@ -59,7 +62,6 @@ export type ExecutionStatus =
   | 'idle'
   | 'sending'
   | 'running'
-  | 'queued'
   | 'stopping'
   | 'queue-loading'
   | 'feedback'
@@ -68,7 +70,6 @@ export type ExecutionStatus =
 interface ActionsProps {
   onSend: () => void;
   onQueue: () => void;
-  onCancelQueue: () => void;
   onStop: () => void;
   onPasteFiles: (files: File[]) => void;
 }
@@ -129,6 +130,19 @@ interface ReviewCommentsProps {
   onClear: () => void;
 }
 
+interface QueueProps {
+  /** Queued follow-up messages, next one first */
+  messages: QueuedMessage[];
+  /** No execution is running to drain the queue */
+  isPaused: boolean;
+  onEdit: (messageId: string) => void;
+  onMoveUp: (messageId: string) => void;
+  onRemove: (messageId: string) => void;
+  /** Start the next message of a paused queue */
+  onResume: () => void;
+  onClear: () => void;
+}
+
 interface SessionChatBoxProps {
   status: ExecutionStatus;
   editor: EditorProps;
@@ -140,6 +154,7 @@ interface SessionChatBoxProps {
   editMode?: EditModeProps;
   approvalMode?: ApprovalModeProps;
   reviewComments?: ReviewCommentsProps;
+  queue?: QueueProps;
   toolbarActions?: ToolbarActionsProps;
   error?: string | null;
   repoIds?: string[];
@@ -170,6 +185,7 @@ export function SessionChatBox({
   editMode,
   approvalMode,
   reviewComments,
+  queue,
   toolbarActions,
   error,
   repoIds,
@@ -216,10 +232,9 @@ export function SessionChatBox({
     editor.value.trim().length > 0 || (reviewComments?.count ?? 0) > 0;
   const canSend =
     hasContent && !['sending', 'stopping', 'queue-loading'].includes(status);
-  const isQueued = status === 'queued';
-  const isRunning = status === 'running' || status === 'queued';
+  const isRunning = status === 'running';
   const showRunningAnimation =
-    (status === 'running' || status === 'queued' || status === 'sending') &&
+    (status === 'running' || status === 'sending') &&
     !isInApprovalMode &&
     editor.value.trim().length === 0;
 
@@ -415,23 +430,6 @@ export function SessionChatBox({
           </>
         );
 
-      case 'queued':
-        return (
-          <>
-            <PrimaryButton
-              onClick={actions.onCancelQueue}
-              value={t('conversation.actions.cancelQueue')}
-              actionIcon={XIcon}
-            />
-            <PrimaryButton
-              onClick={actions.onStop}
-              variant="secondary"
-              value={t('conversation.actions.stop')}
-              actionIcon="spinner"
-            />
-          </>
-        );
-
       case 'stopping':
         return (
           <PrimaryButton
@@ -482,17 +480,72 @@ export function SessionChatBox({
       );
     }
 
-    // Queued message banner
-    if (isQueued) {
+    // Queued messages banner
+    if (queue && queue.messages.length > 0) {
       banners.push(
         <div
           key="queued"
-          className="bg-secondary border-b px-double py-base flex items-center gap-base"
+          className="bg-secondary border-b px-double py-base flex flex-col gap-half"
         >
-          <ClockIcon className="h-4 w-4 text-low" />
-          <span className="text-sm text-low">
-            {t('followUp.queuedMessage')}
-          </span>
+          <div className="flex items-center gap-base">
+            <ClockIcon className="h-4 w-4 text-low flex-shrink-0" />
+            <span className="text-sm text-low flex-1">
+              {queue.isPaused
+                ? t('conversation.queue.paused', {
+                    count: queue.messages.length,
+                  })
+                : t('conversation.queue.count', {
+                    count: queue.messages.length,
+                  })}
+            </span>
+            {queue.isPaused && (
+              <button
+                onClick={queue.onResume}
+                className="text-low hover:text-normal transition-colors p-1 -m-1"
+                title={t('conversation.queue.resume')}
+              >
+                <PlayIcon className="h-4 w-4" />
+              </button>
+            )}
+            <button
+              onClick={queue.onClear}
+              className="text-low hover:text-normal transition-colors p-1 -m-1"
+              title={t('conversation.queue.clear')}
+            >
+              <TrashIcon className="h-4 w-4" />
+            </button>
+          </div>
+          {queue.messages.map((queued, index) => (
+            <div key={queued.id} className="flex items-center gap-base pl-6">
+              <span className="text-sm text-low">{index + 1}.</span>
+              <span className="text-sm text-normal flex-1 truncate">
+                {queued.message}
+              </span>
+              {index > 0 && (
+                <button
+                  onClick={() => queue.onMoveUp(queued.id)}
+                  className="text-low hover:text-normal transition-colors p-1 -m-1"
+                  title={t('conversation.queue.moveUp')}
+                >
+                  <ArrowUpIcon className="h-4 w-4" />
+                </button>
+              )}
+              <button
+                onClick={() => queue.onEdit(queued.id)}
+                className="text-low hover:text-normal transition-colors p-1 -m-1"
+                title={t('conversation.queue.edit')}
+              >
+                <PencilSimpleIcon className="h-4 w-4" />
+              </button>
+              <button
+                onClick={() => queue.onRemove(queued.id)}
+                className="text-low hover:text-normal transition-colors p-1 -m-1"
+                title={t('conversation.queue.remove')}
+              >
+                <XIcon className="h-4 w-4" />
+              </button>
+            </div>
+          ))}
         </div>
       );
     }
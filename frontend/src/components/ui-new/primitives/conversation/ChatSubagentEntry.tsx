@@ -51,7 +51,9 @@ export function ChatSubagentEntry({
       statusType === 'denied' ||
       statusType === 'timed_out';
     const isPending =
-      statusType === 'created' || statusType === 'pending_approval';
+      statusType === 'created' ||
+      statusType === 'auto_approved' ||
+      statusType === 'pending_approval';
 
     if (isSuccess) {
       return (
@@ -16,10 +16,17 @@ export function ToolStatusDot({ status, className }: ToolStatusDotProps) {
     statusType === 'denied' ||
     statusType === 'timed_out';
   const isPending =
-    statusType === 'created' || statusType === 'pending_approval';
+    statusType === 'created' ||
+    statusType === 'auto_approved' ||
+    statusType === 'pending_approval';
+  // Auto-approval rule that answered the call
+  const rule =
+    status.status === 'auto_approved' || status.status === 'denied'
+      ? status.rule
+      : undefined;
 
   return (
-    <span className={cn('inline-flex', className)}>
+    <span className={cn('inline-flex', className)} title={rule}>
       <span
         className={cn(
           'size-1.5 rounded-full',
@@ -2,6 +2,7 @@ import { useMutation, useQueryClient } from '@tanstack/react-query';
 import { attemptsApi } from '@/lib/api';
 import { workspaceSummaryKeys } from '@/components/ui-new/hooks/useWorkspaces';
 import type {
+  CreateTaskAttemptResponse,
   ExecutorProfileId,
   WorkspaceRepoInput,
   Workspace,
@@ -10,6 +11,8 @@ import type {
 type CreateAttemptArgs = {
   profile: ExecutorProfileId;
   repos: WorkspaceRepoInput[];
+  /** Profiles to start sibling attempts with, for comparison */
+  additionalProfiles?: ExecutorProfileId[];
 };
 
 type UseAttemptCreationArgs = {
@@ -24,16 +27,24 @@ export function useAttemptCreation({
   const queryClient = useQueryClient();
 
   const mutation = useMutation({
-    mutationFn: ({ profile, repos }: CreateAttemptArgs) =>
+    mutationFn: ({ profile, repos, additionalProfiles }: CreateAttemptArgs) =>
       attemptsApi.create({
         task_id: taskId,
         executor_profile_id: profile,
         repos,
+        additional_executor_profile_ids: additionalProfiles,
       }),
-    onSuccess: (newAttempt: Workspace) => {
+    onSuccess: (created: CreateTaskAttemptResponse) => {
+      const { siblings, failed_attempts, ...newAttempt } = created;
+      failed_attempts.forEach(({ executor_profile_id, error }) =>
+        console.warn(
+          `Failed to start attempt with ${executor_profile_id.executor}:`,
+          error
+        )
+      );
       queryClient.setQueryData(
         ['taskAttempts', taskId],
-        (old: Workspace[] = []) => [newAttempt, ...old]
+        (old: Workspace[] = []) => [newAttempt, ...siblings, ...old]
       );
       // Invalidate workspace summaries to include the new workspace
       queryClient.invalidateQueries({ queryKey: workspaceSummaryKeys.all });
@@ -1,6 +1,7 @@
 import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
 import { useCallback, useRef } from 'react';
 import { tasksApi } from '@/lib/api';
+import { taskKeys } from '@/hooks/useTask';
 import {
   useDesignChatStore,
   useDesignChatState,
@@ -10,6 +11,8 @@ import type {
   DesignMessage,
   DesignSessionWithMessages,
   DesignChatResponse,
+  CreateDesignPlanRequest,
+  DesignPlanResult,
   Session,
 } from 'shared/types';
 
@@ -162,9 +165,25 @@ export function useDesignSessionMutations(taskId?: string) {
     },
   });
 
+  const createPlan = useMutation({
+    mutationFn: (data: CreateDesignPlanRequest) => {
+      if (!taskId) throw new Error('Task ID is required');
+      return tasksApi.createDesignPlan(taskId, data);
+    },
+    onSuccess: (result: DesignPlanResult) => {
+      queryClient.setQueryData(taskKeys.byId(taskId), result.task);
+      // Subtasks and the updated task also reach the board through task patches
+      queryClient.invalidateQueries({ queryKey: taskKeys.all });
+    },
+    onError: (err) => {
+      console.error('Failed to create design plan:', err);
+    },
+  });
+
   return {
     addMessage,
     sendChat,
+    createPlan,
     invalidateQueries,
   };
 }
@@ -267,32 +286,38 @@ export function useDesignChatStream(taskId?: string) {
               assistantMessage = event.data.message;
               break;
 
-            case 'ToolUse':
+            case 'Entry':
               {
-                const currentState = store.getState(taskId);
-                store.setToolEvents(taskId, [...currentState.toolEvents, {
-                  type: 'tool_use',
-                  toolName: event.data.tool_name,
-                  content: JSON.stringify(event.data.tool_input, null, 2),
-                }]);
-                // Add tool event to streamingEvents (this breaks text continuity)
-                store.addStreamingEvent(taskId, {
-                  type: 'tool_use',
-                  toolName: event.data.tool_name,
-                  content: JSON.stringify(event.data.tool_input, null, 2),
-                });
-              }
-              break;
+                const { index, entry } = event.data;
+                // Text arrives through AssistantChunk; only tool calls are shown as entries
+                if (entry.entry_type.type !== 'tool_use') break;
 
-            case 'ToolResult':
-              {
                 const currentState = store.getState(taskId);
-                store.setToolEvents(taskId, [...currentState.toolEvents, {
-                  type: 'tool_result',
-                  toolName: event.data.tool_name,
-                  content: event.data.output,
-                }]);
-                // Skip adding tool_result to streamingEvents
+                const events = currentState.streamingEvents;
+                const existing = events.findIndex(
+                  (e) => e.type === 'entry' && e.index === index
+                );
+                if (existing >= 0) {
+                  // Replace the entry in place (e.g. tool status updates)
+                  store.setStreamingEvents(taskId, [
+                    ...events.slice(0, existing),
+                    { ...events[existing], entry },
+                    ...events.slice(existing + 1),
+                  ]);
+                } else {
+                  store.setToolEvents(taskId, [...currentState.toolEvents, {
+                    type: 'tool_use',
+                    toolName: entry.entry_type.tool_name,
+                    content: entry.content,
+                  }]);
+                  // Add tool entry to streamingEvents (this breaks text continuity)
+                  store.addStreamingEvent(taskId, {
+                    type: 'entry',
+                    content: entry.content,
+                    entry,
+                    index,
+                  });
+                }
               }
               break;
 
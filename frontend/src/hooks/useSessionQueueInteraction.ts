@@ -1,7 +1,7 @@
-import { useCallback } from 'react';
-import { useQuery, useMutation, useQueryClient } from '@tanstack/react-query';
+import { useCallback, useMemo, useState } from 'react';
 import { queueApi } from '@/lib/api';
-import type { ExecutorProfileId, QueueStatus } from 'shared/types';
+import type { ExecutorProfileId, QueuedMessage, QueueStatus } from 'shared/types';
+import { useJsonPatchWsStream } from './useJsonPatchWsStream';
 
 interface UseSessionQueueInteractionOptions {
   /** Session ID for queue operations */
@@ -9,97 +9,114 @@ interface UseSessionQueueInteractionOptions {
 }
 
 interface UseSessionQueueInteractionResult {
-  /** Whether a message is currently queued */
+  /** Whether any message is currently queued */
   isQueued: boolean;
-  /** The queued message content, if any */
-  queuedMessage: string | null;
+  /** Queued messages, next one first */
+  queuedMessages: QueuedMessage[];
   /** Whether a queue operation is in progress */
   isQueueLoading: boolean;
-  /** Queue a message for later execution */
+  /** Add a message to the end of the queue */
   queueMessage: (
     message: string,
     executorProfileId: ExecutorProfileId
   ) => Promise<void>;
-  /** Cancel the queued message */
+  /** Remove a single queued message */
+  removeQueuedMessage: (messageId: string) => Promise<void>;
+  /** Move a queued message one place towards the front of the queue */
+  moveQueuedMessageUp: (messageId: string) => Promise<void>;
+  /** Start the next message of a paused queue */
+  resumeQueue: () => Promise<void>;
+  /** Remove every queued message */
   cancelQueue: () => Promise<void>;
-  /** Refresh queue status from server */
-  refreshQueueStatus: () => Promise<void>;
 }
 
-const QUEUE_STATUS_KEY = 'queue-status';
+type QueueState = {
+  queue: QueueStatus | null;
+};
 
 /**
- * Hook to manage queue interaction for session messages.
- * Uses TanStack Query for caching and mutation handling.
+ * Hook to manage the follow-up queue of a session.
+ * Queue state is streamed via WebSocket (JSON Patch at /queue).
  */
 export function useSessionQueueInteraction({
   sessionId,
 }: UseSessionQueueInteractionOptions): UseSessionQueueInteractionResult {
-  const queryClient = useQueryClient();
+  const [pendingCount, setPendingCount] = useState(0);
 
-  // Query for queue status
-  const { data: queueStatus = { status: 'empty' as const }, refetch } =
-    useQuery<QueueStatus>({
-      queryKey: [QUEUE_STATUS_KEY, sessionId],
-      queryFn: () => queueApi.getStatus(sessionId!),
-      enabled: !!sessionId,
-    });
+  const endpoint = sessionId ? queueApi.getStreamUrl(sessionId) : undefined;
+  const initialData = useCallback((): QueueState => ({ queue: null }), []);
+  const { data } = useJsonPatchWsStream<QueueState>(
+    endpoint,
+    !!sessionId,
+    initialData
+  );
+
+  const queuedMessages = useMemo(
+    () => data?.queue?.messages ?? [],
+    [data?.queue?.messages]
+  );
 
-  const isQueued = queueStatus.status === 'queued';
-  const queuedMessageData = isQueued
-    ? (queueStatus as Extract<QueueStatus, { status: 'queued' }>).message
-    : null;
-  const queuedMessage = queuedMessageData?.data.message ?? null;
+  // Track in-flight requests; the stream delivers the resulting queue
+  const run = useCallback(async (request: () => Promise<unknown>) => {
+    setPendingCount((count) => count + 1);
+    try {
+      await request();
+    } finally {
+      setPendingCount((count) => count - 1);
+    }
+  }, []);
 
-  // Mutation for queueing a message
-  const queueMutation = useMutation({
-    mutationFn: ({
-      message,
-      executor_profile_id,
-    }: {
-      message: string;
-      executor_profile_id: ExecutorProfileId;
-    }) => queueApi.queue(sessionId!, { message, executor_profile_id }),
-    onSuccess: (status) => {
-      queryClient.setQueryData([QUEUE_STATUS_KEY, sessionId], status);
+  const queueMessage = useCallback(
+    async (message: string, executorProfileId: ExecutorProfileId) => {
+      if (!sessionId) return;
+      await run(() =>
+        queueApi.queue(sessionId, {
+          message,
+          executor_profile_id: executorProfileId,
+        })
+      );
     },
-  });
+    [sessionId, run]
+  );
 
-  // Mutation for cancelling the queue
-  const cancelMutation = useMutation({
-    mutationFn: () => queueApi.cancel(sessionId!),
-    onSuccess: (status) => {
-      queryClient.setQueryData([QUEUE_STATUS_KEY, sessionId], status);
+  const removeQueuedMessage = useCallback(
+    async (messageId: string) => {
+      if (!sessionId) return;
+      await run(() => queueApi.remove(sessionId, messageId));
     },
-  });
+    [sessionId, run]
+  );
 
-  const queueMessage = useCallback(
-    async (message: string, executorProfileId: ExecutorProfileId) => {
+  const moveQueuedMessageUp = useCallback(
+    async (messageId: string) => {
       if (!sessionId) return;
-      await queueMutation.mutateAsync({
-        message,
-        executor_profile_id: executorProfileId,
-      });
+      const ids = queuedMessages.map((queued) => queued.id);
+      const index = ids.indexOf(messageId);
+      if (index <= 0) return;
+      [ids[index - 1], ids[index]] = [ids[index], ids[index - 1]];
+      await run(() => queueApi.reorder(sessionId, ids));
     },
-    [sessionId, queueMutation]
+    [sessionId, queuedMessages, run]
   );
 
-  const cancelQueue = useCallback(async () => {
+  const resumeQueue = useCallback(async () => {
     if (!sessionId) return;
-    await cancelMutation.mutateAsync();
-  }, [sessionId, cancelMutation]);
+    await run(() => queueApi.resume(sessionId));
+  }, [sessionId, run]);
 
-  const refreshQueueStatus = useCallback(async () => {
+  const cancelQueue = useCallback(async () => {
     if (!sessionId) return;
-    await refetch();
-  }, [sessionId, refetch]);
+    await run(() => queueApi.cancel(sessionId));
+  }, [sessionId, run]);
 
   return {
-    isQueued,
-    queuedMessage,
-    isQueueLoading: queueMutation.isPending || cancelMutation.isPending,
+    isQueued: queuedMessages.length > 0,
+    queuedMessages,
+    isQueueLoading: pendingCount > 0,
     queueMessage,
+    removeQueuedMessage,
+    moveQueuedMessageUp,
+    resumeQueue,
     cancelQueue,
-    refreshQueueStatus,
   };
 }
@@ -1,6 +1,9 @@
 // Import all necessary types from shared types
 
 import {
+  ApprovalRecord,
+  AttemptComparison,
+  ApprovalRule,
   ApprovalStatus,
   ApiResponse,
   Config,
@@ -11,27 +14,42 @@ import {
   CreateTask,
   CreateAndStartTaskRequest,
   CreateTaskAttemptBody,
+  CreateTaskAttemptResponse,
+  CompareAttemptsRequest,
   CreateTag,
   DirectoryListResponse,
   DirectoryEntry,
   ExecutionProcess,
   ExecutionProcessRepoState,
+  LogEntryKind,
+  LogSearchHit,
   GitBranch,
   Project,
   Repo,
   RepoWithTargetBranch,
   CreateProject,
   CreateProjectRepo,
+  ProjectDockerSettings,
+  ProjectRetryPolicy,
+  ProjectCost,
+  ProjectLogUsage,
+  ModelPrice,
   UpdateRepo,
   SearchMode,
   SearchResult,
   Task,
+  TaskCost,
   TaskRelationships,
+  TranscriptFormat,
   Tag,
   TagSearchParams,
   TaskWithAttemptStatus,
   UpdateProject,
   UpdateTask,
+  UpsertApprovalRule,
+  UpsertProjectDockerSettings,
+  UpsertProjectRetryPolicy,
+  UpsertModelPrice,
   UpdateTag,
   UserSystemInfo,
   McpServerQuery,
@@ -96,10 +114,18 @@ import {
   MigrationRequest,
   MigrationResponse,
   DooraySettings,
+  DoorayConnection,
+  ProjectDoorayBinding,
+  CreateProjectDoorayBinding,
+  UpdateProjectDoorayBinding,
+  SaveDoorayConnectionRequest,
+  SyncBindingRequest,
   DoorayProject,
   DoorayTask,
   DoorayTagsResponse,
   SaveSettingsRequest,
+  DoorayTokenValidation,
+  ValidateTokenRequest,
   SyncRequest,
   CreateDoorayCommentRequest,
   CreateDoorayCommentResult,
@@ -111,14 +137,27 @@ import {
   CreateDoorayTaskResult,
   DoorayTemplate,
   DoorayTemplateDetail,
+  DoorayWorkflowMapping,
+  DoorayWorkflowMappingsResponse,
+  DoorayStatusConflict,
+  StatusConflictResolution,
+  DoorayReportSettings,
+  DoorayReportSettingsResponse,
+  UpsertDoorayReportSettings,
+  UpsertDoorayWorkflowMapping,
   SyncResult,
   ImportByNumberRequest,
   ImportByIdRequest,
   ImportResult,
+  ImportSubPostsRequest,
+  SubPostImportResult,
   DesignMessage,
   AddDesignMessageRequest,
   DesignSessionWithMessages,
   DesignChatResponse,
+  CreateDesignPlanRequest,
+  DesignPlanResult,
+  NormalizedEntry,
 } from 'shared/types';
 import type { WorkspaceWithSession } from '@/types/attempt';
 import { createWorkspaceWithSession } from '@/types/attempt';
@@ -128,8 +167,7 @@ export type DesignChatStreamEvent =
   | { type: 'UserMessageSaved'; data: { message: DesignMessage } }
   | { type: 'AssistantChunk'; data: { content: string } }
   | { type: 'AssistantComplete'; data: { message: DesignMessage } }
-  | { type: 'ToolUse'; data: { tool_name: string; tool_input: Record<string, unknown> } }
-  | { type: 'ToolResult'; data: { tool_name: string; output: string } }
+  | { type: 'Entry'; data: { index: number; entry: NormalizedEntry } }
   | { type: 'Error'; data: { message: string } };
 
 export class ApiError<E = unknown> extends Error {
@@ -335,6 +373,93 @@ export const projectsApi = {
     return handleApiResponse<Repo[]>(response);
   },
 
+  getApprovalRules: async (projectId: string): Promise<ApprovalRule[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/approval-rules`
+    );
+    return handleApiResponse<ApprovalRule[]>(response);
+  },
+
+  updateApprovalRules: async (
+    projectId: string,
+    rules: UpsertApprovalRule[]
+  ): Promise<ApprovalRule[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/approval-rules`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(rules),
+      }
+    );
+    return handleApiResponse<ApprovalRule[]>(response);
+  },
+
+  getRetryPolicy: async (
+    projectId: string
+  ): Promise<ProjectRetryPolicy | null> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/retry-policy`
+    );
+    return handleApiResponse<ProjectRetryPolicy | null>(response);
+  },
+
+  updateRetryPolicy: async (
+    projectId: string,
+    policy: UpsertProjectRetryPolicy
+  ): Promise<ProjectRetryPolicy> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/retry-policy`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(policy),
+      }
+    );
+    return handleApiResponse<ProjectRetryPolicy>(response);
+  },
+
+  getDockerSettings: async (
+    projectId: string
+  ): Promise<ProjectDockerSettings | null> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/docker-settings`
+    );
+    return handleApiResponse<ProjectDockerSettings | null>(response);
+  },
+
+  updateDockerSettings: async (
+    projectId: string,
+    settings: UpsertProjectDockerSettings
+  ): Promise<ProjectDockerSettings> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/docker-settings`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(settings),
+      }
+    );
+    return handleApiResponse<ProjectDockerSettings>(response);
+  },
+
+  deleteDockerSettings: async (projectId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/docker-settings`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  getCost: async (projectId: string): Promise<ProjectCost> => {
+    const response = await makeRequest(`/api/projects/${projectId}/cost`);
+    return handleApiResponse<ProjectCost>(response);
+  },
+
+  getLogUsage: async (): Promise<ProjectLogUsage[]> => {
+    const response = await makeRequest('/api/projects/log-usage');
+    return handleApiResponse<ProjectLogUsage[]>(response);
+  },
+
   addRepository: async (
     projectId: string,
     data: CreateProjectRepo
@@ -370,6 +495,11 @@ export const tasksApi = {
     return handleApiResponse<Task>(response);
   },
 
+  getCost: async (taskId: string): Promise<TaskCost> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/cost`);
+    return handleApiResponse<TaskCost>(response);
+  },
+
   create: async (data: CreateTask): Promise<Task> => {
     const response = await makeRequest(`/api/tasks`, {
       method: 'POST',
@@ -460,6 +590,20 @@ export const tasksApi = {
     return handleApiResponse<DesignChatResponse>(response);
   },
 
+  createDesignPlan: async (
+    taskId: string,
+    data: CreateDesignPlanRequest
+  ): Promise<DesignPlanResult> => {
+    const response = await makeRequest(
+      `/api/tasks/${taskId}/design-session/plan`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<DesignPlanResult>(response);
+  },
+
   /**
    * Stream design chat response using Server-Sent Events.
    * Returns an async generator that yields stream events.
@@ -468,7 +612,7 @@ export const tasksApi = {
     taskId: string,
     message: string,
     signal?: AbortSignal,
-    options?: { skipHistory?: boolean }
+    options?: { skipHistory?: boolean; executorProfileId?: ExecutorProfileId }
   ): AsyncGenerator<DesignChatStreamEvent> {
     const response = await fetch(
       `/api/tasks/${taskId}/design-session/chat/stream`,
@@ -478,6 +622,9 @@ export const tasksApi = {
         body: JSON.stringify({
           message,
           ...(options?.skipHistory && { skip_history: true }),
+          ...(options?.executorProfileId && {
+            executor_profile_id: options.executorProfileId,
+          }),
         }),
         signal,
       }
@@ -593,6 +740,9 @@ export const sessionsApi = {
     });
     return handleApiResponse<void>(response);
   },
+
+  getTranscriptUrl: (sessionId: string, format: TranscriptFormat): string =>
+    `/api/sessions/${sessionId}/transcript?format=${format}`,
 };
 
 // Task Attempts APIs
@@ -646,12 +796,22 @@ export const attemptsApi = {
     return createWorkspaceWithSession(workspace, sessions[0]);
   },
 
-  create: async (data: CreateTaskAttemptBody): Promise<Workspace> => {
+  create: async (
+    data: CreateTaskAttemptBody
+  ): Promise<CreateTaskAttemptResponse> => {
     const response = await makeRequest(`/api/task-attempts`, {
       method: 'POST',
       body: JSON.stringify(data),
     });
-    return handleApiResponse<Workspace>(response);
+    return handleApiResponse<CreateTaskAttemptResponse>(response);
+  },
+
+  compare: async (data: CompareAttemptsRequest): Promise<AttemptComparison> => {
+    const response = await makeRequest(`/api/task-attempts/compare`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<AttemptComparison>(response);
   },
 
   stop: async (attemptId: string): Promise<void> => {
@@ -1079,6 +1239,13 @@ export const executionProcessesApi = {
     return handleApiResponse<ExecutionProcessRepoState[]>(response);
   },
 
+  getApprovals: async (processId: string): Promise<ApprovalRecord[]> => {
+    const response = await makeRequest(
+      `/api/execution-processes/${processId}/approvals`
+    );
+    return handleApiResponse<ApprovalRecord[]>(response);
+  },
+
   stopExecutionProcess: async (processId: string): Promise<void> => {
     const response = await makeRequest(
       `/api/execution-processes/${processId}/stop`,
@@ -1271,6 +1438,30 @@ export const tagsApi = {
   },
 };
 
+// Model price table used for token cost accounting
+export const modelPricesApi = {
+  list: async (): Promise<ModelPrice[]> => {
+    const response = await makeRequest('/api/model-prices');
+    return handleApiResponse<ModelPrice[]>(response);
+  },
+
+  upsert: async (data: UpsertModelPrice): Promise<ModelPrice> => {
+    const response = await makeRequest('/api/model-prices', {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<ModelPrice>(response);
+  },
+
+  delete: async (modelPattern: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/model-prices/${encodeURIComponent(modelPattern)}`,
+      { method: 'DELETE' }
+    );
+    return handleApiResponse<void>(response);
+  },
+};
+
 // MCP Servers APIs
 export const mcpServersApi = {
   load: async (query: McpServerQuery): Promise<GetMcpServerResponse> => {
@@ -1653,11 +1844,11 @@ export const agentsApi = {
 // Queue API for session follow-up messages
 export const queueApi = {
   /**
-   * Queue a follow-up message to be executed when current execution finishes
+   * Add a follow-up message to the end of the session's queue
    */
   queue: async (
     sessionId: string,
-    data: { message: string; executor_profile_id: ExecutorProfileId }
+    data: { message: string; executor_profile_id: ExecutorProfileId | null }
   ): Promise<QueueStatus> => {
     const response = await makeRequest(`/api/sessions/${sessionId}/queue`, {
       method: 'POST',
@@ -1667,7 +1858,69 @@ export const queueApi = {
   },
 
   /**
-   * Cancel a queued follow-up message
+   * Edit a queued follow-up message
+   */
+  update: async (
+    sessionId: string,
+    messageId: string,
+    data: { message: string; executor_profile_id: ExecutorProfileId | null }
+  ): Promise<QueueStatus> => {
+    const response = await makeRequest(
+      `/api/sessions/${sessionId}/queue/${messageId}`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<QueueStatus>(response);
+  },
+
+  /**
+   * Remove a single queued follow-up message
+   */
+  remove: async (
+    sessionId: string,
+    messageId: string
+  ): Promise<QueueStatus> => {
+    const response = await makeRequest(
+      `/api/sessions/${sessionId}/queue/${messageId}`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<QueueStatus>(response);
+  },
+
+  /**
+   * Reorder the queue; must list every queued message id, next one first
+   */
+  reorder: async (
+    sessionId: string,
+    messageIds: string[]
+  ): Promise<QueueStatus> => {
+    const response = await makeRequest(
+      `/api/sessions/${sessionId}/queue/reorder`,
+      {
+        method: 'POST',
+        body: JSON.stringify({ message_ids: messageIds }),
+      }
+    );
+    return handleApiResponse<QueueStatus>(response);
+  },
+
+  /**
+   * Start the next queued message of a session whose queue is paused
+   */
+  resume: async (sessionId: string): Promise<ExecutionProcess | null> => {
+    const response = await makeRequest(
+      `/api/sessions/${sessionId}/queue/resume`,
+      { method: 'POST' }
+    );
+    return handleApiResponse<ExecutionProcess | null>(response);
+  },
+
+  /**
+   * Clear every queued follow-up message of the session
    */
   cancel: async (sessionId: string): Promise<QueueStatus> => {
     const response = await makeRequest(`/api/sessions/${sessionId}/queue`, {
@@ -1677,12 +1930,15 @@ export const queueApi = {
   },
 
   /**
-   * Get the current queue status for a session
+   * Get the current queue of a session
    */
   getStatus: async (sessionId: string): Promise<QueueStatus> => {
     const response = await makeRequest(`/api/sessions/${sessionId}/queue`);
     return handleApiResponse<QueueStatus>(response);
   },
+
+  getStreamUrl: (sessionId: string): string =>
+    `/api/sessions/${sessionId}/queue/stream/ws`,
 };
 
 // Migration API
@@ -1696,7 +1952,7 @@ export const migrationApi = {
   },
 };
 
-// Search API (multi-repo file search)
+// Search API (multi-repo file search and conversation log search)
 export const searchApi = {
   searchFiles: async (
     repoIds: string[],
@@ -1712,6 +1968,24 @@ export const searchApi = {
     );
     return handleApiResponse<SearchResult[]>(response);
   },
+
+  searchLogs: async (
+    query: string,
+    filters?: { projectId?: string; kind?: LogEntryKind; limit?: number }
+  ): Promise<LogSearchHit[]> => {
+    const params = new URLSearchParams({ q: query });
+    if (filters?.projectId) {
+      params.set('project_id', filters.projectId);
+    }
+    if (filters?.kind) {
+      params.set('kind', filters.kind);
+    }
+    if (filters?.limit) {
+      params.set('limit', String(filters.limit));
+    }
+    const response = await makeRequest(`/api/search/logs?${params.toString()}`);
+    return handleApiResponse<LogSearchHit[]>(response);
+  },
 };
 
 // Dooray Integration API
@@ -1745,6 +2019,30 @@ export const doorayApi = {
     return handleApiResponse<string>(response);
   },
 
+  /**
+   * Replace the Dooray token while keeping the project and tag selection
+   */
+  rotateToken: async (doorayToken: string): Promise<DooraySettings> => {
+    const response = await makeRequest('/api/dooray/settings/token', {
+      method: 'POST',
+      body: JSON.stringify({ dooray_token: doorayToken }),
+    });
+    return handleApiResponse<DooraySettings>(response);
+  },
+
+  /**
+   * Check a Dooray token (or a stored connection's token) against Dooray
+   */
+  validateToken: async (
+    data: ValidateTokenRequest
+  ): Promise<DoorayTokenValidation> => {
+    const response = await makeRequest('/api/dooray/validate-token', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<DoorayTokenValidation>(response);
+  },
+
   /**
    * Update selected tag IDs for filtering sync
    */
@@ -1771,19 +2069,136 @@ export const doorayApi = {
   },
 
   /**
-   * Get list of Dooray projects
+   * List additional Dooray connections (tokens are masked)
+   */
+  getConnections: async (): Promise<DoorayConnection[]> => {
+    const response = await makeRequest('/api/dooray/connections');
+    return handleApiResponse<DoorayConnection[]>(response);
+  },
+
+  /**
+   * Add a Dooray connection (token is validated against Dooray)
    */
-  getProjects: async (): Promise<DoorayProject[]> => {
-    const response = await makeRequest('/api/dooray/projects');
+  createConnection: async (
+    data: SaveDoorayConnectionRequest
+  ): Promise<DoorayConnection> => {
+    const response = await makeRequest('/api/dooray/connections', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<DoorayConnection>(response);
+  },
+
+  /**
+   * Update a Dooray connection; omit the token to keep the current one
+   */
+  updateConnection: async (
+    connectionId: string,
+    data: SaveDoorayConnectionRequest
+  ): Promise<DoorayConnection> => {
+    const response = await makeRequest(
+      `/api/dooray/connections/${connectionId}`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<DoorayConnection>(response);
+  },
+
+  /**
+   * Remove a Dooray connection together with its project bindings
+   */
+  deleteConnection: async (connectionId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/dooray/connections/${connectionId}`,
+      { method: 'DELETE' }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  /**
+   * List Dooray project bindings, optionally of one kanban project
+   */
+  getBindings: async (projectId?: string): Promise<ProjectDoorayBinding[]> => {
+    const query = projectId ? `?project_id=${projectId}` : '';
+    const response = await makeRequest(`/api/dooray/bindings${query}`);
+    return handleApiResponse<ProjectDoorayBinding[]>(response);
+  },
+
+  /**
+   * Bind a kanban project to a Dooray project
+   */
+  createBinding: async (
+    data: CreateProjectDoorayBinding
+  ): Promise<ProjectDoorayBinding> => {
+    const response = await makeRequest('/api/dooray/bindings', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<ProjectDoorayBinding>(response);
+  },
+
+  /**
+   * Update a binding's tag filter or make it the project's default
+   */
+  updateBinding: async (
+    bindingId: string,
+    data: UpdateProjectDoorayBinding
+  ): Promise<ProjectDoorayBinding> => {
+    const response = await makeRequest(`/api/dooray/bindings/${bindingId}`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<ProjectDoorayBinding>(response);
+  },
+
+  /**
+   * Remove a binding
+   */
+  deleteBinding: async (bindingId: string): Promise<void> => {
+    const response = await makeRequest(`/api/dooray/bindings/${bindingId}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+
+  /**
+   * Sync one binding
+   */
+  syncBinding: async (
+    bindingId: string,
+    data: SyncBindingRequest = {}
+  ): Promise<SyncResult> => {
+    const response = await makeRequest(
+      `/api/dooray/bindings/${bindingId}/sync`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<SyncResult>(response);
+  },
+
+  /**
+   * Get list of Dooray projects, of the default connection unless one is given
+   */
+  getProjects: async (connectionId?: string): Promise<DoorayProject[]> => {
+    const query = connectionId ? `?connection_id=${connectionId}` : '';
+    const response = await makeRequest(`/api/dooray/projects${query}`);
     return handleApiResponse<DoorayProject[]>(response);
   },
 
   /**
    * Get tags from a Dooray project
    */
-  getTags: async (doorayProjectId: string): Promise<DoorayTagsResponse> => {
+  getTags: async (
+    doorayProjectId: string,
+    connectionId?: string
+  ): Promise<DoorayTagsResponse> => {
+    const query = connectionId ? `?connection_id=${connectionId}` : '';
     const response = await makeRequest(
-      `/api/dooray/projects/${doorayProjectId}/tags`
+      `/api/dooray/projects/${doorayProjectId}/tags${query}`
     );
     return handleApiResponse<DoorayTagsResponse>(response);
   },
@@ -1836,6 +2251,16 @@ export const doorayApi = {
     return handleApiResponse<ImportResult>(response);
   },
 
+  importSubPosts: async (
+    data: ImportSubPostsRequest
+  ): Promise<SubPostImportResult> => {
+    const response = await makeRequest('/api/dooray/import-sub-posts', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<SubPostImportResult>(response);
+  },
+
   /**
    * Create a comment on a Dooray task
    */
@@ -1926,4 +2351,87 @@ export const doorayApi = {
     });
     return handleApiResponse<CreateDoorayCommentResult>(response);
   },
+
+  /**
+   * Get the workflows of a Dooray project with their task status mappings
+   */
+  getWorkflowMappings: async (
+    doorayProjectId: string
+  ): Promise<DoorayWorkflowMappingsResponse> => {
+    const response = await makeRequest(
+      `/api/dooray/projects/${doorayProjectId}/workflows`
+    );
+    return handleApiResponse<DoorayWorkflowMappingsResponse>(response);
+  },
+
+  /**
+   * Replace the task status mappings of a Dooray project
+   */
+  updateWorkflowMappings: async (
+    doorayProjectId: string,
+    mappings: UpsertDoorayWorkflowMapping[]
+  ): Promise<DoorayWorkflowMapping[]> => {
+    const response = await makeRequest(
+      `/api/dooray/projects/${doorayProjectId}/workflows`,
+      {
+        method: 'PUT',
+        body: JSON.stringify({ mappings }),
+      }
+    );
+    return handleApiResponse<DoorayWorkflowMapping[]>(response);
+  },
+
+  /**
+   * List tasks whose status changed both locally and on Dooray
+   */
+  getStatusConflicts: async (): Promise<DoorayStatusConflict[]> => {
+    const response = await makeRequest('/api/dooray/status-conflicts');
+    return handleApiResponse<DoorayStatusConflict[]>(response);
+  },
+
+  /**
+   * Resolve a status conflict by keeping the local or the Dooray side
+   */
+  resolveStatusConflict: async (
+    taskId: string,
+    resolution: StatusConflictResolution
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/dooray/status-conflicts/${taskId}/resolve`,
+      {
+        method: 'POST',
+        body: JSON.stringify({ resolution }),
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  /**
+   * Get which events of a project are reported to linked Dooray posts
+   */
+  getReportSettings: async (
+    projectId: string
+  ): Promise<DoorayReportSettingsResponse> => {
+    const response = await makeRequest(
+      `/api/dooray/report-settings/${projectId}`
+    );
+    return handleApiResponse<DoorayReportSettingsResponse>(response);
+  },
+
+  /**
+   * Save a project's Dooray report settings
+   */
+  saveReportSettings: async (
+    projectId: string,
+    data: UpsertDoorayReportSettings
+  ): Promise<DoorayReportSettings> => {
+    const response = await makeRequest(
+      `/api/dooray/report-settings/${projectId}`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<DoorayReportSettings>(response);
+  },
 };
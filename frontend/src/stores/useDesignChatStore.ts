@@ -1,11 +1,13 @@
 import { create } from 'zustand';
-import type { DesignMessage } from 'shared/types';
+import type { DesignMessage, NormalizedEntry } from 'shared/types';
 
 // 스트리밍 이벤트 타입 (useDesignSession.ts의 정의와 동일)
 export interface StreamingEvent {
-  type: 'text' | 'tool_use' | 'tool_result';
+  type: 'text' | 'entry';
   content: string;
-  toolName?: string;
+  // 'entry' 이벤트: 에이전트 대화의 정규화된 항목과 그 인덱스
+  entry?: NormalizedEntry;
+  index?: number;
 }
 
 // 툴 이벤트 타입
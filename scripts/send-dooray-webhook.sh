@@ -0,0 +1,30 @@
+#!/usr/bin/env bash
+# Send a sample Dooray webhook to a locally running backend
+# Usage: scripts/send-dooray-webhook.sh [payload.json] [post-id]
+#   payload.json  defaults to the postWorkflowChanged sample
+#   post-id       replaces the sample's post id, e.g. the dooray_task_id of a local task
+# Needs VK_DOORAY_WEBHOOK_SECRET (the same value the backend runs with) and
+# BACKEND_PORT, or VK_BACKEND_URL for a backend elsewhere.
+set -eo pipefail
+
+FIXTURES="$(cd "$(dirname "$0")/.." && pwd)/crates/services/tests/fixtures/dooray_webhook"
+PAYLOAD="${1:-$FIXTURES/post_workflow_changed.json}"
+POST_ID="$2"
+BASE_URL="${VK_BACKEND_URL:-http://127.0.0.1:${BACKEND_PORT:?set BACKEND_PORT or VK_BACKEND_URL}}"
+
+if [ -z "$VK_DOORAY_WEBHOOK_SECRET" ]; then
+  echo "VK_DOORAY_WEBHOOK_SECRET is not set" >&2
+  exit 1
+fi
+
+BODY=$(cat "$PAYLOAD")
+if [ -n "$POST_ID" ]; then
+  # The first "id" after "post" is the post id
+  BODY=$(printf '%s' "$BODY" | sed -E "/\"post\"/,/\"id\"/ s/\"id\": \"[0-9]+\"/\"id\": \"$POST_ID\"/")
+fi
+
+curl -sS -X POST "$BASE_URL/api/dooray/webhook" \
+  -H "Content-Type: application/json" \
+  -H "X-Dooray-Webhook-Secret: $VK_DOORAY_WEBHOOK_SECRET" \
+  --data "$BODY"
+echo
@@ -26,6 +26,45 @@ export type ProjectRepo = { id: string, project_id: string, repo_id: string, };
 
 export type CreateProjectRepo = { display_name: string, git_repo_path: string, };
 
+/**
+ * Docker image the workspaces of a project run their processes in
+ */
+export type ProjectDockerSettings = { project_id: string, 
+/**
+ * Must provide the executors and tools the project needs
+ */
+image: string, created_at: string, updated_at: string, };
+
+export type UpsertProjectDockerSettings = { image: string, };
+
+/**
+ * What happens when a coding agent run of a project fails
+ */
+export type ProjectRetryPolicy = { project_id: string, 
+/**
+ * Retries of the same executor on transient failures
+ */
+max_retries: bigint, 
+/**
+ * Delay before the first retry, doubled for every further retry
+ */
+backoff_seconds: bigint, 
+/**
+ * Profile to continue with in a new session once a failure can't be
+ * retried; no fallback if unset
+ */
+fallback_executor_profile_id: ExecutorProfileId | null, created_at: string, updated_at: string, };
+
+export type UpsertProjectRetryPolicy = { max_retries: bigint, backoff_seconds: bigint, fallback_executor_profile_id: ExecutorProfileId | null, };
+
+/**
+ * Price of the models whose name starts with `model_pattern`, in USD per
+ * million tokens
+ */
+export type ModelPrice = { model_pattern: string, input_price: number, output_price: number, cache_read_price: number, cache_write_price: number, created_at: string, updated_at: string, };
+
+export type UpsertModelPrice = { model_pattern: string, input_price: number, output_price: number, cache_read_price: number, cache_write_price: number, };
+
 export type WorkspaceRepo = { id: string, workspace_id: string, repo_id: string, target_branch: string, created_at: Date, updated_at: Date, };
 
 export type CreateWorkspaceRepo = { repo_id: string, target_branch: string, };
@@ -40,13 +79,13 @@ export type UpdateTag = { tag_name: string | null, content: string | null, };
 
 export type TaskStatus = "todo" | "inprogress" | "inreview" | "done" | "cancelled";
 
-export type Task = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, created_at: string, updated_at: string, dooray_task_id: string | null, dooray_project_id: string | null, dooray_task_number: string | null, design_session_id: string | null, reference_dooray_url: string | null, };
+export type Task = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, created_at: string, updated_at: string, dooray_task_id: string | null, dooray_project_id: string | null, dooray_task_number: string | null, design_session_id: string | null, reference_dooray_url: string | null, parent_task_id: string | null, plan: string | null, };
 
-export type TaskWithAttemptStatus = { has_in_progress_attempt: boolean, last_attempt_failed: boolean, executor: string, workspace_count: bigint, id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, created_at: string, updated_at: string, dooray_task_id: string | null, dooray_project_id: string | null, dooray_task_number: string | null, design_session_id: string | null, reference_dooray_url: string | null, };
+export type TaskWithAttemptStatus = { has_in_progress_attempt: boolean, last_attempt_failed: boolean, executor: string, workspace_count: bigint, id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, created_at: string, updated_at: string, dooray_task_id: string | null, dooray_project_id: string | null, dooray_task_number: string | null, design_session_id: string | null, reference_dooray_url: string | null, parent_task_id: string | null, plan: string | null, };
 
-export type TaskRelationships = { parent_task: Task | null, current_workspace: Workspace, children: Array<Task>, };
+export type TaskRelationships = { parent_task: Task | null, current_workspace: Workspace, children: Array<Task>, dooray_parent_task: Task | null, dooray_subtasks: Array<Task>, };
 
-export type CreateTask = { project_id: string, title: string, description: string | null, status: TaskStatus | null, parent_workspace_id: string | null, image_ids: Array<string> | null, dooray_task_id: string | null, dooray_project_id: string | null, dooray_task_number: string | null, reference_dooray_url: string | null, };
+export type CreateTask = { project_id: string, title: string, description: string | null, status: TaskStatus | null, parent_workspace_id: string | null, image_ids: Array<string> | null, dooray_task_id: string | null, dooray_project_id: string | null, dooray_task_number: string | null, reference_dooray_url: string | null, parent_task_id?: string, };
 
 export type UpdateTask = { title: string | null, description: string | null, status: TaskStatus | null, parent_workspace_id: string | null, image_ids: Array<string> | null, };
 
@@ -80,6 +119,58 @@ member_id: string | null, };
 
 export type UpdateDooraySettings = { dooray_token: string | null, selected_project_id: string | null, selected_project_name: string | null, };
 
+export type DoorayConnection = { id: string, name: string, dooray_token: string, 
+/**
+ * Dooray domain (e.g., "nhnent.dooray.com")
+ */
+dooray_domain: string | null, 
+/**
+ * Token owner's Dooray organizationMemberId
+ */
+member_id: string | null, created_at: string, updated_at: string, };
+
+export type CreateDoorayConnection = { name: string, dooray_token: string, dooray_domain: string | null, member_id: string | null, };
+
+export type ProjectDoorayBinding = { id: string, project_id: string, 
+/**
+ * Connection whose token is used; `None` for the default connection in settings
+ */
+connection_id: string | null, dooray_project_id: string, 
+/**
+ * Used for task numbers (e.g. `PROJ/123`)
+ */
+dooray_project_code: string, 
+/**
+ * Tag IDs to filter when syncing
+ */
+tag_ids: Array<string>, 
+/**
+ * Binding used for new Dooray posts created from this kanban project
+ */
+is_default: boolean, 
+/**
+ * Newest post `updatedAt` seen by the last sync
+ */
+sync_watermark: string | null, created_at: string, updated_at: string, };
+
+export type CreateProjectDoorayBinding = { project_id: string, connection_id: string | null, dooray_project_id: string, dooray_project_code: string, tag_ids: Array<string>, is_default: boolean, };
+
+export type UpdateProjectDoorayBinding = { tag_ids: Array<string> | null, is_default: boolean | null, };
+
+export type DoorayWorkflowMapping = { id: string, dooray_project_id: string, workflow_id: string, workflow_name: string | null, task_status: TaskStatus, 
+/**
+ * Workflow to set on Dooray when a task moves into `task_status` locally
+ */
+is_push_target: boolean, created_at: string, updated_at: string, };
+
+export type UpsertDoorayWorkflowMapping = { workflow_id: string, workflow_name: string | null, task_status: TaskStatus, is_push_target: boolean, };
+
+export type DoorayTaskSyncState = { task_id: string, task_status: TaskStatus, workflow_id: string | null, 
+/**
+ * Remote workflow that conflicted with a local change, until resolved
+ */
+conflict_workflow_id: string | null, synced_at: string, };
+
 export type DesignMessage = { id: string, session_id: string, role: DesignMessageRole, content: string, created_at: string, };
 
 export type DesignMessageRole = "user" | "assistant";
@@ -88,7 +179,16 @@ export type CreateDesignMessage = { role: DesignMessageRole, content: string, };
 
 export type DoorayProject = { id: string, code: string, name: string, description: string | null, };
 
-export type DoorayTask = { id: string, number: bigint, subject: string, workflowClass: string | null, body: DoorayTaskBody | null, };
+export type DoorayTask = { id: string, number: bigint, subject: string, workflowClass: string | null, workflow: DoorayWorkflowRef | null, body: DoorayTaskBody | null, updatedAt: string | null, 
+/**
+ * Parent post, for sub-posts
+ */
+parent: DoorayPostRef | null, };
+
+/**
+ * Post reference embedded in posts (e.g. the parent of a sub-post)
+ */
+export type DoorayPostRef = { id: string, number: bigint | null, subject: string | null, };
 
 export type DoorayTaskBody = { mimeType: string | null, content: string | null, };
 
@@ -106,9 +206,77 @@ export type SaveSettingsRequest = { dooray_token: string, selected_project_id: s
  */
 dooray_domain: string | null, };
 
-export type SyncRequest = { project_id: string, dooray_project_id: string, dooray_project_code: string, };
+export type RotateTokenRequest = { dooray_token: string, };
 
-export type SyncResult = { created: number, updated: number, skipped: number, };
+export type ValidateTokenRequest = { 
+/**
+ * Token to check; omit to check the stored token of `connection_id`
+ */
+dooray_token?: string, 
+/**
+ * Stored connection to check; omit for the default connection
+ */
+connection_id?: string, };
+
+export type DoorayTokenProblem = "malformed" | "rejected" | "missing_member_scope" | "missing_project_scope" | "unreachable" | "unexpected";
+
+export type DoorayTokenValidation = { 
+/**
+ * Whether the token can be used for the integration
+ */
+valid: boolean, 
+/**
+ * Token owner's Dooray organizationMemberId
+ */
+member_id: string | null, member_name: string | null, problem: DoorayTokenProblem | null, 
+/**
+ * Explanation of `problem`
+ */
+message: string | null, };
+
+export type SaveDoorayConnectionRequest = { name: string, 
+/**
+ * Required when creating; omit to keep the current token on update
+ */
+dooray_token: string | null, 
+/**
+ * Dooray domain (e.g., "nhnent.dooray.com")
+ */
+dooray_domain: string | null, };
+
+export type SyncRequest = { project_id: string, dooray_project_id: string, dooray_project_code: string, 
+/**
+ * Re-walk every open post instead of only those changed since the last sync
+ */
+full?: boolean, };
+
+export type SyncBindingRequest = { 
+/**
+ * Re-walk every open post instead of only those changed since the last sync
+ */
+full?: boolean, };
+
+export type SyncResult = { created: number, updated: number, skipped: number, 
+/**
+ * Tasks whose status changed on both sides since the last sync
+ */
+conflicts: number, };
+
+export type DooraySyncTrigger = "manual" | "scheduled" | "webhook";
+
+export type DooraySyncPhase = "running" | "completed" | "failed";
+
+export type DooraySyncProgress = { binding_id: string, project_id: string, dooray_project_id: string, trigger: DooraySyncTrigger, phase: DooraySyncPhase, 
+/**
+ * Whether only posts changed since the previous sync were considered
+ */
+incremental: boolean, processed: number, total: number | null, result: SyncResult, error: string | null, started_at: string, finished_at: string | null, };
+
+export type DoorayWebhookEvent = "post_created" | "post_updated" | "post_commented" | "workflow_changed" | "other";
+
+export type DoorayWebhookAction = "updated" | "synced" | "commented" | "ignored";
+
+export type DoorayWebhookResult = { event: DoorayWebhookEvent, action: DoorayWebhookAction, dooray_post_id: string | null, task_id: string | null, received_at: string, };
 
 export type ImportByNumberRequest = { project_id: string, dooray_project_id: string, dooray_project_code: string, task_number: bigint, };
 
@@ -116,6 +284,18 @@ export type ImportByIdRequest = { project_id: string, dooray_project_id: string,
 
 export type ImportResult = { success: boolean, task_id: string | null, message: string, };
 
+export type ImportSubPostsRequest = { project_id: string, dooray_project_id: string, dooray_project_code: string, dooray_task_id: string, };
+
+export type SubPostImportResult = { 
+/**
+ * Task of the parent post
+ */
+parent_task_id: string | null, created: number, 
+/**
+ * Sub-posts that were already imported and are now linked to the parent
+ */
+linked: number, skipped: number, };
+
 export type CreateDoorayCommentRequest = { dooray_task_id: string, dooray_project_id: string, content: string, };
 
 export type CreateDoorayCommentResult = { success: boolean, message: string, };
@@ -124,6 +304,13 @@ export type DoorayComment = { id: string, author_name: string, content: string,
 
 export type GetDoorayCommentsResponse = { comments: Array<DoorayComment>, };
 
+/**
+ * A Dooray post with its markdown body
+ */
+export type DoorayPost = { id: string, number: bigint | null, subject: string, workflow: DoorayWorkflowRef | null, body: string | null, };
+
+export type SetDoorayWorkflowRequest = { workflow_id: string, };
+
 export type CreateDoorayTaskRequest = { dooray_project_id: string, subject: string, body: string | null, local_project_id: string, tag_ids: Array<string> | null, 
 /**
  * Parent task ID for creating subtasks
@@ -144,7 +331,63 @@ export type CrossReferenceRequest = { target_url: string, source_task_id: string
 
 export type DoorayMember = { id: string, name: string, };
 
-export type ChangeAssigneeRequest = { target_url: string, member_id: string, member_name: string, };
+export type ChangeAssigneeRequest = { target_url: string, member_id: string, member_name: string, 
+/**
+ * Dooray project of the target task; defaults to the project selected in settings
+ */
+dooray_project_id?: string, };
+
+export type DoorayWorkflow = { id: string, name: string, order: number | null, 
+/**
+ * One of `backlog`, `registered`, `working` or `closed`
+ */
+class: string, };
+
+export type DoorayWorkflowRef = { id: string, name: string | null, };
+
+export type StatusConflictResolution = "local" | "remote";
+
+export type DoorayWorkflowMappingsResponse = { workflows: Array<DoorayWorkflow>, mappings: Array<DoorayWorkflowMapping>, };
+
+export type UpdateWorkflowMappingsRequest = { mappings: Array<UpsertDoorayWorkflowMapping>, };
+
+export type DoorayStatusConflict = { task_id: string, title: string, dooray_task_number: string | null, 
+/**
+ * Current kanban status
+ */
+local_status: TaskStatus, 
+/**
+ * Status both sides last agreed on
+ */
+synced_status: TaskStatus, 
+/**
+ * Dooray workflow the post moved to in the meantime
+ */
+remote_workflow_id: string, };
+
+export type ResolveStatusConflictRequest = { resolution: StatusConflictResolution, };
+
+export type DoorayReportSettings = { project_id: string, 
+/**
+ * Comment when a coding agent run finishes (successfully or not)
+ */
+on_execution_finished: boolean, on_pr_created: boolean, on_pr_merged: boolean, 
+/**
+ * Comment template with `{placeholders}`; `None` uses the built-in template
+ */
+comment_template: string | null, created_at: string, updated_at: string, };
+
+export type UpsertDoorayReportSettings = { on_execution_finished: boolean, on_pr_created: boolean, on_pr_merged: boolean, comment_template: string | null, };
+
+export type DoorayReportSettingsResponse = { 
+/**
+ * `None` until reporting is configured for the project
+ */
+settings: DoorayReportSettings | null, 
+/**
+ * Template used when `comment_template` is empty
+ */
+default_template: string, };
 
 export type DraftFollowUpData = { message: string, executor_profile_id: ExecutorProfileId, };
 
@@ -246,6 +489,36 @@ export type ExecutionProcessRunReason = "setupscript" | "cleanupscript" | "archi
 
 export type ExecutionProcessRepoState = { id: string, execution_process_id: string, repo_id: string, before_head_commit: string | null, after_head_commit: string | null, merge_commit: string | null, created_at: Date, updated_at: Date, };
 
+export type LogEntryKind = "user_message" | "assistant_message" | "tool_use" | "error_message";
+
+export type LogSearchHit = { execution_id: string, 
+/**
+ * Index of the entry in the normalized conversation of the process
+ */
+entry_index: bigint, kind: LogEntryKind, 
+/**
+ * Text around the matches, which are wrapped in `<mark>` tags; the rest
+ * is not escaped
+ */
+snippet: string, run_reason: ExecutionProcessRunReason, session_id: string, executor: string | null, workspace_id: string, workspace_branch: string, task_id: string, task_title: string, project_id: string, logged_at: string, };
+
+/**
+ * Log storage of the execution processes of a project
+ */
+export type ProjectLogUsage = { project_id: string, project_name: string, execution_count: bigint, 
+/**
+ * Uncompressed size of the logs
+ */
+log_bytes: bigint, 
+/**
+ * Size on disk of the logs
+ */
+stored_bytes: bigint, 
+/**
+ * Size on disk of raw output, the part that retention prunes
+ */
+raw_stored_bytes: bigint, };
+
 export type Merge = { "type": "direct" } & DirectMerge | { "type": "pr" } & PrMerge;
 
 export type DirectMerge = { id: string, workspace_id: string, repo_id: string, merge_commit: string, target_branch_name: string, created_at: string, };
@@ -262,6 +535,32 @@ export type CreateApprovalRequest = { tool_name: string, tool_input: JsonValue,
 
 export type ApprovalResponse = { execution_process_id: string, status: ApprovalStatus, };
 
+export type ApprovalRecord = { id: string, execution_process_id: string, tool_name: string, tool_input: JsonValue, tool_call_id: string, status: ApprovalStatus, responded_by: ApprovalResponder | null, 
+/**
+ * Rule that answered the request, if it was answered by policy
+ */
+matched_rule: string | null, created_at: string, timeout_at: string, responded_at: string | null, };
+
+export type ApprovalResponder = "user" | "timeout" | "cancellation" | "restart" | "policy";
+
+export type ApprovalRule = { id: string, project_id: string, position: bigint, action: ApprovalRuleAction, 
+/**
+ * Glob on the tool name, case-insensitive
+ */
+tool_name: string | null, 
+/**
+ * Glob on the command of command runs
+ */
+command: string | null, 
+/**
+ * Glob on the path of file edits, relative to the workspace
+ */
+path: string | null, created_at: string, updated_at: string, };
+
+export type ApprovalRuleAction = "allow" | "deny" | "ask";
+
+export type UpsertApprovalRule = { action: ApprovalRuleAction, tool_name: string | null, command: string | null, path: string | null, };
+
 export type Diff = { change: DiffChangeKind, oldPath: string | null, newPath: string | null, oldContent: string | null, newContent: string | null, 
 /**
  * True when file contents are intentionally omitted (e.g., too large)
@@ -352,7 +651,12 @@ export type UserSystemInfo = { config: Config, analytics_user_id: string, login_
 /**
  * Capabilities supported per executor (e.g., { "CLAUDE_CODE": ["SESSION_FORK"] })
  */
-capabilities: { [key in string]?: Array<BaseAgentCapability> }, executors: { [key in BaseCodingAgent]?: ExecutorConfig }, };
+capabilities: { [key in string]?: Array<BaseAgentCapability> }, 
+/**
+ * What ACP agents advertised on their last run, per executor and variant
+ * (e.g., { "ACP_AGENT": { "DEFAULT": { ... } } })
+ */
+acp_capabilities: { [key in string]?: { [key in string]?: AcpAgentInfo } }, executors: { [key in BaseCodingAgent]?: ExecutorConfig }, };
 
 export type Environment = { os_type: string, os_version: string, os_architecture: string, bitness: string, };
 
@@ -405,11 +709,39 @@ export type DesignChatRequest = { message: string,
  * When true, skip saving messages to DB and skip loading conversation history.
  * Used by changelog generation to avoid context accumulation across steps.
  */
-skip_history: boolean | null, };
+skip_history: boolean | null, 
+/**
+ * Executor profile to chat with. Defaults to the configured executor profile.
+ */
+executor_profile_id: ExecutorProfileId | null, };
 
 export type DesignChatResponse = { user_message: DesignMessage, assistant_message: DesignMessage, };
 
-export type DesignChatStreamEvent = { "type": "UserMessageSaved", "data": { message: DesignMessage, } } | { "type": "AssistantChunk", "data": { content: string, } } | { "type": "AssistantComplete", "data": { message: DesignMessage, } } | { "type": "ToolUse", "data": { tool_name: string, tool_input: JsonValue, } } | { "type": "ToolResult", "data": { tool_name: string, output: string, } } | { "type": "Error", "data": { message: string, } };
+export type DesignChatStreamEvent = { "type": "UserMessageSaved", "data": { message: DesignMessage, } } | { "type": "AssistantChunk", "data": { content: string, } } | { "type": "AssistantComplete", "data": { message: DesignMessage, } } | { "type": "Entry", "data": { index: number, entry: NormalizedEntry, } } | { "type": "Error", "data": { message: string, } };
+
+export type CreateDesignPlanRequest = { 
+/**
+ * Executor profile to ask for the plan. Defaults to the configured executor profile.
+ */
+executor_profile_id: ExecutorProfileId | null, 
+/**
+ * Create a child task (a Dooray sub-post when the task is linked) per step
+ */
+create_subtasks: boolean, };
+
+export type DesignPlan = { goals: Array<string>, steps: Array<PlanStep>, acceptance_criteria: Array<string>, };
+
+export type PlanStep = { title: string, description: string | null, };
+
+export type DesignPlanResult = { 
+/**
+ * The task with its plan
+ */
+task: Task, plan: DesignPlan, 
+/**
+ * Child tasks created for the plan's steps
+ */
+subtasks: Array<Task>, };
 
 export type CreatePrApiRequest = { title: string, body: string | null, target_branch: string | null, draft: boolean | null, repo_id: string, auto_generate_description: boolean, };
 
@@ -417,10 +749,138 @@ export type ImageResponse = { id: string, file_path: string, original_name: stri
 
 export type ImageMetadata = { exists: boolean, file_name: string | null, path: string | null, size_bytes: bigint | null, format: string | null, proxy_url: string | null, };
 
-export type CreateTaskAttemptBody = { task_id: string, executor_profile_id: ExecutorProfileId, repos: Array<WorkspaceRepoInput>, };
+export type CreateTaskAttemptBody = { task_id: string, executor_profile_id: ExecutorProfileId, repos: Array<WorkspaceRepoInput>, 
+/**
+ * Start a sibling attempt in its own workspace for each of these profiles,
+ * to compare with the first one
+ */
+additional_executor_profile_ids?: Array<ExecutorProfileId>, };
+
+/**
+ * The attempt of `executor_profile_id`, with the siblings started next to it
+ */
+export type CreateTaskAttemptResponse = { id: string, task_id: string, container_ref: string | null, branch: string, agent_working_dir: string | null, setup_completed_at: string | null, created_at: string, updated_at: string, archived: boolean, pinned: boolean, name: string | null, 
+/**
+ * Attempts started for `additional_executor_profile_ids`, in request order
+ */
+siblings: Array<Workspace>, 
+/**
+ * Additional profiles whose attempt couldn't be created
+ */
+failed_attempts: Array<FailedAttempt>, };
+
+export type FailedAttempt = { executor_profile_id: ExecutorProfileId, error: string, };
 
 export type WorkspaceRepoInput = { repo_id: string, target_branch: string, };
 
+export type CompareAttemptsRequest = { task_id: string, 
+/**
+ * Attempts to compare; every unarchived attempt of the task if omitted
+ */
+workspace_ids?: Array<string>, 
+/**
+ * Ask the agent of this profile to rank the attempts
+ */
+reviewer_executor_profile_id?: ExecutorProfileId, };
+
+export type AttemptComparison = { attempts: Array<AttemptSummary>, 
+/**
+ * Set when a reviewer was asked to rank the attempts
+ */
+ranking: AttemptRanking | null, };
+
+export type AttemptSummary = { workspace_id: string, branch: string, 
+/**
+ * Profile of the first coding agent run
+ */
+executor_profile_id: ExecutorProfileId | null, 
+/**
+ * Status of the latest coding agent run
+ */
+agent_status: ExecutionProcessStatus | null, 
+/**
+ * Number of files changed against the target branches; diff stats are
+ * unset once the worktree is gone
+ */
+files_changed: number | null, lines_added: number | null, lines_removed: number | null, 
+/**
+ * Setup and cleanup script runs, oldest first
+ */
+scripts: Array<ScriptRunResult>, 
+/**
+ * Sum of the tokens each coding agent run last reported; unset if the
+ * executor reports none
+ */
+total_tokens: bigint | null, 
+/**
+ * Time spent in coding agent runs
+ */
+agent_duration_seconds: bigint, };
+
+export type AttemptRanking = { 
+/**
+ * Best attempt first
+ */
+workspace_ids: Array<string>, rationale: string, };
+
+export type ScriptRunResult = { run_reason: ExecutionProcessRunReason, status: ExecutionProcessStatus, exit_code: bigint | null, };
+
+export type ModelCost = { 
+/**
+ * Unset for runs whose executor didn't name the model
+ */
+model: string | null, runs: bigint, input_tokens: bigint, output_tokens: bigint, cache_read_tokens: bigint, cache_write_tokens: bigint, 
+/**
+ * Unset when no price matches the model
+ */
+cost_usd: number | null, };
+
+export type TaskCost = { task_id: string, title: string, dooray_task_id: string | null, dooray_task_number: string | null, models: Array<ModelCost>, 
+/**
+ * Sum of the priced models
+ */
+cost_usd: number, };
+
+export type ProjectCost = { project_id: string, 
+/**
+ * Tasks with recorded usage, most expensive first
+ */
+tasks: Array<TaskCost>, cost_usd: number, };
+
+export type TranscriptFormat = "markdown" | "html" | "json";
+
+export type Transcript = { schema_version: number, session_id: string, executor: string | null, workspace_id: string | null, branch: string | null, task_id: string | null, task_title: string | null, exported_at: string, turns: Array<TranscriptTurn>, };
+
+export type TranscriptTurn = { execution_id: string, 
+/**
+ * Executor profile of the run, e.g. `CLAUDE_CODE:PLAN`
+ */
+executor_profile: string | null, status: ExecutionProcessStatus, started_at: string, completed_at: string | null, entries: Array<TranscriptEntry>, };
+
+export type TranscriptEntryKind = "user_message" | "user_feedback" | "assistant_message" | "thinking" | "system_message" | "error_message" | "tool_call";
+
+export type TranscriptEntry = { kind: TranscriptEntryKind, content: string, timestamp: string | null, 
+/**
+ * Set for `tool_call` entries
+ */
+tool_call: TranscriptToolCall | null, };
+
+export type TranscriptToolStatus = "pending" | "success" | "failed" | "denied" | "timed_out";
+
+export type TranscriptToolCall = { tool_name: string, 
+/**
+ * `pending` if no outcome was logged, e.g. the run stopped during the call
+ */
+status: TranscriptToolStatus, 
+/**
+ * Command, path, query, URL or arguments of the call
+ */
+input: string | null, output: string | null, 
+/**
+ * Unified diff of the files the call changed
+ */
+diff: string | null, };
+
 export type RunAgentSetupRequest = { executor_profile_id: ExecutorProfileId, };
 
 export type RunAgentSetupResponse = Record<string, never>;
@@ -553,7 +1013,7 @@ export type DirectoryListResponse = { entries: Array<DirectoryEntry>, current_pa
 
 export type SearchMode = "taskform" | "settings";
 
-export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, language: UiLanguage, git_branch_prefix: string, showcases: ShowcaseState, pr_auto_description_enabled: boolean, pr_auto_description_prompt: string | null, beta_workspaces: boolean, beta_workspaces_invitation_sent: boolean, commit_reminder_enabled: boolean, commit_reminder_prompt: string | null, send_message_shortcut: SendMessageShortcut, };
+export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, language: UiLanguage, git_branch_prefix: string, showcases: ShowcaseState, pr_auto_description_enabled: boolean, pr_auto_description_prompt: string | null, beta_workspaces: boolean, beta_workspaces_invitation_sent: boolean, commit_reminder_enabled: boolean, commit_reminder_prompt: string | null, send_message_shortcut: SendMessageShortcut, log_retention: LogRetentionConfig, };
 
 export type NotificationConfig = { sound_enabled: boolean, push_enabled: boolean, sound_file: SoundFile, };
 
@@ -575,23 +1035,34 @@ export type ShowcaseState = { seen_features: Array<string>, };
 
 export type SendMessageShortcut = "ModifierEnter" | "Enter";
 
-export type GitBranch = { name: string, is_current: boolean, is_remote: boolean, last_commit_date: Date, };
-
-export type QueuedMessage = { 
 /**
- * The session this message is queued for
+ * How long the raw output of finished execution processes is kept. Coding
+ * agent runs keep their normalized conversation either way.
  */
-session_id: string, 
+export type LogRetentionConfig = { 
 /**
- * The follow-up data (message + variant)
+ * Prune raw output this many days after the process finished
  */
-data: DraftFollowUpData, 
+raw_log_days: number | null, 
 /**
- * Timestamp when the message was queued
+ * Prune the raw output of the oldest processes beyond this many
+ * megabytes of compressed raw output
  */
-queued_at: string, };
+raw_log_max_mb: number | null, };
 
-export type QueueStatus = { "status": "empty" } | { "status": "queued", message: QueuedMessage, };
+export type GitBranch = { name: string, is_current: boolean, is_remote: boolean, last_commit_date: Date, };
+
+export type QueuedMessage = { id: string, session_id: string, position: bigint, message: string, 
+/**
+ * Profile to run the message with; the session's latest profile if unset
+ */
+executor_profile_id: ExecutorProfileId | null, created_at: string, updated_at: string, };
+
+export type QueueStatus = { session_id: string, 
+/**
+ * Queued messages, next one first
+ */
+messages: Array<QueuedMessage>, };
 
 export type ConflictOp = "rebase" | "merge" | "cherry_pick" | "revert";
 
@@ -612,9 +1083,9 @@ working_dir: string | null, };
 
 export type ScriptRequestLanguage = "Bash";
 
-export enum BaseCodingAgent { CLAUDE_CODE = "CLAUDE_CODE", AMP = "AMP", GEMINI = "GEMINI", CODEX = "CODEX", OPENCODE = "OPENCODE", CURSOR_AGENT = "CURSOR_AGENT", QWEN_CODE = "QWEN_CODE", COPILOT = "COPILOT", DROID = "DROID" }
+export enum BaseCodingAgent { CLAUDE_CODE = "CLAUDE_CODE", AMP = "AMP", GEMINI = "GEMINI", CODEX = "CODEX", OPENCODE = "OPENCODE", CURSOR_AGENT = "CURSOR_AGENT", QWEN_CODE = "QWEN_CODE", COPILOT = "COPILOT", DROID = "DROID", CUSTOM_AGENT = "CUSTOM_AGENT", ACP_AGENT = "ACP_AGENT" }
 
-export type CodingAgent = { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid };
+export type CodingAgent = { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } | { "CUSTOM_AGENT": CustomAgent } | { "ACP_AGENT": AcpAgent };
 
 export type SlashCommandDescription = { 
 /**
@@ -644,19 +1115,36 @@ executor: BaseCodingAgent,
  */
 variant: string | null, };
 
-export type ExecutorConfig = { [key in string]?: { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } };
+export type ExecutorConfig = { [key in string]?: { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } | { "CUSTOM_AGENT": CustomAgent } | { "ACP_AGENT": AcpAgent } };
 
 export type ExecutorConfigs = { executors: { [key in BaseCodingAgent]?: ExecutorConfig }, };
 
 export enum BaseAgentCapability { SESSION_FORK = "SESSION_FORK", SETUP_HELPER = "SETUP_HELPER", CONTEXT_USAGE = "CONTEXT_USAGE" }
 
-export type ClaudeCode = { append_prompt: AppendPrompt, claude_code_router?: boolean | null, plan?: boolean | null, approvals?: boolean | null, model?: string | null, dangerously_skip_permissions?: boolean | null, disable_api_key?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+/**
+ * Agent identity and capabilities from its `initialize` response
+ */
+export type AcpAgentInfo = { name: string | null, version: string | null, 
+/**
+ * Can resume sessions with `session/load`
+ */
+load_session: boolean, 
+/**
+ * Content types accepted in prompts besides text
+ */
+image: boolean, audio: boolean, embedded_context: boolean, 
+/**
+ * MCP server transports accepted besides stdio
+ */
+mcp_http: boolean, mcp_sse: boolean, };
+
+export type ClaudeCode = { append_prompt: AppendPrompt, claude_code_router?: boolean | null, plan?: boolean | null, approvals?: boolean | null, model?: string | null, dangerously_skip_permissions?: boolean | null, disable_api_key?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, linux_sandbox?: LinuxSandbox | null, };
 
-export type Gemini = { append_prompt: AppendPrompt, model?: string | null, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Gemini = { append_prompt: AppendPrompt, model?: string | null, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, linux_sandbox?: LinuxSandbox | null, };
 
-export type Amp = { append_prompt: AppendPrompt, dangerously_allow_all?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Amp = { append_prompt: AppendPrompt, dangerously_allow_all?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, linux_sandbox?: LinuxSandbox | null, };
 
-export type Codex = { append_prompt: AppendPrompt, sandbox?: SandboxMode | null, ask_for_approval?: AskForApproval | null, oss?: boolean | null, model?: string | null, model_reasoning_effort?: ReasoningEffort | null, model_reasoning_summary?: ReasoningSummary | null, model_reasoning_summary_format?: ReasoningSummaryFormat | null, profile?: string | null, base_instructions?: string | null, include_apply_patch_tool?: boolean | null, model_provider?: string | null, compact_prompt?: string | null, developer_instructions?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Codex = { append_prompt: AppendPrompt, sandbox?: SandboxMode | null, ask_for_approval?: AskForApproval | null, oss?: boolean | null, model?: string | null, model_reasoning_effort?: ReasoningEffort | null, model_reasoning_summary?: ReasoningSummary | null, model_reasoning_summary_format?: ReasoningSummaryFormat | null, profile?: string | null, base_instructions?: string | null, include_apply_patch_tool?: boolean | null, model_provider?: string | null, compact_prompt?: string | null, developer_instructions?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, linux_sandbox?: LinuxSandbox | null, };
 
 export type SandboxMode = "auto" | "read-only" | "workspace-write" | "danger-full-access";
 
@@ -668,9 +1156,9 @@ export type ReasoningSummary = "auto" | "concise" | "detailed" | "none";
 
 export type ReasoningSummaryFormat = "none" | "experimental";
 
-export type CursorAgent = { append_prompt: AppendPrompt, force?: boolean | null, model?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type CursorAgent = { append_prompt: AppendPrompt, force?: boolean | null, model?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, linux_sandbox?: LinuxSandbox | null, };
 
-export type Copilot = { append_prompt: AppendPrompt, model?: string | null, allow_all_tools?: boolean | null, allow_tool?: string | null, deny_tool?: string | null, add_dir?: Array<string> | null, disable_mcp_server?: Array<string> | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Copilot = { append_prompt: AppendPrompt, model?: string | null, allow_all_tools?: boolean | null, allow_tool?: string | null, deny_tool?: string | null, add_dir?: Array<string> | null, disable_mcp_server?: Array<string> | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, linux_sandbox?: LinuxSandbox | null, };
 
 export type Opencode = { append_prompt: AppendPrompt, model?: string | null, variant?: string | null, agent?: string | null, 
 /**
@@ -680,16 +1168,36 @@ auto_approve: boolean,
 /**
  * Enable auto-compaction when the context length approaches the model's context window limit
  */
-auto_compact: boolean, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+auto_compact: boolean, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, linux_sandbox?: LinuxSandbox | null, };
 
-export type QwenCode = { append_prompt: AppendPrompt, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type QwenCode = { append_prompt: AppendPrompt, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, linux_sandbox?: LinuxSandbox | null, };
 
-export type Droid = { append_prompt: AppendPrompt, autonomy: Autonomy, model?: string | null, reasoning_effort?: DroidReasoningEffort | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Droid = { append_prompt: AppendPrompt, autonomy: Autonomy, model?: string | null, reasoning_effort?: DroidReasoningEffort | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, linux_sandbox?: LinuxSandbox | null, };
 
 export type Autonomy = "normal" | "low" | "medium" | "high" | "skip-permissions-unsafe";
 
 export type DroidReasoningEffort = "none" | "dynamic" | "off" | "low" | "medium" | "high";
 
+/**
+ * Any CLI agent, described entirely by its profile
+ */
+export type CustomAgent = { append_prompt: AppendPrompt, command: string, follow_up_command?: string | null, prompt_input: CustomPromptInput, session_id_pattern?: string | null, log_format: CustomLogFormat, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, linux_sandbox?: LinuxSandbox | null, };
+
+export type CustomPromptInput = "stdin" | "arg" | "file";
+
+export type CustomLogFormat = "plain_text" | "claude_stream_json" | "acp";
+
+/**
+ * Any agent that speaks the Agent Client Protocol over stdio
+ */
+export type AcpAgent = { append_prompt: AppendPrompt, command: string, model?: string | null, mode?: string | null, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, linux_sandbox?: LinuxSandbox | null, };
+
+export type LinuxSandbox = { backend: LinuxSandboxBackend, network: LinuxSandboxNetwork, writable_paths: Array<string>, };
+
+export type LinuxSandboxBackend = "bubblewrap" | "unshare";
+
+export type LinuxSandboxNetwork = "allow" | "deny";
+
 export type AppendPrompt = string | null;
 
 export type CodingAgentInitialRequest = { prompt: string, 
@@ -734,7 +1242,17 @@ export type NormalizedEntry = { timestamp: string | null, entry_type: Normalized
 
 export type NormalizedEntryType = { "type": "user_message" } | { "type": "user_feedback", denied_tool: string, } | { "type": "assistant_message" } | { "type": "tool_use", tool_name: string, action_type: ActionType, status: ToolStatus, } | { "type": "system_message" } | { "type": "error_message", error_type: NormalizedEntryError, } | { "type": "thinking" } | { "type": "loading" } | { "type": "next_action", failed: boolean, execution_processes: number, needs_setup: boolean, } | { "type": "token_usage_info" } & TokenUsageInfo;
 
-export type TokenUsageInfo = { total_tokens: number, model_context_window: number, };
+export type TokenUsageInfo = { total_tokens: number, model_context_window: number, 
+/**
+ * Tokens billed for the run so far, if the executor reports them
+ */
+usage?: TokenUsage, };
+
+/**
+ * Cumulative token counts of one run. Input tokens exclude cache reads and
+ * writes, which are billed at their own rates.
+ */
+export type TokenUsage = { model: string | null, input_tokens: bigint, output_tokens: bigint, cache_read_tokens: bigint, cache_write_tokens: bigint, };
 
 export type FileChange = { "action": "write", content: string, } | { "action": "delete" } | { "action": "rename", new_path: string, } | { "action": "edit", 
 /**
@@ -760,7 +1278,11 @@ value: JsonValue, };
 
 export type ToolResultValueType = { "type": "markdown" } | { "type": "json" };
 
-export type ToolStatus = { "status": "created" } | { "status": "success" } | { "status": "failed" } | { "status": "denied", reason: string | null, } | { "status": "pending_approval", approval_id: string, requested_at: string, timeout_at: string, } | { "status": "timed_out" };
+export type ToolStatus = { "status": "created" } | { "status": "success" } | { "status": "failed" } | { "status": "denied", reason: string | null, 
+/**
+ * Auto-approval rule that denied the call
+ */
+rule?: string, } | { "status": "auto_approved", rule: string, } | { "status": "pending_approval", approval_id: string, requested_at: string, timeout_at: string, } | { "status": "timed_out" };
 
 export type PatchType = { "type": "NORMALIZED_ENTRY", "content": NormalizedEntry } | { "type": "STDOUT", "content": string } | { "type": "STDERR", "content": string } | { "type": "DIFF", "content": Diff };
 